@@ -0,0 +1,53 @@
+use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId};
+use markovian::mixing::{accelerated::{lifted_cycle, reversible_cycle}, total_variation_profile};
+use rand::prelude::thread_rng;
+
+
+// To bench: steps until the total variation distance drops below 1/4
+fn lifted_mixing_time(n: usize) -> Option<usize> {
+	let mc = lifted_cycle(&vec![1.; n], 1. / n as f64, thread_rng());
+	total_variation_profile(&mc, 4 * n * n)?.iter().position(|&d| d < 0.25)
+}
+
+// To compare with
+fn reversible_mixing_time(n: usize) -> Option<usize> {
+	let mc = reversible_cycle(&vec![1.; n], thread_rng());
+	total_variation_profile(&mc, 4 * n * n)?.iter().position(|&d| d < 0.25)
+}
+
+// To bench: sampling a trajectory
+fn lifted_trajectory(n: usize) -> Vec<(usize, markovian::mixing::accelerated::Direction)> {
+	lifted_cycle(&vec![1.; n], 1. / n as f64, thread_rng()).take(10_000).collect()
+}
+
+// To compare with
+fn reversible_trajectory(n: usize) -> Vec<usize> {
+	reversible_cycle(&vec![1.; n], thread_rng()).take(10_000).collect()
+}
+
+
+fn bench_mixing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Mixing time on a cycle");
+    group.sample_size(10);
+    for i in [11, 21, 41].iter() {
+        group.bench_with_input(BenchmarkId::new("Lifted", i), i, 
+            |b, i| b.iter(|| lifted_mixing_time(*i)));
+        group.bench_with_input(BenchmarkId::new("Reversible", i), i, 
+            |b, i| b.iter(|| reversible_mixing_time(*i)));
+    }
+    group.finish();
+}
+
+fn bench_sampling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Sampling on a cycle");
+    for i in [11, 21, 41].iter() {
+        group.bench_with_input(BenchmarkId::new("Lifted", i), i, 
+            |b, i| b.iter(|| lifted_trajectory(*i)));
+        group.bench_with_input(BenchmarkId::new("Reversible", i), i, 
+            |b, i| b.iter(|| reversible_trajectory(*i)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_mixing, bench_sampling);
+criterion_main!(benches);
@@ -60,7 +60,8 @@ fn sample_population(
                 (death_prob, 0),
                 (birth_prob, 2),
                 (1.0 - birth_prob - death_prob, 1)
-            ];
+            ]
+            .compile();
             let mut branching_process = Branching::new(init_state, density, thread_rng());
 
             branching_process.nth(iterations).unwrap()
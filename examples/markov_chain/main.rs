@@ -19,11 +19,8 @@ fn main() {
 
     let init_state: i32 = 0;
     let transition2 = |state: &i32| {
-        let v: Vec<(f64, i32)> = (1..)
-            .map(move |s| ((2.0_f32).powf(-s as f32), state + s as i32))
-            .map(|(p, s)| (p as f64, s as i32))
-            .collect();
-        Raw::new(v)
+        let state = *state;
+        Raw::new((1..).map(move |s: i32| ((2.0_f64).powi(-s), state + s)))
     };
 
     let mut mc = MarkovChain::new(init_state, transition2, thread_rng());
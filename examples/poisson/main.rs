@@ -5,7 +5,6 @@
 use rand::prelude::*;
 use markovian::prelude::*;
 use preexplorer::prelude::*;
-use itertools_num::ItertoolsNum;
 
 const STEPS: usize = 10;
 const LAMBDA: f64 = 2.;
@@ -15,10 +14,11 @@ fn main() {
     let (times, values): (Vec<_>, Vec<_>) = Poisson::<f64, usize, _>::new(LAMBDA, thread_rng())
         .unwrap()
         .trajectory()
+        .with_absolute_time()
         .take(STEPS)
         .unzip();
     // Plotting
-    (times.iter().cumsum::<f64>(), values).preexplore()
+    (times, values).preexplore()
         .set_title(format!("Poisson process, lambda = {}", LAMBDA))
         .set_xlabel("time")
         .set_ylabel("state")
@@ -0,0 +1,143 @@
+//! Monte Carlo estimation of costs until absorption.
+//!
+//! For a finite chain, the expected cost is computed exactly by
+//! [FiniteMarkovChain::expected_cost_to_absorption]. For general processes,
+//! independent trajectories are simulated until absorption and their costs averaged.
+//!
+//! # Examples
+//!
+//! Expected number of steps of a random walk started at 2 until it hits 0 or 5.
+//! ```
+//! # use markovian::{MarkovChain, prelude::*, absorption::estimate_cost_to_absorption};
+//! # use rand::SeedableRng;
+//! let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+//! let mc = MarkovChain::new(2, transition, rand_pcg::Pcg64::seed_from_u64(1));
+//! let is_absorbed = |state: &i32| *state == 0 || *state == 5;
+//! let estimate = estimate_cost_to_absorption(mc, |_: &i32, _: &i32| 1.0, is_absorbed, 1_000, usize::MAX);
+//! // Exact value: 2 * 3 = 6
+//! assert!((estimate.mean - 6.).abs() < 4. * estimate.standard_error);
+//! ```
+//!
+//! [FiniteMarkovChain::expected_cost_to_absorption]: ../struct.FiniteMarkovChain.html#method.expected_cost_to_absorption
+
+// Traits
+use crate::{RandomSource, State};
+use rand::SeedableRng;
+
+/// Monte Carlo estimate of an expected cost until absorption.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AbsorptionEstimate {
+    /// Sample mean of the cost over trajectories that were absorbed.
+    pub mean: f64,
+    /// Standard error of `mean`.
+    pub standard_error: f64,
+    /// Number of trajectories that were absorbed.
+    pub samples: usize,
+    /// Number of trajectories that were not absorbed within the maximum number of steps.
+    pub truncated: usize,
+}
+
+/// Estimates the expected total cost until absorption of `process`, from its current state,
+/// by simulating `samples` independent trajectories.
+///
+/// The cost of each jump from `x` to `y` is `cost(x, y)`. Each trajectory is a [fork] of `process`
+/// and is simulated for at most `max_steps` steps; trajectories that are not absorbed by then
+/// are not included in the mean, but counted as truncated.
+///
+/// # Panics
+///
+/// If `samples` is zero.
+///
+/// [fork]: ../trait.RandomSource.html#method.fork
+#[inline]
+pub fn estimate_cost_to_absorption<P, C, A>(
+    mut process: P,
+    cost: C,
+    is_absorbed: A,
+    samples: usize,
+    max_steps: usize,
+) -> AbsorptionEstimate
+where
+    P: Iterator + State + RandomSource + Clone,
+    P::Rng: SeedableRng,
+    <P as State>::Item: Clone,
+    C: Fn(&<P as State>::Item, &<P as State>::Item) -> f64,
+    A: Fn(&<P as State>::Item) -> bool,
+{
+    assert!(samples > 0, "There must be at least one sample.");
+    let mut costs = Vec::with_capacity(samples);
+    let mut truncated = 0;
+    for _ in 0..samples {
+        let mut trajectory = process.fork();
+        let mut total = 0.0;
+        let mut absorbed = false;
+        for _ in 0..max_steps {
+            let previous = match trajectory.state() {
+                Some(state) if is_absorbed(state) => {
+                    absorbed = true;
+                    break;
+                },
+                Some(state) => state.clone(),
+                None => break,
+            };
+            if trajectory.next().is_none() {
+                break;
+            }
+            if let Some(state) = trajectory.state() {
+                total += cost(&previous, state);
+            }
+        }
+        if !absorbed {
+            absorbed = trajectory.state().is_some_and(&is_absorbed);
+        }
+        if absorbed {
+            costs.push(total);
+        } else {
+            truncated += 1;
+        }
+    }
+
+    let n = costs.len() as f64;
+    let mean = if costs.is_empty() { f64::NAN } else { costs.iter().sum::<f64>() / n };
+    let standard_error = if costs.len() < 2 {
+        f64::NAN
+    } else {
+        (costs.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / (n - 1.0) / n).sqrt()
+    };
+    AbsorptionEstimate {
+        mean,
+        standard_error,
+        samples: costs.len(),
+        truncated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::MarkovChain;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn deterministic() {
+        let transition = |state: &i32| raw_dist![(1.0, state + 1)];
+        let mc = MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 1);
+        let estimate = estimate_cost_to_absorption(mc, |x: &i32, _: &i32| *x as f64, |x: &i32| *x == 4, 10, 100);
+
+        assert_eq!(estimate.mean, 6.);
+        assert_eq!(estimate.standard_error, 0.);
+        assert_eq!(estimate.samples, 10);
+    }
+
+    #[test]
+    fn truncated() {
+        let transition = |state: &i32| raw_dist![(1.0, *state)];
+        let mc = MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 2);
+        let estimate = estimate_cost_to_absorption(mc, |_: &i32, _: &i32| 1.0, |x: &i32| *x == 1, 10, 100);
+
+        assert_eq!(estimate.samples, 0);
+        assert_eq!(estimate.truncated, 10);
+        assert!(estimate.mean.is_nan());
+    }
+}
@@ -0,0 +1,18 @@
+pub use self::absolute_time::{AbsoluteTime, Until};
+pub use self::extremum::{Drawdown, RunningMax, RunningMin};
+pub use self::observe::{Observe, ObserveWithTime};
+pub use self::on_grid::OnGrid;
+pub use self::time_changed::TimeChanged;
+pub use self::tracked::Tracked;
+
+mod absolute_time;
+mod extremum;
+mod observe;
+mod on_grid;
+mod time_changed;
+mod tracked;
+
+/// Iterator returned by the [trajectory] method.
+/// 
+/// [trajectory]: ../trait.StateIterator.html#method.trajectory
+pub type Trajectory<I> = core::iter::Chain<std::option::IntoIter<<I as Iterator>::Item>, I>;
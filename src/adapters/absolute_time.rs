@@ -0,0 +1,114 @@
+// Traits
+use core::ops::Add;
+
+/// Iterator that accumulates the periods of a timed iterator. 
+/// 
+/// This struct is created by the [with_absolute_time] method. 
+/// 
+/// [with_absolute_time]: ../trait.TimedIterator.html#method.with_absolute_time
+#[derive(Debug, Clone)]
+pub struct AbsoluteTime<I, N> {
+    iter: I,
+    time: N,
+}
+
+impl<I, N> AbsoluteTime<I, N> {
+    #[inline]
+    pub(crate) fn new(iter: I, time: N) -> Self {
+        AbsoluteTime { iter, time }
+    }
+
+    /// Returns the absolute time of the last element yielded.
+    #[inline]
+    pub fn time(&self) -> &N {
+        &self.time
+    }
+}
+
+impl<I, N, T> Iterator for AbsoluteTime<I, N>
+where
+    I: Iterator<Item = (N, T)>,
+    N: Add<Output = N> + Copy,
+{
+    type Item = (N, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (period, state) = self.iter.next()?;
+        self.time = self.time + period;
+        Some((self.time, state))
+    }
+}
+
+/// Iterator that stops once the time of its elements exceeds a time horizon. 
+/// 
+/// This struct is created by the [until] method. 
+/// 
+/// [until]: ../trait.TimedIterator.html#method.until
+#[derive(Debug, Clone)]
+pub struct Until<I, N> {
+    iter: I,
+    horizon: N,
+    finished: bool,
+}
+
+impl<I, N> Until<I, N> {
+    #[inline]
+    pub(crate) fn new(iter: I, horizon: N) -> Self {
+        Until { iter, horizon, finished: false }
+    }
+}
+
+impl<I, N, T> Iterator for Until<I, N>
+where
+    I: Iterator<Item = (N, T)>,
+    N: PartialOrd,
+{
+    type Item = (N, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.iter.next() {
+            Some((time, state)) if time <= self.horizon => Some((time, state)),
+            _ => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn absolute_time() {
+        let periods = vec![(1., 'a'), (0.5, 'b'), (2., 'c')];
+        let sample: Vec<(f64, char)> = periods.into_iter().with_absolute_time().collect();
+
+        assert_eq!(sample, vec![(1., 'a'), (1.5, 'b'), (3.5, 'c')]);
+    }
+
+    #[test]
+    fn until() {
+        let times = vec![(1., 'a'), (2., 'b'), (3., 'c'), (2., 'd')];
+        let sample: Vec<(f64, char)> = times.into_iter().until(2.).collect();
+
+        assert_eq!(sample, vec![(1., 'a'), (2., 'b')]);
+    }
+
+    #[test]
+    fn trajectory_until() {
+        let rng = crate::tests::rng(1);
+        let transition = |state: &u64| raw_dist![(1.0, (1.0, state + 1))];
+        let mc = crate::TimedMarkovChain::new(0, transition, rng);
+        let sample: Vec<(f64, u64)> = mc.trajectory_until(3.5).collect();
+
+        assert_eq!(sample, vec![(0., 0), (1., 1), (2., 2), (3., 3)]);
+    }
+}
@@ -0,0 +1,274 @@
+// Traits
+use crate::{State, StateIterator};
+use core::fmt::Debug;
+use core::ops::Sub;
+
+/// Iterator that yields each element together with the maximum of the elements so far.
+///
+/// The state of the process, before the first step, counts as an element.
+///
+/// This struct is created by the [running_max] method.
+///
+/// [running_max]: ../trait.StateIterator.html#method.running_max
+#[derive(Debug, Clone)]
+pub struct RunningMax<I>
+where
+    I: Iterator,
+{
+    iter: I,
+    current: Option<(I::Item, I::Item)>,
+}
+
+impl<I> RunningMax<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    #[inline]
+    pub(crate) fn new(iter: I, initial: Option<I::Item>) -> Self {
+        RunningMax {
+            iter,
+            current: initial.map(|item| (item.clone(), item)),
+        }
+    }
+
+    /// Returns the maximum so far.
+    #[inline]
+    pub fn max(&self) -> Option<&I::Item> {
+        self.current.as_ref().map(|(_, max)| max)
+    }
+}
+
+impl<I> Iterator for RunningMax<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd + Clone,
+{
+    type Item = (I::Item, I::Item);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let max = match self.current.take() {
+            Some((_, max)) if max >= item => max,
+            _ => item.clone(),
+        };
+        self.current = Some((item, max));
+        self.current.clone()
+    }
+}
+
+impl<I> State for RunningMax<I>
+where
+    I: Iterator,
+    I::Item: Debug,
+{
+    type Item = (I::Item, I::Item);
+
+    #[inline]
+    fn state(&self) -> Option<&<Self as State>::Item> {
+        self.current.as_ref()
+    }
+}
+
+impl<I> StateIterator for RunningMax<I>
+where
+    I: Iterator,
+    I::Item: Debug + PartialOrd + Clone,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.current.clone()
+    }
+}
+
+/// Iterator that yields each element together with the minimum of the elements so far.
+///
+/// The state of the process, before the first step, counts as an element.
+///
+/// This struct is created by the [running_min] method.
+///
+/// [running_min]: ../trait.StateIterator.html#method.running_min
+#[derive(Debug, Clone)]
+pub struct RunningMin<I>
+where
+    I: Iterator,
+{
+    iter: I,
+    current: Option<(I::Item, I::Item)>,
+}
+
+impl<I> RunningMin<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    #[inline]
+    pub(crate) fn new(iter: I, initial: Option<I::Item>) -> Self {
+        RunningMin {
+            iter,
+            current: initial.map(|item| (item.clone(), item)),
+        }
+    }
+
+    /// Returns the minimum so far.
+    #[inline]
+    pub fn min(&self) -> Option<&I::Item> {
+        self.current.as_ref().map(|(_, min)| min)
+    }
+}
+
+impl<I> Iterator for RunningMin<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd + Clone,
+{
+    type Item = (I::Item, I::Item);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let min = match self.current.take() {
+            Some((_, min)) if min <= item => min,
+            _ => item.clone(),
+        };
+        self.current = Some((item, min));
+        self.current.clone()
+    }
+}
+
+impl<I> State for RunningMin<I>
+where
+    I: Iterator,
+    I::Item: Debug,
+{
+    type Item = (I::Item, I::Item);
+
+    #[inline]
+    fn state(&self) -> Option<&<Self as State>::Item> {
+        self.current.as_ref()
+    }
+}
+
+impl<I> StateIterator for RunningMin<I>
+where
+    I: Iterator,
+    I::Item: Debug + PartialOrd + Clone,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.current.clone()
+    }
+}
+
+/// Iterator that yields each element together with its drawdown:
+/// the difference between the maximum of the elements so far and the element.
+///
+/// The state of the process, before the first step, counts as an element.
+///
+/// This struct is created by the [drawdown] method.
+///
+/// [drawdown]: ../trait.StateIterator.html#method.drawdown
+#[derive(Debug, Clone)]
+pub struct Drawdown<I>
+where
+    I: Iterator,
+{
+    running_max: RunningMax<I>,
+    current: Option<(I::Item, I::Item)>,
+}
+
+impl<I> Drawdown<I>
+where
+    I: Iterator,
+    I::Item: Clone + Sub<Output = I::Item>,
+{
+    #[inline]
+    pub(crate) fn new(iter: I, initial: Option<I::Item>) -> Self {
+        let current = initial.clone().map(|item| (item.clone(), item.clone() - item));
+        Drawdown {
+            running_max: RunningMax::new(iter, initial),
+            current,
+        }
+    }
+
+    /// Returns the maximum so far.
+    #[inline]
+    pub fn max(&self) -> Option<&I::Item> {
+        self.running_max.max()
+    }
+}
+
+impl<I> Iterator for Drawdown<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd + Clone + Sub<Output = I::Item>,
+{
+    type Item = (I::Item, I::Item);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (item, max) = self.running_max.next()?;
+        self.current = Some((item.clone(), max - item));
+        self.current.clone()
+    }
+}
+
+impl<I> State for Drawdown<I>
+where
+    I: Iterator,
+    I::Item: Debug,
+{
+    type Item = (I::Item, I::Item);
+
+    #[inline]
+    fn state(&self) -> Option<&<Self as State>::Item> {
+        self.current.as_ref()
+    }
+}
+
+impl<I> StateIterator for Drawdown<I>
+where
+    I: Iterator,
+    I::Item: Debug + PartialOrd + Clone + Sub<Output = I::Item>,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.current.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn extrema() {
+        let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+        let mc = || crate::MarkovChain::new(0, transition, crate::tests::rng(1));
+        let path: Vec<i32> = mc().take(50).collect();
+
+        let maxima: Vec<i32> = mc().running_max().take(50).map(|(_, max)| max).collect();
+        let minima: Vec<i32> = mc().running_min().take(50).map(|(_, min)| min).collect();
+        for k in 0..50 {
+            assert_eq!(maxima[k], path[..=k].iter().cloned().fold(0, i32::max));
+            assert_eq!(minima[k], path[..=k].iter().cloned().fold(0, i32::min));
+        }
+
+        let drawdowns: Vec<(i32, i32)> = mc().drawdown().take(50).collect();
+        for (k, (state, drawdown)) in drawdowns.into_iter().enumerate() {
+            assert_eq!(state, path[k]);
+            assert_eq!(drawdown, maxima[k] - path[k]);
+        }
+    }
+
+    #[test]
+    fn trajectory() {
+        let transition = |state: &i32| raw_dist![(1.0, state - 1)];
+        let mc = crate::MarkovChain::new(3, transition, crate::tests::rng(2));
+        let path: Vec<(i32, i32)> = mc.running_max().trajectory().take(3).collect();
+
+        assert_eq!(path, vec![(3, 3), (2, 3), (1, 3)]);
+    }
+}
@@ -0,0 +1,122 @@
+// Traits
+use core::ops::Add;
+
+/// Iterator that calls a function on every element before yielding it.
+///
+/// This struct is created by the [observe] method.
+///
+/// [observe]: ../trait.StateIterator.html#method.observe
+#[derive(Debug, Clone)]
+pub struct Observe<I, F> {
+    iter: I,
+    observer: F,
+    step: usize,
+}
+
+impl<I, F> Observe<I, F> {
+    #[inline]
+    pub(crate) fn new(iter: I, observer: F) -> Self {
+        Observe { iter, observer, step: 0 }
+    }
+
+    /// Returns the number of elements yielded so far.
+    #[inline]
+    pub fn step(&self) -> usize {
+        self.step
+    }
+}
+
+impl<I, F> Iterator for Observe<I, F>
+where
+    I: Iterator,
+    F: FnMut(usize, &I::Item),
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        self.step += 1;
+        (self.observer)(self.step, &item);
+        Some(item)
+    }
+}
+
+/// Iterator that calls a function on every element of a timed iterator,
+/// together with the elapsed time, before yielding it.
+///
+/// This struct is created by the [observe_with_time] method.
+///
+/// [observe_with_time]: ../trait.TimedIterator.html#method.observe_with_time
+#[derive(Debug, Clone)]
+pub struct ObserveWithTime<I, N, F> {
+    iter: I,
+    observer: F,
+    step: usize,
+    time: N,
+}
+
+impl<I, N, F> ObserveWithTime<I, N, F> {
+    #[inline]
+    pub(crate) fn new(iter: I, time: N, observer: F) -> Self {
+        ObserveWithTime { iter, observer, step: 0, time }
+    }
+
+    /// Returns the number of elements yielded so far.
+    #[inline]
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns the sum of the periods of the elements yielded so far.
+    #[inline]
+    pub fn time(&self) -> &N {
+        &self.time
+    }
+}
+
+impl<I, N, T, F> Iterator for ObserveWithTime<I, N, F>
+where
+    I: Iterator<Item = (N, T)>,
+    N: Add<Output = N> + Copy,
+    F: FnMut(usize, N, &T),
+{
+    type Item = (N, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (period, state) = self.iter.next()?;
+        self.step += 1;
+        self.time = self.time + period;
+        (self.observer)(self.step, self.time, &state);
+        Some((period, state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn observe() {
+        let transition = |state: &u64| raw_dist![(1.0, state + 1)];
+        let mc = crate::MarkovChain::new(0, transition, crate::tests::rng(1));
+        let mut observed = Vec::new();
+        let sample: Vec<u64> = mc.observe(|step, state| observed.push((step, *state))).take(3).collect();
+
+        assert_eq!(sample, vec![1, 2, 3]);
+        assert_eq!(observed, vec![(1, 1), (2, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn observe_with_time() {
+        let transition = |state: &u64| raw_dist![(1.0, (0.5, state + 1))];
+        let mc = crate::TimedMarkovChain::new(0, transition, crate::tests::rng(2));
+        let mut observed = Vec::new();
+        let sample: Vec<(f64, u64)> = mc.observe_with_time(|step, time, state| observed.push((step, time, *state))).take(2).collect();
+
+        assert_eq!(sample, vec![(0.5, 1), (0.5, 2)]);
+        assert_eq!(observed, vec![(1, 0.5, 1), (2, 1.0, 2)]);
+    }
+}
@@ -0,0 +1,104 @@
+// Traits
+use num_traits::Float;
+
+// Structs
+use core::iter::Fuse;
+
+/// Iterator that observes a timed iterator on a uniform time grid. 
+/// 
+/// This struct is created by the [sample_on_grid] method. 
+/// 
+/// [sample_on_grid]: ../trait.TimedIterator.html#method.sample_on_grid
+#[derive(Debug, Clone)]
+pub struct OnGrid<I, N, T> 
+where
+    I: Iterator<Item = (N, T)>,
+{
+    iter: Fuse<I>,
+    step: N,
+    index: usize,
+    remaining: usize,
+    current: Option<T>,
+    upcoming: Option<(N, T)>,
+}
+
+impl<I, N, T> OnGrid<I, N, T> 
+where
+    I: Iterator<Item = (N, T)>,
+{
+    /// The elements of `iter` must be `(time, state)`, with time in increasing order. 
+    #[inline]
+    pub(crate) fn new(iter: I, step: N, remaining: usize) -> Self {
+        OnGrid { 
+            iter: iter.fuse(), 
+            step, 
+            index: 0,
+            remaining, 
+            current: None, 
+            upcoming: None,
+        }
+    }
+}
+
+impl<I, N, T> Iterator for OnGrid<I, N, T>
+where
+    I: Iterator<Item = (N, T)>,
+    N: Float,
+    T: Clone,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        if self.current.is_none() {
+            self.current = Some(self.iter.next()?.1);
+        }
+        let time = N::from(self.index).unwrap() * self.step;
+        loop {
+            if self.upcoming.is_none() {
+                self.upcoming = self.iter.next();
+            }
+            match &self.upcoming {
+                Some((jump_time, _)) if *jump_time <= time => {
+                    self.current = self.upcoming.take().map(|(_, state)| state);
+                },
+                _ => break,
+            }
+        }
+        self.index += 1;
+        self.remaining -= 1;
+        self.current.clone()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn boundaries() {
+        let rng = crate::tests::rng(1);
+        let transition = |state: &u64| raw_dist![(1.0, (1.0, state + 1))];
+        let mc = crate::TimedMarkovChain::new(0, transition, rng);
+        let sample: Vec<u64> = mc.sample_on_grid(0.5, 5).collect();
+
+        assert_eq!(sample, vec![0, 0, 1, 1, 2]);
+    }
+
+    #[test]
+    fn finite_iterator() {
+        let times = vec![(0., 'a'), (1., 'b')];
+        let sample: Vec<char> = super::OnGrid::new(times.into_iter(), 0.75, 4).collect();
+
+        assert_eq!(sample, vec!['a', 'a', 'b', 'b']);
+    }
+}
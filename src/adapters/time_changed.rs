@@ -0,0 +1,122 @@
+// Traits
+use crate::{Reset, State, StateIterator};
+use num_traits::Zero;
+
+/// Process that makes one step of a base process at each arrival of a clock process.
+///
+/// The clock is a timed iterator, whose periods are the times between arrivals, 
+/// e.g. a Poisson or renewal process. The elements of the clock are otherwise ignored.
+/// The time-changed process yields pairs `(period, state)`, so it is a timed iterator itself.
+///
+/// This struct is created by the [time_changed] method.
+///
+/// [time_changed]: ../trait.StateIterator.html#method.time_changed
+#[derive(Debug, Clone)]
+pub struct TimeChanged<P, C> {
+    process: P,
+    clock: C,
+}
+
+impl<P, C> TimeChanged<P, C> {
+    #[inline]
+    pub(crate) fn new(process: P, clock: C) -> Self {
+        TimeChanged { process, clock }
+    }
+
+    /// Returns the base process.
+    #[inline]
+    pub fn process(&self) -> &P {
+        &self.process
+    }
+
+    /// Returns the clock process.
+    #[inline]
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns the base and clock processes.
+    #[inline]
+    pub fn into_inner(self) -> (P, C) {
+        (self.process, self.clock)
+    }
+}
+
+impl<P, C, N, K> Iterator for TimeChanged<P, C>
+where
+    P: Iterator,
+    C: Iterator<Item = (N, K)>,
+{
+    type Item = (N, P::Item);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (period, _) = self.clock.next()?;
+        let state = self.process.next()?;
+        Some((period, state))
+    }
+}
+
+impl<P, C> State for TimeChanged<P, C>
+where
+    P: State,
+{
+    type Item = P::Item;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        self.process.state()
+    }
+
+    #[inline]
+    fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        self.process.state_mut()
+    }
+
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, crate::errors::InvalidState<Self::Item>> {
+        self.process.set_state(new_state)
+    }
+}
+
+impl<P, C> Reset for TimeChanged<P, C>
+where
+    P: Reset,
+    C: Reset,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.process.reset();
+        self.clock.reset();
+    }
+}
+
+impl<P, C, N, K> StateIterator for TimeChanged<P, C>
+where
+    P: StateIterator,
+    C: Iterator<Item = (N, K)>,
+    N: Zero,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.process.state_as_item().map(|state| (N::zero(), state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn time_changed() {
+        let transition = |state: &u32| raw_dist![(1.0, state + 1)];
+        let walk = crate::MarkovChain::new(0, transition, crate::tests::rng(1));
+        let clock = vec![(0.5, ()), (1.5, ()), (0.25, ())].into_iter();
+        let path: Vec<(f64, u32)> = walk.time_changed(clock).trajectory().with_absolute_time().collect();
+        assert_eq!(path, vec![(0., 0), (0.5, 1), (2., 2), (2.25, 3)]);
+    }
+}
@@ -0,0 +1,178 @@
+// Traits
+use crate::traits::Period;
+use crate::{Checkpoint, RandomSource, Reset, State, StochasticProcess};
+
+// Structs
+use crate::errors::InvalidState;
+use crate::Snapshot;
+
+/// Process that counts the steps it takes and the time they take, so that its snapshots record them.
+///
+/// Each element is the time of the step and the new state, as in [StochasticProcess].
+///
+/// This struct is created by the [tracked] method.
+///
+/// [StochasticProcess]: ../trait.StochasticProcess.html
+/// [tracked]: ../trait.Checkpoint.html#method.tracked
+#[derive(Debug, Clone)]
+pub struct Tracked<P> {
+    process: P,
+    step: usize,
+    elapsed: f64,
+}
+
+impl<P> Tracked<P> {
+    #[inline]
+    pub(crate) fn new(process: P) -> Self {
+        Tracked {
+            process,
+            step: 0,
+            elapsed: 0.,
+        }
+    }
+
+    /// Returns the number of steps taken so far.
+    #[inline]
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns the time elapsed so far.
+    #[inline]
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Returns the underlying process.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.process
+    }
+}
+
+impl<P> Iterator for Tracked<P>
+where
+    P: StochasticProcess,
+    P::Time: Period,
+{
+    type Item = (P::Time, P::State);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (period, state) = self.process.step()?;
+        self.step += 1;
+        self.elapsed += period.length();
+        Some((period, state))
+    }
+}
+
+impl<P> StochasticProcess for Tracked<P>
+where
+    P: StochasticProcess,
+    P::Time: Period,
+{
+    type Time = P::Time;
+    type State = P::State;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next()
+    }
+}
+
+impl<P> State for Tracked<P>
+where
+    P: State,
+{
+    type Item = P::Item;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        self.process.state()
+    }
+
+    #[inline]
+    fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        self.process.state_mut()
+    }
+
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        self.process.set_state(new_state)
+    }
+}
+
+impl<P> RandomSource for Tracked<P>
+where
+    P: RandomSource,
+{
+    type Rng = P::Rng;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        self.process.rng()
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        self.process.rng_mut()
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        self.process.set_rng(rng)
+    }
+}
+
+impl<P> Reset for Tracked<P>
+where
+    P: Reset,
+{
+    /// Restarts the process, with no steps taken nor time elapsed.
+    #[inline]
+    fn reset(&mut self) {
+        self.process.reset();
+        self.step = 0;
+        self.elapsed = 0.;
+    }
+}
+
+impl<P> Checkpoint for Tracked<P>
+where
+    P: Checkpoint,
+{
+    type Hidden = P::Hidden;
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {
+        self.process.hidden_state()
+    }
+
+    #[inline]
+    fn set_hidden_state(&mut self, hidden: Self::Hidden) {
+        self.process.set_hidden_state(hidden)
+    }
+
+    #[inline]
+    fn progress(&self) -> (usize, f64) {
+        (self.step, self.elapsed)
+    }
+
+    /// Restores the state, hidden progress and random number generator saved in `snapshot`,
+    /// together with the number of steps and the elapsed time.
+    #[inline]
+    fn resume(&mut self, snapshot: &Snapshot<Self::Item, Self::Hidden, Self::Rng>) -> Result<(), InvalidState<Self::Item>>
+    where
+        Self::Item: Clone,
+        Self::Hidden: Clone,
+        Self::Rng: Clone,
+    {
+        self.process.resume(snapshot)?;
+        self.step = snapshot.step;
+        self.elapsed = snapshot.elapsed;
+        Ok(())
+    }
+}
@@ -0,0 +1,402 @@
+//! Cellular automata with stochastic rules.
+//!
+//! A [StochasticCellularAutomaton] is a grid of cells, where each cell updates its value
+//! by sampling from a distribution that depends on the values of its [Neighborhood].
+//! Updates are either synchronous, all cells at once from the previous grid,
+//! or asynchronous, one cell chosen at random at a time, see [Update].
+//!
+//! Grids are `Array2<T>`; one-dimensional automata are grids with a single row.
+//! Grids wrap around, unless cells outside of the grid are given a fixed value.
+//!
+//! # Examples
+//!
+//! The rule 90 of Wolfram, where each cell becomes the exclusive or of its two neighbours,
+//! and fails to do so with probability 0.01.
+//! ```
+//! # use markovian::{automata::{StochasticCellularAutomaton, Update}, prelude::*};
+//! let mut cells = vec![false; 41];
+//! cells[20] = true;
+//! let rule = |neighborhood: &Vec<bool>| {
+//!     let value = neighborhood[1] ^ neighborhood[2];
+//!     raw_dist![(0.99, value), (0.01, !value)]
+//! };
+//! let automaton = StochasticCellularAutomaton::line(cells, 1, rule, Update::Synchronous, rand::thread_rng());
+//! for grid in automaton.take(20) {
+//!     let line: String = grid.iter().map(|&cell| if cell { '#' } else { ' ' }).collect();
+//!     println!("{}", line);
+//! }
+//! ```
+//!
+//! [StochasticCellularAutomaton]: struct.StochasticCellularAutomaton.html
+//! [Neighborhood]: enum.Neighborhood.html
+//! [Update]: enum.Update.html
+
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess, Transition};
+use core::fmt::Debug;
+use rand::{Rng, SeedableRng};
+
+// Structs
+use crate::errors::InvalidState;
+use ndarray::Array2;
+
+// Functions
+use core::mem;
+
+/// Cells whose values determine the update of a cell, given by offsets `(row, column)` from it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Neighborhood {
+    /// Cells in the same row at distance at most `radius`.
+    Line(usize),
+    /// Cells at Manhattan distance at most `radius`.
+    VonNeumann(usize),
+    /// Cells at distance at most `radius` in each coordinate.
+    Moore(usize),
+    /// Cells at the given offsets.
+    Custom(Vec<(isize, isize)>),
+}
+
+impl Neighborhood {
+    /// Returns the offsets of the neighbourhood, starting with the cell itself, `(0, 0)`,
+    /// and then row by row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use markovian::automata::Neighborhood;
+    /// assert_eq!(Neighborhood::Line(1).offsets(), vec![(0, 0), (0, -1), (0, 1)]);
+    /// assert_eq!(Neighborhood::VonNeumann(1).offsets().len(), 5);
+    /// assert_eq!(Neighborhood::Moore(1).offsets().len(), 9);
+    /// ```
+    #[inline]
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        let square = |radius: usize| {
+            let radius = radius as isize;
+            (-radius..=radius).flat_map(move |i| (-radius..=radius).map(move |j| (i, j)))
+        };
+        let others: Vec<(isize, isize)> = match self {
+            Neighborhood::Line(radius) => {
+                let radius = *radius as isize;
+                (-radius..=radius).map(|j| (0, j)).collect()
+            }
+            Neighborhood::VonNeumann(radius) => square(*radius).filter(|(i, j)| (i.abs() + j.abs()) as usize <= *radius).collect(),
+            Neighborhood::Moore(radius) => square(*radius).collect(),
+            Neighborhood::Custom(offsets) => offsets.clone(),
+        };
+        let mut offsets = vec![(0, 0)];
+        offsets.extend(others.into_iter().filter(|&offset| offset != (0, 0)));
+        offsets
+    }
+}
+
+/// Order in which cells are updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Update {
+    /// All cells are updated at once, from the values of the previous grid.
+    Synchronous,
+    /// Cells chosen uniformly at random are updated one at a time, as many times as cells in the grid.
+    Asynchronous,
+}
+
+/// Cellular automaton whose cells update by sampling from a probability kernel of their neighbourhood.
+///
+/// The rule receives the values of the neighbourhood, in the order of [offsets],
+/// so the first value is the one of the cell itself.
+///
+/// # Examples
+///
+/// A voter model: each cell copies a random neighbour, until there is consensus.
+/// ```
+/// # use ndarray::Array2;
+/// # use markovian::{automata::{Neighborhood, StochasticCellularAutomaton, Update}, prelude::*};
+/// # use rand::seq::SliceRandom;
+/// struct Voter;
+/// impl markovian::Transition<Vec<u8>, u8> for Voter {
+///     fn sample_from<R: rand::Rng + ?Sized>(&self, neighborhood: &Vec<u8>, rng: &mut R) -> u8 {
+///         *neighborhood[1..].choose(rng).unwrap()
+///     }
+/// }
+/// let grid = Array2::from_shape_fn((4, 4), |(i, j)| ((i + j) % 2) as u8);
+/// let automaton = StochasticCellularAutomaton::new(grid, Neighborhood::VonNeumann(1), Voter, Update::Asynchronous, rand::thread_rng());
+/// let consensus = automaton.skip_while(|grid| grid.iter().any(|&cell| cell != grid[[0, 0]])).next();
+/// assert!(consensus.is_some());
+/// ```
+///
+/// [offsets]: enum.Neighborhood.html#method.offsets
+#[derive(Debug, Clone)]
+pub struct StochasticCellularAutomaton<T, F, R> {
+    state: Array2<T>,
+    initial_state: Array2<T>,
+    offsets: Vec<(isize, isize)>,
+    rule: F,
+    update: Update,
+    outside: Option<T>,
+    rng: R,
+}
+
+impl<T, F, R> StochasticCellularAutomaton<T, F, R>
+where
+    T: Clone,
+    F: Transition<Vec<T>, T>,
+    R: Rng,
+{
+    /// Constructs a new `StochasticCellularAutomaton<T, F, R>` from an initial `grid`.
+    ///
+    /// # Panics
+    ///
+    /// If `grid` is empty.
+    #[inline]
+    pub fn new(grid: Array2<T>, neighborhood: Neighborhood, rule: F, update: Update, rng: R) -> Self {
+        assert!(!grid.is_empty(), "The grid must have some cell.");
+        StochasticCellularAutomaton {
+            initial_state: grid.clone(),
+            state: grid,
+            offsets: neighborhood.offsets(),
+            rule,
+            update,
+            outside: None,
+            rng,
+        }
+    }
+
+    /// Constructs a new `StochasticCellularAutomaton<T, F, R>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [new].
+    ///
+    /// [new]: struct.StochasticCellularAutomaton.html#method.new
+    #[inline]
+    pub fn with_seed(grid: Array2<T>, neighborhood: Neighborhood, rule: F, update: Update, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        StochasticCellularAutomaton::new(grid, neighborhood, rule, update, R::seed_from_u64(seed))
+    }
+
+    /// Constructs a new one-dimensional `StochasticCellularAutomaton<T, F, R>`, where the neighbourhood
+    /// of a cell is formed by the `radius` cells on each side, from left to right.
+    ///
+    /// # Panics
+    ///
+    /// If `cells` is empty.
+    #[inline]
+    pub fn line(cells: Vec<T>, radius: usize, rule: F, update: Update, rng: R) -> Self {
+        let n = cells.len();
+        let grid = Array2::from_shape_vec((1, n), cells).expect("One row has as many cells as cells.");
+        StochasticCellularAutomaton::new(grid, Neighborhood::Line(radius), rule, update, rng)
+    }
+
+    /// Cells outside of the grid have the fixed value `outside`, instead of wrapping around.
+    #[inline]
+    pub fn with_fixed_boundary(mut self, outside: T) -> Self {
+        self.outside = Some(outside);
+        self
+    }
+
+    /// Returns the order of updates.
+    #[inline]
+    pub fn update(&self) -> Update {
+        self.update
+    }
+
+    /// Updates the grid, either synchronously or as many random cells as cells in the grid.
+    #[inline]
+    pub fn sweep(&mut self) {
+        let mut neighborhood = Vec::with_capacity(self.offsets.len());
+        match self.update {
+            Update::Synchronous => {
+                let mut next = self.state.clone();
+                for ((i, j), cell) in next.indexed_iter_mut() {
+                    self.fill_neighborhood(i, j, &mut neighborhood);
+                    *cell = self.rule.sample_from(&neighborhood, &mut self.rng);
+                }
+                self.state = next;
+            }
+            Update::Asynchronous => {
+                let (nrows, ncols) = self.state.dim();
+                for _ in 0..nrows * ncols {
+                    let (i, j) = (self.rng.gen_range(0..nrows), self.rng.gen_range(0..ncols));
+                    self.fill_neighborhood(i, j, &mut neighborhood);
+                    self.state[[i, j]] = self.rule.sample_from(&neighborhood, &mut self.rng);
+                }
+            }
+        }
+    }
+
+    /// Replaces the content of `neighborhood` by the values of the neighbourhood of cell `(i, j)`.
+    #[inline]
+    fn fill_neighborhood(&self, i: usize, j: usize, neighborhood: &mut Vec<T>) {
+        let (nrows, ncols) = self.state.dim();
+        neighborhood.clear();
+        for &(di, dj) in &self.offsets {
+            let (row, col) = (i as isize + di, j as isize + dj);
+            let inside = 0 <= row && row < nrows as isize && 0 <= col && col < ncols as isize;
+            let value = match &self.outside {
+                Some(outside) if !inside => outside.clone(),
+                _ => {
+                    let row = row.rem_euclid(nrows as isize) as usize;
+                    let col = col.rem_euclid(ncols as isize) as usize;
+                    self.state[[row, col]].clone()
+                }
+            };
+            neighborhood.push(value);
+        }
+    }
+}
+
+impl<T, F, R> Reset for StochasticCellularAutomaton<T, F, R>
+where
+    T: Clone,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+    }
+}
+
+impl<T, F, R> RandomSource for StochasticCellularAutomaton<T, F, R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<T, F, R> State for StochasticCellularAutomaton<T, F, R>
+where
+    T: Debug + Clone,
+{
+    type Item = Array2<T>;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    /// Changes the grid, as long as its dimensions do not change.
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        if new_state.dim() == self.state.dim() {
+            Ok(Some(mem::replace(&mut self.state, new_state)))
+        } else {
+            Err(InvalidState::new(new_state))
+        }
+    }
+}
+
+impl<T, F, R> Checkpoint for StochasticCellularAutomaton<T, F, R>
+where
+    T: Debug + Clone,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<T, F, R> Iterator for StochasticCellularAutomaton<T, F, R>
+where
+    T: Debug + Clone,
+    F: Transition<Vec<T>, T>,
+    R: Rng,
+{
+    type Item = Array2<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sweep();
+        Some(self.state.clone())
+    }
+}
+
+impl<T, F, R> StochasticProcess for StochasticCellularAutomaton<T, F, R>
+where
+    T: Debug + Clone,
+    F: Transition<Vec<T>, T>,
+    R: Rng,
+{
+    type Time = ();
+    type State = Array2<T>;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<T, F, R> StateIterator for StochasticCellularAutomaton<T, F, R>
+where
+    T: Debug + Clone,
+    F: Transition<Vec<T>, T>,
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        Some(self.state.clone())
+    }
+
+    #[inline]
+    fn advance(&mut self) -> bool {
+        self.sweep();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::Raw;
+    use ndarray::array;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn rule_90() {
+        let rule = |neighborhood: &Vec<u8>| Raw::new(vec![(1., neighborhood[1] ^ neighborhood[2])]);
+        let mut automaton = StochasticCellularAutomaton::line(vec![0, 0, 1, 0, 0], 1, rule, Update::Synchronous, crate::tests::rng(1));
+        assert_eq!(automaton.next(), Some(array![[0, 1, 0, 1, 0]]));
+        assert_eq!(automaton.next(), Some(array![[1, 0, 0, 0, 1]]));
+        // Wraps around
+        assert_eq!(automaton.next(), Some(array![[1, 1, 0, 1, 1]]));
+
+        let mut fixed = StochasticCellularAutomaton::line(vec![0, 0, 1, 0, 0], 1, rule, Update::Synchronous, crate::tests::rng(1))
+            .with_fixed_boundary(0);
+        fixed.nth(1);
+        assert_eq!(fixed.next(), Some(array![[0, 1, 0, 1, 0]]));
+    }
+
+    #[test]
+    fn noise() {
+        let rule = |_: &Vec<bool>| Raw::new(vec![(0.3, true), (0.7, false)]);
+        for &update in &[Update::Synchronous, Update::Asynchronous] {
+            let grid = Array2::from_elem((20, 20), false);
+            let mut automaton = StochasticCellularAutomaton::new(grid, Neighborhood::Moore(1), rule, update, crate::tests::rng(2));
+            let grid = automaton.nth(10).unwrap();
+            let density = grid.iter().filter(|&&cell| cell).count() as f64 / 400.;
+            assert!((density - 0.3).abs() < 0.07);
+        }
+        let mut automaton = StochasticCellularAutomaton::new(Array2::from_elem((2, 2), false), Neighborhood::Moore(1), rule, Update::Synchronous, crate::tests::rng(3));
+        assert!(automaton.set_state(Array2::from_elem((1, 4), true)).is_err());
+    }
+}
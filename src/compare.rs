@@ -0,0 +1,279 @@
+//! Comparing processes with common random numbers.
+//!
+//! The difference between the expectations of two similar processes, for example two
+//! parameterizations of a queue, is estimated with much lower variance if both processes
+//! are driven by the same randomness, since the noise of each pair of samples mostly cancels.
+//!
+//! # Examples
+//!
+//! Effect of an upward drift on the position of a random walk after 100 steps.
+//! ```
+//! # use rand::SeedableRng;
+//! # use markovian::{MarkovChain, prelude::*, compare::with_common_randomness};
+//! let symmetric = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+//! let drifted = |state: &i32| raw_dist![(0.55, state + 1), (0.45, state - 1)];
+//! let a = MarkovChain::new(0, symmetric, rand_pcg::Pcg64::seed_from_u64(1));
+//! let b = MarkovChain::new(0, drifted, rand_pcg::Pcg64::seed_from_u64(1));
+//! let samples = with_common_randomness(a, b, 1_000, |path| path.nth(99).unwrap() as f64);
+//! // Exact value: 100 * 0.1 = 10
+//! let difference = samples.mean_difference();
+//! assert!((difference - 10.).abs() < 4. * samples.standard_error());
+//! ```
+//!
+//! Finite chains, for example an estimated chain and the ground truth, are also compared
+//! directly through distances between their transition matrices or stationary distributions.
+//! ```
+//! # use ndarray::array;
+//! # use markovian::{FiniteMarkovChain, compare::frobenius_distance};
+//! let truth = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.2, 0.8]], rand::thread_rng()));
+//! let estimate = FiniteMarkovChain::from((0, array![[0.45, 0.55], [0.2, 0.8]], rand::thread_rng()));
+//! assert!((frobenius_distance(&truth, &estimate) - 0.05 * 2f64.sqrt()).abs() < 1e-12);
+//! ```
+
+// Traits
+use crate::{stats::Welford, RandomSource};
+use core::fmt::Debug;
+use num_traits::ToPrimitive;
+use rand::{Rng, SeedableRng};
+use rand_distr::{weighted_alias::AliasableWeight, Uniform};
+
+// Structs
+use crate::FiniteMarkovChain;
+
+/// Paired samples of a functional of two processes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairedSamples {
+    /// Samples from the first process.
+    pub first: Vec<f64>,
+    /// Samples from the second process.
+    pub second: Vec<f64>,
+}
+
+impl PairedSamples {
+    /// Returns the number of pairs.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.first.len()
+    }
+
+    /// Returns `true` if there are no pairs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.first.is_empty()
+    }
+
+    /// Returns the differences of each pair, second minus first.
+    #[inline]
+    pub fn differences(&self) -> Vec<f64> {
+        self.first.iter().zip(&self.second).map(|(a, b)| b - a).collect()
+    }
+
+    /// Returns the sample mean of the differences.
+    #[inline]
+    pub fn mean_difference(&self) -> f64 {
+        self.statistics().mean()
+    }
+
+    /// Returns the standard error of the mean of the differences.
+    #[inline]
+    pub fn standard_error(&self) -> f64 {
+        self.statistics().standard_error()
+    }
+
+    #[inline]
+    fn statistics(&self) -> Welford {
+        self.differences().into_iter().collect()
+    }
+}
+
+/// Returns `n` paired samples of `f` evaluated on `process_a` and `process_b`,
+/// driven by synchronized random number generators.
+///
+/// For each pair, both processes are cloned and their random number generators are
+/// seeded with the same seed, taken from the random number generator of `process_a`.
+/// Then, `f` receives each clone as an iterator.
+///
+/// # Remarks
+///
+/// Synchronization is most effective when both processes consume randomness in the
+/// same way, for example if they only differ in their parameters.
+#[inline]
+pub fn with_common_randomness<P, Q, T, F>(mut process_a: P, process_b: Q, n: usize, mut f: F) -> PairedSamples
+where
+    P: Iterator<Item = T> + RandomSource + Clone,
+    P::Rng: SeedableRng,
+    Q: Iterator<Item = T> + RandomSource + Clone,
+    Q::Rng: SeedableRng,
+    F: FnMut(&mut dyn Iterator<Item = T>) -> f64,
+{
+    let mut first = Vec::with_capacity(n);
+    let mut second = Vec::with_capacity(n);
+    for _ in 0..n {
+        let seed: u64 = process_a.rng_mut().gen();
+        let mut a = process_a.clone();
+        a.set_rng(P::Rng::seed_from_u64(seed));
+        let mut b = process_b.clone();
+        b.set_rng(Q::Rng::seed_from_u64(seed));
+        first.push(f(&mut a));
+        second.push(f(&mut b));
+    }
+    PairedSamples { first, second }
+}
+
+/// Returns, for each state, the total variation distance between the transition
+/// probabilities of `a` and `b` from that state.
+///
+/// # Panics
+///
+/// If the chains do not have the same state space.
+#[inline]
+pub fn total_variation_rows<T, W1, R1, W2, R2>(a: &FiniteMarkovChain<T, W1, R1>, b: &FiniteMarkovChain<T, W2, R2>) -> Vec<f64>
+where
+    T: Debug + PartialEq + Clone,
+    W1: AliasableWeight + Debug + Clone + ToPrimitive,
+    Uniform<W1>: Debug + Clone,
+    R1: Rng,
+    W2: AliasableWeight + Debug + Clone + ToPrimitive,
+    Uniform<W2>: Debug + Clone,
+    R2: Rng,
+{
+    check_state_spaces(a, b);
+    let difference = a.transition_probabilities() - b.transition_probabilities();
+    difference
+        .genrows()
+        .into_iter()
+        .map(|row| row.iter().map(|x| x.abs()).sum::<f64>() / 2.)
+        .collect()
+}
+
+/// Returns the Frobenius distance between the transition matrices of `a` and `b`.
+///
+/// # Panics
+///
+/// If the chains do not have the same state space.
+#[inline]
+pub fn frobenius_distance<T, W1, R1, W2, R2>(a: &FiniteMarkovChain<T, W1, R1>, b: &FiniteMarkovChain<T, W2, R2>) -> f64
+where
+    T: Debug + PartialEq + Clone,
+    W1: AliasableWeight + Debug + Clone + ToPrimitive,
+    Uniform<W1>: Debug + Clone,
+    R1: Rng,
+    W2: AliasableWeight + Debug + Clone + ToPrimitive,
+    Uniform<W2>: Debug + Clone,
+    R2: Rng,
+{
+    check_state_spaces(a, b);
+    let difference = a.transition_probabilities() - b.transition_probabilities();
+    difference.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+/// Returns the Wasserstein distance, of order one, between the stationary distributions
+/// of `a` and `b`, seen as distributions on the real line,
+/// or `None` if any of the stationary distributions is not unique.
+///
+/// # Panics
+///
+/// If the chains do not have the same state space, or a state can not be converted to `f64`.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use markovian::{FiniteMarkovChain, compare::stationary_wasserstein};
+/// let a = FiniteMarkovChain::new(0, vec![vec![1., 0.], vec![1., 0.]], vec![0, 10], rand::thread_rng());
+/// let b = FiniteMarkovChain::new(0, vec![vec![0., 1.], vec![0., 1.]], vec![0, 10], rand::thread_rng());
+/// assert_eq!(stationary_wasserstein(&a, &b), Some(10.));
+/// ```
+#[inline]
+pub fn stationary_wasserstein<T, W1, R1, W2, R2>(a: &FiniteMarkovChain<T, W1, R1>, b: &FiniteMarkovChain<T, W2, R2>) -> Option<f64>
+where
+    T: Debug + PartialEq + Clone + ToPrimitive,
+    W1: AliasableWeight + Debug + Clone + ToPrimitive,
+    Uniform<W1>: Debug + Clone,
+    R1: Rng,
+    W2: AliasableWeight + Debug + Clone + ToPrimitive,
+    Uniform<W2>: Debug + Clone,
+    R2: Rng,
+{
+    check_state_spaces(a, b);
+    let pi_a = a.stationary_distribution()?;
+    let pi_b = b.stationary_distribution()?;
+    let difference = pi_a - pi_b;
+    let mut points: Vec<(f64, f64)> = a
+        .state_space()
+        .iter()
+        .map(|state| state.to_f64().expect("States must be convertible to f64."))
+        .zip(difference.iter().copied())
+        .collect();
+    points.sort_by(|x, y| x.0.partial_cmp(&y.0).expect("States must be comparable."));
+    // The distance is the integral of the absolute difference of the cumulative distribution functions
+    let mut cumulative = 0f64;
+    let mut distance = 0.;
+    for window in points.windows(2) {
+        cumulative += window[0].1;
+        distance += cumulative.abs() * (window[1].0 - window[0].0);
+    }
+    Some(distance)
+}
+
+#[inline]
+fn check_state_spaces<T, W1, R1, W2, R2>(a: &FiniteMarkovChain<T, W1, R1>, b: &FiniteMarkovChain<T, W2, R2>)
+where
+    T: Debug + PartialEq + Clone,
+    W1: AliasableWeight + Debug + Clone,
+    Uniform<W1>: Debug + Clone,
+    R1: Rng,
+    W2: AliasableWeight + Debug + Clone,
+    Uniform<W2>: Debug + Clone,
+    R2: Rng,
+{
+    assert_eq!(a.state_space(), b.state_space(), "Chains must have the same state space.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn identical() {
+        let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+        let a = crate::MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 1);
+        let b = crate::MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 2);
+        let samples = with_common_randomness(a, b, 100, |path| path.nth(9).unwrap() as f64);
+
+        assert_eq!(samples.len(), 100);
+        assert_eq!(samples.first, samples.second);
+        assert_eq!(samples.standard_error(), 0.);
+    }
+
+    #[test]
+    fn variance_reduction() {
+        let slow = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+        let fast = |state: &i32| raw_dist![(0.6, state + 1), (0.4, state - 1)];
+        let a = crate::MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, slow, 3);
+        let b = crate::MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, fast, 3);
+        let samples = with_common_randomness(a, b, 1_000, |path| path.nth(49).unwrap() as f64);
+
+        // Monotone coupling: the drifted walk is always above
+        assert!(samples.differences().iter().all(|&d| d >= 0.));
+        // Exact value: 50 * 0.2 = 10
+        assert!((samples.mean_difference() - 10.).abs() < 4. * samples.standard_error());
+    }
+
+    #[test]
+    fn distances() {
+        use ndarray::array;
+        let a = crate::FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.2, 0.8]], crate::tests::rng(1)));
+        let b = crate::FiniteMarkovChain::from((0, array![[0.9, 0.1], [0.2, 0.8]], crate::tests::rng(2)));
+
+        let tv = total_variation_rows(&a, &b);
+        assert!((tv[0] - 0.4).abs() < 1e-12);
+        assert_eq!(tv[1], 0.);
+        assert_eq!(frobenius_distance(&a, &a), 0.);
+        // Stationary distributions: (2/7, 5/7) and (2/3, 1/3)
+        let w = stationary_wasserstein(&a, &b).unwrap();
+        assert!((w - (2. / 3. - 2. / 7.)).abs() < 1e-12);
+    }
+}
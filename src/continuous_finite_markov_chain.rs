@@ -1,19 +1,24 @@
 // Traits
-use crate::{State, StateIterator};
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
 use core::fmt::Debug;
-use rand::Rng;
-use rand_distr::{weighted_alias::{WeightedAliasIndex, AliasableWeight}, Distribution};
+use rand::{Rng, SeedableRng};
+use rand_distr::{weighted_alias::{WeightedAliasIndex, AliasableWeight}, Uniform, Distribution};
 use num_traits::float::Float;
 
 // Structs
 use crate::errors::InvalidState;
+use crate::finite_markov_chain::Initial;
 use rand_distr::{Exp1, Exp};
 
 // Functions
+use crate::finite_markov_chain::sample_proportional;
 use core::mem;
 
 /// Finite state Markov Chain in continuous time. 
 /// 
+/// The iterator yields pairs `(period, state)`, where `period` is the time spent
+/// in the previous state before jumping to `state`.
+/// 
 /// # Costs
 /// 
 /// Construction cost: O(n), n: size of the state space.
@@ -26,6 +31,8 @@ where
     R: Rng,
 {
     state_index: usize,
+    initial: Initial<W>,
+    transition_weights: Vec<Vec<W>>,
     transition_matrix: Vec<WeightedAliasIndex<W>>,
     transiton_clock: Vec<W>,
     state_space: Vec<T>,
@@ -49,11 +56,13 @@ where
             .into_iter()
             .map(|weights| WeightedAliasIndex::new(weights).unwrap())
             .collect();
-        let transiton_clock: Vec<W> = transition_weights.into_iter()
-            .map(|weights| weights.into_iter().sum::<W>())
+        let transiton_clock: Vec<W> = transition_weights.iter()
+            .map(|weights| weights.iter().copied().sum::<W>())
             .collect();
         ContFiniteMarkovChain {
             state_index,
+            initial: Initial::Index(state_index),
+            transition_weights,
             transition_matrix,
             transiton_clock,
             state_space,
@@ -61,6 +70,286 @@ where
         }
     }
 
+    /// Constructs a new `ContFiniteMarkovChain<T, W, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(
+        state_index: usize,
+        transition_weights: Vec<Vec<W>>,
+        state_space: Vec<T>,
+        seed: u64,
+    ) -> Self
+    where
+        R: SeedableRng,
+    {
+        ContFiniteMarkovChain::new(state_index, transition_weights, state_space, R::seed_from_u64(seed))
+    }
+
+    /// Constructs a new `ContFiniteMarkovChain<T, W, R>` whose initial state is sampled
+    /// according to `initial_weights`. 
+    ///
+    /// Every time the chain is [reset], a new initial state is sampled.
+    /// 
+    /// # Panics
+    ///
+    /// If `initial_weights` or any vector of `transition_weights` is not a valid vector of weights,
+    /// or the length of `initial_weights` is not the number of states.
+    ///
+    /// [reset]: trait.Reset.html#tymethod.reset
+    #[inline]
+    pub fn from_initial_distribution(
+        initial_weights: Vec<W>,
+        transition_weights: Vec<Vec<W>>,
+        state_space: Vec<T>,
+        rng: R,
+    ) -> Self {
+        assert_eq!(initial_weights.len(), state_space.len(), "There must be one initial weight for each state.");
+        let mut mc = ContFiniteMarkovChain::new(0, transition_weights, state_space, rng);
+        mc.initial = Initial::Distribution(WeightedAliasIndex::new(initial_weights).unwrap());
+        mc.state_index = mc.sample_initial_index();
+        mc
+    }
+
+    /// Samples a possible index for the initial state.
+    ///
+    /// If the chain was constructed with an initial state, its index is returned.
+    /// Otherwise, an index is sampled from the initial distribution.
+    #[inline]
+    pub fn sample_initial_index(&mut self) -> usize {
+        self.initial.sample(&mut self.rng)
+    }
+
+    /// Returns the state space of the Markov Chain.
+    #[inline]
+    pub fn state_space(&self) -> &Vec<T> {
+        &self.state_space
+    }
+
+    /// Returns the size of the state space.
+    #[inline]
+    pub fn nstates(&self) -> usize {
+        self.state_space.len()
+    }
+
+    /// Returns the generator matrix Q of the chain.
+    ///
+    /// Off-diagonal entries are the rates of each transition and the diagonal makes 
+    /// every row sum up to zero. Weights of transitions from a state to itself
+    /// do not change the law of the process, so they are ignored.
+    ///
+    /// # Examples
+    ///
+    /// A chain with two states.
+    /// ```
+    /// # use markovian::ContFiniteMarkovChain;
+    /// # use ndarray::array;
+    /// let mc = ContFiniteMarkovChain::new(0, vec![vec![0., 2.], vec![3., 1.]], vec!['a', 'b'], rand::thread_rng());
+    /// assert_eq!(mc.generator(), array![[-2., 2.], [3., -3.]]);
+    /// ```
+    #[inline]
+    pub fn generator(&self) -> ndarray::Array2<W> {
+        let n = self.transition_weights.len();
+        ndarray::Array2::from_shape_fn((n, n), |(i, j)| {
+            if i == j {
+                -self.transition_weights[i].iter()
+                    .enumerate()
+                    .filter(|&(k, _)| k != i)
+                    .map(|(_, &w)| w)
+                    .sum::<W>()
+            } else {
+                self.transition_weights[i].get(j).copied().unwrap_or_else(W::zero)
+            }
+        })
+    }
+
+    /// Returns the matrix of transition probabilities P(t) = exp(Q t), 
+    /// whose entry (i, j) is the probability of being in state j at time `time`, starting from state i.
+    ///
+    /// # Remarks
+    ///
+    /// The exponential is computed by uniformization in `f64` precision.
+    ///
+    /// # Examples
+    ///
+    /// At time zero, the chain has not moved.
+    /// ```
+    /// # use markovian::ContFiniteMarkovChain;
+    /// # use ndarray::array;
+    /// let mc = ContFiniteMarkovChain::new(0, vec![vec![0., 2.], vec![3., 0.]], vec!['a', 'b'], rand::thread_rng());
+    /// assert_eq!(mc.transition_probabilities(0.), array![[1., 0.], [0., 1.]]);
+    /// ```
+    #[inline]
+    pub fn transition_probabilities(&self, time: W) -> ndarray::Array2<W> {
+        let generator = self.generator().mapv(|w| w.to_f64().unwrap());
+        crate::linalg::expm_generator(&generator, time.to_f64().unwrap())
+            .mapv(|p| W::from(p).unwrap())
+    }
+
+    /// Returns the log-likelihood of a panel of observations `(time, state)` of the chain,
+    /// observed only at those times, conditional on the first observation.
+    ///
+    /// Each pair of consecutive observations contributes the transition probability
+    /// between them over the elapsed time, so any number of unobserved jumps may happen in between.
+    /// Impossible transitions give negative infinity.
+    ///
+    /// # Errors
+    ///
+    /// If some observed state is not in the state space.
+    ///
+    /// # Panics
+    ///
+    /// If the times of `observations` are decreasing.
+    ///
+    /// # Examples
+    ///
+    /// A patient observed at three visits.
+    /// ```
+    /// # use markovian::ContFiniteMarkovChain;
+    /// let mc = ContFiniteMarkovChain::new(0, vec![vec![0_f64, 0.5], vec![0.1, 0.]], vec!["healthy", "ill"], rand::thread_rng());
+    /// let visits = [(0., "healthy"), (1., "ill"), (3., "ill")];
+    /// let log_likelihood = mc.panel_log_likelihood(&visits).unwrap();
+    /// let expected = mc.transition_probabilities(1.)[[0, 1]].ln() + mc.transition_probabilities(2.)[[1, 1]].ln();
+    /// assert!((log_likelihood - expected).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn panel_log_likelihood(&self, observations: &[(W, T)]) -> Result<W, InvalidState<T>>
+    where
+        T: Debug + PartialEq + Clone,
+    {
+        let mut indexes = Vec::with_capacity(observations.len());
+        for (_, state) in observations {
+            match self.state_space.iter().position(|s| s == state) {
+                Some(index) => indexes.push(index),
+                None => return Err(InvalidState::new(state.clone())),
+            }
+        }
+        let mut log_likelihood = W::zero();
+        // Panels are often observed at regular intervals
+        let mut cache: Option<(W, ndarray::Array2<W>)> = None;
+        for k in 1..observations.len() {
+            let elapsed = observations[k].0 - observations[k - 1].0;
+            assert!(elapsed >= W::zero(), "The times of the observations must be non-decreasing.");
+            let p = match cache {
+                Some((time, ref p)) if time == elapsed => p,
+                _ => &cache.insert((elapsed, self.transition_probabilities(elapsed))).1,
+            };
+            log_likelihood += p[[indexes[k - 1], indexes[k]]].ln();
+        }
+        Ok(log_likelihood)
+    }
+
+    /// Returns the skeleton of the chain at time step `time`, i.e. the discrete time chain
+    /// with transition matrix P(t) = exp(Q t), observing the process every `time` units of time. 
+    ///
+    /// The skeleton starts in the current state and uses the random number generator of `self`.
+    ///
+    /// # Examples
+    ///
+    /// Observing a chain every half unit of time.
+    /// ```
+    /// # use markovian::{ContFiniteMarkovChain, State};
+    /// let mc = ContFiniteMarkovChain::new(0, vec![vec![0., 2.], vec![3., 0.]], vec!['a', 'b'], rand::thread_rng());
+    /// let mut skeleton = mc.skeleton(0.5);
+    /// assert_eq!(skeleton.state(), Some(&'a'));
+    /// println!("At time 0.5, the state is {:?}", skeleton.next());
+    /// ```
+    #[inline]
+    pub fn skeleton(self, time: W) -> crate::FiniteMarkovChain<T, W, R> 
+    where
+        W: Debug,
+        Uniform<W>: Debug + Clone,
+        T: Debug + PartialEq + Clone,
+    {
+        let transition_matrix = self.transition_probabilities(time);
+        crate::FiniteMarkovChain::from((self.state_index, transition_matrix, self.state_space, self.rng))
+    }
+
+    /// Returns the uniformized chain at rate `lambda`, i.e. the discrete time chain with
+    /// transition matrix `I + Q / lambda`, which jumps at the times of a Poisson process
+    /// of rate `lambda`, some of them to the same state.
+    ///
+    /// The uniformized chain starts in the current state and uses the random number generator of `self`.
+    ///
+    /// # Panics
+    ///
+    /// If `lambda` is less than the rate of leaving some state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use markovian::ContFiniteMarkovChain;
+    /// # use ndarray::array;
+    /// let mc = ContFiniteMarkovChain::new(0, vec![vec![0., 2.], vec![3., 0.]], vec!['a', 'b'], rand::thread_rng());
+    /// let uniformized = mc.uniformized(4.);
+    /// assert_eq!(uniformized.transition_probabilities(), array![[0.5, 0.5], [0.75, 0.25]]);
+    /// ```
+    #[inline]
+    pub fn uniformized(self, lambda: W) -> crate::FiniteMarkovChain<T, W, R>
+    where
+        W: Debug,
+        Uniform<W>: Debug + Clone,
+        T: Debug + PartialEq + Clone,
+    {
+        let generator = self.generator();
+        let n = generator.nrows();
+        assert!(
+            (0..n).all(|i| -generator[[i, i]] <= lambda),
+            "The uniformization rate must be at least the rate of leaving any state."
+        );
+        let transition_matrix = ndarray::Array2::from_shape_fn((n, n), |(i, j)| {
+            let identity = if i == j { W::one() } else { W::zero() };
+            identity + generator[[i, j]] / lambda
+        });
+        crate::FiniteMarkovChain::from((self.state_index, transition_matrix, self.state_space, self.rng))
+    }
+
+    /// Moves the chain to a sample of its state after `time` units of time from the current state,
+    /// without simulating each jump, and returns it.
+    ///
+    /// The chain is uniformized at the largest rate `lambda` of leaving a state: 
+    /// the number of jumps is Poisson with mean `lambda * time` and each jump only needs a uniform sample.
+    /// For `lambda * time` larger than the cube of the number of states, the state is sampled 
+    /// from the exact [transition probabilities] instead.
+    ///
+    /// # Panics
+    ///
+    /// If `time` is negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// A chain that is very fast compared to the time horizon.
+    /// ```
+    /// # use markovian::{ContFiniteMarkovChain, State};
+    /// let mut mc = ContFiniteMarkovChain::new(0, vec![vec![0., 1e3], vec![2e3, 0.]], vec!['a', 'b'], rand::thread_rng());
+    /// let state = mc.state_at(1e3);
+    /// assert_eq!(mc.state(), Some(&state));
+    /// ```
+    ///
+    /// [transition probabilities]: #method.transition_probabilities
+    #[inline]
+    pub fn state_at(&mut self, time: W) -> T
+    where
+        T: Clone,
+    {
+        assert!(time.is_finite() && time >= W::zero(), "The time must be non-negative and finite.");
+        let lambda = self.transiton_clock.iter().fold(W::zero(), |max, &rate| max.max(rate));
+        let mean = (lambda * time).to_f64().unwrap();
+        let n = self.nstates();
+        if mean > (n * n * n) as f64 {
+            let p = self.transition_probabilities(time);
+            let weights = p.row(self.state_index).into_iter().map(|weight| weight.to_f64().unwrap());
+            self.state_index = sample_proportional(weights, &mut self.rng).unwrap_or(self.state_index);
+        } else if mean > 0.0 {
+            let jumps = rand_distr::Poisson::new(mean).unwrap().sample(&mut self.rng) as u64;
+            for _ in 0..jumps {
+                let u = W::from(self.rng.gen::<f64>()).unwrap();
+                if u * lambda < self.transiton_clock[self.state_index] {
+                    self.state_index = self.sample_index();
+                }
+            }
+        }
+        self.state_space[self.state_index].clone()
+    }
+
     #[inline]
     fn sample_index(&mut self) -> usize {
         self.transition_matrix[self.state_index].sample(&mut self.rng)
@@ -73,6 +362,84 @@ where
     }
 }
 
+impl<'a, T, W, R> ContFiniteMarkovChain<T, W, &'a mut R>
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+    R: Rng + ?Sized,
+{
+    /// Constructs a new `ContFiniteMarkovChain<T, W, &mut R>` that borrows its random number generator.
+    #[inline]
+    pub fn new_with_rng_mut(
+        state_index: usize,
+        transition_weights: Vec<Vec<W>>,
+        state_space: Vec<T>,
+        rng: &'a mut R,
+    ) -> Self {
+        ContFiniteMarkovChain::new(state_index, transition_weights, state_space, rng)
+    }
+}
+
+impl<T, W, R> Clone for ContFiniteMarkovChain<T, W, R>
+where
+    W: Float + AliasableWeight,
+    Uniform<W>: Clone,
+    Exp1: Distribution<W>,
+    T: Clone,
+    R: Rng + Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        ContFiniteMarkovChain {
+            state_index: self.state_index,
+            initial: self.initial.clone(),
+            transition_weights: self.transition_weights.clone(),
+            transition_matrix: self.transition_matrix.clone(),
+            transiton_clock: self.transiton_clock.clone(),
+            state_space: self.state_space.clone(),
+            rng: self.rng.clone(),
+        }
+    }
+}
+
+impl<T, W, R> Reset for ContFiniteMarkovChain<T, W, R>
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state_index = self.sample_initial_index();
+    }
+}
+
+impl<T, W, R> RandomSource for ContFiniteMarkovChain<T, W, R>
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
 impl<T, W, R> State for ContFiniteMarkovChain<T, W, R>
 where
     W: Float + AliasableWeight,
@@ -107,6 +474,22 @@ where
     }
 }
 
+impl<T, W, R> Checkpoint for ContFiniteMarkovChain<T, W, R>
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
 impl<T, W, R> Iterator for ContFiniteMarkovChain<T, W, R>
 where
     W: Float + AliasableWeight,
@@ -118,12 +501,28 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        self.state_index = self.sample_index();
         let period = self.sample_clock();
+        self.state_index = self.sample_index();
         self.state().cloned().map(|x| (period, x))
     }
 }
 
+impl<T, W, R> StochasticProcess for ContFiniteMarkovChain<T, W, R>
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Time = W;
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next()
+    }
+}
+
 impl<T, W, R> StateIterator for ContFiniteMarkovChain<T, W, R>
 where
     W: Float + AliasableWeight,
@@ -150,10 +549,86 @@ where
     where
         R2: Rng + ?Sized,
     { 
-        let new_index = self.transition_matrix[self.state_index].sample(rng);
         let rate = self.transiton_clock[self.state_index];
         let step = Exp::new(rate).unwrap().sample(rng);
+        let new_index = self.transition_matrix[self.state_index].sample(rng);
 
         (step, self.state_space[new_index].clone())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn state_at() {
+        let (a, b, t) = (2.0, 3.0, 0.4);
+        let expected = a / (a + b) * (1.0 - (-(a + b) * t).exp());
+        let mut mc = ContFiniteMarkovChain::new(0, vec![vec![0., a], vec![b, 1.]], vec![0, 1], crate::tests::rng(3));
+        let samples = 5_000;
+        let mut ones = 0;
+        for _ in 0..samples {
+            mc.set_state(0).unwrap();
+            ones += mc.state_at(t);
+        }
+        assert_abs_diff_eq!(ones as f64 / samples as f64, expected, epsilon = 0.03);
+
+        // Exact transition probabilities for long times
+        let mut ones = 0;
+        for _ in 0..samples {
+            mc.set_state(0).unwrap();
+            ones += mc.state_at(100.);
+        }
+        assert_abs_diff_eq!(ones as f64 / samples as f64, a / (a + b), epsilon = 0.03);
+    }
+
+    #[test]
+    fn panel_log_likelihood() {
+        let mc = ContFiniteMarkovChain::new(0, vec![vec![0., 1., 0.], vec![0.5, 0., 0.5], vec![0., 0., 1.]], vec![0, 1, 2], crate::tests::rng(2));
+        let observations = [(0., 0), (1., 0), (2., 2), (3., 2), (3.5, 2)];
+        let p1 = mc.transition_probabilities(1.);
+        let expected = p1[[0, 0]].ln() + p1[[0, 2]].ln() + p1[[2, 2]].ln();
+        assert_abs_diff_eq!(mc.panel_log_likelihood(&observations).unwrap(), expected, epsilon = 1e-12);
+        assert_eq!(mc.panel_log_likelihood(&[(0., 2), (1., 0)]).unwrap(), f64::NEG_INFINITY);
+        assert_eq!(mc.panel_log_likelihood(&[(0., 3)]).unwrap_err(), InvalidState::new(3));
+    }
+
+    #[test]
+    fn transition_probabilities() {
+        let (a, b, t) = (2.0, 3.0, 0.7);
+        let mc = ContFiniteMarkovChain::new(0, vec![vec![1., a], vec![b, 0.]], vec![0, 1], crate::tests::rng(1));
+        let p = mc.transition_probabilities(t);
+        let expected = a / (a + b) * (1.0 - (-(a + b) * t).exp());
+
+        assert_abs_diff_eq!(p[[0, 1]], expected, epsilon = 1e-12);
+        assert_abs_diff_eq!(p[[1, 0]], b / a * expected, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn skeleton() {
+        let mc = ContFiniteMarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(1, vec![vec![0., 1.], vec![1., 0.]], vec!['a', 'b'], 2);
+        let skeleton = mc.skeleton(1e-3);
+
+        assert_eq!(skeleton.state(), Some(&'b'));
+        assert_eq!(skeleton.state_space(), &vec!['a', 'b']);
+    }
+
+    #[test]
+    fn initial_distribution() {
+        let transition_weights = vec![vec![0., 1., 0.], vec![0., 0., 1.], vec![1., 0., 0.]];
+        let mut mc = ContFiniteMarkovChain::from_initial_distribution(vec![0., 1., 1.], transition_weights, vec![0, 1, 2], crate::tests::rng(3));
+        for _ in 0..100 {
+            mc.reset();
+            assert_ne!(mc.state(), Some(&0));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "one initial weight for each state")]
+    fn initial_distribution_length() {
+        let transition_weights = vec![vec![0., 1.], vec![1., 0.]];
+        ContFiniteMarkovChain::from_initial_distribution(vec![1.], transition_weights, vec![0, 1], crate::tests::rng(4));
+    }
+}
@@ -0,0 +1,756 @@
+//! Density-dependent chains in continuous time, and their fluid and diffusion limits.
+//!
+//! A density-dependent chain with size `N` has states in `Z^d`, usually counts of individuals or
+//! molecules, and jumps by vectors `l` at rates `N β_l(k / N)` from state `k`. As `N` grows,
+//! its density `X_N / N` follows the fluid limit, the solution of the ODE `x' = F(x)`, where the drift
+//! is `F(x) = Σ_l l β_l(x)`, as shown by Kurtz. The fluctuations around the fluid limit are of order
+//! `1 / sqrt(N)`, and approximately Gaussian, with covariance `Σ / N` solving
+//! `Σ' = A Σ + Σ A^T + G(x)`, where `A` is the Jacobian of `F` and `G(x) = Σ_l l l^T β_l(x)`:
+//! this is the linear noise approximation. The chemical Langevin equation,
+//! `dx = F(x) dt + Σ_l l sqrt(β_l(x) / N) dW_l`, is the diffusion approximation of the density.
+//!
+//! [DensityDependent] simulates the chain exactly, by the Gillespie algorithm, and computes these
+//! approximations, with an ODE [Integrator] of choice, to be compared with simulated paths.
+//! For moderate sizes, the mean and covariance of the counts are approximated by moment equations,
+//! closed as described in [Closure], which can be checked against Monte Carlo estimates.
+//!
+//! # Examples
+//!
+//! An SIS epidemic in a population of size 1000, whose fluid limit is the logistic equation.
+//! ```
+//! # use markovian::density_dependent::{DensityDependent, RungeKutta4};
+//! let (beta, gamma) = (2., 1.);
+//! let sis = DensityDependent::new(1_000., vec![vec![1], vec![-1]], move |x: &[f64]| {
+//!     vec![beta * x[0] * (1. - x[0]), gamma * x[0]]
+//! });
+//! let fluid = sis.fluid_limit(&[0.01], 20., 0.1, &RungeKutta4);
+//! let (_, endemic) = fluid.last().unwrap();
+//! assert!((endemic[0] - 0.5).abs() < 1e-3);
+//! let path = sis.sample_path(vec![10], 20., &mut rand::thread_rng());
+//! println!("Infected at the end: {:?}", path.last());
+//! ```
+//!
+//! [DensityDependent]: struct.DensityDependent.html
+//! [Integrator]: trait.Integrator.html
+//! [Closure]: enum.Closure.html
+
+// Traits
+use crate::Transition;
+use rand::Rng;
+use rand_distr::{Exp1, StandardNormal};
+
+// Structs
+use crate::TimedMarkovChain;
+use ndarray::{Array1, Array2};
+
+/// Abstraction over one-step integrators of autonomous ODEs `x' = f(x)`.
+pub trait Integrator {
+    /// Returns the approximate solution after time `dt`, starting from `x`.
+    fn step<F>(&self, f: &F, x: &[f64], dt: f64) -> Vec<f64>
+    where
+        F: Fn(&[f64]) -> Vec<f64>;
+}
+
+/// Explicit Euler method, of order one.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Euler;
+
+impl Integrator for Euler {
+    #[inline]
+    fn step<F>(&self, f: &F, x: &[f64], dt: f64) -> Vec<f64>
+    where
+        F: Fn(&[f64]) -> Vec<f64>,
+    {
+        axpy(x, dt, &f(x))
+    }
+}
+
+/// Classical Runge-Kutta method, of order four.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RungeKutta4;
+
+impl Integrator for RungeKutta4 {
+    #[inline]
+    fn step<F>(&self, f: &F, x: &[f64], dt: f64) -> Vec<f64>
+    where
+        F: Fn(&[f64]) -> Vec<f64>,
+    {
+        let k1 = f(x);
+        let k2 = f(&axpy(x, dt / 2., &k1));
+        let k3 = f(&axpy(x, dt / 2., &k2));
+        let k4 = f(&axpy(x, dt, &k3));
+        (0..x.len())
+            .map(|i| x[i] + dt / 6. * (k1[i] + 2. * k2[i] + 2. * k3[i] + k4[i]))
+            .collect()
+    }
+}
+
+/// Density-dependent chain in continuous time, with size `N`, jump vectors `l`
+/// and intensities `β_l`, given by a function from densities to the intensity of each jump.
+///
+/// As a `Transition<Vec<i64>, (f64, Vec<i64>)>`, it samples the time until the next jump
+/// and the state after it. If all rates are zero, the time until the next jump is infinite.
+#[derive(Debug, Clone)]
+pub struct DensityDependent<F> {
+    size: f64,
+    jumps: Vec<Vec<i64>>,
+    intensities: F,
+}
+
+impl<F> DensityDependent<F>
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+{
+    /// Constructs a new `DensityDependent<F>`.
+    ///
+    /// Negative intensities are taken as zero.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is not positive and finite, there are no jumps, or jumps have different dimensions.
+    #[inline]
+    pub fn new(size: f64, jumps: Vec<Vec<i64>>, intensities: F) -> Self {
+        assert!(size > 0. && size.is_finite(), "The size must be positive and finite.");
+        assert!(!jumps.is_empty(), "There must be at least one jump.");
+        assert!(jumps.iter().all(|l| l.len() == jumps[0].len()), "Jumps must have the same dimension.");
+        DensityDependent { size, jumps, intensities }
+    }
+
+    /// Returns the size `N`.
+    #[inline]
+    pub fn size(&self) -> f64 {
+        self.size
+    }
+
+    /// Returns the dimension of the state space.
+    #[inline]
+    pub fn dimension(&self) -> usize {
+        self.jumps[0].len()
+    }
+
+    /// Returns the jump vectors.
+    #[inline]
+    pub fn jumps(&self) -> &[Vec<i64>] {
+        &self.jumps
+    }
+
+    /// Returns the drift `F(x) = Σ_l l β_l(x)` at density `x`.
+    #[inline]
+    pub fn drift(&self, x: &[f64]) -> Vec<f64> {
+        let mut drift = vec![0.; self.dimension()];
+        for (l, beta) in self.jumps.iter().zip(self.rates(x)) {
+            for (d, &li) in drift.iter_mut().zip(l) {
+                *d += li as f64 * beta;
+            }
+        }
+        drift
+    }
+
+    /// Returns the diffusion matrix `G(x) = Σ_l l l^T β_l(x)` at density `x`.
+    #[inline]
+    pub fn diffusion_matrix(&self, x: &[f64]) -> Array2<f64> {
+        let d = self.dimension();
+        let mut g = Array2::zeros((d, d));
+        for (l, beta) in self.jumps.iter().zip(self.rates(x)) {
+            for i in 0..d {
+                for j in 0..d {
+                    g[[i, j]] += (l[i] * l[j]) as f64 * beta;
+                }
+            }
+        }
+        g
+    }
+
+    /// Returns the fluid limit from density `x0`, at times `0, step, 2 step, ...` up to `horizon`,
+    /// computed by `integrator`.
+    ///
+    /// # Panics
+    ///
+    /// If `x0` does not have the dimension of the state space, `horizon` is negative or not finite,
+    /// or `step` is not positive.
+    #[inline]
+    pub fn fluid_limit<I>(&self, x0: &[f64], horizon: f64, step: f64, integrator: &I) -> Vec<(f64, Vec<f64>)>
+    where
+        I: Integrator,
+    {
+        assert_eq!(x0.len(), self.dimension(), "The initial density must have the dimension of the state space.");
+        integrate(&|x: &[f64]| self.drift(x), x0, horizon, step, integrator)
+    }
+
+    /// Returns the linear noise approximation from density `x0`, at times `0, step, 2 step, ...`
+    /// up to `horizon`, computed by `integrator`.
+    ///
+    /// The Jacobian of the drift is approximated by central finite differences.
+    ///
+    /// # Panics
+    ///
+    /// If `x0` does not have the dimension of the state space, `horizon` is negative or not finite,
+    /// or `step` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// An immigration-death process, whose stationary law is Poisson.
+    /// ```
+    /// # use markovian::density_dependent::{DensityDependent, RungeKutta4};
+    /// let model = DensityDependent::new(100., vec![vec![1], vec![-1]], |x: &[f64]| vec![2., x[0]]);
+    /// let lna = model.linear_noise(&[2.], 10., 0.1, &RungeKutta4);
+    /// let variance = lna.covariances().last().unwrap()[[0, 0]];
+    /// assert!((variance - 2.).abs() < 1e-6);
+    /// ```
+    #[inline]
+    pub fn linear_noise<I>(&self, x0: &[f64], horizon: f64, step: f64, integrator: &I) -> LinearNoise
+    where
+        I: Integrator,
+    {
+        let d = self.dimension();
+        assert_eq!(x0.len(), d, "The initial density must have the dimension of the state space.");
+        // The mean and the covariance are integrated together
+        let field = |z: &[f64]| {
+            let (x, sigma) = z.split_at(d);
+            let sigma = Array2::from_shape_vec((d, d), sigma.to_vec()).unwrap();
+            let a = self.jacobian(x);
+            let a_sigma = a.dot(&sigma);
+            let sigma_dot = &a_sigma + &a_sigma.t() + self.diffusion_matrix(x);
+            let mut dz = self.drift(x);
+            dz.extend(sigma_dot.iter());
+            dz
+        };
+        let mut z0 = x0.to_vec();
+        z0.extend(vec![0.; d * d]);
+        let solution = integrate(&field, &z0, horizon, step, integrator);
+
+        let mut times = Vec::with_capacity(solution.len());
+        let mut means = Vec::with_capacity(solution.len());
+        let mut covariances = Vec::with_capacity(solution.len());
+        for (t, z) in solution {
+            times.push(t);
+            means.push(Array1::from(z[..d].to_vec()));
+            covariances.push(Array2::from_shape_vec((d, d), z[d..].to_vec()).unwrap());
+        }
+        LinearNoise {
+            size: self.size,
+            times,
+            means,
+            covariances,
+        }
+    }
+
+    /// Samples a path of the diffusion approximation of the density, the chemical Langevin equation,
+    /// from density `x0`, at times `0, step, 2 step, ...` up to `horizon`, by the Euler-Maruyama method.
+    ///
+    /// # Panics
+    ///
+    /// If `x0` does not have the dimension of the state space, `horizon` is negative or not finite,
+    /// or `step` is not positive.
+    #[inline]
+    pub fn sample_diffusion<R>(&self, x0: &[f64], horizon: f64, step: f64, rng: &mut R) -> Vec<(f64, Vec<f64>)>
+    where
+        R: Rng + ?Sized,
+    {
+        assert_eq!(x0.len(), self.dimension(), "The initial density must have the dimension of the state space.");
+        grid(horizon, step)
+            .into_iter()
+            .scan((0., x0.to_vec()), |(time, x): &mut (f64, Vec<f64>), t| {
+                let dt = t - *time;
+                if dt > 0. {
+                    let mut next = axpy(x, dt, &self.drift(x));
+                    for (l, beta) in self.jumps.iter().zip(self.rates(x)) {
+                        let noise = (beta * dt / self.size).sqrt() * rng.sample::<f64, _>(StandardNormal);
+                        for (xi, &li) in next.iter_mut().zip(l) {
+                            *xi += li as f64 * noise;
+                        }
+                    }
+                    *x = next;
+                }
+                *time = t;
+                Some((t, x.clone()))
+            })
+            .collect()
+    }
+
+    /// Samples a path of the chain from `counts` up to `horizon`, by the Gillespie algorithm,
+    /// and returns the times of the jumps together with the states after them,
+    /// starting with time zero and `counts`.
+    ///
+    /// # Panics
+    ///
+    /// If `counts` does not have the dimension of the state space.
+    #[inline]
+    pub fn sample_path<R>(&self, counts: Vec<i64>, horizon: f64, rng: &mut R) -> Vec<(f64, Vec<i64>)>
+    where
+        R: Rng + ?Sized,
+    {
+        assert_eq!(counts.len(), self.dimension(), "The counts must have the dimension of the state space.");
+        let mut time = 0.;
+        let mut path = vec![(time, counts)];
+        loop {
+            let (period, next) = self.sample_from(&path[path.len() - 1].1, rng);
+            time += period;
+            if time > horizon {
+                return path;
+            }
+            path.push((time, next));
+        }
+    }
+
+    /// Returns the approximate mean and covariance of the counts, from mean `mean` and covariance
+    /// `covariance`, at times `0, step, 2 step, ...` up to `horizon`, by integrating the moment
+    /// equations closed by `closure` with `integrator`.
+    ///
+    /// With propensities `a_l(k) = N β_l(k / N)`, the moment equations are
+    /// `μ' = Σ_l l E[a_l(X)]` and `C' = Σ_l (l E[(X - μ) a_l(X)]^T + E[(X - μ) a_l(X)] l^T + l l^T E[a_l(X)])`,
+    /// where the expectations are approximated as described in [Closure].
+    /// Derivatives of the propensities are approximated by central finite differences.
+    ///
+    /// # Panics
+    ///
+    /// If `mean` or `covariance` does not have the dimension of the state space,
+    /// `horizon` is negative or not finite, or `step` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// A logistic birth-death process, with births at rate `2 k` and deaths at rate `k + k^2 / N`.
+    /// ```
+    /// # use ndarray::Array2;
+    /// # use markovian::density_dependent::{Closure, DensityDependent, RungeKutta4};
+    /// let model = DensityDependent::new(100., vec![vec![1], vec![-1]], |x: &[f64]| vec![2. * x[0], x[0] + x[0] * x[0]]);
+    /// let moments = model.moment_equations(&[10.], &Array2::zeros((1, 1)), 5., 0.01, Closure::Normal, &RungeKutta4);
+    /// let mean = moments.means().last().unwrap()[0];
+    /// let variance = moments.covariances().last().unwrap()[[0, 0]];
+    /// println!("At time 5, the population is {} ± {}", mean, variance.sqrt());
+    /// ```
+    ///
+    /// [Closure]: enum.Closure.html
+    #[inline]
+    pub fn moment_equations<I>(
+        &self,
+        mean: &[f64],
+        covariance: &Array2<f64>,
+        horizon: f64,
+        step: f64,
+        closure: Closure,
+        integrator: &I,
+    ) -> Moments
+    where
+        I: Integrator,
+    {
+        let d = self.dimension();
+        assert_eq!(mean.len(), d, "The mean must have the dimension of the state space.");
+        assert_eq!(covariance.dim(), (d, d), "The covariance must have the dimension of the state space.");
+        let field = |z: &[f64]| {
+            let (mu, c) = z.split_at(d);
+            let c = Array2::from_shape_vec((d, d), c.to_vec()).unwrap();
+            let propensities = self.propensities(mu);
+            let gradients = self.propensity_gradients(mu);
+            let mut expected = Array1::from(propensities);
+            if closure == Closure::Normal {
+                // Second order correction, exact for quadratic propensities and Gaussian counts
+                expected += &(self.propensity_hessians(mu).iter().map(|h| (h * &c).sum() / 2.).collect::<Array1<f64>>());
+            }
+            let mut mu_dot = Array1::zeros(d);
+            let mut c_dot = Array2::zeros((d, d));
+            for (l, (&m, gradient)) in self.jumps.iter().zip(expected.iter().zip(&gradients)) {
+                let l = l.iter().map(|&li| li as f64).collect::<Array1<f64>>();
+                let cross = c.dot(gradient);
+                mu_dot.scaled_add(m, &l);
+                for i in 0..d {
+                    for j in 0..d {
+                        c_dot[[i, j]] += l[i] * cross[j] + cross[i] * l[j] + l[i] * l[j] * m;
+                    }
+                }
+            }
+            let mut dz = mu_dot.to_vec();
+            dz.extend(c_dot.iter());
+            dz
+        };
+        let mut z0 = mean.to_vec();
+        z0.extend(covariance.iter());
+        let solution = integrate(&field, &z0, horizon, step, integrator);
+
+        let mut times = Vec::with_capacity(solution.len());
+        let mut means = Vec::with_capacity(solution.len());
+        let mut covariances = Vec::with_capacity(solution.len());
+        for (t, z) in solution {
+            times.push(t);
+            means.push(Array1::from(z[..d].to_vec()));
+            covariances.push(Array2::from_shape_vec((d, d), z[d..].to_vec()).unwrap());
+        }
+        Moments { times, means, covariances }
+    }
+
+    /// Returns the sample mean and covariance of the counts of `npaths` paths simulated from `counts`,
+    /// at times `0, step, 2 step, ...` up to `horizon`, to be compared with [moment_equations].
+    ///
+    /// # Panics
+    ///
+    /// If `counts` does not have the dimension of the state space, `npaths` is smaller than two,
+    /// `horizon` is negative or not finite, or `step` is not positive.
+    ///
+    /// [moment_equations]: #method.moment_equations
+    #[inline]
+    pub fn monte_carlo_moments<R>(&self, counts: Vec<i64>, horizon: f64, step: f64, npaths: usize, rng: &mut R) -> Moments
+    where
+        R: Rng + ?Sized,
+    {
+        assert!(npaths > 1, "At least two paths are needed.");
+        let d = self.dimension();
+        let times = grid(horizon, step);
+        let mut sums = vec![Array1::<f64>::zeros(d); times.len()];
+        let mut products = vec![Array2::<f64>::zeros((d, d)); times.len()];
+        for _ in 0..npaths {
+            let path = self.sample_path(counts.clone(), horizon, rng);
+            let mut jump = 0;
+            for (k, &t) in times.iter().enumerate() {
+                while jump + 1 < path.len() && path[jump + 1].0 <= t {
+                    jump += 1;
+                }
+                let state = path[jump].1.iter().map(|&c| c as f64).collect::<Array1<f64>>();
+                sums[k] += &state;
+                for i in 0..d {
+                    for j in 0..d {
+                        products[k][[i, j]] += state[i] * state[j];
+                    }
+                }
+            }
+        }
+        let n = npaths as f64;
+        let means: Vec<Array1<f64>> = sums.into_iter().map(|sum| sum / n).collect();
+        let covariances = products
+            .into_iter()
+            .zip(&means)
+            .map(|(product, mean)| Array2::from_shape_fn((d, d), |(i, j)| (product[[i, j]] - n * mean[i] * mean[j]) / (n - 1.)))
+            .collect();
+        Moments { times, means, covariances }
+    }
+
+    /// Returns a continuous time Markov chain simulating the chain from `counts`.
+    ///
+    /// # Panics
+    ///
+    /// If `counts` does not have the dimension of the state space.
+    #[inline]
+    pub fn into_markov_chain<R>(self, counts: Vec<i64>, rng: R) -> TimedMarkovChain<f64, Vec<i64>, Self, R>
+    where
+        R: Rng,
+    {
+        assert_eq!(counts.len(), self.dimension(), "The counts must have the dimension of the state space.");
+        TimedMarkovChain::new(counts, self, rng)
+    }
+
+    /// Returns the non-negative intensity of each jump at density `x`.
+    #[inline]
+    fn rates(&self, x: &[f64]) -> Vec<f64> {
+        (self.intensities)(x).into_iter().map(|beta| beta.max(0.)).collect()
+    }
+
+    /// Returns the propensity `a_l(k) = N β_l(k / N)` of each jump at counts `k`.
+    #[inline]
+    fn propensities(&self, counts: &[f64]) -> Vec<f64> {
+        let x: Vec<f64> = counts.iter().map(|&k| k / self.size).collect();
+        self.rates(&x).into_iter().map(|beta| self.size * beta).collect()
+    }
+
+    /// Returns the gradient of the propensity of each jump at `counts`, by central finite differences.
+    #[inline]
+    fn propensity_gradients(&self, counts: &[f64]) -> Vec<Array1<f64>> {
+        let d = self.dimension();
+        let mut gradients = vec![Array1::zeros(d); self.jumps.len()];
+        for j in 0..d {
+            let h = 1e-4 * counts[j].abs().max(1.);
+            let forward = self.propensities(&shifted(counts, &[(j, h)]));
+            let backward = self.propensities(&shifted(counts, &[(j, -h)]));
+            for (gradient, (f, b)) in gradients.iter_mut().zip(forward.iter().zip(&backward)) {
+                gradient[j] = (f - b) / (2. * h);
+            }
+        }
+        gradients
+    }
+
+    /// Returns the Hessian of the propensity of each jump at `counts`, by central finite differences.
+    #[inline]
+    fn propensity_hessians(&self, counts: &[f64]) -> Vec<Array2<f64>> {
+        let d = self.dimension();
+        let mut hessians = vec![Array2::zeros((d, d)); self.jumps.len()];
+        let h: Vec<f64> = counts.iter().map(|k| 1e-3 * k.abs().max(1.)).collect();
+        for i in 0..d {
+            for j in 0..d {
+                let corners = [(1., 1., 1.), (1., -1., -1.), (-1., 1., -1.), (-1., -1., 1.)];
+                for &(si, sj, sign) in corners.iter() {
+                    let values = self.propensities(&shifted(counts, &[(i, si * h[i]), (j, sj * h[j])]));
+                    for (hessian, value) in hessians.iter_mut().zip(values) {
+                        hessian[[i, j]] += sign * value / (4. * h[i] * h[j]);
+                    }
+                }
+            }
+        }
+        hessians
+    }
+
+    /// Returns the Jacobian of the drift at `x`, by central finite differences.
+    #[inline]
+    fn jacobian(&self, x: &[f64]) -> Array2<f64> {
+        let d = self.dimension();
+        let mut a = Array2::zeros((d, d));
+        for j in 0..d {
+            let h = 1e-6 * x[j].abs().max(1.);
+            let mut forward = x.to_vec();
+            forward[j] += h;
+            let mut backward = x.to_vec();
+            backward[j] -= h;
+            let (forward, backward) = (self.drift(&forward), self.drift(&backward));
+            for i in 0..d {
+                a[[i, j]] = (forward[i] - backward[i]) / (2. * h);
+            }
+        }
+        a
+    }
+}
+
+impl<F> Transition<Vec<i64>, (f64, Vec<i64>)> for DensityDependent<F>
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+{
+    #[inline]
+    fn sample_from<R>(&self, counts: &Vec<i64>, rng: &mut R) -> (f64, Vec<i64>)
+    where
+        R: Rng + ?Sized,
+    {
+        let x: Vec<f64> = counts.iter().map(|&k| k as f64 / self.size).collect();
+        let rates: Vec<f64> = self.rates(&x).into_iter().map(|beta| self.size * beta).collect();
+        let total: f64 = rates.iter().sum();
+        if total == 0. {
+            return (f64::INFINITY, counts.clone());
+        }
+        let period = rng.sample::<f64, _>(Exp1) / total;
+        let mut u = rng.gen::<f64>() * total;
+        let mut chosen = rates.iter().rposition(|&rate| rate > 0.).unwrap_or(0);
+        for (l, &rate) in rates.iter().enumerate() {
+            if u < rate {
+                chosen = l;
+                break;
+            }
+            u -= rate;
+        }
+        let next = counts.iter().zip(&self.jumps[chosen]).map(|(k, l)| k + l).collect();
+        (period, next)
+    }
+}
+
+/// Linear noise approximation of a density-dependent chain, computed by [linear_noise].
+///
+/// [linear_noise]: struct.DensityDependent.html#method.linear_noise
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearNoise {
+    size: f64,
+    times: Vec<f64>,
+    means: Vec<Array1<f64>>,
+    covariances: Vec<Array2<f64>>,
+}
+
+impl LinearNoise {
+    /// Returns the times of the approximation.
+    #[inline]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Returns the fluid limit, the approximate mean density, at each time.
+    #[inline]
+    pub fn means(&self) -> &[Array1<f64>] {
+        &self.means
+    }
+
+    /// Returns the covariance `Σ` of the fluctuations at each time, so that the covariance
+    /// of the density is approximately `Σ / N`, and that of the counts `N Σ`.
+    #[inline]
+    pub fn covariances(&self) -> &[Array2<f64>] {
+        &self.covariances
+    }
+
+    /// Returns, at each time, the approximate mean count of `coordinate`, together with
+    /// the band of `z` standard deviations around it, as `(time, low, mean, high)`.
+    ///
+    /// # Panics
+    ///
+    /// If `coordinate` is not smaller than the dimension of the state space.
+    #[inline]
+    pub fn band(&self, coordinate: usize, z: f64) -> Vec<(f64, f64, f64, f64)> {
+        self.times
+            .iter()
+            .zip(&self.means)
+            .zip(&self.covariances)
+            .map(|((&t, mean), covariance)| {
+                let mean = self.size * mean[coordinate];
+                let deviation = (self.size * covariance[[coordinate, coordinate]].max(0.)).sqrt();
+                (t, mean - z * deviation, mean, mean + z * deviation)
+            })
+            .collect()
+    }
+}
+
+/// Approximation of the expectations of propensities in the moment equations,
+/// in terms of the mean `μ` and covariance `C` of the counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Closure {
+    /// First order: `E[a(X)] = a(μ)` and `E[(X - μ) a(X)] = C ∇a(μ)`, which gives
+    /// the linear noise approximation, and is exact for linear propensities.
+    MeanField,
+    /// Normal, or Gaussian: third central moments vanish, so that `E[a(X)] = a(μ) + tr(∇²a(μ) C) / 2`
+    /// and `E[(X - μ) a(X)] = C ∇a(μ)`, which is exact for quadratic propensities and Gaussian counts.
+    Normal,
+}
+
+/// Means and covariances of the counts of a density-dependent chain over time,
+/// computed by [moment_equations] or [monte_carlo_moments].
+///
+/// [moment_equations]: struct.DensityDependent.html#method.moment_equations
+/// [monte_carlo_moments]: struct.DensityDependent.html#method.monte_carlo_moments
+#[derive(Debug, Clone, PartialEq)]
+pub struct Moments {
+    times: Vec<f64>,
+    means: Vec<Array1<f64>>,
+    covariances: Vec<Array2<f64>>,
+}
+
+impl Moments {
+    /// Returns the times of the moments.
+    #[inline]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Returns the mean of the counts at each time.
+    #[inline]
+    pub fn means(&self) -> &[Array1<f64>] {
+        &self.means
+    }
+
+    /// Returns the covariance of the counts at each time.
+    #[inline]
+    pub fn covariances(&self) -> &[Array2<f64>] {
+        &self.covariances
+    }
+}
+
+/// Returns the times `0, step, 2 step, ...` up to `horizon`, which is always the last one.
+#[inline]
+fn grid(horizon: f64, step: f64) -> Vec<f64> {
+    assert!(horizon >= 0. && horizon.is_finite(), "The horizon must be non-negative and finite.");
+    assert!(step > 0., "The step must be positive.");
+    let nsteps = (horizon / step).ceil() as usize;
+    (0..=nsteps).map(|k| (k as f64 * step).min(horizon)).collect()
+}
+
+/// Solves `x' = f(x)` on the grid of `horizon` and `step` with `integrator`.
+#[inline]
+fn integrate<F, I>(f: &F, x0: &[f64], horizon: f64, step: f64, integrator: &I) -> Vec<(f64, Vec<f64>)>
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+    I: Integrator,
+{
+    grid(horizon, step)
+        .into_iter()
+        .scan((0., x0.to_vec()), |(time, x): &mut (f64, Vec<f64>), t| {
+            if t > *time {
+                *x = integrator.step(f, x, t - *time);
+            }
+            *time = t;
+            Some((t, x.clone()))
+        })
+        .collect()
+}
+
+/// Returns `x` with the entries given by `shifts` displaced.
+#[inline]
+fn shifted(x: &[f64], shifts: &[(usize, f64)]) -> Vec<f64> {
+    let mut x = x.to_vec();
+    for &(i, h) in shifts {
+        x[i] += h;
+    }
+    x
+}
+
+/// Returns `x + a y`.
+#[inline]
+fn axpy(x: &[f64], a: f64, y: &[f64]) -> Vec<f64> {
+    x.iter().zip(y).map(|(xi, yi)| xi + a * yi).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn immigration_death() -> DensityDependent<impl Fn(&[f64]) -> Vec<f64>> {
+        DensityDependent::new(100., vec![vec![1], vec![-1]], |x: &[f64]| vec![2., x[0]])
+    }
+
+    #[test]
+    fn fluid_limit() {
+        // x(t) = 2 (1 - exp(-t))
+        let model = immigration_death();
+        let exact = |t: f64| 2. * (1. - (-t).exp());
+        for (t, x) in model.fluid_limit(&[0.], 3., 0.01, &RungeKutta4) {
+            assert_abs_diff_eq!(x[0], exact(t), epsilon = 1e-8);
+        }
+        let (t, x) = model.fluid_limit(&[0.], 3., 0.001, &Euler).pop().unwrap();
+        assert_abs_diff_eq!(t, 3.);
+        assert_abs_diff_eq!(x[0], exact(3.), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn linear_noise_and_simulation() {
+        let model = immigration_death();
+        let lna = model.linear_noise(&[0.], 3., 0.01, &RungeKutta4);
+        let (_, low, mean, high) = *lna.band(0, 2.).last().unwrap();
+        assert_abs_diff_eq!(mean, 200. * (1. - (-3f64).exp()), epsilon = 1e-6);
+
+        // Exact simulations and the diffusion approximation agree with the linear noise approximation
+        let mut rng = crate::tests::rng(1);
+        let finals: Vec<f64> = (0..1_000)
+            .map(|_| model.sample_path(vec![0], 3., &mut rng).pop().unwrap().1[0] as f64)
+            .collect();
+        let empirical_mean = finals.iter().sum::<f64>() / 1_000.;
+        assert_abs_diff_eq!(empirical_mean, mean, epsilon = 2.);
+        let inside = finals.iter().filter(|&&k| low <= k && k <= high).count();
+        assert!(inside > 930, "{} paths inside the band", inside);
+
+        let diffusion_mean = (0..1_000)
+            .map(|_| model.sample_diffusion(&[0.], 3., 0.01, &mut rng).pop().unwrap().1[0])
+            .sum::<f64>()
+            / 1_000.;
+        assert_abs_diff_eq!(diffusion_mean * 100., mean, epsilon = 2.);
+
+        let mut mc = model.into_markov_chain(vec![0], rng);
+        let (period, counts) = mc.next().unwrap();
+        assert!(period > 0.);
+        assert_eq!(counts, vec![1]);
+    }
+
+    #[test]
+    fn moment_equations() {
+        // Linear propensities: both closures are exact, and counts from zero are Poisson
+        let model = immigration_death();
+        for &closure in &[Closure::MeanField, Closure::Normal] {
+            let moments = model.moment_equations(&[0.], &Array2::zeros((1, 1)), 3., 0.01, closure, &RungeKutta4);
+            for ((&t, mean), covariance) in moments.times().iter().zip(moments.means()).zip(moments.covariances()) {
+                let exact = 200. * (1. - (-t).exp());
+                assert_abs_diff_eq!(mean[0], exact, epsilon = 1e-4);
+                assert_abs_diff_eq!(covariance[[0, 0]], exact, epsilon = 1e-4);
+            }
+        }
+
+        // Quadratic propensities: the normal closure corrects the mean, as seen by Monte Carlo
+        let logistic = DensityDependent::new(20., vec![vec![1], vec![-1]], |x: &[f64]| vec![2. * x[0], x[0] + x[0] * x[0]]);
+        let normal = logistic.moment_equations(&[10.], &Array2::zeros((1, 1)), 2., 0.5, Closure::Normal, &RungeKutta4);
+        let mean_field = logistic.moment_equations(&[10.], &Array2::zeros((1, 1)), 2., 0.5, Closure::MeanField, &RungeKutta4);
+        let monte_carlo = logistic.monte_carlo_moments(vec![10], 2., 0.5, 4_000, &mut crate::tests::rng(2));
+        assert_eq!(monte_carlo.times(), normal.times());
+        let last = normal.times().len() - 1;
+        let (estimate, variance) = (monte_carlo.means()[last][0], monte_carlo.covariances()[last][[0, 0]]);
+        let standard_error = (variance / 4_000.).sqrt();
+        assert_abs_diff_eq!(normal.means()[last][0], estimate, epsilon = 4. * standard_error);
+        assert!(mean_field.means()[last][0] > normal.means()[last][0]);
+        assert_abs_diff_eq!(normal.covariances()[last][[0, 0]], variance, epsilon = 0.15 * variance);
+    }
+}
@@ -1,7 +1,19 @@
-pub use self::raw::Raw;
+pub use self::raw::{CompiledRaw, Raw};
 pub use self::unary::Unary;
 pub use self::binary::Binary;
+pub use self::exponential_race::ExponentialRace;
+pub use self::empirical::Empirical;
+pub use self::density::Density;
+pub use self::determinantal::Determinantal;
+pub use self::countable::Countable;
+pub use self::phase_type::PhaseType;
 
 mod raw;
 mod unary;
-mod binary;
\ No newline at end of file
+mod binary;
+mod exponential_race;
+mod empirical;
+mod density;
+mod determinantal;
+mod countable;
+mod phase_type;
@@ -0,0 +1,108 @@
+// Traits
+use rand::Rng;
+use rand_distr::Distribution;
+
+/// Distribution over the non-negative integers given by a density and a tail bound.
+///
+/// Sampling is by inversion of the cumulative distribution function. The tail bound
+/// `tail_inverse(eps)` must return an index `k` such that the probability of values
+/// greater than `k` is at most `eps`. It caps the search, so that sampling is correct
+/// and always terminates, even if floating point accumulation of the density falls short of one.
+///
+/// # Costs
+///
+/// Sample cost: O(sampled value).
+/// Construction cost: O(1).
+///
+/// # Examples
+///
+/// A geometric distribution, whose tail after `k` is `q^(k + 1)`.
+/// ```
+/// # use rand::prelude::*;
+/// # use markovian::distributions::Countable;
+/// let q: f64 = 0.5;
+/// let dis = Countable::new(
+///     move |k: u64| (1. - q) * q.powi(k as i32),
+///     move |eps: f64| (eps.ln() / q.ln()).ceil().max(0.) as u64,
+/// );
+/// println!("Sampled {}", dis.sample(&mut thread_rng()));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Countable<F, G> {
+    density: F,
+    tail_inverse: G,
+}
+
+impl<F, G> Countable<F, G>
+where
+    F: Fn(u64) -> f64,
+    G: Fn(f64) -> u64,
+{
+    /// Constructs a new `Countable<F, G>` from its density and its inverse tail bound.
+    #[inline]
+    pub fn new(density: F, tail_inverse: G) -> Self {
+        Countable { density, tail_inverse }
+    }
+
+    /// Returns the probability of `k`.
+    #[inline]
+    pub fn density(&self, k: u64) -> f64 {
+        (self.density)(k)
+    }
+}
+
+impl<F, G> Distribution<u64> for Countable<F, G>
+where
+    F: Fn(u64) -> f64,
+    G: Fn(f64) -> u64,
+{
+    /// # Panics
+    ///
+    /// If the density is negative at some value.
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        let goal: f64 = rng.gen();
+        let cap = (self.tail_inverse)(1.0 - goal);
+        let mut acc = 0.0;
+        for k in 0..cap {
+            let prob = (self.density)(k);
+            assert!(prob >= 0.0, "Probabilities can not be negative. Tried to use {:?}", prob);
+            acc += prob;
+            if acc > goal {
+                return k;
+            }
+        }
+        cap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn geometric() {
+        let mut rng = crate::tests::rng(1);
+        let q: f64 = 0.5;
+        let dis = Countable::new(
+            move |k: u64| (1. - q) * q.powi(k as i32),
+            move |eps: f64| (eps.ln() / q.ln()).ceil().max(0.) as u64,
+        );
+        let samples = 10_000;
+        let mean = (0..samples).map(|_| dis.sample(&mut rng) as f64).sum::<f64>() / samples as f64;
+
+        assert_abs_diff_eq!(mean, q / (1. - q), epsilon = 0.05);
+    }
+
+    #[test]
+    fn deficient_density() {
+        let mut rng = crate::tests::rng(2);
+        // The density misses mass, but the tail bound caps the search.
+        let dis = Countable::new(|_: u64| 0.0, |_: f64| 3);
+        for _ in 0..10 {
+            assert_eq!(dis.sample(&mut rng), 3);
+        }
+    }
+}
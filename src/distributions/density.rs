@@ -0,0 +1,211 @@
+// Traits
+use num_traits::ToPrimitive;
+use rand::Rng;
+use rand_distr::Distribution;
+
+// Structs
+use crate::errors::DensityError;
+use rand_distr::weighted_alias::WeightedAliasIndex;
+
+/// Validated distribution over finitely many values.
+///
+/// Unlike [Raw], weights are checked when constructing, can be of any
+/// numeric type (integers included) and sampling costs O(1).
+///
+/// A density is either stochastic, with weights normalized to sum up to one,
+/// or sub-stochastic, with probabilities adding up to at most one. In the latter case,
+/// the missing mass is the probability of sampling `None` as an `Option<T>`.
+///
+/// # Examples
+///
+/// Integer weights are normalized.
+/// ```
+/// # use rand::prelude::*;
+/// # use markovian::distributions::Density;
+/// let dis = Density::try_new(vec![(1, 'a'), (3, 'b')]).unwrap();
+/// assert_eq!(dis.probabilities(), &[0.25, 0.75]);
+/// assert_eq!(dis.total_mass(), 4.);
+/// let sample: char = dis.sample(&mut thread_rng());
+/// ```
+///
+/// A sub-stochastic density, which may sample no value.
+/// ```
+/// # use rand::prelude::*;
+/// # use markovian::distributions::Density;
+/// let dis = Density::try_sub_stochastic(vec![(0.5, 'a'), (0.25, 'b')]).unwrap();
+/// assert_eq!(dis.total_mass(), 0.75);
+/// let sample: Option<char> = dis.sample(&mut thread_rng()); // None with probability 0.25
+/// ```
+///
+/// [Raw]: struct.Raw.html
+#[derive(Debug, Clone)]
+pub struct Density<T> {
+    values: Vec<T>,
+    probabilities: Vec<f64>,
+    total_mass: f64,
+    sub_stochastic: bool,
+    alias: WeightedAliasIndex<f64>,
+}
+
+impl<T> Density<T> {
+    /// Constructs a new `Density<T>` from pairs `(weight, value)`, normalizing the weights.
+    ///
+    /// # Errors
+    ///
+    /// If there are no pairs, some weight is negative or not finite, or all weights are zero.
+    #[inline]
+    pub fn try_new<P, I>(pairs: I) -> Result<Self, DensityError>
+    where
+        P: ToPrimitive,
+        I: IntoIterator<Item = (P, T)>,
+    {
+        let (weights, values) = validate(pairs)?;
+        let total_mass: f64 = weights.iter().sum();
+        let probabilities = weights.iter().map(|w| w / total_mass).collect();
+        Ok(Density {
+            values,
+            probabilities,
+            total_mass,
+            sub_stochastic: false,
+            alias: WeightedAliasIndex::new(weights).map_err(|_| DensityError::ZeroMass)?,
+        })
+    }
+
+    /// Constructs a new sub-stochastic `Density<T>` from pairs `(probability, value)`,
+    /// without normalizing.
+    ///
+    /// # Errors
+    ///
+    /// If there are no pairs, some probability is negative or not finite,
+    /// all are zero or they add up to more than one.
+    #[inline]
+    pub fn try_sub_stochastic<P, I>(pairs: I) -> Result<Self, DensityError>
+    where
+        P: ToPrimitive,
+        I: IntoIterator<Item = (P, T)>,
+    {
+        let (probabilities, values) = validate(pairs)?;
+        let total_mass: f64 = probabilities.iter().sum();
+        if total_mass > 1.0 + f64::EPSILON * probabilities.len() as f64 {
+            return Err(DensityError::MassAboveOne(total_mass));
+        }
+        Ok(Density {
+            values,
+            alias: WeightedAliasIndex::new(probabilities.clone()).map_err(|_| DensityError::ZeroMass)?,
+            probabilities,
+            total_mass,
+            sub_stochastic: true,
+        })
+    }
+
+    /// Returns the values, in the order they were given.
+    #[inline]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the probability of each value.
+    #[inline]
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+
+    /// Returns the sum of the weights as given when constructing.
+    ///
+    /// For a sub-stochastic density, this is the probability of sampling some value.
+    #[inline]
+    pub fn total_mass(&self) -> f64 {
+        self.total_mass
+    }
+
+    /// Returns `true` if the density was constructed as sub-stochastic.
+    #[inline]
+    pub fn is_sub_stochastic(&self) -> bool {
+        self.sub_stochastic
+    }
+}
+
+/// Checks the weights and splits the pairs.
+#[inline]
+fn validate<P, T, I>(pairs: I) -> Result<(Vec<f64>, Vec<T>), DensityError>
+where
+    P: ToPrimitive,
+    I: IntoIterator<Item = (P, T)>,
+{
+    let mut weights = Vec::new();
+    let mut values = Vec::new();
+    for (weight, value) in pairs {
+        let weight = weight.to_f64().unwrap_or(f64::NAN);
+        if !(weight >= 0.0 && weight.is_finite()) {
+            return Err(DensityError::InvalidWeight(weight));
+        }
+        weights.push(weight);
+        values.push(value);
+    }
+    if weights.is_empty() {
+        Err(DensityError::Empty)
+    } else if weights.iter().all(|&w| w == 0.0) {
+        Err(DensityError::ZeroMass)
+    } else {
+        Ok((weights, values))
+    }
+}
+
+/// Samples a value. For a sub-stochastic density, the value is sampled
+/// conditionally on sampling some value.
+impl<T> Distribution<T> for Density<T>
+where
+    T: Clone,
+{
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.values[self.alias.sample(rng)].clone()
+    }
+}
+
+/// Samples a value, or `None` with probability one minus the total mass
+/// of a sub-stochastic density.
+impl<T> Distribution<Option<T>> for Density<T>
+where
+    T: Clone,
+{
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<T> {
+        if self.sub_stochastic && rng.gen::<f64>() >= self.total_mass {
+            None
+        } else {
+            Some(self.values[self.alias.sample(rng)].clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn construction() {
+        assert_eq!(Density::<u8>::try_new(Vec::<(f64, u8)>::new()).unwrap_err(), DensityError::Empty);
+        assert_eq!(Density::try_new(vec![(-1, 'a')]).unwrap_err(), DensityError::InvalidWeight(-1.));
+        assert_eq!(Density::try_new(vec![(0, 'a')]).unwrap_err(), DensityError::ZeroMass);
+        assert_eq!(Density::try_sub_stochastic(vec![(0.6, 'a'), (0.6, 'b')]).unwrap_err(), DensityError::MassAboveOne(1.2));
+        assert!(Density::try_sub_stochastic(vec![(0.1, 'a'); 10]).is_ok());
+    }
+
+    #[test]
+    fn sampling() {
+        let mut rng = crate::tests::rng(1);
+        let dis = Density::try_new(vec![(0_u32, 'a'), (2, 'b')]).unwrap();
+        for _ in 0..100 {
+            let sample: char = dis.sample(&mut rng);
+            assert_eq!(sample, 'b');
+            let sample: Option<char> = dis.sample(&mut rng);
+            assert_eq!(sample, Some('b'));
+        }
+
+        let dis = Density::try_sub_stochastic(vec![(0.5, 'a')]).unwrap();
+        let nones = (0..1_000).filter(|_| Distribution::<Option<char>>::sample(&dis, &mut rng).is_none()).count();
+        assert!(400 < nones && nones < 600);
+    }
+}
@@ -0,0 +1,223 @@
+// Traits
+use rand::Rng;
+use rand_distr::Distribution;
+
+// Structs
+use crate::errors::DeterminantalError;
+use ndarray::{Array1, Array2};
+
+/// Tolerance for the symmetry and the eigenvalues of kernels.
+const TOLERANCE: f64 = 1e-9;
+
+/// Determinantal point process over the items `0..n`, given by an L-ensemble.
+///
+/// A subset `Y` is sampled with probability `det(L_Y) / det(L + I)`, where `L_Y` is the
+/// submatrix of the kernel `L` indexed by `Y`. Items that are similar, i.e. whose rows in `L`
+/// are close to parallel, rarely appear together, so samples are diverse subsets.
+///
+/// # Costs
+///
+/// **Construction**: O(n^3), where n is the number of items.
+///
+/// **Sample**: O(n k^3), where k is the size of the sample, since the remaining eigenvectors
+/// are orthonormalized again after each selected item.
+///
+/// # Examples
+///
+/// Items on a line, with a Gaussian similarity: close items repel each other.
+/// ```
+/// # use ndarray::Array2;
+/// # use markovian::distributions::Determinantal;
+/// # use rand::distributions::Distribution;
+/// let kernel = Array2::from_shape_fn((10, 10), |(i, j)| (-((i as f64 - j as f64) / 2.).powi(2)).exp());
+/// let dpp = Determinantal::from_kernel(kernel).unwrap();
+/// let subset: Vec<usize> = dpp.sample(&mut rand::thread_rng());
+/// assert!(subset.windows(2).all(|pair| pair[0] < pair[1]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Determinantal {
+    kernel: Array2<f64>,
+    eigenvalues: Array1<f64>,
+    eigenvectors: Array2<f64>,
+}
+
+impl Determinantal {
+    /// Constructs a new `Determinantal` from the symmetric positive semidefinite kernel `l_matrix`.
+    ///
+    /// # Errors
+    ///
+    /// If `l_matrix` is not square, not symmetric, or has a negative eigenvalue.
+    #[inline]
+    pub fn from_kernel(l_matrix: Array2<f64>) -> Result<Self, DeterminantalError> {
+        if l_matrix.nrows() != l_matrix.ncols() {
+            return Err(DeterminantalError::NotSquare);
+        }
+        let scale = l_matrix.iter().fold(1.0, |max: f64, x| max.max(x.abs()));
+        if l_matrix.indexed_iter().any(|((i, j), &x)| !x.is_finite() || (x - l_matrix[[j, i]]).abs() > TOLERANCE * scale) {
+            return Err(DeterminantalError::NotSymmetric);
+        }
+        let (eigenvalues, eigenvectors) = crate::linalg::symmetric_eigen(l_matrix.clone());
+        if let Some(&lambda) = eigenvalues.iter().find(|&&lambda| lambda < -TOLERANCE * scale) {
+            return Err(DeterminantalError::NotPositiveSemidefinite(lambda));
+        }
+        Ok(Determinantal {
+            kernel: l_matrix,
+            eigenvalues: eigenvalues.mapv(|lambda| lambda.max(0.0)),
+            eigenvectors,
+        })
+    }
+
+    /// Returns the number of items.
+    #[inline]
+    pub fn nitems(&self) -> usize {
+        self.kernel.nrows()
+    }
+
+    /// Returns the kernel `L`.
+    #[inline]
+    pub fn kernel(&self) -> &Array2<f64> {
+        &self.kernel
+    }
+
+    /// Returns the eigenvalues of the kernel.
+    #[inline]
+    pub fn eigenvalues(&self) -> &Array1<f64> {
+        &self.eigenvalues
+    }
+
+    /// Returns the expected size of a sample.
+    #[inline]
+    pub fn expected_size(&self) -> f64 {
+        self.eigenvalues.iter().map(|lambda| lambda / (1.0 + lambda)).sum()
+    }
+
+    /// Returns the marginal kernel `K = L (L + I)^{-1}`, whose minors are the probabilities
+    /// that a sample contains a given subset.
+    #[inline]
+    pub fn marginal_kernel(&self) -> Array2<f64> {
+        let weights = self.eigenvalues.mapv(|lambda| lambda / (1.0 + lambda));
+        (&self.eigenvectors * &weights).dot(&self.eigenvectors.t())
+    }
+
+    /// Returns the probability of sampling exactly the items in `subset`.
+    ///
+    /// # Panics
+    ///
+    /// If `subset` has repeated items or items out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::distributions::Determinantal;
+    /// let dpp = Determinantal::from_kernel(array![[1., 0.], [0., 1.]]).unwrap();
+    /// assert!((dpp.probability(&[0, 1]) - 0.25).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn probability(&self, subset: &[usize]) -> f64 {
+        let mut seen = vec![false; self.nitems()];
+        for &item in subset {
+            assert!(!seen[item], "Item {} is repeated in the subset", item);
+            seen[item] = true;
+        }
+        let minor = Array2::from_shape_fn((subset.len(), subset.len()), |(i, j)| self.kernel[[subset[i], subset[j]]]);
+        let normalization: f64 = self.eigenvalues.iter().map(|lambda| 1.0 + lambda).product();
+        crate::linalg::determinant(minor) / normalization
+    }
+}
+
+impl Distribution<Vec<usize>> for Determinantal {
+    /// Samples a subset, sorted increasingly, by the spectral algorithm of Hough, Krishnapur, Peres and Virág.
+    #[inline]
+    fn sample<R>(&self, rng: &mut R) -> Vec<usize>
+    where
+        R: Rng + ?Sized,
+    {
+        // Elementary process: each eigenvector is selected independently
+        let mut vectors: Vec<Array1<f64>> = self
+            .eigenvalues
+            .iter()
+            .enumerate()
+            .filter(|(_, &lambda)| rng.gen::<f64>() * (1.0 + lambda) < lambda)
+            .map(|(k, _)| self.eigenvectors.column(k).to_owned())
+            .collect();
+
+        let n = self.nitems();
+        let mut subset = Vec::with_capacity(vectors.len());
+        while !vectors.is_empty() {
+            // Item i with probability proportional to the squared norm of the projection of e_i
+            let weights: Vec<f64> = (0..n).map(|i| vectors.iter().map(|v| v[i] * v[i]).sum()).collect();
+            let mut threshold = rng.gen::<f64>() * weights.iter().sum::<f64>();
+            let mut item = n - 1;
+            for (i, weight) in weights.iter().enumerate() {
+                if threshold < *weight {
+                    item = i;
+                    break;
+                }
+                threshold -= weight;
+            }
+            subset.push(item);
+
+            // Restrict to the subspace orthogonal to e_item
+            let pivot = (0..vectors.len())
+                .fold(0, |best, k| if vectors[k][item].abs() > vectors[best][item].abs() { k } else { best });
+            let pivot = vectors.swap_remove(pivot);
+            for v in vectors.iter_mut() {
+                let factor = v[item] / pivot[item];
+                v.scaled_add(-factor, &pivot);
+            }
+            // Gram-Schmidt
+            for k in 0..vectors.len() {
+                for l in 0..k {
+                    let projection = vectors[k].dot(&vectors[l]);
+                    let previous = vectors[l].clone();
+                    vectors[k].scaled_add(-projection, &previous);
+                }
+                let norm = vectors[k].dot(&vectors[k]).sqrt();
+                vectors[k] /= norm;
+            }
+        }
+        subset.sort_unstable();
+        subset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn construction() {
+        assert_eq!(Determinantal::from_kernel(Array2::zeros((2, 3))).unwrap_err(), DeterminantalError::NotSquare);
+        assert_eq!(Determinantal::from_kernel(array![[1., 0.], [1., 1.]]).unwrap_err(), DeterminantalError::NotSymmetric);
+        assert!(matches!(
+            Determinantal::from_kernel(array![[1., 2.], [2., 1.]]),
+            Err(DeterminantalError::NotPositiveSemidefinite(_))
+        ));
+    }
+
+    #[test]
+    fn sampling() {
+        let mut rng = crate::tests::rng(1);
+        let kernel = array![[2., 1., 0.], [1., 2., 1.], [0., 1., 2.]];
+        let dpp = Determinantal::from_kernel(kernel).unwrap();
+        let subsets: Vec<Vec<usize>> = vec![vec![], vec![0], vec![1], vec![2], vec![0, 1], vec![0, 2], vec![1, 2], vec![0, 1, 2]];
+        let total: f64 = subsets.iter().map(|subset| dpp.probability(subset)).sum();
+        assert_abs_diff_eq!(total, 1., epsilon = 1e-12);
+
+        let samples = 20_000;
+        let mut counts = vec![0; subsets.len()];
+        for _ in 0..samples {
+            let sample = dpp.sample(&mut rng);
+            counts[subsets.iter().position(|subset| subset == &sample).unwrap()] += 1;
+        }
+        for (subset, count) in subsets.iter().zip(counts) {
+            assert_abs_diff_eq!(count as f64 / samples as f64, dpp.probability(subset), epsilon = 0.015);
+        }
+        let inclusion = dpp.marginal_kernel();
+        assert_abs_diff_eq!(inclusion[[0, 0]], subsets.iter().filter(|s| s.contains(&0)).map(|s| dpp.probability(s)).sum(), epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,148 @@
+// Traits
+use core::hash::Hash;
+use rand::Rng;
+use rand_distr::Distribution;
+
+// Structs
+use rand_distr::{weighted_alias::WeightedAliasIndex, WeightedError};
+use std::collections::HashMap;
+
+/// Empirical distribution of observed values.
+///
+/// Each distinct value is sampled with probability proportional to the number
+/// of times it was observed, or to its total weight.
+///
+/// # Costs
+///
+/// Construction cost: O(n), n: number of samples.
+/// Sample cost: O(1).
+///
+/// # Examples
+///
+/// Driving transitions from observed data.
+/// ```
+/// # use rand::prelude::*;
+/// # use markovian::distributions::Empirical;
+/// let dis = Empirical::from_samples(&['a', 'b', 'a', 'a']).unwrap();
+/// assert_eq!(dis.values(), &['a', 'b']);
+/// assert_eq!(dis.probability(&'a'), 0.75);
+/// println!("Sampled {}", dis.sample(&mut thread_rng()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Empirical<T> {
+    values: Vec<T>,
+    weights: Vec<f64>,
+    total: f64,
+    alias: WeightedAliasIndex<f64>,
+}
+
+impl<T> Empirical<T>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Constructs a new `Empirical<T>` from observed values.
+    ///
+    /// # Errors
+    ///
+    /// If `samples` is empty.
+    #[inline]
+    pub fn from_samples(samples: &[T]) -> Result<Self, WeightedError> {
+        Self::from_weighted_samples(samples.iter().map(|value| (value.clone(), 1.0)))
+    }
+
+    /// Constructs a new `Empirical<T>` from pairs `(value, weight)`, for example,
+    /// values with their counts. Weights of repeated values are added up.
+    ///
+    /// # Errors
+    ///
+    /// If there are no pairs, some weight is negative or not finite,
+    /// or all weights are zero.
+    #[inline]
+    pub fn from_weighted_samples<I>(samples: I) -> Result<Self, WeightedError>
+    where
+        I: IntoIterator<Item = (T, f64)>,
+    {
+        let mut indexes: HashMap<T, usize> = HashMap::new();
+        let mut values = Vec::new();
+        let mut weights: Vec<f64> = Vec::new();
+        for (value, weight) in samples {
+            if !(weight >= 0.0 && weight.is_finite()) {
+                return Err(WeightedError::InvalidWeight);
+            }
+            match indexes.get(&value) {
+                Some(&index) => weights[index] += weight,
+                None => {
+                    indexes.insert(value.clone(), values.len());
+                    values.push(value);
+                    weights.push(weight);
+                }
+            }
+        }
+        let alias = WeightedAliasIndex::new(weights.clone())?;
+        Ok(Empirical {
+            values,
+            total: weights.iter().sum(),
+            weights,
+            alias,
+        })
+    }
+
+    /// Returns the distinct observed values, in order of first appearance.
+    #[inline]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the total weight of each distinct value, in the same order as [values].
+    ///
+    /// [values]: struct.Empirical.html#method.values
+    #[inline]
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Returns the probability of sampling `value`, which is zero if it was never observed.
+    #[inline]
+    pub fn probability(&self, value: &T) -> f64 {
+        self.values.iter()
+            .position(|v| v == value)
+            .map_or(0.0, |index| self.weights[index] / self.total)
+    }
+}
+
+impl<T> Distribution<T> for Empirical<T>
+where
+    T: Clone,
+{
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.values[self.alias.sample(rng)].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn construction() {
+        assert!(Empirical::<u32>::from_samples(&[]).is_err());
+        assert!(Empirical::from_weighted_samples(vec![(1, -1.0)]).is_err());
+        assert!(Empirical::from_weighted_samples(vec![(1, 0.0)]).is_err());
+
+        let dis = Empirical::from_weighted_samples(vec![(1, 2.0), (2, 1.0), (1, 1.0)]).unwrap();
+        assert_eq!(dis.values(), &[1, 2]);
+        assert_eq!(dis.weights(), &[3.0, 1.0]);
+        assert_eq!(dis.probability(&3), 0.0);
+    }
+
+    #[test]
+    fn sampling() {
+        let mut rng = crate::tests::rng(1);
+        let dis = Empirical::from_weighted_samples(vec![(1, 1.0), (2, 0.0)]).unwrap();
+        for _ in 0..100 {
+            assert_eq!(dis.sample(&mut rng), 1);
+        }
+    }
+}
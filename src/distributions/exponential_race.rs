@@ -0,0 +1,155 @@
+// Traits
+use core::fmt::Debug;
+use num_traits::Float;
+use rand::Rng;
+use rand_distr::{weighted_alias::{WeightedAliasIndex, AliasableWeight}, Distribution, Uniform};
+
+// Structs
+use rand_distr::{Exp, Exp1, WeightedError};
+
+/// Race of independent exponential clocks.
+///
+/// Samples `(winner_index, time)`, where `time` is the minimum of independent
+/// exponential random variables with rates `rates` and `winner_index` is the index
+/// of the clock attaining it. The time is exponential with the sum of all rates and,
+/// independently, each clock wins with probability proportional to its rate.
+///
+/// # Costs
+///
+/// Construction cost: O(n), n: number of clocks.
+/// Sample cost: O(1).
+///
+/// # Examples
+///
+/// Building a custom continuous time transition: a queue where arrivals
+/// have rate 1 and departures rate 2.
+/// ```
+/// # use rand::prelude::*;
+/// # use markovian::{distributions::ExponentialRace, Transition};
+/// struct Queue;
+/// impl Transition<u32, (f64, u32)> for Queue {
+///     fn sample_from<R: ?Sized>(&self, state: &u32, rng: &mut R) -> (f64, u32)
+///     where
+///         R: Rng
+///     {
+///         if *state == 0 {
+///             let (_, time) = ExponentialRace::new(vec![1.0]).unwrap().sample(rng);
+///             return (time, 1);
+///         }
+///         match ExponentialRace::new(vec![1.0, 2.0]).unwrap().sample(rng) {
+///             (0, time) => (time, state + 1),
+///             (_, time) => (time, state - 1),
+///         }
+///     }
+/// }
+/// let mut mc = markovian::TimedMarkovChain::new(0, Queue, thread_rng());
+/// assert_eq!(mc.next().unwrap().1, 1);
+/// ```
+pub struct ExponentialRace<W>
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+{
+    winner: WeightedAliasIndex<W>,
+    clock: Exp<W>,
+    total_rate: W,
+}
+
+impl<W> ExponentialRace<W>
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+{
+    /// Constructs a new `ExponentialRace<W>` with one clock per rate.
+    ///
+    /// # Errors
+    ///
+    /// If `rates` is not a valid vector of weights, for example, if it is empty,
+    /// has negative entries or all rates are zero.
+    #[inline]
+    pub fn new(rates: Vec<W>) -> Result<Self, WeightedError> {
+        let total_rate = rates.iter().copied().fold(W::zero(), |acc, rate| acc + rate);
+        let winner = WeightedAliasIndex::new(rates)?;
+        Ok(ExponentialRace {
+            winner,
+            clock: Exp::new(total_rate).map_err(|_| WeightedError::InvalidWeight)?,
+            total_rate,
+        })
+    }
+
+    /// Returns the sum of all rates, i.e. the rate of the time of the race.
+    #[inline]
+    pub fn total_rate(&self) -> W {
+        self.total_rate
+    }
+}
+
+impl<W> Distribution<(usize, W)> for ExponentialRace<W>
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+{
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> (usize, W) {
+        (self.winner.sample(rng), self.clock.sample(rng))
+    }
+}
+
+impl<W> Debug for ExponentialRace<W>
+where
+    W: Float + AliasableWeight + Debug,
+    Uniform<W>: Debug,
+    Exp1: Distribution<W>,
+{
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ExponentialRace")
+            .field("winner", &self.winner)
+            .field("clock", &self.clock)
+            .field("total_rate", &self.total_rate)
+            .finish()
+    }
+}
+
+impl<W> Clone for ExponentialRace<W>
+where
+    W: Float + AliasableWeight,
+    Uniform<W>: Clone,
+    Exp1: Distribution<W>,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        ExponentialRace {
+            winner: self.winner.clone(),
+            clock: self.clock,
+            total_rate: self.total_rate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn construction() {
+        assert!(ExponentialRace::new(vec![1.0, 2.0]).is_ok());
+        assert!(ExponentialRace::<f64>::new(Vec::new()).is_err());
+        assert!(ExponentialRace::new(vec![0.0, 0.0]).is_err());
+        assert!(ExponentialRace::new(vec![-1.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn correctness() {
+        let mut rng = crate::tests::rng(1);
+        let race = ExponentialRace::new(vec![1.0, 3.0]).unwrap();
+        let samples = 10_000;
+        let (wins, total_time) = (0..samples)
+            .map(|_| race.sample(&mut rng))
+            .fold((0, 0.0), |(wins, time), (winner, t)| (wins + winner, time + t));
+
+        assert_abs_diff_eq!(wins as f64 / samples as f64, 0.75, epsilon = 0.02);
+        assert_abs_diff_eq!(total_time / samples as f64, 0.25, epsilon = 0.02);
+    }
+}
@@ -0,0 +1,363 @@
+// Traits
+use rand::Rng;
+use rand_distr::Distribution;
+
+// Structs
+use crate::errors::PhaseTypeError;
+use ndarray::{Array1, Array2};
+use rand_distr::{weighted_alias::WeightedAliasIndex, Exp};
+
+/// Tolerance for the sums of probabilities and rates.
+const TOLERANCE: f64 = 1e-9;
+
+/// Relative increase of the log-likelihood below which the EM algorithm stops.
+const EM_TOLERANCE: f64 = 1e-10;
+
+/// Phase-type distribution: the time until absorption of a chain in continuous time
+/// with transient phases `0..n` and one absorbing state.
+///
+/// The chain starts in phase `i` with probability `initial[i]` and is absorbed immediately
+/// with the remaining probability. Rates between phases are the off-diagonal entries of
+/// `subgenerator`, while the rate of absorption from each phase makes its row sum up to zero.
+///
+/// # Costs
+///
+/// **Construction**: O(n^3), where n is the number of phases.
+///
+/// **Sample**: O(1) per jump until absorption.
+///
+/// # Examples
+///
+/// An Erlang distribution with two phases of rate 2.
+/// ```
+/// # use ndarray::array;
+/// # use markovian::distributions::PhaseType;
+/// let erlang = PhaseType::new(array![1., 0.], array![[-2., 2.], [0., -2.]]).unwrap();
+/// assert!((erlang.mean() - 1.).abs() < 1e-12);
+/// assert!((erlang.cdf(1.) - (1. - 3. * (-2f64).exp())).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PhaseType {
+    initial: Array1<f64>,
+    subgenerator: Array2<f64>,
+    exit_rates: Array1<f64>,
+    green: Array2<f64>,
+    initial_variable: WeightedAliasIndex<f64>,
+    jump_variables: Vec<WeightedAliasIndex<f64>>,
+}
+
+impl PhaseType {
+    /// Constructs a new `PhaseType` from its initial probabilities and subgenerator.
+    ///
+    /// # Errors
+    ///
+    /// If dimensions do not match, `initial` is not a (sub-)probability vector,
+    /// `subgenerator` is not a subgenerator, or absorption is not certain from every phase.
+    #[inline]
+    pub fn new(initial: Array1<f64>, subgenerator: Array2<f64>) -> Result<Self, PhaseTypeError> {
+        let n = initial.len();
+        if subgenerator.dim() != (n, n) {
+            return Err(PhaseTypeError::DimensionMismatch);
+        }
+        if initial.iter().any(|&p| p < 0.0 || !p.is_finite()) || initial.sum() > 1.0 + TOLERANCE {
+            return Err(PhaseTypeError::InvalidInitial);
+        }
+        let invalid_rate = subgenerator
+            .indexed_iter()
+            .any(|((i, j), &q)| !q.is_finite() || (i != j && q < 0.0));
+        let exit_rates = -subgenerator.sum_axis(ndarray::Axis(1));
+        if invalid_rate || exit_rates.iter().any(|&rate| rate < -TOLERANCE) {
+            return Err(PhaseTypeError::InvalidSubgenerator);
+        }
+        let exit_rates = exit_rates.mapv(|rate| rate.max(0.0));
+        let green = crate::linalg::inverse(-&subgenerator).ok_or(PhaseTypeError::NotTransient)?;
+        if green.iter().any(|&x| !x.is_finite()) {
+            return Err(PhaseTypeError::NotTransient);
+        }
+
+        let mut initial_weights = initial.to_vec();
+        initial_weights.push((1.0 - initial.sum()).max(0.0));
+        let initial_variable = WeightedAliasIndex::new(initial_weights).map_err(|_| PhaseTypeError::InvalidInitial)?;
+        let jump_variables = (0..n)
+            .map(|i| {
+                let mut weights: Vec<f64> = (0..n).map(|j| if i == j { 0.0 } else { subgenerator[[i, j]] }).collect();
+                weights.push(exit_rates[i]);
+                WeightedAliasIndex::new(weights).map_err(|_| PhaseTypeError::NotTransient)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(PhaseType {
+            initial,
+            subgenerator,
+            exit_rates,
+            green,
+            initial_variable,
+            jump_variables,
+        })
+    }
+
+    /// Returns the number of phases.
+    #[inline]
+    pub fn order(&self) -> usize {
+        self.initial.len()
+    }
+
+    /// Returns the initial probabilities of the phases.
+    #[inline]
+    pub fn initial(&self) -> &Array1<f64> {
+        &self.initial
+    }
+
+    /// Returns the subgenerator, i.e. the rates between phases.
+    #[inline]
+    pub fn subgenerator(&self) -> &Array2<f64> {
+        &self.subgenerator
+    }
+
+    /// Returns the rates of absorption from each phase.
+    #[inline]
+    pub fn exit_rates(&self) -> &Array1<f64> {
+        &self.exit_rates
+    }
+
+    /// Returns the density at `x`, i.e. `α exp(T x) t`,
+    /// where `α` are the initial probabilities, `T` the subgenerator and `t` the exit rates.
+    ///
+    /// The atom at zero, if any, is not part of the density.
+    #[inline]
+    pub fn pdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            return 0.0;
+        }
+        self.survival_vector(x).dot(&self.exit_rates)
+    }
+
+    /// Returns the cumulative distribution function at `x`, i.e. `1 - α exp(T x) 1`.
+    #[inline]
+    pub fn cdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            return 0.0;
+        }
+        1.0 - self.survival_vector(x).sum()
+    }
+
+    /// Returns the moment of order `k`, i.e. `k! α (-T)^{-k} 1`.
+    #[inline]
+    pub fn moment(&self, k: u32) -> f64 {
+        let mut vector = self.initial.clone();
+        let mut factorial = 1.0;
+        for j in 1..=k {
+            vector = vector.dot(&self.green);
+            factorial *= j as f64;
+        }
+        factorial * vector.sum()
+    }
+
+    /// Returns the mean.
+    #[inline]
+    pub fn mean(&self) -> f64 {
+        self.moment(1)
+    }
+
+    /// Returns the variance.
+    #[inline]
+    pub fn variance(&self) -> f64 {
+        self.moment(2) - self.mean().powi(2)
+    }
+
+    /// Returns the phase-type distribution with `order` phases fitted to `samples`
+    /// by the expectation-maximization (EM) algorithm, for at most `max_iterations` iterations.
+    ///
+    /// The algorithm starts from phases with different rates, where every transition is possible,
+    /// and stops once the log-likelihood stops increasing.
+    /// At each iteration, the expected number of starts in each phase, jumps between phases and
+    /// absorptions, and the expected time spent in each phase, given each sample,
+    /// are computed exactly by matrix exponentials, and parameters are updated as their ratios.
+    ///
+    /// # Costs
+    ///
+    /// O(m n^3) per iteration, where m is the number of samples and n is `order`.
+    ///
+    /// # Remarks
+    ///
+    /// The likelihood may have several local maxima, so the fit is only a local maximum.
+    /// Phase-type distributions are not identifiable: different parameters may define
+    /// the same distribution, so only the distribution should be interpreted.
+    ///
+    /// # Panics
+    ///
+    /// If `order` is zero, `samples` is empty or some sample is negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// Fitting an Erlang distribution.
+    /// ```
+    /// # use ndarray::array;
+    /// # use rand_distr::Distribution;
+    /// # use markovian::distributions::PhaseType;
+    /// let erlang = PhaseType::new(array![1., 0.], array![[-2., 2.], [0., -2.]]).unwrap();
+    /// let samples: Vec<f64> = erlang.sample_iter(rand::thread_rng()).take(500).collect();
+    /// let fit = PhaseType::fit(&samples, 2, 200);
+    /// assert!((fit.mean() - 1.).abs() < 0.2);
+    /// ```
+    #[inline]
+    pub fn fit(samples: &[f64], order: usize, max_iterations: usize) -> Self {
+        assert!(order > 0, "There must be at least one phase.");
+        assert!(!samples.is_empty(), "There must be at least one sample.");
+        assert!(
+            samples.iter().all(|&x| x >= 0.0 && x.is_finite()),
+            "Samples must be non-negative and finite."
+        );
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let scale = order as f64 / mean.max(f64::MIN_POSITIVE);
+        let initial = Array1::from_elem(order, 1.0 / order as f64);
+        let subgenerator = Array2::from_shape_fn((order, order), |(i, j)| {
+            let rate = scale * (i + 1) as f64 / order as f64;
+            if i == j {
+                -rate
+            } else {
+                rate / (2 * order) as f64
+            }
+        });
+        let mut distribution = PhaseType::new(initial, subgenerator).expect("Initial parameters are valid.");
+        let mut log_likelihood = f64::NEG_INFINITY;
+        for _ in 0..max_iterations {
+            let (next, previous) = distribution.em_step(samples);
+            distribution = next;
+            if previous - log_likelihood <= EM_TOLERANCE * previous.abs() {
+                break;
+            }
+            log_likelihood = previous;
+        }
+        distribution
+    }
+
+    /// Returns the distribution after one iteration of the EM algorithm,
+    /// together with the log-likelihood of `samples` before the iteration.
+    ///
+    /// For a sample `y`, the expected time in phase `i` and the expected number of jumps
+    /// from `i` to `j` are, respectively, `C_ii` and `T_ij C_ji`, divided by the density at `y`,
+    /// where `C` is the integral of `exp(T (y - u)) t α exp(T u)` over `[0, y]`.
+    /// This integral is a block of the exponential of `[[T, t α], [0, T]] y`.
+    #[inline]
+    fn em_step(&self, samples: &[f64]) -> (Self, f64) {
+        let n = self.order();
+        let mut starts = Array1::<f64>::zeros(n);
+        let mut times = Array1::<f64>::zeros(n);
+        let mut jumps = Array2::<f64>::zeros((n, n));
+        let mut exits = Array1::<f64>::zeros(n);
+        let mut log_likelihood = 0.0;
+
+        let mut block = Array2::zeros((2 * n, 2 * n));
+        for ((i, j), &q) in self.subgenerator.indexed_iter() {
+            block[[i, j]] = q;
+            block[[n + i, n + j]] = q;
+            block[[i, n + j]] = self.exit_rates[i] * self.initial[j];
+        }
+        for &y in samples {
+            let exponential = crate::linalg::expm_metzler(&block, y);
+            let transition = exponential.slice(ndarray::s![..n, ..n]);
+            let integral = exponential.slice(ndarray::s![..n, n..]);
+            let forward = self.initial.dot(&transition);
+            let backward = transition.dot(&self.exit_rates);
+            let density = self.initial.dot(&backward).max(f64::MIN_POSITIVE);
+            log_likelihood += density.ln();
+            starts += &(&self.initial * &backward / density);
+            exits += &(&forward * &self.exit_rates / density);
+            for i in 0..n {
+                times[i] += integral[[i, i]] / density;
+                for j in (0..n).filter(|&j| j != i) {
+                    jumps[[i, j]] += self.subgenerator[[i, j]] * integral[[j, i]] / density;
+                }
+            }
+        }
+
+        let initial = starts / samples.len() as f64;
+        let mut subgenerator = Array2::from_shape_fn((n, n), |(i, j)| {
+            if i == j || times[i] <= 0.0 {
+                0.0
+            } else {
+                jumps[[i, j]] / times[i]
+            }
+        });
+        for i in 0..n {
+            let exit_rate = if times[i] > 0.0 { exits[i] / times[i] } else { self.exit_rates[i] };
+            subgenerator[[i, i]] = -(subgenerator.row(i).sum() + exit_rate);
+        }
+        let distribution = PhaseType::new(initial, subgenerator).expect("The EM algorithm preserves valid parameters.");
+        (distribution, log_likelihood)
+    }
+
+    /// Returns `α exp(T x)`, the probabilities of being in each phase at time `x`.
+    #[inline]
+    fn survival_vector(&self, x: f64) -> Array1<f64> {
+        self.initial.dot(&crate::linalg::expm_metzler(&self.subgenerator, x))
+    }
+}
+
+impl Distribution<f64> for PhaseType {
+    /// Samples an absorption time by simulating the phases.
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let n = self.order();
+        let mut phase = self.initial_variable.sample(rng);
+        let mut time = 0.0;
+        while phase < n {
+            time += Exp::new(-self.subgenerator[[phase, phase]]).unwrap().sample(rng);
+            phase = self.jump_variables[phase].sample(rng);
+        }
+        time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn construction() {
+        assert_eq!(
+            PhaseType::new(array![1.], array![[-1., 0.], [0., -1.]]).unwrap_err(),
+            PhaseTypeError::DimensionMismatch
+        );
+        assert_eq!(PhaseType::new(array![0.6, 0.6], array![[-1., 0.], [0., -1.]]).unwrap_err(), PhaseTypeError::InvalidInitial);
+        assert_eq!(PhaseType::new(array![1., 0.], array![[-1., 2.], [0., -1.]]).unwrap_err(), PhaseTypeError::InvalidSubgenerator);
+        assert_eq!(PhaseType::new(array![1., 0.], array![[-1., 1.], [1., -1.]]).unwrap_err(), PhaseTypeError::NotTransient);
+    }
+
+    #[test]
+    fn hyperexponential() {
+        // Mixture of exponentials with rates 1 and 4, and an atom at zero
+        let distribution = PhaseType::new(array![0.5, 0.25], array![[-1., 0.], [0., -4.]]).unwrap();
+        assert_abs_diff_eq!(distribution.mean(), 0.5 + 0.25 / 4., epsilon = 1e-12);
+        assert_abs_diff_eq!(distribution.moment(2), 0.5 * 2. + 0.25 * 2. / 16., epsilon = 1e-12);
+        assert_abs_diff_eq!(distribution.cdf(0.), 0.25, epsilon = 1e-12);
+        let x = 0.7_f64;
+        assert_abs_diff_eq!(distribution.pdf(x), 0.5 * (-x).exp() + (-4. * x).exp(), epsilon = 1e-12);
+
+        let mut rng = crate::tests::rng(1);
+        let samples: Vec<f64> = (0..20_000).map(|_| distribution.sample(&mut rng)).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        assert_abs_diff_eq!(mean, distribution.mean(), epsilon = 0.03);
+        let zeros = samples.iter().filter(|&&x| x == 0.).count() as f64 / samples.len() as f64;
+        assert_abs_diff_eq!(zeros, 0.25, epsilon = 0.02);
+    }
+
+    #[test]
+    fn fit() {
+        let distribution = PhaseType::new(array![0.7, 0.3], array![[-1., 0.], [0., -10.]]).unwrap();
+        let mut rng = crate::tests::rng(2);
+        let samples: Vec<f64> = (0..500).map(|_| distribution.sample(&mut rng)).collect();
+        let fit = PhaseType::fit(&samples, 2, 50);
+
+        assert_abs_diff_eq!(fit.initial().sum(), 1., epsilon = 1e-9);
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        assert_abs_diff_eq!(fit.mean(), mean, epsilon = 0.02);
+        for &x in &[0.1, 0.5, 2.] {
+            let empirical = samples.iter().filter(|&&y| y <= x).count() as f64 / samples.len() as f64;
+            assert_abs_diff_eq!(fit.cdf(x), empirical, epsilon = 0.05);
+        }
+    }
+}
@@ -4,6 +4,8 @@ use num_traits::{One, Zero};
 use rand::Rng;
 use rand_distr::Distribution;
 
+// Structs
+
 // use num_traits::Zero;
 
 /// Distribution over possibly infinte iterators. 
@@ -50,6 +52,219 @@ impl<I> Raw<I> {
     pub fn new(iter: I) -> Self {
         Raw { iter }
     }
+
+    /// Precomputes an alias table, so that sampling costs O(1).
+    ///
+    /// Only finite iterators can be compiled.
+    ///
+    /// # Panics
+    ///
+    /// If probabilities are negative, not finite or all zero.
+    ///
+    /// # Examples
+    ///
+    /// Compiling a density before sampling many times from it.
+    /// ```
+    /// # use markovian::prelude::*;
+    /// # use rand::prelude::*;
+    /// let dis = raw_dist![(0.3, 0), (0.4, 1), (0.3, 2)].compile();
+    /// let sample: u32 = dis.sample(&mut thread_rng());
+    /// assert!(sample <= 2);
+    /// ```
+    #[inline]
+    pub fn compile<P, T>(self) -> CompiledRaw<T>
+    where
+        P: Debug,
+        f64: From<P>,
+        I: IntoIterator<Item = (P, T)>,
+    {
+        let (probabilities, values): (Vec<f64>, Vec<T>) = self.iter.into_iter()
+            .map(|(prob, value)| {
+                let prob = f64::from(prob);
+                assert!(prob >= 0.0 && prob.is_finite(), "Probabilities must be non-negative and finite. Tried to use {:?}", prob);
+                (prob, value)
+            })
+            .unzip();
+        let (thresholds, aliases) = alias_table(probabilities);
+        CompiledRaw {
+            values,
+            thresholds,
+            aliases,
+        }
+    }
+}
+
+impl<P, T, I> Raw<I>
+where
+    P: Zero + One + PartialOrd + Debug + Copy,
+    f64: From<P>,
+    I: IntoIterator<Item = (P, T)> + Clone,
+    T: Clone,
+{
+    /// Returns `n` independent samples.
+    ///
+    /// All uniform random numbers are generated at once and sorted, so that the iterator
+    /// is traversed only once, instead of once per sample.
+    ///
+    /// # Costs
+    ///
+    /// O(n log n + iterator length), instead of O(n iterator length) for `n` calls to `sample`.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [sample].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use markovian::prelude::*;
+    /// let dis = raw_dist![(0.5, 'a'), (0.5, 'b')];
+    /// let samples = dis.sample_batch(1_000, &mut rand::thread_rng());
+    /// assert_eq!(samples.len(), 1_000);
+    /// ```
+    ///
+    /// [sample]: struct.Raw.html#impl-Distribution<T>
+    #[inline]
+    pub fn sample_batch<R>(&self, n: usize, rng: &mut R) -> Vec<T>
+    where
+        R: Rng + ?Sized,
+    {
+        let uniforms = uniforms(n, rng);
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_unstable_by(|&a, &b| uniforms[a].partial_cmp(&uniforms[b]).unwrap());
+
+        let mut samples: Vec<Option<T>> = vec![None; n];
+        let mut pending = order.into_iter().peekable();
+        let mut acc: f64 = 0.0;
+        let one = f64::from(P::one());
+        for (prob, state) in self.iter.clone() {
+            if pending.peek().is_none() {
+                break;
+            }
+            assert!(P::zero() <= prob, "Probabilities can not be negative. Tried to use {:?}", prob);
+            assert!(one >= acc, "Probabilities can not be more than one. Tried to use {:?}", acc);
+            acc += f64::from(prob);
+            while let Some(index) = pending.next_if(|&index| acc >= uniforms[index]) {
+                samples[index] = Some(state.clone());
+            }
+        }
+        assert!(pending.peek().is_none(), "Sampling was not possible: probabilities did not cover all posiibilities.");
+        samples.into_iter().map(|sample| sample.unwrap()).collect()
+    }
+}
+
+/// Returns `n` uniform random numbers in `[0, 1)`, generated at once.
+#[inline]
+fn uniforms<R>(n: usize, rng: &mut R) -> Vec<f64>
+where
+    R: Rng + ?Sized,
+{
+    let mut bits = vec![0_u64; n];
+    rng.fill(&mut bits[..]);
+    // Same conversion as the standard distribution: the 53 most significant bits
+    bits.into_iter().map(|x| (x >> 11) as f64 * (1.0 / (1_u64 << 53) as f64)).collect()
+}
+
+/// Returns the alias table of `weights` by Vose's method: the threshold below which
+/// each column keeps its own index and the index it is aliased to otherwise.
+///
+/// # Panics
+///
+/// If weights are empty or all zero.
+#[inline]
+fn alias_table(weights: Vec<f64>) -> (Vec<f64>, Vec<usize>) {
+    let n = weights.len();
+    let total: f64 = weights.iter().sum();
+    assert!(n > 0 && total > 0.0, "Probabilities can not be empty nor all zero.");
+    let mut thresholds: Vec<f64> = weights.into_iter().map(|weight| weight * n as f64 / total).collect();
+    let mut aliases: Vec<usize> = (0..n).collect();
+    let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..n).partition(|&i| thresholds[i] < 1.0);
+    while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+        small.pop();
+        aliases[s] = l;
+        thresholds[l] -= 1.0 - thresholds[s];
+        if thresholds[l] < 1.0 {
+            large.pop();
+            small.push(l);
+        }
+    }
+    // Leftovers are full columns, up to rounding errors
+    for i in small.into_iter().chain(large) {
+        thresholds[i] = 1.0;
+    }
+    (thresholds, aliases)
+}
+
+/// Distribution over finitely many values, obtained by [compiling] a `Raw`.
+///
+/// # Costs
+///
+/// Sample cost: O(1).
+/// Construction cost: O(length).
+///
+/// [compiling]: struct.Raw.html#method.compile
+#[derive(Debug, Clone)]
+pub struct CompiledRaw<T> {
+    values: Vec<T>,
+    thresholds: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl<T> CompiledRaw<T> {
+    /// Returns the index of the alias table given by the uniform random number `u` in `[0, 1)`:
+    /// its integer part picks a column and its fractional part decides between the column and its alias.
+    #[inline]
+    fn index(&self, u: f64) -> usize {
+        let x = u * self.values.len() as f64;
+        let column = (x as usize).min(self.values.len() - 1);
+        if x - (column as f64) < self.thresholds[column] {
+            column
+        } else {
+            self.aliases[column]
+        }
+    }
+}
+
+impl<T> CompiledRaw<T>
+where
+    T: Clone,
+{
+    /// Returns `n` independent samples.
+    ///
+    /// All uniform random numbers are generated at once, filling a buffer,
+    /// and each one indexes the alias table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use markovian::prelude::*;
+    /// let dis = raw_dist![(0.3, 0), (0.7, 1)].compile();
+    /// let samples: Vec<u32> = dis.sample_batch(1_000, &mut rand::thread_rng());
+    /// assert!(samples.iter().all(|&x| x <= 1));
+    /// ```
+    #[inline]
+    pub fn sample_batch<R>(&self, n: usize, rng: &mut R) -> Vec<T>
+    where
+        R: Rng + ?Sized,
+    {
+        uniforms(n, rng)
+            .into_iter()
+            .map(|u| self.values[self.index(u)].clone())
+            .collect()
+    }
+}
+
+impl<T> Distribution<T> for CompiledRaw<T>
+where
+    T: Clone,
+{
+    #[inline]
+    fn sample<R>(&self, rng: &mut R) -> T
+    where
+        R: Rng + ?Sized,
+    {
+        self.values[self.index(rng.gen())].clone()
+    }
 }
 
 impl<P, T, I> Distribution<T> for Raw<I>
@@ -135,4 +350,47 @@ mod tests {
 
         assert_eq!(sample, expected);
     }
+
+    #[test]
+    fn compile() {
+        let mut rng = crate::tests::rng(3);
+        let dis = raw_dist![(0.0, 1), (1.0, 2)].compile();
+        for _ in 0..100 {
+            assert_eq!(dis.sample(&mut rng), 2);
+        }
+    }
+
+    #[test]
+    fn sample_batch() {
+        let mut rng = crate::tests::rng(4);
+        let dis = raw_dist![(0.2, 0), (0.0, 1), (0.5, 2), (0.3, 3)];
+        let samples = dis.sample_batch(10_000, &mut rng);
+        let compiled = dis.clone().compile().sample_batch(10_000, &mut rng);
+        for batch in [samples, compiled].iter() {
+            let mut frequencies = [0_f64; 4];
+            for &x in batch {
+                frequencies[x as usize] += 1e-4;
+            }
+            for (frequency, exact) in frequencies.iter().zip(&[0.2, 0.0, 0.5, 0.3]) {
+                assert!((frequency - exact).abs() < 0.02);
+            }
+        }
+        // Samples are in random order
+        let samples = dis.sample_batch(100, &mut rng);
+        assert!(samples.windows(2).any(|w| w[0] > w[1]));
+    }
+
+    #[test]
+    fn alias_table() {
+        let weights = [0.2, 0.0, 0.5, 0.3, 1.0];
+        let (thresholds, aliases) = super::alias_table(weights.to_vec());
+        let mut probabilities = [0_f64; 5];
+        for (column, (threshold, &alias)) in thresholds.iter().zip(&aliases).enumerate() {
+            probabilities[column] += threshold / 5.;
+            probabilities[alias] += (1. - threshold) / 5.;
+        }
+        for (probability, weight) in probabilities.iter().zip(&weights) {
+            assert!((probability - weight / 2.).abs() < 1e-12);
+        }
+    }
 }
@@ -0,0 +1,136 @@
+// Traits
+use crate::StochasticProcess;
+use num_traits::ToPrimitive;
+
+/// Boxed process with states of type `T` and time measured in `f64`,
+/// so that processes of different types can be stored together.
+///
+/// Continuous-time processes keep their periods, converted to `f64`,
+/// while discrete-time processes take one unit of time per step.
+///
+/// # Examples
+///
+/// A simulator managing a random walk and a Poisson process.
+/// ```
+/// # use markovian::{DynProcess, MarkovChain, StochasticProcess, prelude::*};
+/// let transition = |state: &u32| raw_dist![(0.5, state + 1), (0.5, *state)];
+/// let mut processes = vec![
+///     DynProcess::discrete(MarkovChain::new(0, transition, rand::thread_rng())),
+///     DynProcess::timed(Poisson::<f64, u32, _>::new(1., rand::thread_rng()).unwrap()),
+/// ];
+/// for process in processes.iter_mut() {
+///     let (period, state) = process.step().unwrap();
+///     println!("After {}, the state is {}", period, state);
+/// }
+/// ```
+pub struct DynProcess<'a, T> {
+    process: Box<dyn StochasticProcess<Time = f64, State = T> + 'a>,
+}
+
+/// Discrete-time process with unit time steps.
+struct UnitTime<P>(P);
+
+impl<P> StochasticProcess for UnitTime<P>
+where
+    P: StochasticProcess<Time = ()>,
+{
+    type Time = f64;
+    type State = P::State;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.0.step().map(|((), state)| (1., state))
+    }
+}
+
+/// Continuous-time process with periods converted to `f64`.
+struct FloatTime<P>(P);
+
+impl<P> StochasticProcess for FloatTime<P>
+where
+    P: StochasticProcess,
+    P::Time: ToPrimitive,
+{
+    type Time = f64;
+    type State = P::State;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.0.step().map(|(period, state)| (period.to_f64().unwrap(), state))
+    }
+}
+
+impl<'a, T> DynProcess<'a, T> {
+    /// Constructs a new `DynProcess` from a discrete-time process, taking one unit of time per step.
+    #[inline]
+    pub fn discrete<P>(process: P) -> Self
+    where
+        P: StochasticProcess<Time = (), State = T> + 'a,
+    {
+        DynProcess {
+            process: Box::new(UnitTime(process)),
+        }
+    }
+
+    /// Constructs a new `DynProcess` from a continuous-time process.
+    ///
+    /// # Panics
+    ///
+    /// When stepping, if a period can not be represented as `f64`.
+    #[inline]
+    pub fn timed<P>(process: P) -> Self
+    where
+        P: StochasticProcess<State = T> + 'a,
+        P::Time: ToPrimitive,
+    {
+        DynProcess {
+            process: Box::new(FloatTime(process)),
+        }
+    }
+}
+
+impl<'a, T> core::fmt::Debug for DynProcess<'a, T> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DynProcess").finish()
+    }
+}
+
+impl<'a, T> StochasticProcess for DynProcess<'a, T> {
+    type Time = f64;
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.process.step()
+    }
+}
+
+impl<'a, T> Iterator for DynProcess<'a, T> {
+    type Item = (f64, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.step()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn heterogeneous() {
+        let transition = |state: &u32| raw_dist![(1.0, state + 1)];
+        let timed_transition = |state: &u32| raw_dist![(1.0, (0.5, state + 2))];
+        let processes = vec![
+            DynProcess::discrete(crate::MarkovChain::new(0, transition, crate::tests::rng(1))),
+            DynProcess::timed(crate::TimedMarkovChain::new(0, timed_transition, crate::tests::rng(2))),
+        ];
+        let paths: Vec<Vec<(f64, u32)>> = processes.into_iter().map(|p| p.take(2).collect()).collect();
+
+        assert_eq!(paths, vec![vec![(1., 1), (1., 2)], vec![(0.5, 2), (0.5, 4)]]);
+    }
+}
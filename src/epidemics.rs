@@ -0,0 +1,595 @@
+//! Stochastic epidemic models in a closed population.
+//!
+//! [Sir] and [Seir] are continuous-time Markov chains, simulated exactly by the Gillespie algorithm,
+//! that yield the period until the next event and the new compartment sizes.
+//! Infections happen at rate `beta * S * I / N`, where `N` is the size of the population,
+//! incubations at rate `sigma * E` and recoveries at rate `gamma * I`.
+//!
+//! Once the epidemic is over, that is, nobody is exposed nor infected, the processes stay
+//! in the same state: they yield an infinite period.
+//!
+//! # Examples
+//!
+//! Final size of an epidemic with basic reproduction number two.
+//! ```
+//! # use markovian::epidemics::Sir;
+//! let mut sir = Sir::new(1_000, 10, 2., 1., rand::thread_rng());
+//! println!("In total, {} individuals were infected", sir.final_size());
+//! ```
+//!
+//! [Sir]: struct.Sir.html
+//! [Seir]: struct.Seir.html
+
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Exp1};
+
+// Structs
+use crate::errors::InvalidState;
+use crate::stats::Welford;
+
+// Functions
+use core::mem;
+
+/// Stochastic SIR epidemic, with states `(susceptible, infected, recovered)`.
+///
+/// # Examples
+///
+/// Trajectory up to time 10.
+/// ```
+/// # use markovian::{epidemics::Sir, prelude::*};
+/// let sir = Sir::new(100, 1, 3., 1., rand::thread_rng());
+/// for (time, (s, i, r)) in sir.trajectory_until(10.) {
+///     println!("At time {}: S = {}, I = {}, R = {}", time, s, i, r);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sir<R> {
+    state: (u64, u64, u64),
+    initial_state: (u64, u64, u64),
+    beta: f64,
+    gamma: f64,
+    rng: R,
+}
+
+impl<R> Sir<R>
+where
+    R: Rng,
+{
+    /// Constructs a new `Sir<R>` in a population of size `population`, with `infected` infected individuals
+    /// and the rest susceptible.
+    ///
+    /// # Panics
+    ///
+    /// If `infected` is greater than `population`, or some rate is negative.
+    #[inline]
+    pub fn new(population: u64, infected: u64, beta: f64, gamma: f64, rng: R) -> Self {
+        assert!(infected <= population, "There can not be more infected than individuals.");
+        assert!(beta >= 0. && gamma >= 0., "Rates must be non-negative.");
+        let state = (population - infected, infected, 0);
+        Sir {
+            state,
+            initial_state: state,
+            beta,
+            gamma,
+            rng,
+        }
+    }
+
+    /// Constructs a new `Sir<R>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [new].
+    ///
+    /// [new]: struct.Sir.html#method.new
+    #[inline]
+    pub fn with_seed(population: u64, infected: u64, beta: f64, gamma: f64, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        Sir::new(population, infected, beta, gamma, R::seed_from_u64(seed))
+    }
+
+    /// Returns the size of the population.
+    #[inline]
+    pub fn population(&self) -> u64 {
+        let (s, i, r) = self.state;
+        s + i + r
+    }
+
+    /// Returns the basic reproduction number `beta / gamma`.
+    #[inline]
+    pub fn basic_reproduction_number(&self) -> f64 {
+        self.beta / self.gamma
+    }
+
+    /// Returns `true` if nobody is infected.
+    #[inline]
+    pub fn is_over(&self) -> bool {
+        self.state.1 == 0
+    }
+
+    /// Simulates the epidemic until it is over and returns its final size:
+    /// the number of individuals that were ever infected, including the initially infected.
+    ///
+    /// # Remarks
+    ///
+    /// If `gamma` is zero, the epidemic is never over and this method does not return.
+    #[inline]
+    pub fn final_size(&mut self) -> u64 {
+        while !self.is_over() {
+            self.next();
+        }
+        self.state.2
+    }
+
+    /// Returns the statistics of the final size of `samples` independent epidemics,
+    /// simulated by [forks] of `self` from its current state.
+    ///
+    /// [forks]: ../trait.RandomSource.html#method.fork
+    #[inline]
+    pub fn final_size_statistics(&mut self, samples: usize) -> Welford
+    where
+        R: SeedableRng + Clone,
+    {
+        let mut statistics = Welford::new();
+        for _ in 0..samples {
+            statistics.push(self.fork().final_size() as f64);
+        }
+        statistics
+    }
+}
+
+impl<R> Reset for Sir<R> {
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state;
+    }
+}
+
+impl<R> RandomSource for Sir<R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<R> State for Sir<R> {
+    type Item = (u64, u64, u64);
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    /// Changes the compartment sizes, as long as the size of the population does not change.
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        let (s, i, r) = new_state;
+        let (s0, i0, r0) = self.state;
+        if s + i + r == s0 + i0 + r0 {
+            Ok(Some(mem::replace(&mut self.state, new_state)))
+        } else {
+            Err(InvalidState::new(new_state))
+        }
+    }
+}
+
+impl<R> Checkpoint for Sir<R>
+where
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<R> Iterator for Sir<R>
+where
+    R: Rng,
+{
+    type Item = (f64, (u64, u64, u64));
+
+    /// Changes the state to the compartment sizes after the next event,
+    /// and returns them with the period until that event.
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (period, state) = sample_sir(self.state, self.beta, self.gamma, &mut self.rng);
+        self.state = state;
+        Some((period, state))
+    }
+}
+
+impl<R> StochasticProcess for Sir<R>
+where
+    R: Rng,
+{
+    type Time = f64;
+    type State = (u64, u64, u64);
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next()
+    }
+}
+
+impl<R> StateIterator for Sir<R>
+where
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        Some((0., self.state))
+    }
+}
+
+impl<R> Distribution<(f64, (u64, u64, u64))> for Sir<R>
+where
+    R: Rng,
+{
+    /// Sample a possible next state.
+    #[inline]
+    fn sample<R2>(&self, rng: &mut R2) -> (f64, (u64, u64, u64))
+    where
+        R2: Rng + ?Sized,
+    {
+        sample_sir(self.state, self.beta, self.gamma, rng)
+    }
+}
+
+/// Stochastic SEIR epidemic, with states `(susceptible, exposed, infected, recovered)`.
+///
+/// Infected individuals expose susceptible ones, who become infected themselves
+/// after an exponential incubation period with rate `sigma`.
+///
+/// # Examples
+///
+/// Peak of infections of an epidemic with an incubation period of five days on average.
+/// ```
+/// # use markovian::epidemics::Seir;
+/// let seir = Seir::new(1_000, 10, 0.5, 0.2, 0.25, rand::thread_rng());
+/// let peak = seir.take_while(|(period, _)| period.is_finite())
+///     .map(|(_, (_, _, i, _))| i)
+///     .max();
+/// println!("At most, {:?} individuals were infected at the same time", peak);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Seir<R> {
+    state: (u64, u64, u64, u64),
+    initial_state: (u64, u64, u64, u64),
+    beta: f64,
+    sigma: f64,
+    gamma: f64,
+    rng: R,
+}
+
+impl<R> Seir<R>
+where
+    R: Rng,
+{
+    /// Constructs a new `Seir<R>` in a population of size `population`, with `infected` infected individuals
+    /// and the rest susceptible.
+    ///
+    /// # Panics
+    ///
+    /// If `infected` is greater than `population`, or some rate is negative.
+    #[inline]
+    pub fn new(population: u64, infected: u64, beta: f64, sigma: f64, gamma: f64, rng: R) -> Self {
+        assert!(infected <= population, "There can not be more infected than individuals.");
+        assert!(beta >= 0. && sigma >= 0. && gamma >= 0., "Rates must be non-negative.");
+        let state = (population - infected, 0, infected, 0);
+        Seir {
+            state,
+            initial_state: state,
+            beta,
+            sigma,
+            gamma,
+            rng,
+        }
+    }
+
+    /// Constructs a new `Seir<R>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [new].
+    ///
+    /// [new]: struct.Seir.html#method.new
+    #[inline]
+    pub fn with_seed(population: u64, infected: u64, beta: f64, sigma: f64, gamma: f64, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        Seir::new(population, infected, beta, sigma, gamma, R::seed_from_u64(seed))
+    }
+
+    /// Returns the size of the population.
+    #[inline]
+    pub fn population(&self) -> u64 {
+        let (s, e, i, r) = self.state;
+        s + e + i + r
+    }
+
+    /// Returns the basic reproduction number `beta / gamma`.
+    #[inline]
+    pub fn basic_reproduction_number(&self) -> f64 {
+        self.beta / self.gamma
+    }
+
+    /// Returns `true` if nobody is exposed nor infected.
+    #[inline]
+    pub fn is_over(&self) -> bool {
+        self.state.1 == 0 && self.state.2 == 0
+    }
+
+    /// Simulates the epidemic until it is over and returns its final size:
+    /// the number of individuals that were ever infected, including the initially infected.
+    ///
+    /// # Remarks
+    ///
+    /// If `sigma` or `gamma` is zero, the epidemic may never be over and this method may not return.
+    #[inline]
+    pub fn final_size(&mut self) -> u64 {
+        while !self.is_over() {
+            self.next();
+        }
+        self.state.3
+    }
+
+    /// Returns the statistics of the final size of `samples` independent epidemics,
+    /// simulated by [forks] of `self` from its current state.
+    ///
+    /// [forks]: ../trait.RandomSource.html#method.fork
+    #[inline]
+    pub fn final_size_statistics(&mut self, samples: usize) -> Welford
+    where
+        R: SeedableRng + Clone,
+    {
+        let mut statistics = Welford::new();
+        for _ in 0..samples {
+            statistics.push(self.fork().final_size() as f64);
+        }
+        statistics
+    }
+}
+
+impl<R> Reset for Seir<R> {
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state;
+    }
+}
+
+impl<R> RandomSource for Seir<R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<R> State for Seir<R> {
+    type Item = (u64, u64, u64, u64);
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    /// Changes the compartment sizes, as long as the size of the population does not change.
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        let (s, e, i, r) = new_state;
+        let (s0, e0, i0, r0) = self.state;
+        if s + e + i + r == s0 + e0 + i0 + r0 {
+            Ok(Some(mem::replace(&mut self.state, new_state)))
+        } else {
+            Err(InvalidState::new(new_state))
+        }
+    }
+}
+
+impl<R> Checkpoint for Seir<R>
+where
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<R> Iterator for Seir<R>
+where
+    R: Rng,
+{
+    type Item = (f64, (u64, u64, u64, u64));
+
+    /// Changes the state to the compartment sizes after the next event,
+    /// and returns them with the period until that event.
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (period, state) = sample_seir(self.state, self.beta, self.sigma, self.gamma, &mut self.rng);
+        self.state = state;
+        Some((period, state))
+    }
+}
+
+impl<R> StochasticProcess for Seir<R>
+where
+    R: Rng,
+{
+    type Time = f64;
+    type State = (u64, u64, u64, u64);
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next()
+    }
+}
+
+impl<R> StateIterator for Seir<R>
+where
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        Some((0., self.state))
+    }
+}
+
+impl<R> Distribution<(f64, (u64, u64, u64, u64))> for Seir<R>
+where
+    R: Rng,
+{
+    /// Sample a possible next state.
+    #[inline]
+    fn sample<R2>(&self, rng: &mut R2) -> (f64, (u64, u64, u64, u64))
+    where
+        R2: Rng + ?Sized,
+    {
+        sample_seir(self.state, self.beta, self.sigma, self.gamma, rng)
+    }
+}
+
+/// Samples the period until the next event of a SIR epidemic and the compartment sizes after it.
+#[inline]
+fn sample_sir<R>(state: (u64, u64, u64), beta: f64, gamma: f64, rng: &mut R) -> (f64, (u64, u64, u64))
+where
+    R: Rng + ?Sized,
+{
+    let (s, i, r) = state;
+    let population = (s + i + r) as f64;
+    let infection = beta * s as f64 * i as f64 / population;
+    let recovery = gamma * i as f64;
+    let total = infection + recovery;
+    if total <= 0. {
+        return (f64::INFINITY, state);
+    }
+    let period: f64 = Exp1.sample(rng);
+    if rng.gen::<f64>() * total < infection {
+        (period / total, (s - 1, i + 1, r))
+    } else {
+        (period / total, (s, i - 1, r + 1))
+    }
+}
+
+/// Samples the period until the next event of a SEIR epidemic and the compartment sizes after it.
+#[inline]
+fn sample_seir<R>(
+    state: (u64, u64, u64, u64),
+    beta: f64,
+    sigma: f64,
+    gamma: f64,
+    rng: &mut R,
+) -> (f64, (u64, u64, u64, u64))
+where
+    R: Rng + ?Sized,
+{
+    let (s, e, i, r) = state;
+    let population = (s + e + i + r) as f64;
+    let infection = beta * s as f64 * i as f64 / population;
+    let incubation = sigma * e as f64;
+    let recovery = gamma * i as f64;
+    let total = infection + incubation + recovery;
+    if total <= 0. {
+        return (f64::INFINITY, state);
+    }
+    let period: f64 = Exp1.sample(rng);
+    let event = rng.gen::<f64>() * total;
+    if event < infection {
+        (period / total, (s - 1, e + 1, i, r))
+    } else if event < infection + incubation {
+        (period / total, (s, e - 1, i + 1, r))
+    } else {
+        (period / total, (s, e, i - 1, r + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimedIterator;
+    use pretty_assertions::assert_eq;
+    use rand_pcg::Pcg64;
+
+    #[test]
+    fn conservation() {
+        let sir = Sir::new(50, 5, 2., 1., crate::tests::rng(1));
+        for (_, (s, i, r)) in sir.take(200) {
+            assert_eq!(s + i + r, 50);
+        }
+        let seir = Seir::new(50, 5, 2., 1., 1., crate::tests::rng(2));
+        for (_, (s, e, i, r)) in seir.take(200) {
+            assert_eq!(s + e + i + r, 50);
+        }
+    }
+
+    #[test]
+    fn over() {
+        let mut sir = Sir::new(10, 3, 0., 1., crate::tests::rng(3));
+        assert_eq!(sir.final_size(), 3);
+        assert_eq!(sir.next(), Some((f64::INFINITY, (7, 0, 3))));
+
+        let sir = Sir::new(10, 0, 2., 1., crate::tests::rng(4));
+        let path: Vec<(f64, (u64, u64, u64))> = sir.trajectory_until(1.).collect();
+        assert_eq!(path, vec![(0., (10, 0, 0))]);
+    }
+
+    #[test]
+    fn final_size() {
+        // Below the threshold, a single infected infects on average R0 / (1 - R0) others
+        let mut sir = Sir::<Pcg64>::with_seed(10_000, 1, 0.5, 1., 5);
+        let statistics = sir.final_size_statistics(2_000);
+        assert!((statistics.mean() - 2.).abs() < 0.2);
+
+        // Far above the threshold, almost everybody is infected in large outbreaks
+        let mut seir = Seir::<Pcg64>::with_seed(200, 20, 5., 1., 1., 6);
+        let statistics = seir.final_size_statistics(100);
+        assert!(statistics.min() > 180.);
+    }
+}
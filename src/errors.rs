@@ -12,3 +12,120 @@ impl<T: std::fmt::Debug> InvalidState<T> {
         InvalidState { state }
     }
 }
+
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Error)]
+#[error("the state space has more than {limit} states")]
+pub struct StateSpaceTooLarge {
+    limit: usize,
+}
+
+impl StateSpaceTooLarge {
+	#[inline]
+    pub fn new(limit: usize) -> Self {
+        StateSpaceTooLarge { limit }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Error)]
+pub enum DensityError {
+    #[error("there are no weights")]
+    Empty,
+    #[error("the weight {0} is negative or not finite")]
+    InvalidWeight(f64),
+    #[error("all weights are zero")]
+    ZeroMass,
+    #[error("the total mass {0} is more than one")]
+    MassAboveOne(f64),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Error)]
+pub enum MatrixError {
+    #[error("the matrix is not square")]
+    NotSquare,
+    #[error("the entry {0} is negative or not finite")]
+    InvalidEntry(f64),
+    #[error("the matrix has a row or a column of zeros")]
+    ZeroLine,
+    #[error("the normalization did not converge after {0} iterations")]
+    NotConverged(usize),
+    #[error("the matrices have different dimensions")]
+    DimensionMismatch,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Error)]
+pub enum DeterminantalError {
+    #[error("the kernel is not a square matrix")]
+    NotSquare,
+    #[error("the kernel is not symmetric")]
+    NotSymmetric,
+    #[error("the kernel has the negative eigenvalue {0}")]
+    NotPositiveSemidefinite(f64),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Error)]
+pub enum EmbeddingError {
+    #[error("the transition matrix has no real logarithm that could be computed")]
+    NoLogarithm,
+    #[error("the logarithm of the transition matrix has the negative rate {0}")]
+    NotEmbeddable(f64),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Error)]
+pub enum MetastabilityError {
+    #[error("{0} metastable sets were asked, but there must be between one and the number of states")]
+    InvalidNumberOfSets(usize),
+    #[error("the chain is not irreducible")]
+    NotIrreducible,
+    #[error("some metastable set is empty")]
+    Degenerate,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Error)]
+pub enum MsmError {
+    #[error("no transitions were observed at the lag")]
+    NoTransitions,
+    #[error("the state {0} has no observed transitions")]
+    UnvisitedState(usize),
+    #[error("the estimated chain has no positive stationary distribution")]
+    NotConnected,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Error)]
+pub enum PhaseTypeError {
+    #[error("the initial distribution and the subgenerator have different dimensions")]
+    DimensionMismatch,
+    #[error("the initial probabilities are negative or sum up to more than one")]
+    InvalidInitial,
+    #[error("the subgenerator has negative rates or rows summing up to more than zero")]
+    InvalidSubgenerator,
+    #[error("absorption is not certain from every phase")]
+    NotTransient,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Error)]
+pub enum QbdError {
+    #[error("the blocks are not square matrices of the same size, or do not form a generator")]
+    InvalidBlocks,
+    #[error("the process is not positive recurrent")]
+    Unstable,
+    #[error("the iteration did not converge after {0} iterations")]
+    NotConverged(usize),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Error)]
+pub enum QueueingError {
+    #[error("the queue lengths, rates and routing matrix have different dimensions")]
+    DimensionMismatch,
+    #[error("the rate {0} is negative or not finite")]
+    InvalidRate(f64),
+    #[error("the routing probabilities from node {0} are negative or sum up to more than one")]
+    InvalidRouting(usize),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Error)]
+pub enum SpatialError {
+    #[error("the intensity {0} is negative or not finite")]
+    InvalidIntensity(f64),
+    #[error("the region has non-finite or inverted bounds")]
+    InvalidRegion,
+}
@@ -0,0 +1,770 @@
+//! Statistical inference of Markov models from observed data.
+//!
+//! Before fitting a Markov chain to a sequence, [test_markov_order] checks
+//! how much memory the sequence needs.
+//! Data feeds that do not fit in memory are handled by [OnlineChainEstimator].
+//! Chains in continuous time, observed at every jump, are estimated by [fit_ctmc],
+//! while chains observed only at some times are estimated by [fit_ctmc_em].
+//!
+//! [test_markov_order]: fn.test_markov_order.html
+//! [OnlineChainEstimator]: struct.OnlineChainEstimator.html
+//! [fit_ctmc]: fn.fit_ctmc.html
+//! [fit_ctmc_em]: fn.fit_ctmc_em.html
+
+// Traits
+use core::fmt::Debug;
+use core::hash::Hash;
+use rand::Rng;
+
+// Structs
+use crate::stats::{special, ChiSquareTest};
+use crate::{ContFiniteMarkovChain, FiniteMarkovChain};
+use ndarray::{Array1, Array2};
+use std::collections::{HashMap, HashSet};
+
+/// Weight above which all counts are rescaled, to avoid overflow.
+const MAX_WEIGHT: f64 = 1e100;
+
+/// Relative increase of the log-likelihood below which the EM algorithm stops.
+const EM_TOLERANCE: f64 = 1e-10;
+
+/// Estimator of a finite Markov chain from a stream of transitions, with exponential forgetting.
+///
+/// Each observed transition `(previous, next)` has weight one when observed and its weight is
+/// multiplied by `forgetting` after each new observation, so that the estimate follows
+/// a chain that changes over time. States are added as they are observed.
+///
+/// # Costs
+///
+/// **Update**: O(1) if both states were observed before, O(n) otherwise,
+/// where n is the number of observed states, since the counts grow by a row and a column.
+/// Counts are also rescaled in O(n^2) once their weights become too large.
+///
+/// **Snapshot**: O(n^2).
+///
+/// # Examples
+///
+/// ```
+/// # use markovian::{estimation::OnlineChainEstimator, State};
+/// let mut estimator = OnlineChainEstimator::new(0.99);
+/// estimator.extend(vec![("home", "cart"), ("cart", "home"), ("home", "home")]);
+/// assert_eq!(estimator.nstates(), 2);
+/// let mc = estimator.snapshot(rand::thread_rng());
+/// assert_eq!(mc.state(), Some(&"home"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnlineChainEstimator<T>
+where
+    T: Hash + Eq,
+{
+    indexes: HashMap<T, usize>,
+    state_space: Vec<T>,
+    counts: Vec<Vec<f64>>,
+    forgetting: f64,
+    weight: f64,
+    last: Option<usize>,
+}
+
+impl<T> OnlineChainEstimator<T>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Constructs a new `OnlineChainEstimator<T>` without observations.
+    ///
+    /// A `forgetting` factor of one weights all observations equally.
+    ///
+    /// # Panics
+    ///
+    /// If `forgetting` is not in `(0, 1]`.
+    #[inline]
+    pub fn new(forgetting: f64) -> Self {
+        assert!(forgetting > 0.0 && forgetting <= 1.0, "The forgetting factor must be in (0, 1].");
+        OnlineChainEstimator {
+            indexes: HashMap::new(),
+            state_space: Vec::new(),
+            counts: Vec::new(),
+            forgetting,
+            weight: 1.0,
+            last: None,
+        }
+    }
+
+    /// Returns the forgetting factor.
+    #[inline]
+    pub fn forgetting(&self) -> f64 {
+        self.forgetting
+    }
+
+    /// Returns the number of observed states.
+    #[inline]
+    pub fn nstates(&self) -> usize {
+        self.state_space.len()
+    }
+
+    /// Returns the observed states, in order of appearance.
+    #[inline]
+    pub fn state_space(&self) -> &[T] {
+        &self.state_space
+    }
+
+    /// Adds the observation of a transition from `previous` to `next`.
+    #[inline]
+    pub fn push(&mut self, previous: T, next: T) {
+        let i = self.index(previous);
+        let j = self.index(next);
+        self.weight /= self.forgetting;
+        if self.weight > MAX_WEIGHT {
+            for count in self.counts.iter_mut().flatten() {
+                *count /= self.weight;
+            }
+            self.weight = 1.0;
+        }
+        self.counts[i][j] += self.weight;
+        self.last = Some(j);
+    }
+
+    /// Returns the estimated transition matrix, indexed as the state space.
+    ///
+    /// States without observed transitions from them are absorbing.
+    #[inline]
+    pub fn transition_probabilities(&self) -> Array2<f64> {
+        let n = self.nstates();
+        let mut matrix = Array2::from_shape_fn((n, n), |(i, j)| self.counts[i][j]);
+        for (i, mut row) in matrix.genrows_mut().into_iter().enumerate() {
+            let total = row.sum();
+            if total > 0.0 {
+                row /= total;
+            } else {
+                row[i] = 1.0;
+            }
+        }
+        matrix
+    }
+
+    /// Returns the estimated chain, with the last observed state as its current state.
+    ///
+    /// States without observed transitions from them are absorbing.
+    ///
+    /// # Panics
+    ///
+    /// If there are no observations.
+    #[inline]
+    pub fn snapshot<R>(&self, rng: R) -> FiniteMarkovChain<T, f64, R>
+    where
+        T: Debug,
+        R: Rng,
+    {
+        let state_index = self.last.expect("There must be at least one observation.");
+        let transition_matrix = self
+            .transition_probabilities()
+            .genrows()
+            .into_iter()
+            .map(|row| row.to_vec())
+            .collect();
+        FiniteMarkovChain::new(state_index, transition_matrix, self.state_space.clone(), rng)
+    }
+
+    /// Returns the index of `state`, adding it if it was not observed.
+    #[inline]
+    fn index(&mut self, state: T) -> usize {
+        if let Some(&index) = self.indexes.get(&state) {
+            return index;
+        }
+        let index = self.state_space.len();
+        self.indexes.insert(state.clone(), index);
+        self.state_space.push(state);
+        for row in self.counts.iter_mut() {
+            row.push(0.0);
+        }
+        self.counts.push(vec![0.0; index + 1]);
+        index
+    }
+}
+
+impl<T> Extend<(T, T)> for OnlineChainEstimator<T>
+where
+    T: Hash + Eq + Clone,
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = (T, T)>>(&mut self, transitions: I) {
+        for (previous, next) in transitions {
+            self.push(previous, next);
+        }
+    }
+}
+
+/// Maximum likelihood estimate of a chain in continuous time from its jumps.
+///
+/// This struct is created by [fit_ctmc].
+///
+/// [fit_ctmc]: fn.fit_ctmc.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct CtmcFit<T> {
+    /// Observed states, in order of appearance.
+    pub state_space: Vec<T>,
+    /// Number of jumps between each pair of states, indexed as the state space.
+    pub counts: Array2<f64>,
+    /// Total time spent in each state before jumping, indexed as the state space.
+    pub holding_times: Array1<f64>,
+    /// Index of the last observed state.
+    pub last: usize,
+}
+
+impl<T> CtmcFit<T> {
+    /// Returns the number of observed states.
+    #[inline]
+    pub fn nstates(&self) -> usize {
+        self.state_space.len()
+    }
+
+    /// Returns the estimated rates: the number of jumps between each pair of states
+    /// divided by the holding time of the first one.
+    ///
+    /// States with no holding time observed have zero rates.
+    #[inline]
+    pub fn rates(&self) -> Array2<f64> {
+        let n = self.nstates();
+        Array2::from_shape_fn((n, n), |(i, j)| {
+            if self.holding_times[i] > 0.0 {
+                self.counts[[i, j]] / self.holding_times[i]
+            } else {
+                0.0
+            }
+        })
+    }
+
+    /// Returns the estimated generator matrix.
+    #[inline]
+    pub fn generator(&self) -> Array2<f64> {
+        let mut generator = self.rates();
+        for (i, mut row) in generator.genrows_mut().into_iter().enumerate() {
+            row[i] = -row.sum();
+        }
+        generator
+    }
+
+    /// Returns exact confidence intervals, of confidence `level`, for each rate.
+    ///
+    /// Given the holding time `R` of a state, the number of jumps `N` to another state
+    /// is Poisson distributed with mean `q R`, so intervals are those of a Poisson mean
+    /// divided by `R`. Rates from states with no holding time observed have
+    /// the uninformative interval `[0, ∞)`.
+    ///
+    /// # Panics
+    ///
+    /// If `level` is not in `(0, 1)`.
+    #[inline]
+    pub fn confidence_intervals(&self, level: f64) -> Array2<(f64, f64)> {
+        assert!(level > 0.0 && level < 1.0, "The confidence level must be in (0, 1).");
+        let alpha = 1.0 - level;
+        let n = self.nstates();
+        Array2::from_shape_fn((n, n), |(i, j)| {
+            let time = self.holding_times[i];
+            if i == j {
+                (0.0, 0.0)
+            } else if time > 0.0 {
+                let count = self.counts[[i, j]] as usize;
+                let low = special::chi_square_quantile(alpha / 2.0, 2 * count) / (2.0 * time);
+                let high = special::chi_square_quantile(1.0 - alpha / 2.0, 2 * count + 2) / (2.0 * time);
+                (low, high)
+            } else {
+                (0.0, f64::INFINITY)
+            }
+        })
+    }
+
+    /// Returns the estimated chain, with the last observed state as its current state.
+    ///
+    /// States without observed jumps from them have weight one on a jump to themselves,
+    /// which does not change the generator of the chain, so that they are absorbing.
+    #[inline]
+    pub fn chain<R>(&self, rng: R) -> ContFiniteMarkovChain<T, f64, R>
+    where
+        T: Clone,
+        R: Rng,
+    {
+        let mut rates = self.rates();
+        for (i, mut row) in rates.genrows_mut().into_iter().enumerate() {
+            if row.sum() == 0.0 {
+                row[i] = 1.0;
+            }
+        }
+        let transition_weights = rates.genrows().into_iter().map(|row| row.to_vec()).collect();
+        ContFiniteMarkovChain::new(self.last, transition_weights, self.state_space.clone(), rng)
+    }
+}
+
+/// Returns the maximum likelihood estimate of a chain in continuous time,
+/// observed at every jump.
+///
+/// Each element of `jumps` is a pair `(period, state)`, where `period` is the time spent
+/// in the previous state before jumping to `state`, as yielded by [ContFiniteMarkovChain].
+/// The period of the first element is ignored, so that a trajectory of a chain can be used directly.
+/// Consecutive repeated states are not jumps: their periods count as holding time.
+///
+/// # Panics
+///
+/// If `jumps` is empty or some period is negative.
+///
+/// # Examples
+///
+/// ```
+/// # use rand::SeedableRng;
+/// # use markovian::{ContFiniteMarkovChain, StateIterator, estimation::fit_ctmc};
+/// let rng = rand_pcg::Pcg64::seed_from_u64(1);
+/// let mc = ContFiniteMarkovChain::new(0, vec![vec![0., 2.], vec![3., 0.]], vec!['a', 'b'], rng);
+/// let jumps: Vec<(f64, char)> = mc.trajectory().take(10_000).collect();
+/// let fit = fit_ctmc(&jumps);
+/// let (low, high) = fit.confidence_intervals(0.999)[[0, 1]];
+/// assert!(low < 2. && 2. < high);
+/// ```
+///
+/// [ContFiniteMarkovChain]: ../struct.ContFiniteMarkovChain.html
+#[inline]
+pub fn fit_ctmc<T>(jumps: &[(f64, T)]) -> CtmcFit<T>
+where
+    T: PartialEq + Clone,
+{
+    assert!(!jumps.is_empty(), "There must be at least one observation.");
+    let mut state_space: Vec<T> = Vec::new();
+    let indexes: Vec<usize> = jumps
+        .iter()
+        .map(|(_, state)| {
+            state_space.iter().position(|s| s == state).unwrap_or_else(|| {
+                state_space.push(state.clone());
+                state_space.len() - 1
+            })
+        })
+        .collect();
+    let n = state_space.len();
+    let mut counts = Array2::zeros((n, n));
+    let mut holding_times = Array1::zeros(n);
+    for (k, window) in indexes.windows(2).enumerate() {
+        let period = jumps[k + 1].0;
+        assert!(period >= 0.0, "Periods must be non-negative.");
+        holding_times[window[0]] += period;
+        if window[0] != window[1] {
+            counts[[window[0], window[1]]] += 1.0;
+        }
+    }
+    CtmcFit {
+        state_space,
+        counts,
+        holding_times,
+        last: indexes[indexes.len() - 1],
+    }
+}
+
+/// Estimate of a chain in continuous time from observations at some times.
+///
+/// This struct is created by [fit_ctmc_em].
+///
+/// [fit_ctmc_em]: fn.fit_ctmc_em.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct CtmcEmFit<T> {
+    /// Observed states, in order of appearance.
+    pub state_space: Vec<T>,
+    /// Estimated generator matrix, indexed as the state space.
+    pub generator: Array2<f64>,
+    /// Log-likelihood of the observations under `generator`.
+    pub log_likelihood: f64,
+    /// Number of iterations of the EM algorithm.
+    pub iterations: usize,
+}
+
+/// Returns an estimate of a chain in continuous time observed only at some times,
+/// by the expectation-maximization (EM) algorithm.
+///
+/// Each path is a sequence of observations `(time, state)`, with non-decreasing times.
+/// Paths are independent and observation times need not be equally spaced.
+///
+/// The algorithm starts from a generator with equal rates between all states and stops
+/// after `max_iterations` iterations, or once the log-likelihood stops increasing.
+/// At each iteration, the expected number of jumps between each pair of states and the
+/// expected time spent in each state, given the observations, are computed exactly
+/// by matrix exponentials, and rates are updated as their ratio.
+///
+/// # Costs
+///
+/// O(d n^3) per iteration, where d is the number of distinct times between observations
+/// and n is the number of observed states.
+///
+/// # Remarks
+///
+/// The likelihood may have several local maxima and rates may converge slowly to zero,
+/// so the estimate is only a local maximum of the likelihood.
+///
+/// # Panics
+///
+/// If times of a path decrease, or a path has different states at the same time.
+///
+/// # Examples
+///
+/// Observing a chain every unit of time.
+/// ```
+/// # use rand::SeedableRng;
+/// # use markovian::{ContFiniteMarkovChain, TimedIterator, estimation::fit_ctmc_em};
+/// let rng = rand_pcg::Pcg64::seed_from_u64(1);
+/// let mc = ContFiniteMarkovChain::new(0, vec![vec![0., 1.], vec![2., 0.]], vec!['a', 'b'], rng);
+/// let path: Vec<(f64, char)> = mc.sample_on_grid(1., 2_000)
+///     .enumerate()
+///     .map(|(k, state)| (k as f64, state))
+///     .collect();
+/// let fit = fit_ctmc_em(&[path], 100);
+/// assert!((fit.generator[[0, 1]] - 1.).abs() < 0.2);
+/// ```
+#[inline]
+pub fn fit_ctmc_em<T>(paths: &[Vec<(f64, T)>], max_iterations: usize) -> CtmcEmFit<T>
+where
+    T: PartialEq + Clone,
+{
+    let mut state_space: Vec<T> = Vec::new();
+    let mut index = |state: &T| {
+        state_space.iter().position(|s| s == state).unwrap_or_else(|| {
+            state_space.push(state.clone());
+            state_space.len() - 1
+        })
+    };
+    // Transitions between observations, grouped by the time between them
+    let mut transitions: Vec<(f64, usize, usize)> = Vec::new();
+    let mut total_time = 0.0;
+    let mut changes = 0;
+    for path in paths {
+        let indexes: Vec<usize> = path.iter().map(|(_, state)| index(state)).collect();
+        for (k, window) in indexes.windows(2).enumerate() {
+            let period = path[k + 1].0 - path[k].0;
+            assert!(period >= 0.0, "Times of a path must be non-decreasing.");
+            if period == 0.0 {
+                assert!(window[0] == window[1], "A path must have one state at each time.");
+                continue;
+            }
+            transitions.push((period, window[0], window[1]));
+            total_time += period;
+            if window[0] != window[1] {
+                changes += 1;
+            }
+        }
+    }
+    let n = state_space.len();
+    let mut groups: Vec<(f64, Array2<f64>)> = Vec::new();
+    for (period, i, j) in transitions {
+        let position = match groups.iter().position(|(p, _)| *p == period) {
+            Some(position) => position,
+            None => {
+                groups.push((period, Array2::zeros((n, n))));
+                groups.len() - 1
+            }
+        };
+        groups[position].1[[i, j]] += 1.0;
+    }
+
+    let rate = if n > 1 && total_time > 0.0 {
+        changes.max(1) as f64 / total_time / (n - 1) as f64
+    } else {
+        0.0
+    };
+    let mut generator = Array2::from_shape_fn((n, n), |(i, j)| if i == j { -rate * (n - 1) as f64 } else { rate });
+    let mut log_likelihood = em_log_likelihood(&generator, &groups);
+    let mut iterations = 0;
+    while iterations < max_iterations && rate > 0.0 {
+        generator = em_step(&generator, &groups);
+        iterations += 1;
+        let previous = log_likelihood;
+        log_likelihood = em_log_likelihood(&generator, &groups);
+        if log_likelihood - previous <= EM_TOLERANCE * previous.abs() {
+            break;
+        }
+    }
+    CtmcEmFit {
+        state_space,
+        generator,
+        log_likelihood,
+        iterations,
+    }
+}
+
+/// Returns the log-likelihood of counts of transitions, grouped by the time between observations.
+#[inline]
+fn em_log_likelihood(generator: &Array2<f64>, groups: &[(f64, Array2<f64>)]) -> f64 {
+    groups
+        .iter()
+        .map(|(period, counts)| {
+            let p = crate::linalg::expm_generator(generator, *period);
+            counts
+                .indexed_iter()
+                .filter(|(_, &count)| count > 0.0)
+                .map(|(index, count)| count * p[index].ln())
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+/// Returns the generator after one iteration of the EM algorithm.
+///
+/// Given counts `C` of transitions after time `t`, the expected time in state `k` and
+/// the expected number of jumps from `k` to `l` are, respectively, the entries `(k, k)` and
+/// `(k, l)`, the latter multiplied by `q_kl`, of the integral of `exp(Q^T s) W exp(Q^T (t - s))`
+/// over `[0, t]`, where `W_ij = C_ij / P_ij(t)`. This integral is a block of the exponential
+/// of `[[Q^T, W], [0, Q^T]] t`.
+#[inline]
+fn em_step(generator: &Array2<f64>, groups: &[(f64, Array2<f64>)]) -> Array2<f64> {
+    let n = generator.nrows();
+    let mut integrals = Array2::<f64>::zeros((n, n));
+    for (period, counts) in groups {
+        let p = crate::linalg::expm_generator(generator, *period);
+        let mut block = Array2::zeros((2 * n, 2 * n));
+        for ((i, j), &q) in generator.indexed_iter() {
+            block[[j, i]] = q;
+            block[[n + j, n + i]] = q;
+            if counts[[i, j]] > 0.0 {
+                block[[i, n + j]] = counts[[i, j]] / p[[i, j]];
+            }
+        }
+        let exponential = crate::linalg::expm_metzler(&block, *period);
+        integrals += &exponential.slice(ndarray::s![..n, n..]);
+    }
+    let mut updated = Array2::from_shape_fn((n, n), |(k, l)| {
+        if k == l {
+            0.0
+        } else if integrals[[k, k]] > 0.0 {
+            generator[[k, l]] * integrals[[k, l]] / integrals[[k, k]]
+        } else {
+            generator[[k, l]]
+        }
+    });
+    for (k, mut row) in updated.genrows_mut().into_iter().enumerate() {
+        row[k] = -row.sum();
+    }
+    updated
+}
+
+/// Returns likelihood-ratio tests for the order of `sequence` as a Markov chain,
+/// where the test at index `k` has, as null hypothesis, a chain of order `k`
+/// against the alternative of a chain of order `k + 1`, for `k` less than `max_order`.
+///
+/// Order zero means independent and identically distributed values.
+/// The statistic of each test is `G² = 2 Σ N(a c x) ln(N(a c x) N(c) / (N(a c) N(c x)))`,
+/// where `c` ranges over contexts of length `k`, `a` over the value before the context
+/// and `x` over the value after it, and `N` counts occurrences among windows of length `k + 2`.
+/// Degrees of freedom discount transitions that are never observed.
+///
+/// # Remarks
+///
+/// Tests are asymptotic, so they are reliable only if contexts are observed many times.
+/// A sequence is usually modeled with the smallest order whose test is not rejected.
+///
+/// # Panics
+///
+/// If `sequence` has less than `max_order + 1` values.
+///
+/// # Examples
+///
+/// A sequence generated by a Markov chain is not independent, but it is of order one.
+/// ```
+/// # use ndarray::array;
+/// # use rand::SeedableRng;
+/// # use markovian::{FiniteMarkovChain, estimation::test_markov_order};
+/// let rng = rand_pcg::Pcg64::seed_from_u64(1);
+/// let mc = FiniteMarkovChain::from((0, array![[0.9, 0.1], [0.3, 0.7]], rng));
+/// let sequence: Vec<usize> = mc.take(5_000).collect();
+/// let tests = test_markov_order(&sequence, 2);
+/// assert!(tests[0].p_value < 1e-6);
+/// assert!(tests[1].p_value > 1e-3);
+/// ```
+#[inline]
+pub fn test_markov_order<T>(sequence: &[T], max_order: usize) -> Vec<ChiSquareTest>
+where
+    T: Hash + Eq,
+{
+    assert!(sequence.len() > max_order, "The sequence must have more than `max_order` values.");
+    (0..max_order).map(|order| order_test(sequence, order)).collect()
+}
+
+/// Likelihood-ratio test of order `order` against order `order + 1`.
+#[inline]
+fn order_test<T>(sequence: &[T], order: usize) -> ChiSquareTest
+where
+    T: Hash + Eq,
+{
+    let mut windows: HashMap<&[T], usize> = HashMap::new();
+    let mut before: HashMap<&[T], usize> = HashMap::new();
+    let mut after: HashMap<&[T], usize> = HashMap::new();
+    let mut contexts: HashMap<&[T], Context<T>> = HashMap::new();
+    for window in sequence.windows(order + 2) {
+        *windows.entry(window).or_insert(0) += 1;
+        *before.entry(&window[..=order]).or_insert(0) += 1;
+        *after.entry(&window[1..]).or_insert(0) += 1;
+        let context = contexts.entry(&window[1..=order]).or_default();
+        context.count += 1;
+        context.previous.insert(&window[0]);
+        context.next.insert(&window[order + 1]);
+    }
+
+    let statistic = 2.0 * windows
+        .iter()
+        .map(|(window, &count)| {
+            let count = count as f64;
+            let context = contexts[&window[1..=order]].count as f64;
+            let before = before[&window[..=order]] as f64;
+            let after = after[&window[1..]] as f64;
+            count * (count * context / (before * after)).ln()
+        })
+        .sum::<f64>();
+    let degrees_of_freedom = contexts
+        .values()
+        .map(|context| (context.previous.len() - 1) * (context.next.len() - 1))
+        .sum();
+    let p_value = if degrees_of_freedom == 0 {
+        1.0
+    } else {
+        special::chi_square_survival(statistic, degrees_of_freedom)
+    };
+    ChiSquareTest {
+        statistic,
+        degrees_of_freedom,
+        p_value,
+    }
+}
+
+/// Occurrences of a context and the values observed around it.
+#[derive(Debug)]
+struct Context<'a, T> {
+    count: usize,
+    previous: HashSet<&'a T>,
+    next: HashSet<&'a T>,
+}
+
+impl<'a, T> Default for Context<'a, T> {
+    #[inline]
+    fn default() -> Self {
+        Context {
+            count: 0,
+            previous: HashSet::new(),
+            next: HashSet::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rand::Rng;
+
+    #[test]
+    fn independent() {
+        let mut rng = crate::tests::rng(1);
+        let sequence: Vec<u8> = (0..5_000).map(|_| rng.gen_range(0..3)).collect();
+        let tests = test_markov_order(&sequence, 2);
+
+        assert_eq!(tests.len(), 2);
+        assert_eq!(tests[0].degrees_of_freedom, 4);
+        assert_eq!(tests[1].degrees_of_freedom, 12);
+        assert!(tests.iter().all(|test| test.p_value > 1e-3));
+    }
+
+    #[test]
+    fn second_order() {
+        // The next value repeats the value two steps before, with some noise
+        let mut rng = crate::tests::rng(2);
+        let mut sequence = vec![0, 1];
+        for t in 2..5_000 {
+            let next = if rng.gen_bool(0.9) { sequence[t - 2] } else { rng.gen_range(0..2) };
+            sequence.push(next);
+        }
+        let tests = test_markov_order(&sequence, 3);
+
+        assert!(tests[1].p_value < 1e-6);
+        assert!(tests[2].p_value > 1e-3);
+    }
+
+    #[test]
+    fn deterministic() {
+        let sequence: Vec<u8> = (0..100).map(|t| t % 2).collect();
+        let tests = test_markov_order(&sequence, 2);
+
+        assert!(tests[0].p_value < 1e-6);
+        assert_eq!(tests[1].degrees_of_freedom, 0);
+        assert_eq!(tests[1].p_value, 1.);
+    }
+
+    #[test]
+    fn online() {
+        use ndarray::array;
+        let mut estimator = OnlineChainEstimator::new(1.0);
+        estimator.extend(vec![(0, 1), (1, 0), (0, 0), (0, 1)]);
+        assert_eq!(estimator.state_space(), &[0, 1]);
+        assert_eq!(estimator.transition_probabilities(), array![[1. / 3., 2. / 3.], [1., 0.]]);
+
+        let mut estimator = OnlineChainEstimator::new(0.5);
+        estimator.extend(vec![(0, 1), (0, 0), (2, 2)]);
+        // Weights 1 and 2 for the transitions from 0, and 2 is new
+        assert_eq!(estimator.transition_probabilities().row(0).to_vec(), vec![2. / 3., 1. / 3., 0.]);
+        assert_eq!(estimator.transition_probabilities().row(1).to_vec(), vec![0., 1., 0.]);
+    }
+
+    #[test]
+    fn forgetting() {
+        let mut estimator = OnlineChainEstimator::new(0.9);
+        for _ in 0..10_000 {
+            estimator.push(0, 1);
+        }
+        for _ in 0..200 {
+            estimator.push(0, 0);
+        }
+        let p = estimator.transition_probabilities();
+        assert!(p[[0, 0]] > 1. - 1e-8);
+        assert_eq!(estimator.snapshot(crate::tests::rng(1)).state_space(), &vec![0, 1]);
+    }
+
+    #[test]
+    fn ctmc() {
+        use ndarray::array;
+        let jumps = vec![(7., 'a'), (1., 'b'), (2., 'a'), (1., 'a'), (2., 'b'), (3., 'c')];
+        let fit = fit_ctmc(&jumps);
+
+        assert_eq!(fit.state_space, vec!['a', 'b', 'c']);
+        assert_eq!(fit.holding_times, array![4., 5., 0.]);
+        assert_eq!(fit.generator(), array![[-0.5, 0.5, 0.], [0.2, -0.4, 0.2], [0., 0., 0.]]);
+        assert_eq!(fit.confidence_intervals(0.9)[[2, 0]], (0., f64::INFINITY));
+        let (low, high) = fit.confidence_intervals(0.9)[[0, 2]];
+        assert_eq!(low, 0.);
+        // With no jumps observed, the upper bound is -ln(0.05) / R
+        assert!((high - 20_f64.ln() / 4.).abs() < 1e-8);
+        assert_eq!(fit.chain(crate::tests::rng(1)).generator(), fit.generator());
+    }
+
+    #[test]
+    fn ctmc_em() {
+        use ndarray::array;
+        use rand::SeedableRng;
+        let generator = array![[-1., 0.5, 0.5], [1., -2., 1.], [0.2, 0.3, -0.5]];
+        let weights: Vec<Vec<f64>> = generator
+            .genrows()
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| row.iter().enumerate().map(|(j, &q)| if i == j { 0. } else { q }).collect())
+            .collect();
+        let mut paths = Vec::new();
+        for seed in 0..20 {
+            let rng = rand_pcg::Pcg64::seed_from_u64(seed);
+            let mc = crate::ContFiniteMarkovChain::new(0, weights.clone(), vec![0, 1, 2], rng);
+            let path: Vec<(f64, usize)> = crate::TimedIterator::sample_on_grid(mc, 0.5, 500)
+                .enumerate()
+                .map(|(k, state)| (k as f64 * 0.5, state))
+                .collect();
+            paths.push(path);
+        }
+        let fit = fit_ctmc_em(&paths, 500);
+
+        assert_eq!(fit.state_space, vec![0, 1, 2]);
+        for (estimate, exact) in fit.generator.iter().zip(generator.iter()) {
+            assert!((estimate - exact).abs() < 0.15, "{} {}", estimate, exact);
+        }
+        // The likelihood does not decrease
+        let start = fit_ctmc_em(&paths, 0);
+        let step = fit_ctmc_em(&paths, 1);
+        assert!(start.log_likelihood <= step.log_likelihood);
+        assert!(step.log_likelihood <= fit.log_likelihood);
+    }
+}
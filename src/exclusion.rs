@@ -0,0 +1,461 @@
+//! Exclusion processes: particles jumping between neighbouring sites, at most one per site.
+//!
+//! An [Exclusion] process lives on a ring or a segment of sites, and its state is the occupancy
+//! of each site. Particles jump to the right at rate `right` and to the left at rate `left`,
+//! provided the target site is empty. Special cases are
+//! - TASEP, the totally asymmetric simple exclusion process: `right = 1`, `left = 0`.
+//! - SSEP, the symmetric simple exclusion process: `right = left = 1`.
+//!
+//! On an open segment, see [Boundary], particles enter the first site at rate `alpha`
+//! and leave the last site at rate `beta`.
+//!
+//! Dynamics are simulated by random sequential updates: each step is a sweep of as many attempts
+//! as bonds, each on a bond chosen uniformly at random, and lasts `1 / max_rate` units of time.
+//! Along the way, the process measures the current through each bond and the density profile.
+//!
+//! # Examples
+//!
+//! The current of TASEP on an open segment, in the maximal current phase, approaches one fourth.
+//! ```
+//! # use markovian::exclusion::{Boundary, Exclusion};
+//! let boundary = Boundary::Open { alpha: 1., beta: 1. };
+//! let mut tasep = Exclusion::tasep(vec![false; 100], boundary, rand::thread_rng());
+//! tasep.nth(1_000);
+//! tasep.reset_measurements();
+//! tasep.nth(1_000);
+//! println!("The current is {}", tasep.current());
+//! ```
+//!
+//! [Exclusion]: struct.Exclusion.html
+//! [Boundary]: enum.Boundary.html
+
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
+use rand::{Rng, SeedableRng};
+
+// Structs
+use crate::errors::InvalidState;
+
+// Functions
+use core::mem;
+
+/// Boundary conditions of an exclusion process.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary {
+    /// Sites form a ring: the last site is next to the first one.
+    Ring,
+    /// Sites form a segment and particles can not leave it.
+    Closed,
+    /// Sites form a segment, particles enter the first site at rate `alpha`
+    /// and leave the last site at rate `beta`.
+    Open { alpha: f64, beta: f64 },
+}
+
+/// Exclusion process on a ring or a segment, with states the occupancy of each site.
+///
+/// # Examples
+///
+/// SSEP on a ring conserves the number of particles.
+/// ```
+/// # use markovian::exclusion::{Boundary, Exclusion};
+/// let ssep = Exclusion::ssep(vec![true, true, false, false], Boundary::Ring, rand::thread_rng());
+/// for occupancy in ssep.take(10) {
+///     assert_eq!(occupancy.iter().filter(|&&occupied| occupied).count(), 2);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Exclusion<R> {
+    state: Vec<bool>,
+    initial_state: Vec<bool>,
+    right: f64,
+    left: f64,
+    boundary: Boundary,
+    max_rate: f64,
+    time: f64,
+    flux: Vec<i64>,
+    occupation: Vec<u64>,
+    sweeps: u64,
+    rng: R,
+}
+
+impl<R> Exclusion<R>
+where
+    R: Rng,
+{
+    /// Constructs a new `Exclusion<R>` with initial `occupancy`, where particles jump
+    /// to the right at rate `right` and to the left at rate `left`.
+    ///
+    /// # Panics
+    ///
+    /// If there are no sites, or some rate is negative or not finite, or all rates are zero.
+    #[inline]
+    pub fn new(occupancy: Vec<bool>, right: f64, left: f64, boundary: Boundary, rng: R) -> Self {
+        assert!(!occupancy.is_empty(), "There must be some site.");
+        let mut rates = vec![right, left];
+        if let Boundary::Open { alpha, beta } = boundary {
+            rates.push(alpha);
+            rates.push(beta);
+        }
+        assert!(rates.iter().all(|rate| rate.is_finite() && *rate >= 0.), "Rates must be non-negative.");
+        let max_rate = rates.iter().fold(right + left, |max: f64, &rate| max.max(rate));
+        assert!(max_rate > 0., "Some rate must be positive.");
+        let nbonds = nbonds(occupancy.len(), boundary);
+        Exclusion {
+            initial_state: occupancy.clone(),
+            occupation: vec![0; occupancy.len()],
+            state: occupancy,
+            right,
+            left,
+            boundary,
+            max_rate,
+            time: 0.,
+            flux: vec![0; nbonds],
+            sweeps: 0,
+            rng,
+        }
+    }
+
+    /// Constructs a new `Exclusion<R>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [new].
+    ///
+    /// [new]: struct.Exclusion.html#method.new
+    #[inline]
+    pub fn with_seed(occupancy: Vec<bool>, right: f64, left: f64, boundary: Boundary, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        Exclusion::new(occupancy, right, left, boundary, R::seed_from_u64(seed))
+    }
+
+    /// Constructs a totally asymmetric simple exclusion process, where particles jump to the right at rate one.
+    ///
+    /// # Panics
+    ///
+    /// If there are no sites, or boundary rates are negative or not finite.
+    #[inline]
+    pub fn tasep(occupancy: Vec<bool>, boundary: Boundary, rng: R) -> Self {
+        Exclusion::new(occupancy, 1., 0., boundary, rng)
+    }
+
+    /// Constructs a symmetric simple exclusion process, where particles jump to each side at rate one.
+    ///
+    /// # Panics
+    ///
+    /// If there are no sites, or boundary rates are negative or not finite.
+    #[inline]
+    pub fn ssep(occupancy: Vec<bool>, boundary: Boundary, rng: R) -> Self {
+        Exclusion::new(occupancy, 1., 1., boundary, rng)
+    }
+
+    /// Returns the number of sites.
+    #[inline]
+    pub fn nsites(&self) -> usize {
+        self.state.len()
+    }
+
+    /// Returns the number of particles.
+    #[inline]
+    pub fn nparticles(&self) -> usize {
+        self.state.iter().filter(|&&occupied| occupied).count()
+    }
+
+    /// Returns the proportion of occupied sites.
+    #[inline]
+    pub fn density(&self) -> f64 {
+        self.nparticles() as f64 / self.nsites() as f64
+    }
+
+    /// Returns the boundary conditions.
+    #[inline]
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// Returns the time elapsed since the last reset of the measurements.
+    #[inline]
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Performs one sweep of random sequential updates, i.e. one attempt per bond on average.
+    #[inline]
+    pub fn sweep(&mut self) {
+        let nbonds = self.flux.len();
+        for _ in 0..nbonds {
+            let bond = self.rng.gen_range(0..nbonds);
+            let u = self.rng.gen::<f64>() * self.max_rate;
+            self.attempt(bond, u);
+        }
+        self.time += 1. / self.max_rate;
+        self.sweeps += 1;
+        for (occupation, &occupied) in self.occupation.iter_mut().zip(&self.state) {
+            *occupation += occupied as u64;
+        }
+    }
+
+    /// Returns the net number of particles that crossed each bond to the right, per unit of time,
+    /// since the last reset of the measurements.
+    ///
+    /// Bonds are numbered from left to right. On a ring, bond `i` joins sites `i` and `i + 1`,
+    /// modulo the number of sites. On an open segment, the first bond enters the first site
+    /// and the last bond leaves the last site.
+    #[inline]
+    pub fn bond_currents(&self) -> Vec<f64> {
+        if self.time == 0. {
+            return vec![0.; self.flux.len()];
+        }
+        self.flux.iter().map(|&flux| flux as f64 / self.time).collect()
+    }
+
+    /// Returns the average of the currents through every bond, since the last reset of the measurements.
+    #[inline]
+    pub fn current(&self) -> f64 {
+        let currents = self.bond_currents();
+        currents.iter().sum::<f64>() / currents.len().max(1) as f64
+    }
+
+    /// Returns the proportion of sweeps after which each site was occupied,
+    /// since the last reset of the measurements.
+    #[inline]
+    pub fn density_profile(&self) -> Vec<f64> {
+        if self.sweeps == 0 {
+            return self.state.iter().map(|&occupied| occupied as u64 as f64).collect();
+        }
+        self.occupation.iter().map(|&occupation| occupation as f64 / self.sweeps as f64).collect()
+    }
+
+    /// Discards the measurements of current and density, for example, after a burn-in period.
+    #[inline]
+    pub fn reset_measurements(&mut self) {
+        self.time = 0.;
+        self.sweeps = 0;
+        self.flux.iter_mut().for_each(|flux| *flux = 0);
+        self.occupation.iter_mut().for_each(|occupation| *occupation = 0);
+    }
+
+    /// Attempts a jump through `bond`, which happens if `u` is below its rate.
+    #[inline]
+    fn attempt(&mut self, bond: usize, u: f64) {
+        let n = self.state.len();
+        let (from, to) = match self.boundary {
+            Boundary::Ring => (bond, (bond + 1) % n),
+            Boundary::Closed => (bond, bond + 1),
+            Boundary::Open { alpha, beta } => {
+                if bond == 0 {
+                    if u < alpha && !self.state[0] {
+                        self.state[0] = true;
+                        self.flux[0] += 1;
+                    }
+                    return;
+                }
+                if bond == n {
+                    if u < beta && self.state[n - 1] {
+                        self.state[n - 1] = false;
+                        self.flux[n] += 1;
+                    }
+                    return;
+                }
+                (bond - 1, bond)
+            }
+        };
+        if from == to {
+            return;
+        }
+        if u < self.right {
+            if self.state[from] && !self.state[to] {
+                self.state.swap(from, to);
+                self.flux[bond] += 1;
+            }
+        } else if u < self.right + self.left && !self.state[from] && self.state[to] {
+            self.state.swap(from, to);
+            self.flux[bond] -= 1;
+        }
+    }
+}
+
+impl<R> Reset for Exclusion<R> {
+    /// Restarts from the initial occupancy and discards the measurements.
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+        self.time = 0.;
+        self.sweeps = 0;
+        self.flux.iter_mut().for_each(|flux| *flux = 0);
+        self.occupation.iter_mut().for_each(|occupation| *occupation = 0);
+    }
+}
+
+impl<R> RandomSource for Exclusion<R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<R> State for Exclusion<R> {
+    type Item = Vec<bool>;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    /// Changes the occupancy, as long as the number of sites does not change.
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        if new_state.len() == self.state.len() {
+            Ok(Some(mem::replace(&mut self.state, new_state)))
+        } else {
+            Err(InvalidState::new(new_state))
+        }
+    }
+}
+
+impl<R> Checkpoint for Exclusion<R>
+where
+    R: Rng,
+{
+    /// The hidden progress is the time, the number of sweeps, the flux through each bond
+    /// and the occupation of each site, accumulated since the last reset of the statistics.
+    type Hidden = (f64, u64, Vec<i64>, Vec<u64>);
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {
+        (self.time, self.sweeps, self.flux.clone(), self.occupation.clone())
+    }
+
+    #[inline]
+    fn set_hidden_state(&mut self, (time, sweeps, flux, occupation): Self::Hidden) {
+        self.time = time;
+        self.sweeps = sweeps;
+        self.flux = flux;
+        self.occupation = occupation;
+    }
+
+    #[inline]
+    fn progress(&self) -> (usize, f64) {
+        (self.sweeps as usize, self.time)
+    }
+}
+
+impl<R> Iterator for Exclusion<R>
+where
+    R: Rng,
+{
+    type Item = Vec<bool>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sweep();
+        Some(self.state.clone())
+    }
+}
+
+impl<R> StochasticProcess for Exclusion<R>
+where
+    R: Rng,
+{
+    type Time = ();
+    type State = Vec<bool>;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<R> StateIterator for Exclusion<R>
+where
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        Some(self.state.clone())
+    }
+
+    #[inline]
+    fn advance(&mut self) -> bool {
+        self.sweep();
+        true
+    }
+}
+
+/// Returns the number of bonds of `nsites` sites with `boundary` conditions.
+#[inline]
+fn nbonds(nsites: usize, boundary: Boundary) -> usize {
+    match boundary {
+        Boundary::Ring => nsites,
+        Boundary::Closed => nsites - 1,
+        Boundary::Open { .. } => nsites + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn ring_current() {
+        // The stationary distribution is uniform, with current N (L - N) / (L (L - 1))
+        let occupancy = (0..10).map(|i| i < 5).collect();
+        let mut tasep = Exclusion::tasep(occupancy, Boundary::Ring, crate::tests::rng(1));
+        tasep.nth(100);
+        tasep.reset_measurements();
+        tasep.nth(20_000);
+        assert_eq!(tasep.nparticles(), 5);
+        assert_abs_diff_eq!(tasep.current(), 25. / 90., epsilon = 0.01);
+        for density in tasep.density_profile() {
+            assert_abs_diff_eq!(density, 0.5, epsilon = 0.03);
+        }
+
+        let mut ssep = Exclusion::ssep(vec![true, false, true, false], Boundary::Ring, crate::tests::rng(2));
+        ssep.nth(20_000);
+        assert_abs_diff_eq!(ssep.current(), 0., epsilon = 0.01);
+    }
+
+    #[test]
+    fn open_profile() {
+        // SSEP between reservoirs has a linear density profile, and zero current on closed segments
+        let boundary = Boundary::Open { alpha: 1., beta: 1. };
+        let mut ssep = Exclusion::ssep(vec![false; 9], boundary, crate::tests::rng(3));
+        ssep.nth(1_000);
+        ssep.reset_measurements();
+        ssep.nth(50_000);
+        let profile = ssep.density_profile();
+        assert!(profile[0] > profile[4] && profile[4] > profile[8]);
+        let currents = ssep.bond_currents();
+        assert_eq!(currents.len(), 10);
+        assert!(currents.iter().all(|&current| (current - ssep.current()).abs() < 0.01));
+
+        let mut closed = Exclusion::new(vec![true, false, false], 2., 1., Boundary::Closed, crate::tests::rng(4));
+        closed.nth(1_000);
+        assert_eq!(closed.nparticles(), 1);
+        assert!(closed.current().abs() < 0.01);
+        closed.reset();
+        assert_eq!(closed.state(), Some(&vec![true, false, false]));
+        assert_eq!(closed.time(), 0.);
+    }
+}
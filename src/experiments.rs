@@ -0,0 +1,271 @@
+//! Parameter sweeps over families of processes.
+//!
+//! An [Experiment] declares a grid of parameters, a factory that constructs a process
+//! from a parameter and a seed, and a summary of each trajectory. Running it simulates
+//! a number of replicates for each parameter and returns a tidy [Table], with one row per trajectory.
+//!
+//! Each trajectory gets its own seed, derived from the seed of the experiment,
+//! so results are reproducible and do not depend on the order of execution.
+//! With the `rayon` feature, experiments can be run in parallel by [run_parallel].
+//!
+//! # Examples
+//!
+//! Expected number of arrivals of Poisson processes up to time 10, for several rates.
+//! ```
+//! # use rand::SeedableRng;
+//! # use markovian::{prelude::*, experiments::Experiment};
+//! let table = Experiment::new(
+//!     vec![0.5, 1.0, 2.0],
+//!     |&lambda: &f64, seed| {
+//!         Poisson::<f64, usize, _>::new(lambda, rand_pcg::Pcg64::seed_from_u64(seed)).unwrap()
+//!     },
+//!     |process| process.with_absolute_time().take_while(|(time, _)| *time < 10.).count(),
+//! )
+//! .with_replicates(100)
+//! .run();
+//! for (lambda, statistics) in table.statistics() {
+//!     println!("lambda = {}: {} ± {}", lambda, statistics.mean(), statistics.standard_error());
+//! }
+//! ```
+//!
+//! [Experiment]: struct.Experiment.html
+//! [Table]: struct.Table.html
+//! [run_parallel]: struct.Experiment.html#method.run_parallel
+
+// Traits
+use num_traits::ToPrimitive;
+
+// Structs
+use crate::stats::Welford;
+
+/// Result of one trajectory of an experiment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row<P, Y> {
+    /// Parameter of the process.
+    pub parameter: P,
+    /// Index of the replicate, among those of the same parameter.
+    pub replicate: usize,
+    /// Seed given to the factory.
+    pub seed: u64,
+    /// Summary of the trajectory.
+    pub value: Y,
+}
+
+/// Results of an experiment, one row per trajectory.
+///
+/// Rows are ordered by parameter, in the order of the grid, and then by replicate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table<P, Y> {
+    rows: Vec<Row<P, Y>>,
+}
+
+impl<P, Y> Table<P, Y> {
+    /// Returns the rows of the table.
+    #[inline]
+    pub fn rows(&self) -> &[Row<P, Y>] {
+        &self.rows
+    }
+
+    /// Returns the rows of the table, consuming it.
+    #[inline]
+    pub fn into_rows(self) -> Vec<Row<P, Y>> {
+        self.rows
+    }
+
+    /// Returns the statistics of the values of each parameter, in the order of the grid.
+    ///
+    /// # Panics
+    ///
+    /// If some value can not be represented as `f64`.
+    #[inline]
+    pub fn statistics(&self) -> Vec<(&P, Welford)>
+    where
+        P: PartialEq,
+        Y: ToPrimitive,
+    {
+        let mut statistics: Vec<(&P, Welford)> = Vec::new();
+        for row in &self.rows {
+            let value = row.value.to_f64().unwrap();
+            match statistics.last_mut() {
+                Some((parameter, welford)) if *parameter == &row.parameter => welford.push(value),
+                _ => {
+                    let mut welford = Welford::new();
+                    welford.push(value);
+                    statistics.push((&row.parameter, welford));
+                }
+            }
+        }
+        statistics
+    }
+}
+
+/// Declaration of a parameter sweep.
+#[derive(Debug, Clone)]
+pub struct Experiment<P, F, S> {
+    parameters: Vec<P>,
+    factory: F,
+    summary: S,
+    replicates: usize,
+    seed: u64,
+}
+
+impl<P, F, S> Experiment<P, F, S> {
+    /// Constructs a new `Experiment` over the grid `parameters`, where `factory` constructs a process
+    /// from a parameter and a seed, and `summary` summarizes the trajectory of a process.
+    ///
+    /// By default, there is one replicate per parameter and the seed of the experiment is zero.
+    #[inline]
+    pub fn new<C, Y>(parameters: Vec<P>, factory: F, summary: S) -> Self
+    where
+        F: Fn(&P, u64) -> C,
+        S: Fn(C) -> Y,
+    {
+        Experiment {
+            parameters,
+            factory,
+            summary,
+            replicates: 1,
+            seed: 0,
+        }
+    }
+
+    /// Sets the number of trajectories simulated for each parameter.
+    #[inline]
+    pub fn with_replicates(mut self, replicates: usize) -> Self {
+        self.replicates = replicates;
+        self
+    }
+
+    /// Sets the seed from which the seeds of all trajectories are derived.
+    #[inline]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the seed of the trajectory of a given index.
+    #[inline]
+    fn seed_of(&self, index: usize) -> u64 {
+        splitmix64(self.seed ^ splitmix64(index as u64))
+    }
+
+    /// Runs the experiment.
+    #[inline]
+    pub fn run<C, Y>(&self) -> Table<P, Y>
+    where
+        P: Clone,
+        F: Fn(&P, u64) -> C,
+        S: Fn(C) -> Y,
+    {
+        let rows = (0..self.parameters.len() * self.replicates)
+            .map(|index| self.row(index))
+            .collect();
+        Table { rows }
+    }
+
+    /// Runs the experiment, simulating trajectories in parallel.
+    ///
+    /// Results are the same as those of [run].
+    ///
+    /// [run]: struct.Experiment.html#method.run
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub fn run_parallel<C, Y>(&self) -> Table<P, Y>
+    where
+        P: Clone + Send + Sync,
+        F: Fn(&P, u64) -> C + Sync,
+        S: Fn(C) -> Y + Sync,
+        Y: Send,
+    {
+        use rayon::prelude::*;
+        let rows = (0..self.parameters.len() * self.replicates)
+            .into_par_iter()
+            .map(|index| self.row(index))
+            .collect();
+        Table { rows }
+    }
+
+    #[inline]
+    fn row<C, Y>(&self, index: usize) -> Row<P, Y>
+    where
+        P: Clone,
+        F: Fn(&P, u64) -> C,
+        S: Fn(C) -> Y,
+    {
+        let parameter = &self.parameters[index / self.replicates];
+        let seed = self.seed_of(index);
+        Row {
+            parameter: parameter.clone(),
+            replicate: index % self.replicates,
+            seed,
+            value: (self.summary)((self.factory)(parameter, seed)),
+        }
+    }
+}
+
+/// Returns the cartesian product of two grids of parameters.
+///
+/// # Examples
+///
+/// Birth and death probabilities.
+/// ```
+/// # use markovian::experiments::grid;
+/// assert_eq!(grid(&[0.1, 0.2], &[0.5]), vec![(0.1, 0.5), (0.2, 0.5)]);
+/// ```
+#[inline]
+pub fn grid<A, B>(first: &[A], second: &[B]) -> Vec<(A, B)>
+where
+    A: Clone,
+    B: Clone,
+{
+    first.iter()
+        .flat_map(|a| second.iter().map(move |b| (a.clone(), b.clone())))
+        .collect()
+}
+
+/// Scrambles `x` by the finalizer of SplitMix64.
+#[inline]
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+    use rand::SeedableRng;
+
+    fn walk(p: &f64, seed: u64) -> impl Iterator<Item = i32> {
+        let p = *p;
+        let transition = move |state: &i32| raw_dist![(p, state + 1), (1. - p, *state)];
+        crate::MarkovChain::new(0, transition, rand_pcg::Pcg64::seed_from_u64(seed))
+    }
+
+    #[test]
+    fn sweep() {
+        let table = Experiment::new(vec![0.0, 1.0], walk, |mut path| path.nth(9).unwrap())
+            .with_replicates(3)
+            .run();
+
+        assert_eq!(table.rows().len(), 6);
+        assert_eq!(table.rows().iter().map(|row| row.value).collect::<Vec<i32>>(), vec![0, 0, 0, 10, 10, 10]);
+        assert_eq!(table.rows()[4].replicate, 1);
+        let statistics = table.statistics();
+        assert_eq!(statistics.len(), 2);
+        assert_eq!((statistics[1].0, statistics[1].1.mean()), (&1.0, 10.));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel() {
+        let experiment = Experiment::new(vec![0.2, 0.5], walk, |mut path| path.nth(9).unwrap())
+            .with_replicates(10)
+            .with_seed(7);
+
+        assert_eq!(experiment.run_parallel(), experiment.run());
+    }
+}
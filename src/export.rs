@@ -0,0 +1,339 @@
+//! Exporting recorded trajectories.
+//!
+//! [Trajectories] collects the paths of several replicates of a process and writes them as CSV,
+//! in a tidy format with columns `replicate`, `step`, `time` and one column for each field
+//! of the state, so that they can be read directly by data analysis tools.
+//! With the `parquet` feature, they can also be written as Parquet.
+//!
+//! # Examples
+//!
+//! Two replicates of a Poisson process up to time 5.
+//! ```
+//! # use markovian::{prelude::*, export::Trajectories};
+//! let mut trajectories = Trajectories::new();
+//! for _ in 0..2 {
+//!     let poisson = Poisson::<f64, u32, _>::new(1., rand::thread_rng()).unwrap();
+//!     trajectories.push_timed_path(poisson.trajectory_until(5.));
+//! }
+//! let mut csv = Vec::new();
+//! trajectories.to_csv(&mut csv).unwrap();
+//! assert!(String::from_utf8(csv).unwrap().starts_with("replicate,step,time,state\n0,0,0,0\n"));
+//! ```
+//!
+//! [Trajectories]: struct.Trajectories.html
+
+// Traits
+use std::io::Write;
+
+// Functions
+use std::borrow::Cow;
+
+#[cfg(feature = "parquet")]
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, UInt64Array};
+#[cfg(feature = "parquet")]
+use arrow_schema::{DataType, Field, Schema};
+#[cfg(feature = "parquet")]
+use parquet::{arrow::ArrowWriter, errors::ParquetError};
+#[cfg(feature = "parquet")]
+use std::sync::Arc;
+
+/// States that can be written as a fixed number of named fields.
+pub trait Fields {
+    /// Returns the names of the fields.
+    fn names() -> Vec<String>;
+
+    /// Returns the values of the fields, formatted.
+    fn values(&self) -> Vec<String>;
+}
+
+macro_rules! impl_fields_for_primitives {
+    ($($t:ty),*) => {
+        $(
+            impl Fields for $t {
+                #[inline]
+                fn names() -> Vec<String> {
+                    vec!["state".to_string()]
+                }
+
+                #[inline]
+                fn values(&self) -> Vec<String> {
+                    vec![self.to_string()]
+                }
+            }
+        )*
+    };
+}
+
+impl_fields_for_primitives!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, char);
+
+/// Renames the fields of the `index`-th component of a compound state.
+#[inline]
+fn component_names<F: Fields>(index: usize) -> Vec<String> {
+    F::names()
+        .into_iter()
+        .map(|name| name.replacen("state", &format!("state_{}", index), 1))
+        .collect()
+}
+
+macro_rules! impl_fields_for_tuples {
+    ($(($($t:ident $i:tt),+)),*) => {
+        $(
+            impl<$($t: Fields),+> Fields for ($($t,)+) {
+                #[inline]
+                fn names() -> Vec<String> {
+                    let mut names = Vec::new();
+                    $(names.extend(component_names::<$t>($i));)+
+                    names
+                }
+
+                #[inline]
+                fn values(&self) -> Vec<String> {
+                    let mut values = Vec::new();
+                    $(values.extend(self.$i.values());)+
+                    values
+                }
+            }
+        )*
+    };
+}
+
+impl_fields_for_tuples!((A 0, B 1), (A 0, B 1, C 2), (A 0, B 1, C 2, D 3));
+
+impl<F, const N: usize> Fields for [F; N]
+where
+    F: Fields,
+{
+    #[inline]
+    fn names() -> Vec<String> {
+        (0..N).flat_map(component_names::<F>).collect()
+    }
+
+    #[inline]
+    fn values(&self) -> Vec<String> {
+        self.iter().flat_map(Fields::values).collect()
+    }
+}
+
+/// Record of the paths of several replicates of a process.
+///
+/// Discrete-time paths are recorded with their step as time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trajectories<T> {
+    rows: Vec<(usize, usize, f64, T)>,
+    replicates: usize,
+}
+
+impl<T> Trajectories<T> {
+    /// Constructs a new empty `Trajectories`.
+    #[inline]
+    pub fn new() -> Self {
+        Trajectories {
+            rows: Vec::new(),
+            replicates: 0,
+        }
+    }
+
+    /// Records the path of a new replicate of a discrete-time process.
+    #[inline]
+    pub fn push_path<I>(&mut self, path: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let replicate = self.replicates;
+        self.rows.extend(
+            path.into_iter()
+                .enumerate()
+                .map(|(step, state)| (replicate, step, step as f64, state)),
+        );
+        self.replicates += 1;
+    }
+
+    /// Records the path of a new replicate of a continuous-time process,
+    /// given as pairs of absolute time and state.
+    ///
+    /// # Remarks
+    ///
+    /// Paths with absolute time are returned, for example, by [trajectory_until].
+    ///
+    /// [trajectory_until]: ../trait.TimedIterator.html#method.trajectory_until
+    #[inline]
+    pub fn push_timed_path<I>(&mut self, path: I)
+    where
+        I: IntoIterator<Item = (f64, T)>,
+    {
+        let replicate = self.replicates;
+        self.rows.extend(
+            path.into_iter()
+                .enumerate()
+                .map(|(step, (time, state))| (replicate, step, time, state)),
+        );
+        self.replicates += 1;
+    }
+
+    /// Returns the number of replicates.
+    #[inline]
+    pub fn replicates(&self) -> usize {
+        self.replicates
+    }
+
+    /// Returns the number of recorded states, over all replicates.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns `true` if no state was recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Writes the trajectories as CSV, with a header.
+    ///
+    /// Fields are quoted as in RFC 4180 when they contain commas, quotes or line breaks.
+    ///
+    /// # Errors
+    ///
+    /// If writing fails.
+    #[inline]
+    pub fn to_csv<W>(&self, mut writer: W) -> std::io::Result<()>
+    where
+        W: Write,
+        T: Fields,
+    {
+        writeln!(writer, "replicate,step,time,{}", csv_record(&T::names()))?;
+        for (replicate, step, time, state) in &self.rows {
+            writeln!(writer, "{},{},{},{}", replicate, step, time, csv_record(&state.values()))?;
+        }
+        writer.flush()
+    }
+
+    /// Writes the trajectories as Parquet, with the columns of [to_csv].
+    ///
+    /// Columns `replicate` and `step` are unsigned integers, `time` is a float
+    /// and the fields of the state are strings, as formatted by [Fields].
+    ///
+    /// # Errors
+    ///
+    /// If writing fails.
+    ///
+    /// [to_csv]: #method.to_csv
+    /// [Fields]: trait.Fields.html
+    #[cfg(feature = "parquet")]
+    #[inline]
+    pub fn to_parquet<W>(&self, writer: W) -> Result<(), ParquetError>
+    where
+        W: Write + Send,
+        T: Fields,
+    {
+        let names = T::names();
+        let mut fields = vec![
+            Field::new("replicate", DataType::UInt64, false),
+            Field::new("step", DataType::UInt64, false),
+            Field::new("time", DataType::Float64, false),
+        ];
+        fields.extend(names.iter().map(|name| Field::new(name, DataType::Utf8, false)));
+        let schema = Arc::new(Schema::new(fields));
+
+        let mut values = vec![Vec::with_capacity(self.rows.len()); names.len()];
+        for (_, _, _, state) in &self.rows {
+            for (column, value) in values.iter_mut().zip(state.values()) {
+                column.push(value);
+            }
+        }
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt64Array::from_iter_values(self.rows.iter().map(|row| row.0 as u64))),
+            Arc::new(UInt64Array::from_iter_values(self.rows.iter().map(|row| row.1 as u64))),
+            Arc::new(Float64Array::from_iter_values(self.rows.iter().map(|row| row.2))),
+        ];
+        columns.extend(values.into_iter().map(|column| Arc::new(StringArray::from(column)) as ArrayRef));
+        let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+        let mut writer = ArrowWriter::try_new(writer, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+/// Joins `fields` into a CSV record, quoting them as in RFC 4180 when needed.
+#[inline]
+fn csv_record(fields: &[String]) -> String {
+    fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",")
+}
+
+/// Quotes `field` if it contains commas, quotes or line breaks, doubling its quotes.
+#[inline]
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+impl<T> Default for Trajectories<T> {
+    #[inline]
+    fn default() -> Self {
+        Trajectories::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn csv() {
+        let mut trajectories = Trajectories::new();
+        trajectories.push_path(vec![(1, [0.5, 1.5]), (2, [2.5, 3.5])]);
+        trajectories.push_path(vec![(3, [4.5, 5.5])]);
+        let mut csv = Vec::new();
+        trajectories.to_csv(&mut csv).unwrap();
+
+        assert_eq!(trajectories.replicates(), 2);
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "replicate,step,time,state_0,state_1_0,state_1_1\n\
+             0,0,0,1,0.5,1.5\n\
+             0,1,1,2,2.5,3.5\n\
+             1,0,0,3,4.5,5.5\n"
+        );
+    }
+
+    #[test]
+    fn csv_escaping() {
+        let mut trajectories = Trajectories::new();
+        trajectories.push_path(vec![',', '"', 'a']);
+        let mut csv = Vec::new();
+        trajectories.to_csv(&mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "replicate,step,time,state\n\
+             0,0,0,\",\"\n\
+             0,1,1,\"\"\"\"\n\
+             0,2,2,a\n"
+        );
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let mut trajectories = Trajectories::new();
+        trajectories.push_path(vec![(1, 0.5), (2, 2.5)]);
+        trajectories.push_timed_path(vec![(0.25, (3, 4.5))]);
+        let path = std::env::temp_dir().join("markovian_export_parquet.parquet");
+        trajectories.to_parquet(std::fs::File::create(&path).unwrap()).unwrap();
+
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        let columns: Vec<&str> = metadata.schema_descr().columns().iter().map(|column| column.name()).collect();
+        assert_eq!(columns, vec!["replicate", "step", "time", "state_0", "state_1"]);
+        assert_eq!(metadata.num_rows(), 3);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,597 @@
+//! Sequential Monte Carlo estimation of hidden states.
+//!
+//! A hidden Markov model is given by a [Transition] of the hidden state and
+//! the likelihood of an observation given the hidden state.
+//! A particle filter approximates the distribution of the hidden state given
+//! all observations so far by a weighted cloud of particles: at each observation,
+//! particles move according to the transition (predict), are reweighted by the
+//! likelihood of the observation (update) and, if the weights degenerate, are resampled.
+//! If there are finitely many hidden states, the forward filter computes this distribution exactly.
+//!
+//! # Examples
+//!
+//! Tracking a random walk observed with noise.
+//! ```
+//! # use markovian::{prelude::*, filtering::ParticleFilter};
+//! # use rand::SeedableRng;
+//! let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+//! let likelihood = |state: &i32, observation: &i32| if (state - observation).abs() <= 1 { 1.0 } else { 0.0 };
+//! let rng = rand_pcg::Pcg64::seed_from_u64(1);
+//! let mut filter = ParticleFilter::new(vec![0; 100], transition, likelihood, rng);
+//! filter.run(vec![1, 2, 3, 2]);
+//! let mean = filter.mean(|state| *state as f64);
+//! assert!((mean - 2.).abs() <= 1.);
+//! ```
+//!
+//! [Transition]: ../trait.Transition.html
+
+// Traits
+use crate::{RandomSource, Transition};
+use rand::{Rng, SeedableRng};
+use rand_distr::Distribution;
+
+// Structs
+use core::marker::PhantomData;
+use rand::distributions::WeightedIndex;
+
+/// Bootstrap particle filter with systematic resampling.
+///
+/// The proposal of each particle is given by the transition and
+/// the weights are updated by the likelihood of the observation.
+/// The filter resamples whenever the effective sample size falls below
+/// a fraction of the number of particles, by default one half.
+#[derive(Debug, Clone)]
+pub struct ParticleFilter<S, O, T, L, R>
+where
+    T: Transition<S, S>,
+    L: Fn(&S, &O) -> f64,
+    R: Rng,
+{
+    particles: Vec<S>,
+    weights: Vec<f64>,
+    transition: T,
+    likelihood: L,
+    resample_threshold: f64,
+    log_likelihood: f64,
+    rng: R,
+    phantom: PhantomData<O>,
+}
+
+impl<S, O, T, L, R> ParticleFilter<S, O, T, L, R>
+where
+    T: Transition<S, S>,
+    L: Fn(&S, &O) -> f64,
+    R: Rng,
+{
+    /// Constructs a new `ParticleFilter<S, O, T, L, R>` from an initial cloud of particles,
+    /// all with the same weight.
+    ///
+    /// # Panics
+    ///
+    /// If `particles` is empty.
+    #[inline]
+    pub fn new(particles: Vec<S>, transition: T, likelihood: L, rng: R) -> Self {
+        assert!(!particles.is_empty(), "There must be at least one particle.");
+        let n = particles.len();
+        ParticleFilter {
+            particles,
+            weights: vec![1.0 / n as f64; n],
+            transition,
+            likelihood,
+            resample_threshold: 0.5,
+            log_likelihood: 0.0,
+            rng,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Constructs a new `ParticleFilter<S, O, T, L, R>` seeding the random number generator.
+    ///
+    /// # Panics
+    ///
+    /// If `particles` is empty.
+    #[inline]
+    pub fn with_seed(particles: Vec<S>, transition: T, likelihood: L, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        Self::new(particles, transition, likelihood, R::seed_from_u64(seed))
+    }
+
+    /// Sets the fraction of the number of particles below which
+    /// the effective sample size triggers a resampling.
+    ///
+    /// A threshold of one resamples at every step and a threshold of zero never does.
+    ///
+    /// # Panics
+    ///
+    /// If `threshold` is not in the interval [0, 1].
+    #[inline]
+    pub fn resample_threshold(mut self, threshold: f64) -> Self {
+        assert!((0.0..=1.0).contains(&threshold), "The threshold must be in [0, 1].");
+        self.resample_threshold = threshold;
+        self
+    }
+
+    /// Returns the current particles.
+    #[inline]
+    pub fn particles(&self) -> &[S] {
+        &self.particles
+    }
+
+    /// Returns the current normalized weights of the particles.
+    #[inline]
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Returns the number of particles.
+    #[inline]
+    pub fn nparticles(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Returns the effective sample size of the current weights, `1 / sum w_i^2`.
+    #[inline]
+    pub fn effective_sample_size(&self) -> f64 {
+        1.0 / self.weights.iter().map(|w| w * w).sum::<f64>()
+    }
+
+    /// Returns the estimate of the logarithm of the likelihood of all observations so far.
+    #[inline]
+    pub fn log_likelihood(&self) -> f64 {
+        self.log_likelihood
+    }
+
+    /// Returns the weighted mean of `f` over the particle cloud, that is,
+    /// the estimate of the expectation of `f` given the observations so far.
+    #[inline]
+    pub fn mean<F>(&self, f: F) -> f64
+    where
+        F: Fn(&S) -> f64,
+    {
+        self.particles.iter()
+            .zip(&self.weights)
+            .map(|(particle, weight)| weight * f(particle))
+            .sum()
+    }
+
+    /// Moves every particle according to the transition.
+    #[inline]
+    pub fn predict(&mut self) {
+        let transition = &self.transition;
+        let rng = &mut self.rng;
+        for particle in self.particles.iter_mut() {
+            *particle = transition.sample_from(particle, rng);
+        }
+    }
+
+    /// Reweights the particles by the likelihood of `observation` and
+    /// accumulates the logarithm of its estimated likelihood.
+    ///
+    /// # Panics
+    ///
+    /// If the likelihood is negative for some particle, or zero for all of them.
+    #[inline]
+    pub fn update(&mut self, observation: &O) {
+        let mut total = 0.0;
+        for (particle, weight) in self.particles.iter().zip(self.weights.iter_mut()) {
+            let likelihood = (self.likelihood)(particle, observation);
+            assert!(likelihood >= 0.0, "Likelihoods must be non-negative.");
+            *weight *= likelihood;
+            total += *weight;
+        }
+        assert!(total > 0.0, "The observation has zero likelihood for every particle.");
+        for weight in self.weights.iter_mut() {
+            *weight /= total;
+        }
+        self.log_likelihood += total.ln();
+    }
+
+    /// Resamples the particles by systematic resampling, leaving all weights equal.
+    #[inline]
+    pub fn resample(&mut self)
+    where
+        S: Clone,
+    {
+        let n = self.particles.len();
+        let offset: f64 = self.rng.gen();
+        let mut resampled = Vec::with_capacity(n);
+        let mut cumulative = self.weights[0];
+        let mut index = 0;
+        for i in 0..n {
+            let u = (i as f64 + offset) / n as f64;
+            while cumulative < u && index < n - 1 {
+                index += 1;
+                cumulative += self.weights[index];
+            }
+            resampled.push(self.particles[index].clone());
+        }
+        self.particles = resampled;
+        self.weights = vec![1.0 / n as f64; n];
+    }
+
+    /// Performs a predict-update step with `observation`, resampling
+    /// afterwards if the effective sample size is below the threshold.
+    ///
+    /// # Panics
+    ///
+    /// If the likelihood is negative for some particle, or zero for all of them.
+    #[inline]
+    pub fn step(&mut self, observation: &O)
+    where
+        S: Clone,
+    {
+        self.predict();
+        self.update(observation);
+        if self.effective_sample_size() < self.resample_threshold * self.nparticles() as f64 {
+            self.resample();
+        }
+    }
+
+    /// Performs a step for each observation, in order.
+    ///
+    /// # Panics
+    ///
+    /// If at some step the likelihood is negative for some particle, or zero for all of them.
+    #[inline]
+    pub fn run<I>(&mut self, observations: I)
+    where
+        I: IntoIterator<Item = O>,
+        S: Clone,
+    {
+        for observation in observations {
+            self.step(&observation);
+        }
+    }
+}
+
+impl<S, O, T, L, R> RandomSource for ParticleFilter<S, O, T, L, R>
+where
+    T: Transition<S, S>,
+    L: Fn(&S, &O) -> f64,
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut R {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: R) -> R {
+        core::mem::replace(&mut self.rng, rng)
+    }
+}
+
+/// Exact forward filter of a hidden Markov model with finitely many hidden states.
+///
+/// Observations are ingested one at a time and the posterior distribution of the
+/// hidden state given all observations so far is updated in O(n^2),
+/// where n is the number of hidden states. Hidden states are identified by their index.
+///
+/// The prior is the distribution of the hidden state at time zero and
+/// the first observation corresponds to time one, i.e. after the first transition.
+///
+/// # Examples
+///
+/// A weather model where rain is more likely observed in rainy days.
+/// ```
+/// # use ndarray::array;
+/// # use markovian::filtering::ForwardFilter;
+/// let transition = array![[0.7, 0.3], [0.3, 0.7]];
+/// let likelihood = |state: usize, umbrella: &bool| match (state, umbrella) {
+///     (0, true) => 0.9, (0, false) => 0.1,
+///     (_, true) => 0.2, (_, false) => 0.8,
+/// };
+/// let mut filter = ForwardFilter::new(array![0.5, 0.5], transition, likelihood);
+/// filter.step(&true);
+/// assert!((filter.posterior()[0] - 0.818).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ForwardFilter<O, L>
+where
+    L: Fn(usize, &O) -> f64,
+{
+    transition: ndarray::Array2<f64>,
+    likelihood: L,
+    posterior: ndarray::Array1<f64>,
+    log_likelihood: f64,
+    phantom: PhantomData<O>,
+}
+
+impl<O, L> ForwardFilter<O, L>
+where
+    L: Fn(usize, &O) -> f64,
+{
+    /// Constructs a new `ForwardFilter<O, L>` from the prior of the hidden state,
+    /// the matrix of transition probabilities of the hidden states and
+    /// the likelihood of an observation given the index of the hidden state.
+    ///
+    /// # Panics
+    ///
+    /// If `transition` is not a square matrix of the same dimension as `prior`.
+    #[inline]
+    pub fn new(prior: ndarray::Array1<f64>, transition: ndarray::Array2<f64>, likelihood: L) -> Self {
+        assert_eq!(transition.nrows(), transition.ncols(), "The transition matrix must be square.");
+        assert_eq!(prior.len(), transition.nrows(), "The prior and the transition matrix must have the same dimension.");
+        ForwardFilter {
+            transition,
+            likelihood,
+            posterior: prior,
+            log_likelihood: 0.0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Constructs a new `ForwardFilter<O, L>` whose hidden states follow the transitions of `mc`.
+    ///
+    /// # Panics
+    ///
+    /// If `prior` has not as many entries as states of `mc`.
+    #[inline]
+    pub fn from_chain<T, W, R>(mc: &crate::FiniteMarkovChain<T, W, R>, prior: ndarray::Array1<f64>, likelihood: L) -> Self
+    where
+        W: rand_distr::weighted_alias::AliasableWeight + core::fmt::Debug + Clone + num_traits::ToPrimitive,
+        rand_distr::Uniform<W>: core::fmt::Debug + Clone,
+        T: core::fmt::Debug + PartialEq + Clone,
+        R: Rng,
+    {
+        Self::new(prior, mc.transition_probabilities(), likelihood)
+    }
+
+    /// Returns the posterior distribution of the hidden state given the observations so far.
+    #[inline]
+    pub fn posterior(&self) -> &ndarray::Array1<f64> {
+        &self.posterior
+    }
+
+    /// Returns the index of the most likely hidden state given the observations so far.
+    #[inline]
+    pub fn most_likely(&self) -> usize {
+        self.posterior.iter()
+            .enumerate()
+            .fold((0, f64::NEG_INFINITY), |(best, max), (i, &p)| if p > max { (i, p) } else { (best, max) })
+            .0
+    }
+
+    /// Returns the logarithm of the likelihood of all observations so far.
+    #[inline]
+    pub fn log_likelihood(&self) -> f64 {
+        self.log_likelihood
+    }
+
+    /// Returns the predictive distribution of the hidden state at the next time,
+    /// before observing it.
+    #[inline]
+    pub fn predictive(&self) -> ndarray::Array1<f64> {
+        self.posterior.dot(&self.transition)
+    }
+
+    /// Ingests `observation`, updating the posterior distribution of the hidden state.
+    ///
+    /// # Panics
+    ///
+    /// If the likelihood is negative for some hidden state, or
+    /// if `observation` has zero probability given the previous observations.
+    #[inline]
+    pub fn step(&mut self, observation: &O) {
+        let mut posterior = self.predictive();
+        for (state, p) in posterior.iter_mut().enumerate() {
+            let likelihood = (self.likelihood)(state, observation);
+            assert!(likelihood >= 0.0, "Likelihoods must be non-negative.");
+            *p *= likelihood;
+        }
+        let total = posterior.sum();
+        assert!(total > 0.0, "The observation has zero probability.");
+        self.posterior = posterior / total;
+        self.log_likelihood += total.ln();
+    }
+
+    /// Samples a path of hidden states from their exact posterior distribution 
+    /// given `observations`, by forward filtering and backward sampling.
+    ///
+    /// The path starts at the current time, with the current posterior as prior, 
+    /// and has one more element than `observations`. The filter does not change.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [step].
+    ///
+    /// # Examples
+    ///
+    /// Hidden paths of the weather model, given three days of umbrellas.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::filtering::ForwardFilter;
+    /// let transition = array![[0.7, 0.3], [0.3, 0.7]];
+    /// let likelihood = |state: usize, umbrella: &bool| match (state, umbrella) {
+    ///     (0, true) => 0.9, (0, false) => 0.1,
+    ///     (_, true) => 0.2, (_, false) => 0.8,
+    /// };
+    /// let filter = ForwardFilter::new(array![0.5, 0.5], transition, likelihood);
+    /// let path = filter.sample_posterior_path(&[true, false, true], &mut rand::thread_rng());
+    /// assert_eq!(path.len(), 4);
+    /// ```
+    ///
+    /// [step]: struct.ForwardFilter.html#method.step
+    #[inline]
+    pub fn sample_posterior_path<R>(&self, observations: &[O], rng: &mut R) -> Vec<usize>
+    where
+        L: Clone,
+        R: Rng + ?Sized,
+    {
+        let mut filter = ForwardFilter::new(self.posterior.clone(), self.transition.clone(), self.likelihood.clone());
+        let mut filtered = Vec::with_capacity(observations.len() + 1);
+        filtered.push(filter.posterior.clone());
+        for observation in observations {
+            filter.step(observation);
+            filtered.push(filter.posterior.clone());
+        }
+
+        let mut path = vec![0; filtered.len()];
+        let mut index = sample_index(filtered[filtered.len() - 1].iter().copied(), rng);
+        path[filtered.len() - 1] = index;
+        for t in (0..filtered.len() - 1).rev() {
+            let weights = filtered[t].iter().zip(self.transition.column(index)).map(|(p, q)| p * q);
+            index = sample_index(weights, rng);
+            path[t] = index;
+        }
+        path
+    }
+
+    /// Ingests each observation, in order.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [step].
+    ///
+    /// [step]: struct.ForwardFilter.html#method.step
+    #[inline]
+    pub fn run<I>(&mut self, observations: I)
+    where
+        I: IntoIterator<Item = O>,
+    {
+        for observation in observations {
+            self.step(&observation);
+        }
+    }
+}
+
+impl<O, L> Extend<O> for ForwardFilter<O, L>
+where
+    L: Fn(usize, &O) -> f64,
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = O>>(&mut self, observations: I) {
+        self.run(observations)
+    }
+}
+
+/// Samples an index with probability proportional to `weights`.
+#[inline]
+fn sample_index<I, R>(weights: I, rng: &mut R) -> usize
+where
+    I: IntoIterator<Item = f64>,
+    R: Rng + ?Sized,
+{
+    WeightedIndex::new(weights)
+        .expect("The filtered distribution has positive mass.")
+        .sample(rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use approx::assert_abs_diff_eq;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn deterministic() {
+        let transition = |state: &i32| raw_dist![(1.0, state + 1)];
+        let likelihood = |state: &i32, observation: &i32| if state == observation { 0.5 } else { 0.0 };
+        let mut filter = ParticleFilter::new(vec![0; 10], transition, likelihood, crate::tests::rng(1));
+        filter.run(vec![1, 2, 3]);
+
+        assert_eq!(filter.particles(), &[3; 10]);
+        assert_abs_diff_eq!(filter.effective_sample_size(), 10., epsilon = 1e-9);
+        assert_abs_diff_eq!(filter.log_likelihood(), 3. * 0.5_f64.ln(), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn resampling() {
+        let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+        let likelihood = |state: &i32, _: &()| if *state > 0 { 1.0 } else { 0.0 };
+        let mut filter = ParticleFilter::new(vec![0; 100], transition, likelihood, crate::tests::rng(2))
+            .resample_threshold(1.0);
+        filter.step(&());
+
+        assert!(filter.particles().iter().all(|&state| state == 1));
+        assert_abs_diff_eq!(filter.effective_sample_size(), 100., epsilon = 1e-9);
+        assert_abs_diff_eq!(filter.mean(|state| *state as f64), 1., epsilon = 1e-9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn impossible_observation() {
+        let transition = |state: &i32| raw_dist![(1.0, *state)];
+        let likelihood = |_: &i32, _: &()| 0.0;
+        let mut filter = ParticleFilter::new(vec![0; 10], transition, likelihood, crate::tests::rng(3));
+        filter.step(&());
+    }
+
+    #[test]
+    fn forward_filter() {
+        let transition = ndarray::array![[0.7, 0.3], [0.3, 0.7]];
+        let likelihood = |state: usize, umbrella: &bool| match (state, umbrella) {
+            (0, true) => 0.9, (0, false) => 0.1,
+            (_, true) => 0.2, (_, false) => 0.8,
+        };
+        let mut filter = ForwardFilter::new(ndarray::array![0.5, 0.5], transition, likelihood);
+        filter.extend(vec![true, true]);
+
+        // Forward algorithm by hand
+        let alpha_1 = [0.5 * 0.9, 0.5 * 0.2];
+        let alpha_2 = [
+            (alpha_1[0] * 0.7 + alpha_1[1] * 0.3) * 0.9,
+            (alpha_1[0] * 0.3 + alpha_1[1] * 0.7) * 0.2,
+        ];
+        let total = alpha_2[0] + alpha_2[1];
+        assert_abs_diff_eq!(filter.posterior()[0], alpha_2[0] / total, epsilon = 1e-12);
+        assert_abs_diff_eq!(filter.log_likelihood(), total.ln(), epsilon = 1e-12);
+        assert_eq!(filter.most_likely(), 0);
+    }
+
+    #[test]
+    fn forward_filter_from_chain() {
+        let mc = crate::FiniteMarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, vec![vec![0., 1.], vec![1., 0.]], vec![0, 1], 4);
+        let likelihood = |state: usize, observation: &usize| if state == *observation { 1.0 } else { 0.0 };
+        let mut filter = ForwardFilter::from_chain(&mc, ndarray::array![1., 0.], likelihood);
+        filter.step(&1);
+
+        assert_eq!(filter.posterior(), &ndarray::array![0., 1.]);
+        assert_eq!(filter.predictive(), ndarray::array![1., 0.]);
+    }
+
+    #[test]
+    fn sample_posterior_path() {
+        let transition = ndarray::array![[0.7, 0.3], [0.3, 0.7]];
+        let likelihood = |state: usize, umbrella: &bool| match (state, umbrella) {
+            (0, true) => 0.9, (0, false) => 0.1,
+            (_, true) => 0.2, (_, false) => 0.8,
+        };
+        let filter = ForwardFilter::new(ndarray::array![0.5, 0.5], transition.clone(), likelihood);
+        let observations = [true, false];
+
+        // Exact posterior of the hidden state at time one, by enumeration
+        let mut joint = [0.; 2];
+        for x0 in 0..2 {
+            for x1 in 0..2 {
+                for x2 in 0..2 {
+                    joint[x1] += 0.5 * transition[[x0, x1]] * likelihood(x1, &true) * transition[[x1, x2]] * likelihood(x2, &false);
+                }
+            }
+        }
+        let exact = joint[0] / (joint[0] + joint[1]);
+
+        let mut rng = crate::tests::rng(5);
+        let samples = 5_000;
+        let mut zeros = 0;
+        for _ in 0..samples {
+            let path = filter.sample_posterior_path(&observations, &mut rng);
+            assert_eq!(path.len(), 3);
+            if path[1] == 0 {
+                zeros += 1;
+            }
+        }
+        assert_abs_diff_eq!(zeros as f64 / samples as f64, exact, epsilon = 0.03);
+        assert_eq!(filter.posterior(), &ndarray::array![0.5, 0.5]);
+    }
+}
@@ -1,4 +1,16 @@
-pub use fast_sample::FiniteMarkovChain;
+pub use cover::CoverTimes;
+pub use fast_sample::{FiniteMarkovChain, FAST_FORWARD_THRESHOLD};
+pub use jump_chain::JumpChain;
+pub use periodic::PeriodicFiniteMarkovChain;
+pub use smoothing::Smoothing;
+pub(crate) use jump_chain::Jumps;
+pub(crate) use initial::Initial;
+pub(crate) use fast_sample::sample_proportional;
 
+mod cover;
 mod fast_sample;
-// pub mod fast_construction;
\ No newline at end of file
+mod initial;
+mod jump_chain;
+mod periodic;
+mod smoothing;
+// pub mod fast_construction;
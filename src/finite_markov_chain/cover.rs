@@ -0,0 +1,50 @@
+// Structs
+use crate::montecarlo::Estimate;
+use crate::stats::Welford;
+
+/// Cover times of independent runs of a finite chain, together with the order
+/// in which each run visited the states.
+///
+/// This struct is created by the [estimate_cover_time] method.
+///
+/// [estimate_cover_time]: struct.FiniteMarkovChain.html#method.estimate_cover_time
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverTimes<T> {
+    runs: Vec<Vec<(usize, T)>>,
+}
+
+impl<T> CoverTimes<T> {
+    #[inline]
+    pub(crate) fn new(runs: Vec<Vec<(usize, T)>>) -> Self {
+        CoverTimes { runs }
+    }
+
+    /// Returns, for each run, the states in the order they were first visited,
+    /// together with the step of their first visit.
+    #[inline]
+    pub fn runs(&self) -> &[Vec<(usize, T)>] {
+        &self.runs
+    }
+
+    /// Returns the cover time of each run, i.e. the step at which the last state was first visited.
+    #[inline]
+    pub fn cover_times(&self) -> Vec<usize> {
+        self.runs.iter().map(|run| run.last().map_or(0, |(step, _)| *step)).collect()
+    }
+
+    /// Returns the estimate of the expected cover time.
+    #[inline]
+    pub fn estimate(&self) -> Estimate {
+        self.cover_times().into_iter().map(|time| time as f64).collect::<Welford>().into()
+    }
+
+    /// Returns the proportion of runs in which `state` was the last state to be visited.
+    #[inline]
+    pub fn last_visited_frequency(&self, state: &T) -> f64
+    where
+        T: PartialEq,
+    {
+        let last = self.runs.iter().filter(|run| run.last().is_some_and(|(_, s)| s == state)).count();
+        last as f64 / self.runs.len().max(1) as f64
+    }
+}
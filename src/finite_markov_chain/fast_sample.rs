@@ -1,16 +1,23 @@
 // Traits
-use crate::{State, StateIterator};
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
 use core::fmt::Debug;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 use rand_distr::{weighted_alias::{WeightedAliasIndex, AliasableWeight}, Uniform, Distribution};
 
 // Structs
 use crate::errors::InvalidState;
+use crate::finite_markov_chain::{Initial, Jumps, Smoothing};
 use petgraph::graph::DiGraph;
 
 // Functions
 use core::mem;
 
+/// Probability of staying in a state above which, in the [fast-forward] mode,
+/// steps in that state are skipped.
+///
+/// [fast-forward]: struct.FiniteMarkovChain.html#method.fast_forward
+pub const FAST_FORWARD_THRESHOLD: f64 = 0.5;
+
 /// Finite state Markov Chain in discrete time. 
 /// 
 /// # Costs
@@ -43,12 +50,14 @@ where
     W: AliasableWeight + Debug + Clone,
     Uniform<W>: Debug + Clone,
     T: Debug + PartialEq + Clone,
-    R: Rng + Debug + Clone,
+    R: Rng,
 {
     state_index: usize,
+    initial: Initial<W>,
     transition_matrix: Vec<Vec<W>>,
     transition_matrix_variables: Vec<WeightedAliasIndex<W>>,
     state_space: Vec<T>,
+    fast_forward: Option<Jumps>,
     rng: R,
 }
 
@@ -57,21 +66,19 @@ where
     W: AliasableWeight + Debug + Clone,
     Uniform<W>: Debug + Clone,
     T: Debug + PartialEq + Clone,
-    R: Rng + Debug + Clone,
+    R: Rng,
 {
     /// Constructs a new `FiniteMarkovChain<T, W, R>`.
     /// 
     /// # Panics
-    /// 
-    /// # Panics
     ///
     /// This method panics if: 
     /// - The`state_space` vector has repeated elements
-    /// (defined by PartialEq).
+    ///   (defined by PartialEq).
     /// - The dimensions of `state_space` and `transition_matrix` do not match.
     /// - Any vector of `transition_matrix` has more than u32::MAX columns.
     /// - For any entry w of any vector of `transition_matrix` v: 
-    /// w < 0 or w > max where max = W::MAX / v.len().
+    ///   w < 0 or w > max where max = W::MAX / v.len().
     /// - For any vector of `transition_matrix` the sum of weights is zero.
     #[inline]
     pub fn new(
@@ -86,6 +93,69 @@ where
 
         FiniteMarkovChain::new_raw(
             state_index,
+            Initial::Index(state_index),
+            transition_matrix,
+            transition_matrix_variables,
+            state_space,
+            rng
+        )
+    }
+
+    /// Constructs a new `FiniteMarkovChain<T, W, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(
+        state_index: usize,
+        transition_matrix: Vec<Vec<W>>,
+        state_space: Vec<T>,
+        seed: u64,
+    ) -> Self
+    where
+        R: SeedableRng,
+    {
+        FiniteMarkovChain::new(state_index, transition_matrix, state_space, R::seed_from_u64(seed))
+    }
+
+    /// Constructs a new `FiniteMarkovChain<T, W, R>` whose initial state is sampled
+    /// according to `initial_weights`. 
+    ///
+    /// Every time the chain is [reset], a new initial state is sampled.
+    /// 
+    /// # Panics
+    ///
+    /// This method panics if `initial_weights` is not a valid vector of weights,
+    /// its length is not the number of states, or in the same cases as [new].
+    ///
+    /// # Examples
+    ///
+    /// A chain that starts uniformly at random. 
+    /// ```
+    /// # use markovian::{FiniteMarkovChain, Reset, State};
+    /// let transition_matrix = vec![vec![0.5, 0.5], vec![0.0, 1.0]];
+    /// let mut mc = FiniteMarkovChain::from_initial_distribution(vec![1., 1.], transition_matrix, vec!['a', 'b'], rand::thread_rng());
+    /// println!("The chain started at {:?}", mc.state());
+    /// mc.reset();
+    /// println!("The chain restarted at {:?}", mc.state());
+    /// ```
+    ///
+    /// [reset]: trait.Reset.html#tymethod.reset
+    /// [new]: struct.FiniteMarkovChain.html#method.new
+    #[inline]
+    pub fn from_initial_distribution(
+        initial_weights: Vec<W>,
+        transition_matrix: Vec<Vec<W>>,
+        state_space: Vec<T>,
+        mut rng: R,
+    ) -> Self {
+        assert_eq!(initial_weights.len(), state_space.len(), "There must be one initial weight for each state.");
+        let initial = Initial::Distribution(WeightedAliasIndex::new(initial_weights).unwrap());
+        let state_index = initial.sample(&mut rng);
+        let transition_matrix_variables = transition_matrix.clone().into_iter()
+        	.map(|v| WeightedAliasIndex::new(v).unwrap())
+        	.collect();
+
+        FiniteMarkovChain::new_raw(
+            state_index,
+            initial,
             transition_matrix,
             transition_matrix_variables,
             state_space,
@@ -96,6 +166,7 @@ where
     #[inline]
     fn new_raw(
         state_index: usize,
+        initial: Initial<W>,
         transition_matrix: Vec<Vec<W>>,
         transition_matrix_variables: Vec<WeightedAliasIndex<W>>,     
         state_space: Vec<T>,
@@ -108,9 +179,11 @@ where
         assert_eq!(transition_matrix.len(), state_space.len());
         FiniteMarkovChain {
             state_index,
+            initial,
             transition_matrix,
             transition_matrix_variables,
             state_space,
+            fast_forward: None,
             rng,
         }
     }
@@ -136,6 +209,154 @@ where
         self.transition_matrix_variables[self.state_index].sample(&mut self.rng)
     }
 
+    /// Samples a possible path of `k` steps from the current state, 
+    /// without changing the state of the Markov Chain.
+    ///
+    /// # Remarks
+    ///
+    /// As for [sample_index], the random number generator changes.
+    ///
+    /// # Examples
+    ///
+    /// A hypothetical future of a chain that alternates between two states.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::{FiniteMarkovChain, State};
+    /// let mut mc = FiniteMarkovChain::from((0, array![[0.0, 1.0], [1.0, 0.0]], rand::thread_rng()));
+    /// assert_eq!(mc.sample_path(3), vec![1, 0, 1]);
+    /// assert_eq!(mc.state(), Some(&0));
+    /// ```
+    ///
+    /// [sample_index]: struct.FiniteMarkovChain.html#method.sample_index
+    #[inline]
+    pub fn sample_path(&mut self, k: usize) -> Vec<T> {
+        let mut index = self.state_index;
+        (0..k)
+            .map(|_| {
+                index = self.transition_matrix_variables[index].sample(&mut self.rng);
+                self.state_space[index].clone()
+            })
+            .collect()
+    }
+
+    /// Samples the order in which states are first visited, from the current state until
+    /// every state is visited, without changing the state of the Markov Chain.
+    ///
+    /// Returns pairs `(step, state)`, starting with `(0, current_state)`, so that the last step
+    /// is the cover time. Returns `None` as soon as the run reaches a state from which some state 
+    /// not yet visited can not be reached, in particular, if some state is not reachable at all.
+    ///
+    /// # Remarks
+    ///
+    /// As for [sample_index], the random number generator changes.
+    ///
+    /// # Examples
+    ///
+    /// A chain that moves around a cycle.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mut mc = FiniteMarkovChain::from((0, array![[0, 1, 0], [0, 0, 1], [1, 0, 0]], rand::thread_rng()));
+    /// assert_eq!(mc.cover_time_sample(), Some(vec![(0, 0), (1, 1), (2, 2)]));
+    /// ```
+    ///
+    /// [sample_index]: struct.FiniteMarkovChain.html#method.sample_index
+    #[inline]
+    pub fn cover_time_sample(&mut self) -> Option<Vec<(usize, T)>> {
+        let reachable = self.reachability();
+        self.sample_cover(&reachable)
+    }
+
+    /// Samples `n_samples` independent runs, as in [cover_time_sample], 
+    /// or returns `None` if some run does not visit every state.
+    ///
+    /// # Examples
+    ///
+    /// The cover time of a random walk on a cycle of five states is ten.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let cycle = array![
+    ///     [0., 1., 0., 0., 1.],
+    ///     [1., 0., 1., 0., 0.],
+    ///     [0., 1., 0., 1., 0.],
+    ///     [0., 0., 1., 0., 1.],
+    ///     [1., 0., 0., 1., 0.],
+    /// ];
+    /// let mut mc = FiniteMarkovChain::from((0, cycle, rand::thread_rng()));
+    /// let estimate = mc.estimate_cover_time(1_000).unwrap().estimate();
+    /// assert!((estimate.mean - 10.).abs() < 5. * estimate.standard_error);
+    /// ```
+    ///
+    /// [cover_time_sample]: struct.FiniteMarkovChain.html#method.cover_time_sample
+    #[inline]
+    pub fn estimate_cover_time(&mut self, n_samples: usize) -> Option<super::CoverTimes<T>> {
+        let reachable = self.reachability();
+        let runs = (0..n_samples)
+            .map(|_| self.sample_cover(&reachable))
+            .collect::<Option<Vec<_>>>()?;
+        Some(super::CoverTimes::new(runs))
+    }
+
+    /// Samples a run until every state is visited, see [cover_time_sample], 
+    /// where `reachable[i][j]` tells whether state `j` can be reached from state `i`.
+    ///
+    /// [cover_time_sample]: struct.FiniteMarkovChain.html#method.cover_time_sample
+    #[inline]
+    fn sample_cover(&mut self, reachable: &[Vec<bool>]) -> Option<Vec<(usize, T)>> {
+        let n = self.nstates();
+        let mut visited = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        let mut index = self.state_index;
+        let mut step = 0;
+        loop {
+            if !visited[index] {
+                // The set of states not yet visited only shrinks, so checking at first visits is enough
+                if (0..n).any(|j| !visited[j] && !reachable[index][j]) {
+                    return None;
+                }
+                visited[index] = true;
+                order.push((step, self.state_space[index].clone()));
+                if order.len() == n {
+                    return Some(order);
+                }
+            }
+            index = self.transition_matrix_variables[index].sample(&mut self.rng);
+            step += 1;
+        }
+    }
+
+    /// Returns, for each state, which states can be reached from it, including itself.
+    #[inline]
+    fn reachability(&self) -> Vec<Vec<bool>> {
+        let n = self.nstates();
+        (0..n)
+            .map(|start| {
+                let mut reachable = vec![false; n];
+                reachable[start] = true;
+                let mut stack = vec![start];
+                while let Some(i) = stack.pop() {
+                    for (j, w) in self.transition_matrix[i].iter().enumerate() {
+                        if *w > W::ZERO && !reachable[j] {
+                            reachable[j] = true;
+                            stack.push(j);
+                        }
+                    }
+                }
+                reachable
+            })
+            .collect()
+    }
+
+    /// Samples a possible index for the initial state.
+    ///
+    /// If the chain was constructed with an initial state, its index is returned.
+    /// Otherwise, an index is sampled from the initial distribution.
+    #[inline]
+    pub fn sample_initial_index(&mut self) -> usize {
+        self.initial.sample(&mut self.rng)
+    }
+
     /// Returns the state space of the Markov Chain.
     ///
     /// The state space is the collection of all values the chain might ever take,
@@ -173,12 +394,195 @@ where
     #[inline]
     pub fn nstates(&self) -> usize {
         self.state_space().len()
-    }   
+    }
+
+    /// Returns the matrix of transition probabilities,
+    /// whose entry (i, j) is the probability of moving from state i to state j.
+    ///
+    /// Weights are normalized by rows and missing columns are filled with zeros.
+    ///
+    /// # Examples
+    ///
+    /// Weights need not be normalized.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[1, 3], [0, 2]], rand::thread_rng()));
+    /// assert_eq!(mc.transition_probabilities(), array![[0.25, 0.75], [0.0, 1.0]]);
+    /// ```
+    #[inline]
+    pub fn transition_probabilities(&self) -> ndarray::Array2<f64>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let n = self.nstates();
+        let matrix = ndarray::Array2::from_shape_fn((n, n), |(i, j)| {
+            self.transition_matrix[i].get(j).map_or(0.0, |w| w.to_f64().unwrap())
+        });
+        crate::linalg::normalize_rows(matrix)
+    }
+
+    /// Returns the exact distribution of the chain after `n` steps from the current state,
+    /// indexed as the state space.
+    ///
+    /// # Costs
+    ///
+    /// O(min(n s^2, s^3 log n)), where s is the size of the state space: for large `n`,
+    /// the transition matrix is raised to the power `n` by repeated squaring.
+    ///
+    /// # Examples
+    ///
+    /// Two steps of an absorbing chain.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.0, 1.0]], rand::thread_rng()));
+    /// assert_eq!(mc.n_step_distribution(2), array![0.25, 0.75]);
+    /// ```
+    #[inline]
+    pub fn n_step_distribution(&self, n: usize) -> ndarray::Array1<f64>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let mut p = self.transition_probabilities();
+        let mut distribution = ndarray::Array1::zeros(self.nstates());
+        distribution[self.state_index] = 1.0;
+        let squarings = (usize::BITS - n.leading_zeros()) as usize;
+        if n <= self.nstates() * squarings {
+            for _ in 0..n {
+                distribution = distribution.dot(&p);
+            }
+        } else {
+            let mut exponent = n;
+            while exponent > 0 {
+                if exponent % 2 == 1 {
+                    distribution = distribution.dot(&p);
+                }
+                p = p.dot(&p);
+                exponent /= 2;
+            }
+        }
+        distribution
+    }
+
+    /// Samples the state of the chain after `n` steps directly from its exact distribution,
+    /// changes the current state to it and returns it.
+    ///
+    /// Unlike [nth], intermediate states are not sampled, so only one random number is used,
+    /// which is faster for large `n` and small state spaces. See [n_step_distribution] for the costs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::{FiniteMarkovChain, State};
+    /// let mut mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.1, 0.9]], rand::thread_rng()));
+    /// let state = mc.sample_at(1_000_000_000);
+    /// assert_eq!(mc.state(), Some(&state));
+    /// ```
+    ///
+    /// [nth]: #method.nth
+    /// [n_step_distribution]: #method.n_step_distribution
+    #[inline]
+    pub fn sample_at(&mut self, n: usize) -> T
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let distribution = self.n_step_distribution(n);
+        let index = sample_proportional(distribution.iter().copied(), &mut self.rng).unwrap_or(self.state_index);
+        self.state_index = index;
+        self.state_space[index].clone()
+    }
+
+    /// Samples a path of `n_steps` steps from the state of index `start` 
+    /// conditioned to end at the state of index `end`, or returns `None` if it is impossible.
+    ///
+    /// The path has `n_steps + 1` states, including both endpoints, and is sampled exactly
+    /// by backward filtering and forward sampling. The state of the chain does not change.
+    ///
+    /// # Costs
+    ///
+    /// O(n s^2), where n is `n_steps` and s is the size of the state space.
+    ///
+    /// # Examples
+    ///
+    /// Imputing the missing observations of a panel, between two observed states.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mut mc = FiniteMarkovChain::from((0, array![[0.8, 0.2, 0.], [0.1, 0.8, 0.1], [0., 0.2, 0.8]], rand::thread_rng()));
+    /// let path = mc.sample_bridge(0, 2, 4).unwrap();
+    /// assert_eq!((path.len(), path[0], path[4]), (5, 0, 2));
+    /// assert!(mc.sample_bridge(0, 2, 1).is_none());
+    /// ```
+    #[inline]
+    pub fn sample_bridge(&mut self, start: usize, end: usize, n_steps: usize) -> Option<Vec<T>>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let p = self.transition_probabilities();
+        // backward[k][i] is the probability of reaching `end` from `i` in `n_steps - k` steps
+        let mut backward = Vec::with_capacity(n_steps + 1);
+        let mut beta = ndarray::Array1::<f64>::zeros(self.nstates());
+        beta[end] = 1.0;
+        for _ in 0..n_steps {
+            let previous = p.dot(&beta);
+            backward.push(beta);
+            beta = previous;
+        }
+        backward.push(beta);
+        backward.reverse();
+        if backward[0][start] <= 0.0 {
+            return None;
+        }
+
+        let mut index = start;
+        let mut path = Vec::with_capacity(n_steps + 1);
+        path.push(self.state_space[index].clone());
+        for beta in &backward[1..] {
+            let row = p.row(index);
+            let weights = row.iter().zip(beta.iter()).map(|(p, b)| p * b);
+            index = sample_proportional(weights, &mut self.rng)?;
+            path.push(self.state_space[index].clone());
+        }
+        Some(path)
+    }
+
+    /// Returns the exact expectation of `f` at the state of the chain after `n` steps
+    /// from the current state.
+    ///
+    /// Exact expectations are useful as controls for Monte Carlo estimates,
+    /// see [control_variate].
+    ///
+    /// # Examples
+    ///
+    /// Probability of absorption within two steps.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.0, 1.0]], rand::thread_rng()));
+    /// assert_eq!(mc.n_step_expectation(2, |&state| state as f64), 0.75);
+    /// ```
+    ///
+    /// [control_variate]: montecarlo/fn.control_variate.html
+    #[inline]
+    pub fn n_step_expectation<F>(&self, n: usize, f: F) -> f64
+    where
+        W: num_traits::ToPrimitive,
+        F: Fn(&T) -> f64,
+    {
+        self.n_step_distribution(n)
+            .iter()
+            .zip(self.state_space())
+            .map(|(p, state)| p * f(state))
+            .sum()
+    }
 
     /// Changes the state space of the Markov Chain.
     ///
     /// The state space is the collection of all values the chain might ever take,
     /// even if they are not recheable from the current state.
+    /// A chain in [fast-forward] mode stays in that mode.
     ///
     /// # Panics
     ///
@@ -195,18 +599,23 @@ where
     /// let mc = mc.set_state_space(vec!['a', 'b']);
     /// assert_eq!(mc.state(), Some(&'b'));
     /// ```
+    ///
+    /// [fast-forward]: #method.fast_forward
     #[inline]
     pub fn set_state_space<U>(self, new_state_space: Vec<U>) -> FiniteMarkovChain<U, W, R> 
     where
     	U: Debug + PartialEq + Clone,
     {
-        FiniteMarkovChain::new_raw( 
+        let mut mc = FiniteMarkovChain::new_raw( 
 		    self.state_index,
+		    self.initial,
 		    self.transition_matrix,
 		    self.transition_matrix_variables,
 		    new_state_space,
 		    self.rng,
-        )
+        );
+        mc.fast_forward = self.fast_forward;
+        mc
     }
 
     /// Returns all absorbing state, if any.
@@ -254,14 +663,13 @@ where
             .filter(|&i| {
                 let quantities_check = transition_matrix[i].iter()
                     .enumerate()
-                    .map(|(j, w)| {
+                    .all(|(j, w)| {
                         if j == i {
                             w > &W::ZERO
                         } else {
                             w == &W::ZERO
                         }
-                    })
-                    .all(|b| b);
+                    });
                 let existence_check = transition_matrix[i].len() > i;
                 quantities_check && existence_check
             })
@@ -287,7 +695,7 @@ where
     /// ```
     #[inline]
     pub fn may_achieve_index(&self, query: usize) -> bool {
-    	let (graph, node) = self.clone().into();
+    	let (graph, node) = self.graph();
         let mut bfs = petgraph::visit::Bfs::new(&graph, node);
         while let Some(other_node) = bfs.next(&graph) {
             if other_node.index() == query {
@@ -343,65 +751,982 @@ where
     #[inline]
     pub fn may_absorb(&self) -> bool {
         let set: std::collections::HashSet<_> = self.absorbing_states_indexes().into_iter().collect();
-        let (graph, node) = self.clone().into();
+        let (graph, node) = self.graph();
         let mut bfs = petgraph::visit::Bfs::new(&graph, node);
         while let Some(other_node) = bfs.next(&graph) {
             if set.contains(&other_node.index()) {
                 return true
-            } 
+            }
         }
         false
     }
-}
-
-impl<T, W, R> State for FiniteMarkovChain<T, W, R>
-where
-    W: AliasableWeight + Debug + Clone,
-    Uniform<W>: Debug + Clone,
-    T: Debug + PartialEq + Clone,
-    R: Rng + Debug + Clone,
-{
-    type Item = T;
 
+    /// Returns, for each state index, the expected total cost until absorption
+    /// starting from that state, where `cost(i, j)` is the cost of a transition
+    /// from index `i` to index `j`.
+    ///
+    /// Absorbing states have zero cost. States from which absorption
+    /// has probability less than one have infinite cost.
+    ///
+    /// # Remarks
+    ///
+    /// The cost is computed exactly by solving a linear system, in O(n^3),
+    /// or in O(n) for [birth-death] chains.
+    /// For chains that are not finite, see [absorption].
+    ///
+    /// # Examples
+    ///
+    /// Expected number of steps until absorption.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.0, 1.0]], rand::thread_rng()));
+    /// let costs = mc.expected_cost_to_absorption(|_, _| 1.0);
+    /// assert!((costs[0] - 2.0).abs() < 1e-12);
+    /// assert_eq!(costs[1], 0.0);
+    /// ```
+    ///
+    /// [absorption]: absorption/index.html
+    /// [birth-death]: struct.FiniteMarkovChain.html#method.is_birth_death
     #[inline]
-    fn state(&self) -> Option<&Self::Item> {
-        Some(&self.state_space[self.state_index])
+    pub fn expected_cost_to_absorption<F>(&self, cost: F) -> ndarray::Array1<f64>
+    where
+        W: num_traits::ToPrimitive,
+        F: Fn(usize, usize) -> f64,
+    {
+        let mut absorbing = vec![false; self.nstates()];
+        for i in self.absorbing_states_indexes() {
+            absorbing[i] = true;
+        }
+        crate::linalg::expected_cost_to_target(&self.transition_probabilities(), &absorbing, cost)
     }
 
+    /// Returns `true` if the chain is a birth-death chain, i.e. from each state index `i`,
+    /// it can only move to `i - 1`, `i` or `i + 1`.
+    ///
+    /// The exact computations of hitting probabilities and times detect this structure
+    /// and solve their linear systems in O(n), instead of O(n^3).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5, 0.0], [0.5, 0.0, 0.5], [0.0, 0.5, 0.5]], rand::thread_rng()));
+    /// assert!(mc.is_birth_death());
+    /// ```
     #[inline]
-    fn state_mut(&mut self) -> Option<&mut Self::Item> {
-        Some(&mut self.state_space[self.state_index])
+    pub fn is_birth_death(&self) -> bool
+    where
+        W: num_traits::ToPrimitive,
+    {
+        crate::linalg::is_tridiagonal(&self.transition_probabilities())
     }
 
+    /// Returns, for each state index, the probability of ever hitting some state indexed by `target`,
+    /// in zero or more steps.
+    ///
+    /// # Remarks
+    ///
+    /// The probabilities are computed exactly by solving a linear system, in O(n^3),
+    /// or in O(n) for [birth-death] chains.
+    ///
+    /// # Examples
+    ///
+    /// Gambler's ruin: a fair game hits 4 before 0 with probability proportional to the initial wealth.
+    /// ```
+    /// # use ndarray::Array2;
+    /// # use markovian::FiniteMarkovChain;
+    /// let transition = Array2::from_shape_fn((5, 5), |(i, j)| match i {
+    ///     0 | 4 => if i == j { 1. } else { 0. },
+    ///     _ => if i + 1 == j || j + 1 == i { 0.5 } else { 0. },
+    /// });
+    /// let mc = FiniteMarkovChain::from((2, transition, rand::thread_rng()));
+    /// let probabilities = mc.hitting_probabilities(&[4]);
+    /// assert!((probabilities[1] - 0.25).abs() < 1e-12);
+    /// ```
+    ///
+    /// [birth-death]: struct.FiniteMarkovChain.html#method.is_birth_death
     #[inline]
-    fn set_state(
-        &mut self,
-        new_state: Self::Item,
-    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
-        match self.state_space.iter().position(|s| *s == new_state) {
-            Some(mut state_index) => {
-                mem::swap(&mut self.state_index, &mut state_index);
-                Ok(Some(self.state_space[state_index].clone()))
-            }
-            None => Err(InvalidState::new(new_state)),
+    pub fn hitting_probabilities(&self, target: &[usize]) -> ndarray::Array1<f64>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let n = self.nstates();
+        let mut is_target = vec![false; n];
+        for &i in target {
+            is_target[i] = true;
         }
+        crate::linalg::hitting_probabilities(&self.transition_probabilities(), &is_target, &vec![false; n])
     }
-}
 
-impl<T, W, R> Iterator for FiniteMarkovChain<T, W, R>
-where
-    W: AliasableWeight + Debug + Clone,
-    Uniform<W>: Debug + Clone,
-    T: Debug + PartialEq + Clone,
-    R: Rng + Debug + Clone,
-{
-    type Item = T;
+    /// Returns, for each state index, the expected number of steps until hitting some state
+    /// indexed by `target`.
+    ///
+    /// States in `target` have zero steps. States from which hitting `target` has probability
+    /// less than one have infinite steps.
+    ///
+    /// # Remarks
+    ///
+    /// The times are computed exactly by solving a linear system, in O(n^3),
+    /// or in O(n) for [birth-death] chains.
+    ///
+    /// # Examples
+    ///
+    /// Gambler's ruin: a fair game from wealth `i` ends after `i * (4 - i)` rounds on average.
+    /// ```
+    /// # use ndarray::Array2;
+    /// # use markovian::FiniteMarkovChain;
+    /// let transition = Array2::from_shape_fn((5, 5), |(i, j)| match i {
+    ///     0 | 4 => if i == j { 1. } else { 0. },
+    ///     _ => if i + 1 == j || j + 1 == i { 0.5 } else { 0. },
+    /// });
+    /// let mc = FiniteMarkovChain::from((2, transition, rand::thread_rng()));
+    /// let times = mc.mean_hitting_times(&[0, 4]);
+    /// assert!((times[2] - 4.).abs() < 1e-12);
+    /// ```
+    ///
+    /// [birth-death]: struct.FiniteMarkovChain.html#method.is_birth_death
+    #[inline]
+    pub fn mean_hitting_times(&self, target: &[usize]) -> ndarray::Array1<f64>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let mut is_target = vec![false; self.nstates()];
+        for &i in target {
+            is_target[i] = true;
+        }
+        crate::linalg::expected_cost_to_target(&self.transition_probabilities(), &is_target, |_, _| 1.0)
+    }
+
+    /// Returns the stationary distribution of the chain, i.e. the distribution `π` such that `π P = π`,
+    /// or `None` if it is not unique, which happens if the chain has several closed classes.
+    ///
+    /// # Remarks
+    ///
+    /// The distribution is computed exactly by solving a linear system, in O(n^3).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.25, 0.75]], rand::thread_rng()));
+    /// let pi = mc.stationary_distribution().unwrap();
+    /// assert!((pi[0] - 1. / 3.).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn stationary_distribution(&self) -> Option<ndarray::Array1<f64>>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        crate::linalg::stationary_distribution(&self.transition_probabilities())
+    }
+
+    /// Returns the fundamental matrix `Z = (I - P + 1 π)^{-1}` of the chain, where `π` is
+    /// its stationary distribution, or `None` if the stationary distribution is not unique.
+    ///
+    /// # Examples
+    ///
+    /// The rows of the fundamental matrix sum up to one.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.25, 0.75]], rand::thread_rng()));
+    /// let z = mc.fundamental_matrix().unwrap();
+    /// assert!((z[[0, 0]] + z[[0, 1]] - 1.).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn fundamental_matrix(&self) -> Option<ndarray::Array2<f64>>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let pi = self.stationary_distribution()?;
+        let p = self.transition_probabilities();
+        let n = self.nstates();
+        let a = ndarray::Array2::from_shape_fn((n, n), |(i, j)| {
+            let identity = if i == j { 1.0 } else { 0.0 };
+            identity - p[[i, j]] + pi[j]
+        });
+        crate::linalg::inverse(a)
+    }
+
+    /// Returns the derivative of the stationary distribution with respect to the transition
+    /// probability from index `i` to index `j`, or `None` if the stationary distribution is not unique
+    /// or the transition probability is one.
+    ///
+    /// The transition probability from `i` to `j` is increased while the rest of the row `i`
+    /// is scaled down proportionally, so that the matrix stays stochastic.
+    /// Then, by the fundamental matrix `Z`, the derivative is `π_i (Z_j - P_i Z) / (1 - P_ij)`,
+    /// where `Z_j` and `P_i` denote rows.
+    ///
+    /// # Panics
+    ///
+    /// If `i` or `j` are not state indexes.
+    ///
+    /// # Examples
+    ///
+    /// Staying longer in a state increases its stationary probability.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.25, 0.75]], rand::thread_rng()));
+    /// let sensitivity = mc.stationary_sensitivity(0, 0).unwrap();
+    /// assert!(sensitivity[0] > 0.);
+    /// assert!((sensitivity[0] + sensitivity[1]).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn stationary_sensitivity(&self, i: usize, j: usize) -> Option<ndarray::Array1<f64>>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let n = self.nstates();
+        assert!(i < n && j < n, "Indexes must correspond to states.");
+        let z = self.fundamental_matrix()?;
+        let pi = self.stationary_distribution()?;
+        let p = self.transition_probabilities();
+        if p[[i, j]] >= 1. {
+            return None;
+        }
+        let p_i_z = p.row(i).dot(&z);
+        Some((&z.row(j) - &p_i_z) * (pi[i] / (1. - p[[i, j]])))
+    }
+
+    /// Returns a new chain whose transition matrix is the transition matrix of `self` plus `epsilon`,
+    /// with rows normalized to sum up to one.
+    ///
+    /// The new chain has the same state space and current state, and a clone of the random number generator.
+    ///
+    /// # Errors
+    ///
+    /// If `epsilon` does not have the shape of the transition matrix, or the perturbed matrix
+    /// has a negative entry or a row of zeros.
+    ///
+    /// # Examples
+    ///
+    /// Comparing the stationary distribution against a finite difference.
+    /// ```
+    /// # use ndarray::{array, Array2};
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.25, 0.75]], rand::thread_rng()));
+    /// let epsilon = array![[1e-3, -1e-3], [0., 0.]];
+    /// let perturbed = mc.perturbed(&epsilon).unwrap();
+    /// let difference = perturbed.stationary_distribution().unwrap() - mc.stationary_distribution().unwrap();
+    /// let sensitivity = mc.stationary_sensitivity(0, 0).unwrap();
+    /// assert!((difference[0] / 1e-3 - sensitivity[0]).abs() < 1e-2);
+    /// ```
+    #[inline]
+    pub fn perturbed(&self, epsilon: &ndarray::Array2<f64>) -> Result<FiniteMarkovChain<T, f64, R>, crate::errors::MatrixError>
+    where
+        W: num_traits::ToPrimitive,
+        R: Clone,
+    {
+        let p = self.transition_probabilities();
+        if p.dim() != epsilon.dim() {
+            return Err(crate::errors::MatrixError::DimensionMismatch);
+        }
+        let perturbed = crate::matrix::normalize_rows(&(p + epsilon))?;
+        let transition_matrix: Vec<Vec<f64>> = perturbed.genrows().into_iter().map(|row| row.to_vec()).collect();
+        Ok(FiniteMarkovChain::<T, f64, R>::new(self.state_index, transition_matrix, self.state_space.clone(), self.rng.clone()))
+    }
+
+    /// Returns the lazy version of the chain, with transition matrix `(1 - alpha) I + alpha P`:
+    /// at each step, the chain stays put with probability `1 - alpha` and otherwise moves as `self`.
+    ///
+    /// The lazy chain starts in the current state and uses the random number generator of `self`.
+    ///
+    /// # Panics
+    ///
+    /// If `alpha` is not in `(0, 1]`.
+    ///
+    /// # Examples
+    ///
+    /// A periodic chain becomes aperiodic.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0., 1.], [1., 0.]], rand::thread_rng()));
+    /// let lazy = mc.lazy(0.5);
+    /// assert_eq!(lazy.transition_probabilities(), array![[0.5, 0.5], [0.5, 0.5]]);
+    /// ```
+    #[inline]
+    pub fn lazy(self, alpha: f64) -> FiniteMarkovChain<T, f64, R>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        assert!(alpha > 0.0 && alpha <= 1.0, "The laziness parameter must be in (0, 1].");
+        let p = self.transition_probabilities();
+        let lazy = ndarray::Array2::<f64>::eye(self.nstates()) * (1.0 - alpha) + p * alpha;
+        FiniteMarkovChain::<T, f64, R>::from((self.state_index, lazy, self.state_space, self.rng))
+    }
+
+    /// Returns the Metropolis chain with proposals given by `self` and stationary distribution `target_pi`,
+    /// indexed as the state space, which need not be normalized.
+    ///
+    /// A move from `i` to `j`, proposed with probability `Q(i, j)`, is accepted with probability
+    /// `min(1, π(j) Q(j, i) / (π(i) Q(i, j)))`, and otherwise the chain stays in `i`.
+    /// The result is reversible with respect to `target_pi`, and it is irreducible
+    /// if the proposals are and every state has positive target probability.
+    ///
+    /// The Metropolis chain starts in the current state and uses the random number generator of `self`.
+    ///
+    /// # Panics
+    ///
+    /// If `target_pi` does not have one non-negative weight per state, or all weights are zero.
+    ///
+    /// # Examples
+    ///
+    /// A random walk on a path, tilted towards its end.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let walk = array![[0.5, 0.5, 0.], [0.5, 0., 0.5], [0., 0.5, 0.5]];
+    /// let mc = FiniteMarkovChain::from((0, walk, rand::thread_rng()));
+    /// let metropolis = mc.metropolize(&[1., 2., 4.]);
+    /// let pi = metropolis.stationary_distribution().unwrap();
+    /// assert!((pi[2] - 4. / 7.).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn metropolize(self, target_pi: &[f64]) -> FiniteMarkovChain<T, f64, R>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let n = self.nstates();
+        assert_eq!(target_pi.len(), n, "The target must have one weight per state.");
+        assert!(
+            target_pi.iter().all(|&w| w >= 0.0 && w.is_finite()) && target_pi.iter().any(|&w| w > 0.0),
+            "Target weights must be non-negative and not all zero."
+        );
+        let q = self.transition_probabilities();
+        let mut p = ndarray::Array2::<f64>::zeros((n, n));
+        for i in 0..n {
+            for j in (0..n).filter(|&j| j != i && q[[i, j]] > 0.0) {
+                let forward = target_pi[i] * q[[i, j]];
+                let backward = target_pi[j] * q[[j, i]];
+                // Moves out of states with zero target probability are always accepted
+                let acceptance = if forward > 0.0 { (backward / forward).min(1.0) } else { 1.0 };
+                p[[i, j]] = q[[i, j]] * acceptance;
+            }
+            let moving: f64 = p.row(i).sum();
+            p[[i, i]] = (1.0 - moving).max(0.0);
+        }
+        FiniteMarkovChain::<T, f64, R>::from((self.state_index, p, self.state_space, self.rng))
+    }
+
+    /// Returns the jump chain of `self`, which yields the number of steps spent in each state
+    /// together with the next, different, state.
+    ///
+    /// The jump chain starts in the current state and uses the random number generator of `self`.
+    ///
+    /// # Examples
+    ///
+    /// The total number of steps is preserved.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.99, 0.01], [0.02, 0.98]], rand::thread_rng()));
+    /// let steps: u64 = mc.without_self_loops().take(10).map(|(steps, _)| steps).sum();
+    /// println!("Ten changes of state took {} steps", steps);
+    /// ```
+    #[inline]
+    pub fn without_self_loops(self) -> super::JumpChain<T, R>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let p = self.transition_probabilities();
+        super::JumpChain::new(self.state_index, p, self.state_space, self.rng)
+    }
+
+    /// Returns the chain conditioned to reach the state of index `target_index`, 
+    /// or `None` if the target can not be reached from the current state.
+    ///
+    /// The conditioned chain is the Doob h-transform of `self`, where `h` are the [hitting probabilities]
+    /// of the target: the transition probabilities become `P_ij h_j / h_i`. 
+    /// Its trajectories reach the target with probability one, and until then have the law 
+    /// of the trajectories of `self` that reach the target. From the target onwards, 
+    /// the conditioned chain moves as `self`.
+    ///
+    /// # Examples
+    ///
+    /// Gambler's ruin, conditioned to win.
+    /// ```
+    /// # use ndarray::Array2;
+    /// # use markovian::FiniteMarkovChain;
+    /// let transition = Array2::from_shape_fn((5, 5), |(i, j)| match i {
+    ///     0 | 4 => if i == j { 1. } else { 0. },
+    ///     _ => if i + 1 == j || j + 1 == i { 0.5 } else { 0. },
+    /// });
+    /// let mc = FiniteMarkovChain::from((1, transition, rand::thread_rng()));
+    /// let mut winner = mc.conditioned_to_reach(4).unwrap();
+    /// assert_eq!(winner.nth(100), Some(4));
+    /// ```
+    ///
+    /// [hitting probabilities]: #method.hitting_probabilities
+    #[inline]
+    pub fn conditioned_to_reach(self, target_index: usize) -> Option<FiniteMarkovChain<T, f64, R>>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let h = self.hitting_probabilities(&[target_index]);
+        if h[self.state_index] <= 0.0 {
+            return None;
+        }
+        let mut p = self.transition_probabilities();
+        for i in 0..self.nstates() {
+            if i != target_index && h[i] > 0.0 {
+                for j in 0..self.nstates() {
+                    p[[i, j]] *= h[j] / h[i];
+                }
+            }
+        }
+        let p = crate::linalg::normalize_rows(p);
+        Some(FiniteMarkovChain::<T, f64, R>::from((self.state_index, p, self.state_space, self.rng)))
+    }
+
+    /// Returns a chain in continuous time whose transition probabilities at time `dt` are those of `self`,
+    /// so that `self` is its skeleton at time step `dt`.
+    ///
+    /// The generator is the principal matrix logarithm of the transition matrix divided by `dt`. 
+    /// Transition matrices, e.g. estimated from yearly data, may have no such generator, 
+    /// in which case `regularization` decides whether to fail or to return a nearby generator.
+    /// Negative rates smaller in absolute value than [EMBEDDING_TOLERANCE] are always set to zero.
+    ///
+    /// The new chain starts in the current state and uses the random number generator of `self`.
+    ///
+    /// # Errors
+    ///
+    /// If the logarithm could not be computed, e.g. if the transition matrix has negative eigenvalues,
+    /// or it has negative rates and `regularization` is `Regularization::None`.
+    ///
+    /// # Panics
+    ///
+    /// If `dt` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// A yearly rating transition matrix as a chain in continuous time.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::{FiniteMarkovChain, matrix::{Generator, Regularization}};
+    /// let yearly = array![[0.9, 0.08, 0.02], [0.05, 0.85, 0.1], [0., 0., 1.]];
+    /// let mc = FiniteMarkovChain::from((0, yearly.clone(), rand::thread_rng()));
+    /// let ctmc = mc.embed_as_ctmc(1., Regularization::Weighted).unwrap();
+    /// let q = Generator::from(&ctmc);
+    /// assert!((q.transition_probabilities(1.) - &yearly).iter().all(|x| x.abs() < 1e-2));
+    /// ```
+    ///
+    /// [EMBEDDING_TOLERANCE]: matrix/constant.EMBEDDING_TOLERANCE.html
+    #[inline]
+    pub fn embed_as_ctmc(
+        self,
+        dt: f64,
+        regularization: crate::matrix::Regularization,
+    ) -> Result<crate::ContFiniteMarkovChain<T, f64, R>, crate::errors::EmbeddingError>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        assert!(dt > 0.0, "The time step must be positive.");
+        let log = crate::linalg::logm(&self.transition_probabilities())
+            .ok_or(crate::errors::EmbeddingError::NoLogarithm)?;
+        let generator = regularization.generator(log / dt)?;
+        Ok(generator.chain(self.state_index, self.state_space, self.rng))
+    }
+
+    /// Turns on the fast-forward sampling mode, where [nth] skips stretches of steps in the same state.
+    ///
+    /// In this mode, from states whose probability of staying is at least [FAST_FORWARD_THRESHOLD],
+    /// [nth] samples the number of steps until leaving the state from a geometric distribution
+    /// and then the exit state, instead of sampling every step. The law of the chain does not change,
+    /// but the random numbers used do, so trajectories differ from those of the default mode.
+    ///
+    /// # Examples
+    ///
+    /// A near-absorbing chain advanced by a million steps, with few random numbers.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mut mc = FiniteMarkovChain::from((0, array![[0.999_999, 0.000_001], [0.0, 1.0]], rand::thread_rng()))
+    ///     .fast_forward();
+    /// println!("After a million steps, the state is {:?}", mc.nth(999_999));
+    /// ```
+    ///
+    /// [nth]: #method.nth
+    /// [FAST_FORWARD_THRESHOLD]: constant.FAST_FORWARD_THRESHOLD.html
+    #[inline]
+    pub fn fast_forward(mut self) -> Self
+    where
+        W: num_traits::ToPrimitive,
+    {
+        self.fast_forward = Some(Jumps::new(&self.transition_probabilities()));
+        self
+    }
+
+    /// Returns `true` if the fast-forward sampling mode is on.
+    #[inline]
+    pub fn is_fast_forward(&self) -> bool {
+        self.fast_forward.is_some()
+    }
+
+    /// Returns the entropy rate of the chain, in nats, i.e. `- Σ π_i P_ij ln P_ij`,
+    /// where `π` is its stationary distribution, or `None` if the stationary distribution is not unique.
+    ///
+    /// # Examples
+    ///
+    /// A chain of independent fair coin flips produces `ln 2` nats per step.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.5, 0.5]], rand::thread_rng()));
+    /// assert!((mc.entropy_rate().unwrap() - 2f64.ln()).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn entropy_rate(&self) -> Option<f64>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let pi = self.stationary_distribution()?;
+        let p = self.transition_probabilities();
+        let entropy = p
+            .genrows()
+            .into_iter()
+            .zip(pi.iter())
+            .map(|(row, pi_i)| pi_i * entropy(row.iter().copied()))
+            .sum();
+        Some(entropy)
+    }
+
+    /// Returns the entropy of the stationary distribution, in nats,
+    /// or `None` if the stationary distribution is not unique.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.0, 1.0], [1.0, 0.0]], rand::thread_rng()));
+    /// assert!((mc.stationary_entropy().unwrap() - 2f64.ln()).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn stationary_entropy(&self) -> Option<f64>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        self.stationary_distribution().map(|pi| entropy(pi.iter().copied()))
+    }
+
+    /// Returns the Kullback-Leibler divergence rate, in nats, from `self` to `other`,
+    /// i.e. `Σ π_i P_ij ln(P_ij / Q_ij)`, where `π` and `P` correspond to `self` and `Q` to `other`,
+    /// or `None` if the stationary distribution of `self` is not unique.
+    ///
+    /// It is infinite if `self` has a transition, from a state with positive stationary probability,
+    /// that `other` does not have.
+    ///
+    /// # Panics
+    ///
+    /// If the chains do not have the same number of states.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.5, 0.5]], rand::thread_rng()));
+    /// let other = FiniteMarkovChain::from((0, array![[0.9, 0.1], [0.1, 0.9]], rand::thread_rng()));
+    /// assert_eq!(mc.kl_divergence_to(&mc), Some(0.));
+    /// assert!(mc.kl_divergence_to(&other).unwrap() > 0.);
+    /// ```
+    #[inline]
+    pub fn kl_divergence_to<W2, R2>(&self, other: &FiniteMarkovChain<T, W2, R2>) -> Option<f64>
+    where
+        W: num_traits::ToPrimitive,
+        W2: AliasableWeight + Debug + Clone + num_traits::ToPrimitive,
+        Uniform<W2>: Debug + Clone,
+        R2: Rng,
+    {
+        assert_eq!(self.nstates(), other.nstates(), "Chains must have the same number of states.");
+        let pi = self.stationary_distribution()?;
+        let p = self.transition_probabilities();
+        let q = other.transition_probabilities();
+        let mut divergence = 0.0;
+        for ((i, j), &p_ij) in p.indexed_iter() {
+            if pi[i] > 0.0 && p_ij > 0.0 {
+                divergence += pi[i] * p_ij * (p_ij / q[[i, j]]).ln();
+            }
+        }
+        Some(divergence)
+    }
+
+    /// Samples a spanning arborescence directed towards `root`, by Wilson's algorithm,
+    /// or returns `None` if `root` is not reachable from every state.
+    ///
+    /// Each state other than `root` has one outgoing edge, to its parent, and the probability
+    /// of a tree is proportional to the product of the transition probabilities of its edges.
+    /// For a random walk on an undirected graph, this is a uniform spanning tree.
+    ///
+    /// # Costs
+    ///
+    /// O(expected hitting time of `root`, averaged over the stationary distribution).
+    ///
+    /// # Panics
+    ///
+    /// If `root` is not an index of the state space.
+    ///
+    /// # Examples
+    ///
+    /// A uniform spanning tree of a cycle of four states: one of the four paths ending at `0`.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::FiniteMarkovChain;
+    /// let cycle = array![[0., 1., 0., 1.], [1., 0., 1., 0.], [0., 1., 0., 1.], [1., 0., 1., 0.]];
+    /// let mc = FiniteMarkovChain::from((0, cycle, rand::thread_rng()));
+    /// let tree = mc.wilson_spanning_tree(0, &mut rand::thread_rng()).unwrap();
+    /// assert_eq!(tree.node_count(), 4);
+    /// assert_eq!(tree.edge_count(), 3);
+    /// ```
+    #[inline]
+    pub fn wilson_spanning_tree<R2>(&self, root: usize, rng: &mut R2) -> Option<DiGraph<T, W>>
+    where
+        R2: Rng + ?Sized,
+    {
+        let n = self.nstates();
+        assert!(root < n, "The root must be an index of the state space.");
+        let adjacency = ndarray::Array2::from_shape_fn((n, n), |(i, j)| {
+            match self.transition_matrix[i].get(j) {
+                Some(w) if *w > W::ZERO => 1.0,
+                _ => 0.0,
+            }
+        });
+        let target: Vec<bool> = (0..n).map(|i| i == root).collect();
+        if crate::linalg::backward_reachable(&adjacency, &target).contains(&false) {
+            return None;
+        }
+
+        let mut in_tree = target;
+        let mut parent: Vec<usize> = (0..n).collect();
+        for start in 0..n {
+            // Loop-erased random walk: cycles are erased by overwriting the parent
+            let mut u = start;
+            while !in_tree[u] {
+                parent[u] = self.transition_matrix_variables[u].sample(rng);
+                u = parent[u];
+            }
+            let mut u = start;
+            while !in_tree[u] {
+                in_tree[u] = true;
+                u = parent[u];
+            }
+        }
+
+        let mut tree = DiGraph::<T, W>::with_capacity(n, n - 1);
+        let nodes: Vec<_> = self.state_space.iter().map(|state| tree.add_node(state.clone())).collect();
+        for u in (0..n).filter(|&u| u != root) {
+            tree.add_edge(nodes[u], nodes[parent[u]], self.transition_matrix[u][parent[u]]);
+        }
+        Some(tree)
+    }
+
+    /// Returns the transition graph of the chain and the node of the current state.
+    #[inline]
+    fn graph(&self) -> (DiGraph<T, W>, petgraph::graph::NodeIndex) {
+        let mut graph = DiGraph::<T, W>::new();
+        let vertices: Vec<_> = self.state_space.iter()
+            .map(|state| graph.add_node(state.clone()))
+            .collect();
+        for i in 0..self.nstates() {
+            for j in 0..self.transition_matrix[i].len() {
+                if self.transition_matrix[i][j] > W::ZERO {
+                    graph.add_edge(vertices[i], vertices[j], self.transition_matrix[i][j]);
+                }
+            }
+        }
+        (graph, petgraph::graph::NodeIndex::new(self.state_index))
+    }
+}
+
+impl<W, R> FiniteMarkovChain<usize, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    /// Constructs a new `FiniteMarkovChain<usize, W, R>` on the states `0..nstates`,
+    /// where each step rolls a die and moves deterministically.
+    ///
+    /// The die is given by pairs `(weight, roll)` and `move_fn(state, roll)` is the
+    /// state reached from `state` after rolling `roll`, as in board games.
+    ///
+    /// # Panics
+    ///
+    /// If `move_fn` returns a state greater or equal to `nstates`,
+    /// or in the same cases as [new].
+    ///
+    /// # Examples
+    ///
+    /// A tiny snakes and ladders board with a coin: a ladder from 1 to 3,
+    /// a snake from 4 to 0 and the game ends at 5, even if overshooting.
+    /// ```
+    /// # use markovian::FiniteMarkovChain;
+    /// let move_fn = |state: usize, roll: usize| match (state + roll).min(5) {
+    ///     _ if state == 5 => 5,
+    ///     1 => 3,
+    ///     4 => 0,
+    ///     other => other,
+    /// };
+    /// let mc = FiniteMarkovChain::from_move_and_die(0, move_fn, vec![(1., 1), (1., 2)], 6, rand::thread_rng());
+    /// assert_eq!(mc.absorbing_states(), vec![&5]);
+    /// let expected_turns = mc.expected_cost_to_absorption(|_, _| 1.0);
+    /// println!("The game lasts {} turns on average", expected_turns[0]);
+    /// ```
+    ///
+    /// [new]: struct.FiniteMarkovChain.html#method.new
+    #[inline]
+    pub fn from_move_and_die<F, I>(state_index: usize, move_fn: F, die: I, nstates: usize, rng: R) -> Self
+    where
+        F: Fn(usize, usize) -> usize,
+        I: IntoIterator<Item = (W, usize)>,
+    {
+        let die: Vec<(W, usize)> = die.into_iter().collect();
+        let transition_matrix: Vec<Vec<W>> = (0..nstates)
+            .map(|state| {
+                let mut row = vec![W::ZERO; nstates];
+                for &(weight, roll) in die.iter() {
+                    let next = move_fn(state, roll);
+                    assert!(next < nstates, "Moves must stay in the board. Moved from {} to {}.", state, next);
+                    row[next] += weight;
+                }
+                row
+            })
+            .collect();
+        let state_space = (0..nstates).collect();
+        FiniteMarkovChain::new(state_index, transition_matrix, state_space, rng)
+    }
+}
+
+impl<R> FiniteMarkovChain<usize, f64, R>
+where
+    R: Rng,
+{
+    /// Constructs a new `FiniteMarkovChain<usize, f64, R>` from a matrix of transition counts,
+    /// where `counts[[i, j]]` is the number of observed transitions from `i` to `j`.
+    ///
+    /// Counts are turned into probabilities according to `smoothing`, 
+    /// which also decides what to do with states without observed transitions.
+    ///
+    /// # Errors
+    ///
+    /// If `counts` is not square, or the pseudo-count of `smoothing` is negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// Credit ratings, where the default state was never left nor observed in the past.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::{FiniteMarkovChain, Smoothing};
+    /// let counts = array![[90, 10, 0], [20, 75, 5], [0, 0, 0]];
+    /// let mc = FiniteMarkovChain::from_counts(0, &counts, Smoothing::None, rand::thread_rng()).unwrap();
+    /// assert_eq!(mc.absorbing_states(), vec![&2]);
+    /// let mc = FiniteMarkovChain::from_counts(0, &counts, Smoothing::Additive(1.0), rand::thread_rng()).unwrap();
+    /// assert_eq!(mc.transition_probabilities()[[0, 2]], 1. / 103.);
+    /// ```
+    #[inline]
+    pub fn from_counts(
+        state_index: usize,
+        counts: &ndarray::Array2<u64>,
+        smoothing: Smoothing,
+        rng: R,
+    ) -> Result<Self, crate::errors::MatrixError> {
+        let transition_matrix = smoothing.probabilities(counts)?;
+        Ok(FiniteMarkovChain::from((state_index, transition_matrix, rng)))
+    }
+}
+
+impl<'a, T, W, R> FiniteMarkovChain<T, W, &'a mut R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng + ?Sized,
+{
+    /// Constructs a new `FiniteMarkovChain<T, W, &mut R>` that borrows its random number generator.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [new].
+    ///
+    /// # Examples
+    ///
+    /// Borrowing the thread local random number generator.
+    /// ```
+    /// # use markovian::FiniteMarkovChain;
+    /// let mut rng = rand::thread_rng();
+    /// let transition_matrix = vec![vec![0.5, 0.5], vec![0.0, 1.0]];
+    /// let mc = FiniteMarkovChain::new_with_rng_mut(0, transition_matrix, vec!['a', 'b'], &mut rng);
+    /// println!("{:?}", mc.take(3).collect::<Vec<char>>());
+    /// ```
+    ///
+    /// [new]: struct.FiniteMarkovChain.html#method.new
+    #[inline]
+    pub fn new_with_rng_mut(
+        state_index: usize,
+        transition_matrix: Vec<Vec<W>>,
+        state_space: Vec<T>,
+        rng: &'a mut R,
+    ) -> Self {
+        FiniteMarkovChain::new(state_index, transition_matrix, state_space, rng)
+    }
+}
+
+impl<T, W, R> Reset for FiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state_index = self.sample_initial_index();
+    }
+}
+
+impl<T, W, R> RandomSource for FiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<T, W, R> State for FiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Item = T;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state_space[self.state_index])
+    }
+
+    #[inline]
+    fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        Some(&mut self.state_space[self.state_index])
+    }
+
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        match self.state_space.iter().position(|s| *s == new_state) {
+            Some(mut state_index) => {
+                mem::swap(&mut self.state_index, &mut state_index);
+                Ok(Some(self.state_space[state_index].clone()))
+            }
+            None => Err(InvalidState::new(new_state)),
+        }
+    }
+}
+
+impl<T, W, R> Checkpoint for FiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<T, W, R> Iterator for FiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Item = T;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         self.state_index = self.sample_index();
         self.state().cloned()
     }
+
+    /// Advances the chain `n + 1` steps and returns the last state.
+    ///
+    /// In the [fast-forward] mode, stretches of steps in the same state are skipped.
+    ///
+    /// [fast-forward]: #method.fast_forward
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let jumps = match &self.fast_forward {
+            Some(jumps) => jumps,
+            None => {
+                for _ in 0..n {
+                    self.next()?;
+                }
+                return self.next();
+            }
+        };
+        let mut remaining = (n as u64).saturating_add(1);
+        while remaining > 0 {
+            if jumps.stay_probability(self.state_index) >= FAST_FORWARD_THRESHOLD {
+                let (steps, index) = jumps.sample(self.state_index, &mut self.rng);
+                if steps > remaining {
+                    break;
+                }
+                self.state_index = index;
+                remaining -= steps;
+            } else {
+                self.state_index = self.transition_matrix_variables[self.state_index].sample(&mut self.rng);
+                remaining -= 1;
+            }
+        }
+        self.state().cloned()
+    }
+}
+
+impl<T, W, R> StochasticProcess for FiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Time = ();
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
 }
 
 impl<T, W, R> StateIterator for FiniteMarkovChain<T, W, R>
@@ -409,7 +1734,7 @@ where
     W: AliasableWeight + Debug + Clone,
     Uniform<W>: Debug + Clone,
     T: Debug + PartialEq + Clone,
-    R: Rng + Debug + Clone,
+    R: Rng,
 {
     #[inline]
     fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
@@ -422,7 +1747,7 @@ where
     W: AliasableWeight + Debug + Clone,
     Uniform<W>: Debug + Clone,
     T: Debug + PartialEq + Clone,
-    R: Rng + Debug + Clone,
+    R: Rng,
 {
     /// Sample a possible next state. 
     #[inline]
@@ -440,7 +1765,7 @@ impl<W, R> From<(usize, Vec<Vec<W>>, R)> for FiniteMarkovChain<usize, W, R>
 where
     W: AliasableWeight + Debug + Clone,
     Uniform<W>: Debug + Clone,
-    R: Rng + Debug + Clone,
+    R: Rng,
 {
 	/// Performs the conversion.
 	///
@@ -449,7 +1774,7 @@ where
     /// This method panics if: 
     /// - Any vector of `transition_matrix` has more than u32::MAX columns.
     /// - For any entry w of any vector of `transition_matrix` v: 
-    /// w < 0 or w > max where max = W::MAX / v.len().
+    ///   w < 0 or w > max where max = W::MAX / v.len().
     /// - For any vector of `transition_matrix` the sum of weights is zero.
     fn from((state_index, transition_matrix, rng): (usize, Vec<Vec<W>>, R)) -> Self {
         let state_space: Vec<usize> = (0..transition_matrix.len()).collect();
@@ -462,7 +1787,7 @@ where
     W: AliasableWeight + Debug + Clone,
     Uniform<W>: Debug + Clone,
     T: Debug + PartialEq + Clone,
-    R: Rng + Debug + Clone,
+    R: Rng,
 {
 	/// Performs the conversion.
 	///
@@ -472,7 +1797,7 @@ where
     /// - (In debug mode only) The dimensions of `state_space` and `transition_matrix` do not match.
     /// - `transition_matrix` has more than u32::MAX columns.
     /// - For any entry of `transition_matrix` w: 
-    /// w < 0 or w > max where max = W::MAX / transition_matrix.ncols().
+    ///   w < 0 or w > max where max = W::MAX / transition_matrix.ncols().
     /// - For any row of `transition_matrix` the sum of weights is zero.
 	fn from((state_index, transition_matrix, state_space, rng): (usize, ndarray::Array2<W>, Vec<T>, R)) -> Self {
         let transition_matrix: Vec<Vec<W>> = transition_matrix.genrows()
@@ -489,7 +1814,7 @@ impl<W, R> From<(usize, ndarray::Array2<W>, R)> for FiniteMarkovChain<usize, W,
 where
     W: AliasableWeight + Debug + Clone,
     Uniform<W>: Debug + Clone,
-    R: Rng + Debug + Clone,
+    R: Rng,
 {
 	/// Performs the conversion.
 	///
@@ -498,7 +1823,7 @@ where
     /// This method panics if: 
     /// - `transition_matrix` has more than u32::MAX columns.
     /// - For any entry of `transition_matrix` w: 
-    /// w < 0 or w > max where max = W::MAX / transition_matrix.ncols().
+    ///   w < 0 or w > max where max = W::MAX / transition_matrix.ncols().
     /// - For any row of `transition_matrix` the sum of weights is zero.
     ///
     /// # Example
@@ -518,12 +1843,12 @@ where
     }
 }
 
-impl<T, W, R> Into<(DiGraph<T, W>, petgraph::graph::NodeIndex)> for FiniteMarkovChain<T, W, R>
+impl<T, W, R> From<FiniteMarkovChain<T, W, R>> for (DiGraph<T, W>, petgraph::graph::NodeIndex)
 where
     W: AliasableWeight + Debug + Clone,
     Uniform<W>: Debug + Clone,
     T: Debug + PartialEq + Clone,
-    R: Rng + Debug + Clone,
+    R: Rng,
 {
     /// Performs the conversion.
     ///
@@ -541,23 +1866,43 @@ where
     /// assert_eq!(graph.edge_count(), 3);
     /// assert_eq!(graph.node_count(), 2);
     /// ``` 
-    fn into(self) -> (DiGraph<T, W>, petgraph::graph::NodeIndex) { 
-        let mut graph = DiGraph::<T, W>::new();
-        let vertices: Vec<_> = self.state_space.iter()
-            .map(|state| graph.add_node(state.clone()))
-            .collect();
-        for i in 0..self.nstates() {
-            for j in 0..self.transition_matrix[i].len() {
-                if self.transition_matrix[i][j] > W::ZERO {
-                    graph.add_edge(vertices[i], vertices[j], self.transition_matrix[i][j]);
-                }
+    fn from(mc: FiniteMarkovChain<T, W, R>) -> Self {
+        mc.graph()
+    }
+}
+
+
+/// Returns the entropy, in nats, of a probability vector.
+#[inline]
+fn entropy<I>(probabilities: I) -> f64
+where
+    I: Iterator<Item = f64>,
+{
+    probabilities.filter(|&p| p > 0.0).map(|p| -p * p.ln()).sum()
+}
+
+/// Samples an index with probability proportional to `weights`, 
+/// or returns `None` if all weights are zero.
+#[inline]
+pub(crate) fn sample_proportional<I, R>(weights: I, rng: &mut R) -> Option<usize>
+where
+    I: Iterator<Item = f64> + Clone,
+    R: Rng + ?Sized,
+{
+    let mut uniform = rng.gen::<f64>() * weights.clone().sum::<f64>();
+    let mut index = None;
+    for (j, w) in weights.enumerate() {
+        if w > 0.0 {
+            index = Some(j);
+            if uniform < w {
+                break;
             }
+            uniform -= w;
         }
-        (graph, petgraph::graph::NodeIndex::new(self.state_index))
     }
+    index
 }
 
-
 #[cfg(test)]
 mod tests {
 
@@ -576,7 +1921,7 @@ mod tests {
     #[test_case(0, array![[]], vec![1], thread_rng() => panics ""; "not enough transitions")]
     #[test_case(0, array![[]], Vec::new(), thread_rng() => panics ""; "empty transition")]
     fn construction_array2(state_index: usize, transition_matrix: Array2<usize>, state_space: Vec<u64>, rng: rand::prelude::ThreadRng) {
-        FiniteMarkovChain::from((state_index, transition_matrix, state_space, rng));
+        let _ = FiniteMarkovChain::from((state_index, transition_matrix, state_space, rng));
     }
 
     #[test]
@@ -586,4 +1931,329 @@ mod tests {
         assert_eq!(Some(10), previous_state);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn reseed() {
+        let transition_matrix = vec![vec![1, 1], vec![1, 1]];
+        let mut mc = FiniteMarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition_matrix, vec![0, 1], 7);
+        let first: Vec<u8> = mc.by_ref().take(20).collect();
+        mc.set_state(0).unwrap();
+        mc.reseed(7);
+        let second: Vec<u8> = mc.by_ref().take(20).collect();
+        assert_eq!(first, second);
+
+        let previous = mc.set_rng(rand_pcg::Pcg64::seed_from_u64(7));
+        assert_ne!(&previous, mc.rng());
+    }
+
+    #[test]
+    fn initial_distribution() {
+        let transition_matrix = vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 1]];
+        let mut mc = FiniteMarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition_matrix.clone(), vec![0, 1, 2], 8);
+        mc.set_state(2).unwrap();
+        mc.reset();
+        assert_eq!(mc.state(), Some(&0));
+
+        let rng = rand_pcg::Pcg64::seed_from_u64(9);
+        let mut mc = FiniteMarkovChain::from_initial_distribution(vec![0, 1, 1], transition_matrix, vec![0, 1, 2], rng);
+        let mut visited = [false; 3];
+        for _ in 0..100 {
+            mc.reset();
+            visited[*mc.state().unwrap()] = true;
+        }
+        assert_eq!(visited, [false, true, true]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one initial weight for each state")]
+    fn initial_distribution_length() {
+        let transition_matrix = vec![vec![1, 0], vec![0, 1]];
+        FiniteMarkovChain::<_, _, rand_pcg::Pcg64>::from_initial_distribution(vec![1, 1, 1], transition_matrix, vec![0, 1], rand_pcg::Pcg64::seed_from_u64(1));
+    }
+
+    #[test]
+    fn move_and_die() {
+        let move_fn = |state: usize, roll: usize| (state + roll).min(3);
+        let mc = FiniteMarkovChain::from_move_and_die(0, move_fn, vec![(1., 1), (3., 2)], 4, rand_pcg::Pcg64::seed_from_u64(1));
+
+        assert_eq!(mc.transition_probabilities().row(0).to_vec(), vec![0., 0.25, 0.75, 0.]);
+        assert_eq!(mc.transition_probabilities().row(2).to_vec(), vec![0., 0., 0., 1.]);
+        assert_eq!(mc.absorbing_states_indexes(), vec![3]);
+    }
+
+    #[test]
+    fn birth_death() {
+        // Gambler's ruin with a biased coin: closed forms for hitting probabilities and times
+        let (n, up) = (300, 0.6);
+        let transition = Array2::from_shape_fn((n + 1, n + 1), |(i, j)| {
+            if i == 0 || i == n {
+                if i == j { 1. } else { 0. }
+            } else if j == i + 1 {
+                up
+            } else if j + 1 == i {
+                1. - up
+            } else {
+                0.
+            }
+        });
+        let mc = FiniteMarkovChain::from((1, transition, rand_pcg::Pcg64::seed_from_u64(1)));
+        assert!(mc.is_birth_death());
+
+        let ratio: f64 = (1. - up) / up;
+        let probabilities = mc.hitting_probabilities(&[n]);
+        let times = mc.mean_hitting_times(&[0, n]);
+        for i in [0, 1, 10, 150, n].iter().copied() {
+            let ruin = (1. - ratio.powi(i as i32)) / (1. - ratio.powi(n as i32));
+            assert!((probabilities[i] - ruin).abs() < 1e-9);
+            let time = (n as f64 * ruin - i as f64) / (2. * up - 1.);
+            assert!((times[i] - time).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn stationary_sensitivity() {
+        let transition = array![[0.1, 0.6, 0.3], [0.4, 0.2, 0.4], [0.5, 0.25, 0.25]];
+        let mc = FiniteMarkovChain::from((0, transition.clone(), rand_pcg::Pcg64::seed_from_u64(1)));
+        let pi = mc.stationary_distribution().unwrap();
+        assert!((pi.dot(&transition) - &pi).iter().all(|x| x.abs() < 1e-12));
+
+        let h = 1e-6;
+        for (i, j) in [(0, 0), (1, 2), (2, 1)].iter().copied() {
+            let mut epsilon = Array2::zeros((3, 3));
+            epsilon[[i, j]] = h;
+            let perturbed = mc.perturbed(&epsilon).unwrap();
+            let step = perturbed.transition_probabilities()[[i, j]] - transition[[i, j]];
+            let difference = (perturbed.stationary_distribution().unwrap() - &pi) / step;
+            let sensitivity = mc.stationary_sensitivity(i, j).unwrap();
+            assert!((difference - sensitivity).iter().all(|x| x.abs() < 1e-4));
+        }
+
+        // For two states, the stationary probability of the first one is P_10 / (P_01 + P_10)
+        let mc = FiniteMarkovChain::from((0, array![[0.8, 0.2], [0.25, 0.75]], rand_pcg::Pcg64::seed_from_u64(3)));
+        let sensitivity = mc.stationary_sensitivity(0, 0).unwrap();
+        assert!((sensitivity[0] - 0.25 / 0.45_f64.powi(2)).abs() < 1e-12);
+        assert!((sensitivity[0] + sensitivity[1]).abs() < 1e-12);
+
+        let reducible = FiniteMarkovChain::from((0, array![[1.0, 0.0], [0.0, 1.0]], rand_pcg::Pcg64::seed_from_u64(2)));
+        assert!(reducible.stationary_distribution().is_none());
+        assert!(mc.perturbed(&Array2::from_elem((2, 2), -1.)).is_err());
+        assert_eq!(mc.perturbed(&Array2::zeros((3, 3))).err(), Some(crate::errors::MatrixError::DimensionMismatch));
+    }
+
+    #[test]
+    fn information() {
+        let transition = array![[0.1, 0.6, 0.3], [0.4, 0.2, 0.4], [0.5, 0.5, 0.0]];
+        let mc = FiniteMarkovChain::from((0, transition.clone(), rand_pcg::Pcg64::seed_from_u64(1)));
+        let pi = mc.stationary_distribution().unwrap();
+        let rows: Vec<f64> = transition
+            .genrows()
+            .into_iter()
+            .map(|row| row.iter().filter(|&&p| p > 0.).map(|&p: &f64| -p * p.ln()).sum())
+            .collect();
+        let expected: f64 = pi.iter().zip(rows).map(|(a, b)| a * b).sum();
+        assert!((mc.entropy_rate().unwrap() - expected).abs() < 1e-12);
+        assert!(mc.entropy_rate().unwrap() <= mc.stationary_entropy().unwrap());
+
+        let uniform = FiniteMarkovChain::from((0, Array2::from_elem((3, 3), 1.0), rand_pcg::Pcg64::seed_from_u64(2)));
+        assert!(mc.kl_divergence_to(&uniform).unwrap() > 0.);
+        assert_eq!(uniform.kl_divergence_to(&mc), Some(f64::INFINITY));
+    }
+
+    #[test]
+    fn fast_forward() {
+        let transition = array![[0.99, 0.01, 0.], [0.3, 0.4, 0.3], [0., 0.001, 0.999]];
+        let mut mc = FiniteMarkovChain::from((0, transition.clone(), rand_pcg::Pcg64::seed_from_u64(1))).fast_forward();
+        assert!(mc.is_fast_forward());
+
+        let distribution = mc.n_step_distribution(200);
+        let samples = 5_000;
+        let mut counts = [0.; 3];
+        for _ in 0..samples {
+            mc.set_state(0).unwrap();
+            counts[mc.nth(199).unwrap()] += 1. / samples as f64;
+        }
+        for (count, exact) in counts.iter().zip(distribution.iter()) {
+            assert!((count - exact).abs() < 0.03);
+        }
+
+        let mut absorbing = FiniteMarkovChain::from((0, array![[1., 0.], [0.5, 0.5]], rand_pcg::Pcg64::seed_from_u64(2))).fast_forward();
+        assert_eq!(absorbing.nth(usize::MAX - 1), Some(0));
+
+        // Relabelling keeps the mode
+        let mut relabelled = absorbing.set_state_space(vec!['a', 'b']);
+        assert!(relabelled.is_fast_forward());
+        relabelled.set_state('b').unwrap();
+        assert_eq!(relabelled.nth(usize::MAX - 1), Some('a'));
+    }
+
+    #[test]
+    fn sample_at() {
+        let transition = array![[0.5, 0.5, 0.], [0.1, 0.6, 0.3], [0., 0.2, 0.8]];
+        let mut mc = FiniteMarkovChain::from((0, transition.clone(), rand_pcg::Pcg64::seed_from_u64(1)));
+        let mut iterated = ndarray::Array1::from(vec![1., 0., 0.]);
+        for _ in 0..100 {
+            iterated = iterated.dot(&transition);
+        }
+        assert!((mc.n_step_distribution(100) - &iterated).iter().all(|x| x.abs() < 1e-12));
+
+        let samples = 5_000;
+        let mut counts = [0.; 3];
+        for _ in 0..samples {
+            mc.set_state(0).unwrap();
+            counts[mc.sample_at(100)] += 1. / samples as f64;
+        }
+        for (count, exact) in counts.iter().zip(iterated.iter()) {
+            assert!((count - exact).abs() < 0.03);
+        }
+    }
+
+    #[test]
+    fn from_counts() {
+        let counts = array![[3, 1, 0], [0, 0, 0], [2, 0, 2]];
+        let mc = FiniteMarkovChain::from_counts(0, &counts, Smoothing::None, crate::tests::rng(1)).unwrap();
+        assert_eq!(mc.transition_probabilities(), array![[0.75, 0.25, 0.], [0., 1., 0.], [0.5, 0., 0.5]]);
+        let mc = FiniteMarkovChain::from_counts(0, &counts, Smoothing::Backoff, crate::tests::rng(2)).unwrap();
+        assert_eq!(mc.transition_probabilities().row(1), array![5. / 8., 1. / 8., 2. / 8.]);
+        let mc = FiniteMarkovChain::from_counts(0, &counts, Smoothing::Additive(1.), crate::tests::rng(3)).unwrap();
+        assert_eq!(mc.transition_probabilities().row(1), array![1. / 3., 1. / 3., 1. / 3.]);
+
+        let empty = Array2::zeros((2, 2));
+        let mc = FiniteMarkovChain::from_counts(0, &empty, Smoothing::Backoff, crate::tests::rng(4)).unwrap();
+        assert_eq!(mc.transition_probabilities(), array![[0.5, 0.5], [0.5, 0.5]]);
+        assert_eq!(
+            FiniteMarkovChain::from_counts(0, &empty, Smoothing::Additive(-1.), rand_pcg::Pcg64::seed_from_u64(5)).unwrap_err(),
+            crate::errors::MatrixError::InvalidEntry(-1.)
+        );
+        assert_eq!(
+            FiniteMarkovChain::from_counts(0, &Array2::zeros((2, 3)), Smoothing::None, rand_pcg::Pcg64::seed_from_u64(6)).unwrap_err(),
+            crate::errors::MatrixError::NotSquare
+        );
+    }
+
+    #[test]
+    fn conditioned_to_reach() {
+        let transition = array![[1., 0., 0., 0.], [0.6, 0.2, 0.2, 0.], [0., 0.5, 0., 0.5], [0., 0., 0., 1.]];
+        let mc = FiniteMarkovChain::from((1, transition.clone(), rand_pcg::Pcg64::seed_from_u64(1)));
+        let h = mc.hitting_probabilities(&[3]);
+        let conditioned = mc.conditioned_to_reach(3).unwrap();
+        let p = conditioned.transition_probabilities();
+        for i in 1..3 {
+            for j in 0..4 {
+                assert!((p[[i, j]] - transition[[i, j]] * h[j] / h[i]).abs() < 1e-12);
+            }
+        }
+        assert_eq!(p[[1, 0]], 0.);
+        assert_eq!(p.row(3), transition.row(3));
+
+        let mc = FiniteMarkovChain::from((0, transition, rand_pcg::Pcg64::seed_from_u64(2)));
+        assert!(mc.conditioned_to_reach(3).is_none());
+    }
+
+    #[test]
+    fn sample_bridge() {
+        let transition = array![[0.5, 0.5, 0.], [0.25, 0.5, 0.25], [0., 0.5, 0.5]];
+        let mut mc = FiniteMarkovChain::from((0, transition, rand_pcg::Pcg64::seed_from_u64(1)));
+        // From 0 to 0 in two steps: through 0 with probability 1/4 / (1/4 + 1/8)
+        let samples = 3_000;
+        let mut through_zero = 0;
+        for _ in 0..samples {
+            let path = mc.sample_bridge(0, 0, 2).unwrap();
+            assert_eq!((path[0], path[2]), (0, 0));
+            if path[1] == 0 {
+                through_zero += 1;
+            }
+        }
+        assert!((through_zero as f64 / samples as f64 - 2. / 3.).abs() < 0.03);
+        assert_eq!(mc.sample_bridge(1, 1, 0), Some(vec![1]));
+        assert_eq!(mc.sample_bridge(0, 2, 1), None);
+        assert_eq!(mc.state(), Some(&0));
+    }
+
+    #[test]
+    fn wilson_spanning_tree() {
+        let mut rng = crate::tests::rng(1);
+        let mc = FiniteMarkovChain::from((0, array![[0.2, 0.4, 0.4], [0.5, 0.25, 0.25], [0.3, 0.6, 0.1]], crate::tests::rng(2)));
+        // Arborescences towards 0, as the parents of 1 and 2, with weights the products of their edges
+        let trees = [((0, 0), 0.5 * 0.3), ((0, 1), 0.5 * 0.6), ((2, 0), 0.25 * 0.3)];
+        let total: f64 = trees.iter().map(|(_, weight)| weight).sum();
+        let samples = 20_000;
+        let mut counts = [0; 3];
+        for _ in 0..samples {
+            let tree = mc.wilson_spanning_tree(0, &mut rng).unwrap();
+            assert_eq!(tree.edge_count(), 2);
+            let parent = |u: usize| tree.neighbors(petgraph::graph::NodeIndex::new(u)).next().unwrap().index();
+            counts[trees.iter().position(|(parents, _)| *parents == (parent(1), parent(2))).unwrap()] += 1;
+        }
+        for (count, (_, weight)) in counts.iter().zip(trees.iter()) {
+            assert!((*count as f64 / samples as f64 - weight / total).abs() < 0.02);
+        }
+
+        let absorbing = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.0, 1.0]], crate::tests::rng(3)));
+        assert!(absorbing.wilson_spanning_tree(0, &mut rng).is_none());
+        assert!(absorbing.wilson_spanning_tree(1, &mut rng).is_some());
+    }
+
+    #[test]
+    fn cover_time() {
+        // Random walk on a path of three states: the cover time from an end is the hitting time of the other end
+        let path = array![[0., 1., 0.], [0.5, 0., 0.5], [0., 1., 0.]];
+        let mut mc = FiniteMarkovChain::from((0, path, crate::tests::rng(1)));
+        let covers = mc.estimate_cover_time(5_000).unwrap();
+        assert!(covers.runs().iter().all(|run| run.len() == 3 && run[0] == (0, 0) && run[2].1 == 2));
+        assert_eq!(covers.last_visited_frequency(&2), 1.);
+        let estimate = covers.estimate();
+        assert!((estimate.mean - 4.).abs() < 4. * estimate.standard_error);
+        assert_eq!(mc.state(), Some(&0));
+
+        let mut absorbing = FiniteMarkovChain::from((0, array![[0.5, 0.25, 0.25], [0., 1., 0.], [0., 0., 1.]], crate::tests::rng(2)));
+        assert_eq!(absorbing.cover_time_sample(), None);
+        assert_eq!(absorbing.estimate_cover_time(10), None);
+    }
+
+    #[test]
+    fn metropolize() {
+        // Uniform proposals on four states, towards a non-uniform target
+        let mc = FiniteMarkovChain::from((0, ndarray::Array2::from_elem((4, 4), 1.), crate::tests::rng(1)));
+        let target = [0.1, 0.2, 0.3, 0.4];
+        let metropolis = mc.metropolize(&[1., 2., 3., 4.]);
+        let p = metropolis.transition_probabilities();
+        for i in 0..4 {
+            assert!((p.row(i).sum() - 1.).abs() < 1e-12);
+            for j in 0..4 {
+                assert!((target[i] * p[[i, j]] - target[j] * p[[j, i]]).abs() < 1e-12);
+            }
+        }
+        let pi = metropolis.stationary_distribution().unwrap();
+        for i in 0..4 {
+            assert!((pi[i] - target[i]).abs() < 1e-12);
+        }
+        let visits = metropolis.take(40_000).filter(|&state| state == 3).count();
+        assert!((visits as f64 / 40_000. - 0.4).abs() < 0.02);
+    }
+
+    #[test]
+    fn embed_as_ctmc() {
+        use crate::errors::EmbeddingError;
+        use crate::matrix::{Generator, Regularization};
+
+        let q = Generator::new(array![[0., 0.3, 0.1], [0.2, 0., 0.2], [0., 0.5, 0.]]).unwrap();
+        let mc = FiniteMarkovChain::from((0, q.transition_probabilities(2.), rand_pcg::Pcg64::seed_from_u64(1)));
+        let embedded = Generator::from(&mc.embed_as_ctmc(2., Regularization::None).unwrap());
+        assert!((embedded.matrix() - q.matrix()).iter().all(|x| x.abs() < 1e-8));
+
+        // Zero probability of a two-step path: not embeddable
+        let p = array![[0.6, 0.4, 0.], [0., 0.6, 0.4], [0.4, 0., 0.6]];
+        let mc = FiniteMarkovChain::from((0, p.clone(), rand_pcg::Pcg64::seed_from_u64(2)));
+        match mc.embed_as_ctmc(1., Regularization::None) {
+            Err(EmbeddingError::NotEmbeddable(rate)) => assert!(rate < 0.),
+            other => panic!("Expected a negative rate, found {:?}", other.map(|_| ())),
+        }
+        for &regularization in &[Regularization::Diagonal, Regularization::Weighted] {
+            let mc = FiniteMarkovChain::from((0, p.clone(), rand_pcg::Pcg64::seed_from_u64(3)));
+            let generator = Generator::from(&mc.embed_as_ctmc(1., regularization).unwrap());
+            assert!((generator.transition_probabilities(1.) - &p).iter().all(|x| x.abs() < 0.15));
+        }
+
+        let periodic = FiniteMarkovChain::from((0, array![[0., 1.], [1., 0.]], rand_pcg::Pcg64::seed_from_u64(4)));
+        assert_eq!(periodic.embed_as_ctmc(1., Regularization::Diagonal).map(|_| ()), Err(EmbeddingError::NoLogarithm));
+    }
+}
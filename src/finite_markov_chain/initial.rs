@@ -0,0 +1,59 @@
+// Traits
+use core::fmt::Debug;
+use rand::Rng;
+use rand_distr::{weighted_alias::{WeightedAliasIndex, AliasableWeight}, Uniform, Distribution};
+
+/// Initial condition of a finite chain.
+pub(crate) enum Initial<W>
+where
+    W: AliasableWeight,
+{
+    /// Always starts at the same index.
+    Index(usize),
+    /// Starts at an index sampled from a distribution.
+    Distribution(WeightedAliasIndex<W>),
+}
+
+impl<W> Initial<W>
+where
+    W: AliasableWeight,
+{
+    #[inline]
+    pub(crate) fn sample<R>(&self, rng: &mut R) -> usize 
+    where
+        R: Rng + ?Sized,
+    {
+        match self {
+            Initial::Index(index) => *index,
+            Initial::Distribution(distribution) => distribution.sample(rng),
+        }
+    }
+}
+
+impl<W> Debug for Initial<W>
+where
+    W: AliasableWeight + Debug,
+    Uniform<W>: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Initial::Index(index) => f.debug_tuple("Index").field(index).finish(),
+            Initial::Distribution(distribution) => f.debug_tuple("Distribution").field(distribution).finish(),
+        }
+    }
+}
+
+impl<W> Clone for Initial<W>
+where
+    W: AliasableWeight,
+    Uniform<W>: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        match self {
+            Initial::Index(index) => Initial::Index(*index),
+            Initial::Distribution(distribution) => Initial::Distribution(distribution.clone()),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
+use core::fmt::Debug;
+use rand::Rng;
+use rand_distr::Distribution;
+
+// Structs
+use crate::errors::InvalidState;
+use rand_distr::{weighted_alias::WeightedAliasIndex, Geometric};
+
+// Functions
+use core::mem;
+
+/// Finite state Markov Chain in discrete time, observed only when it changes state.
+///
+/// The iterator yields pairs `(holding_steps, state)`, where `holding_steps` is the number of steps
+/// spent in the previous state before jumping to `state`. Holding steps are geometric, so
+/// a chain that sits in the same state for long stretches is simulated with one jump per change.
+/// If the current state is absorbing, the chain yields `u64::MAX` steps and the same state.
+///
+/// This struct is created by the [without_self_loops] method.
+///
+/// # Costs
+///
+/// **Sample**: O(1).
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use markovian::FiniteMarkovChain;
+/// let mc = FiniteMarkovChain::from((0, array![[0.999, 0.001], [0.5, 0.5]], rand::thread_rng()));
+/// let mut jumps = mc.without_self_loops();
+/// let (steps, state) = jumps.next().unwrap();
+/// assert_eq!(state, 1);
+/// println!("The chain stayed {} steps in state 0", steps);
+/// ```
+///
+/// [without_self_loops]: struct.FiniteMarkovChain.html#method.without_self_loops
+#[derive(Debug, Clone)]
+pub struct JumpChain<T, R> {
+    state_index: usize,
+    initial_index: usize,
+    jumps: Jumps,
+    state_space: Vec<T>,
+    rng: R,
+}
+
+impl<T, R> JumpChain<T, R>
+where
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    /// Constructs a new `JumpChain<T, R>` from a transition matrix whose rows sum up to one.
+    #[inline]
+    pub(crate) fn new(state_index: usize, transition_matrix: ndarray::Array2<f64>, state_space: Vec<T>, rng: R) -> Self {
+        JumpChain {
+            state_index,
+            initial_index: state_index,
+            jumps: Jumps::new(&transition_matrix),
+            state_space,
+            rng,
+        }
+    }
+
+    /// Returns the probability of staying in each state at each step, indexed as the state space.
+    #[inline]
+    pub fn stay_probabilities(&self) -> &[f64] {
+        &self.jumps.stay_probabilities
+    }
+
+    /// Returns the state space of the Markov Chain.
+    #[inline]
+    pub fn state_space(&self) -> &Vec<T> {
+        &self.state_space
+    }
+}
+
+impl<T, R> Reset for JumpChain<T, R> {
+    #[inline]
+    fn reset(&mut self) {
+        self.state_index = self.initial_index;
+    }
+}
+
+impl<T, R> RandomSource for JumpChain<T, R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<T, R> State for JumpChain<T, R>
+where
+    T: Debug + PartialEq + Clone,
+{
+    type Item = T;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state_space[self.state_index])
+    }
+
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        match self.state_space.iter().position(|s| *s == new_state) {
+            Some(mut state_index) => {
+                mem::swap(&mut self.state_index, &mut state_index);
+                Ok(Some(self.state_space[state_index].clone()))
+            }
+            None => Err(InvalidState::new(new_state)),
+        }
+    }
+}
+
+impl<T, R> Checkpoint for JumpChain<T, R>
+where
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<T, R> Iterator for JumpChain<T, R>
+where
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Item = (u64, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (steps, index) = self.jumps.sample(self.state_index, &mut self.rng);
+        self.state_index = index;
+        self.state().cloned().map(|state| (steps, state))
+    }
+}
+
+impl<T, R> StochasticProcess for JumpChain<T, R>
+where
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Time = u64;
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next()
+    }
+}
+
+impl<T, R> StateIterator for JumpChain<T, R>
+where
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.state().cloned().map(|state| (0, state))
+    }
+}
+
+impl<T, R> Distribution<(u64, T)> for JumpChain<T, R>
+where
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    /// Sample a possible next jump.
+    #[inline]
+    fn sample<R2>(&self, rng: &mut R2) -> (u64, T)
+    where
+        R2: Rng + ?Sized,
+    {
+        let (steps, index) = self.jumps.sample(self.state_index, rng);
+        (steps, self.state_space[index].clone())
+    }
+}
+
+/// Probabilities of staying in each state and distributions of the next, different, state.
+#[derive(Debug, Clone)]
+pub(crate) struct Jumps {
+    stay_probabilities: Vec<f64>,
+    jump_variables: Vec<Option<WeightedAliasIndex<f64>>>,
+}
+
+impl Jumps {
+    /// Constructs a new `Jumps` from a transition matrix whose rows sum up to one.
+    #[inline]
+    pub(crate) fn new(transition_matrix: &ndarray::Array2<f64>) -> Self {
+        let stay_probabilities: Vec<f64> = transition_matrix.diag().to_vec();
+        let jump_variables = transition_matrix
+            .genrows()
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let weights: Vec<f64> = row.iter().enumerate().map(|(j, &p)| if i == j { 0.0 } else { p }).collect();
+                WeightedAliasIndex::new(weights).ok()
+            })
+            .collect();
+        Jumps {
+            stay_probabilities,
+            jump_variables,
+        }
+    }
+
+    /// Returns the probability of staying in the state of index `index` at each step.
+    #[inline]
+    pub(crate) fn stay_probability(&self, index: usize) -> f64 {
+        self.stay_probabilities[index]
+    }
+
+    /// Samples the number of steps until leaving `index` and the index of the next state.
+    ///
+    /// From absorbing states, the number of steps is `u64::MAX` and the index does not change.
+    #[inline]
+    pub(crate) fn sample<R>(&self, index: usize, rng: &mut R) -> (u64, usize)
+    where
+        R: Rng + ?Sized,
+    {
+        match &self.jump_variables[index] {
+            Some(jump) => {
+                let leave = 1.0 - self.stay_probabilities[index];
+                let failures = Geometric::new(leave).map_or(u64::MAX - 1, |geometric| geometric.sample(rng));
+                (failures.saturating_add(1), jump.sample(rng))
+            }
+            None => (u64::MAX, index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use ndarray::array;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn holding_steps() {
+        let mc = crate::FiniteMarkovChain::from((0, array![[0.9, 0.1, 0.], [0.5, 0., 0.5], [0., 0., 1.]], crate::tests::rng(1)));
+        let mut jumps = mc.without_self_loops();
+        assert_eq!(jumps.stay_probabilities(), &[0.9, 0., 1.]);
+
+        let samples = 10_000;
+        let mean = (0..samples)
+            .map(|_| {
+                jumps.set_state(0).unwrap();
+                jumps.next().unwrap().0 as f64
+            })
+            .sum::<f64>()
+            / samples as f64;
+        assert!((mean - 10.).abs() < 0.5);
+
+        jumps.set_state(1).unwrap();
+        assert_eq!(jumps.next().unwrap().0, 1);
+        jumps.set_state(2).unwrap();
+        assert_eq!(jumps.next(), Some((u64::MAX, 2)));
+    }
+}
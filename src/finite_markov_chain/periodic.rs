@@ -0,0 +1,394 @@
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
+use core::fmt::Debug;
+use rand::{Rng, SeedableRng};
+use rand_distr::{weighted_alias::{WeightedAliasIndex, AliasableWeight}, Uniform, Distribution};
+
+// Structs
+use crate::errors::InvalidState;
+use ndarray::{Array1, Array2};
+
+// Functions
+use core::mem;
+
+/// Finite state Markov Chain in discrete time that cycles through several transition matrices.
+///
+/// The step from time `n` to time `n + 1` uses the transition matrix of index `n % k`, 
+/// where `k` is the period, for example, one matrix for each hour of the day.
+///
+/// # Examples
+///
+/// Weather that is more stable at night than during the day.
+/// ```
+/// # use markovian::{PeriodicFiniteMarkovChain, State};
+/// let day = vec![vec![0.5, 0.5], vec![0.5, 0.5]];
+/// let night = vec![vec![0.9, 0.1], vec![0.1, 0.9]];
+/// let mut mc = PeriodicFiniteMarkovChain::new(0, vec![day, night], vec!["sunny", "rainy"], rand::thread_rng());
+/// assert_eq!(mc.phase(), 0);
+/// mc.next();
+/// assert_eq!(mc.phase(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PeriodicFiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    state_index: usize,
+    initial_index: usize,
+    phase: usize,
+    transition_matrices: Vec<Vec<Vec<W>>>,
+    transition_matrices_variables: Vec<Vec<WeightedAliasIndex<W>>>,
+    state_space: Vec<T>,
+    rng: R,
+}
+
+impl<T, W, R> PeriodicFiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    /// Constructs a new `PeriodicFiniteMarkovChain<T, W, R>` at phase zero.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if:
+    /// - There are no transition matrices.
+    /// - The `state_space` vector has repeated elements
+    ///   (defined by PartialEq).
+    /// - The dimensions of `state_space` and any of `transition_matrices` do not match.
+    /// - Any of `transition_matrices` is not a valid transition matrix for [FiniteMarkovChain::new].
+    ///
+    /// [FiniteMarkovChain::new]: struct.FiniteMarkovChain.html#method.new
+    #[inline]
+    pub fn new(
+        state_index: usize,
+        transition_matrices: Vec<Vec<Vec<W>>>,
+        state_space: Vec<T>,
+        rng: R,
+    ) -> Self {
+        assert!(!transition_matrices.is_empty(), "There must be at least one transition matrix.");
+        let state_space_len_true: usize = state_space.iter()
+            .map(|x| state_space.iter().filter(|&y| x == y).count())
+            .sum();
+        assert_eq!(state_space_len_true, state_space.len());
+        for transition_matrix in &transition_matrices {
+            assert_eq!(transition_matrix.len(), state_space.len());
+        }
+        let transition_matrices_variables = transition_matrices.iter()
+            .map(|transition_matrix| {
+                transition_matrix.iter()
+                    .map(|v| WeightedAliasIndex::new(v.clone()).unwrap())
+                    .collect()
+            })
+            .collect();
+
+        PeriodicFiniteMarkovChain {
+            state_index,
+            initial_index: state_index,
+            phase: 0,
+            transition_matrices,
+            transition_matrices_variables,
+            state_space,
+            rng,
+        }
+    }
+
+    /// Constructs a new `PeriodicFiniteMarkovChain<T, W, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(
+        state_index: usize,
+        transition_matrices: Vec<Vec<Vec<W>>>,
+        state_space: Vec<T>,
+        seed: u64,
+    ) -> Self
+    where
+        R: SeedableRng,
+    {
+        PeriodicFiniteMarkovChain::new(state_index, transition_matrices, state_space, R::seed_from_u64(seed))
+    }
+
+    /// Returns the number of transition matrices the chain cycles through.
+    #[inline]
+    pub fn period(&self) -> usize {
+        self.transition_matrices.len()
+    }
+
+    /// Returns the index of the transition matrix used in the next step.
+    #[inline]
+    pub fn phase(&self) -> usize {
+        self.phase
+    }
+
+    /// Sets the index of the transition matrix used in the next step.
+    ///
+    /// # Panics
+    ///
+    /// If `phase` is not less than the period.
+    #[inline]
+    pub fn set_phase(&mut self, phase: usize) {
+        assert!(phase < self.period(), "The phase must be less than the period.");
+        self.phase = phase;
+    }
+
+    #[inline]
+    pub fn state_space(&self) -> &Vec<T> {
+        &self.state_space
+    }
+
+    #[inline]
+    pub fn nstates(&self) -> usize {
+        self.state_space.len()
+    }
+
+    /// Returns the transition matrix of index `phase`, normalized so that its rows sum up to one.
+    ///
+    /// # Panics
+    ///
+    /// If `phase` is not less than the period.
+    #[inline]
+    pub fn transition_probabilities(&self, phase: usize) -> Array2<f64>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let n = self.nstates();
+        let transition_matrix = &self.transition_matrices[phase];
+        let matrix = Array2::from_shape_fn((n, n), |(i, j)| {
+            transition_matrix[i].get(j).map_or(0.0, |w| w.to_f64().unwrap())
+        });
+        crate::linalg::normalize_rows(matrix)
+    }
+
+    /// Returns the transition matrix of a whole cycle, starting at phase `phase`, 
+    /// that is, the product of the transition matrices of the following `k` steps.
+    ///
+    /// # Panics
+    ///
+    /// If `phase` is not less than the period.
+    #[inline]
+    pub fn cycle_transition_probabilities(&self, phase: usize) -> Array2<f64>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        assert!(phase < self.period(), "The phase must be less than the period.");
+        let k = self.period();
+        (1..k).fold(self.transition_probabilities(phase), |product, step| {
+            product.dot(&self.transition_probabilities((phase + step) % k))
+        })
+    }
+
+    /// Returns the periodically stationary distributions of the chain: 
+    /// the element of index `j` is the distribution of the chain at phase `j`, in the long run.
+    ///
+    /// Returns `None` if the chain observed once per cycle does not have a unique stationary distribution.
+    ///
+    /// # Examples
+    ///
+    /// A chain that alternates deterministically is stationary over cycles.
+    /// ```
+    /// # use markovian::PeriodicFiniteMarkovChain;
+    /// let flip = vec![vec![0., 1.], vec![1., 0.]];
+    /// let mix = vec![vec![0.5, 0.5], vec![0.5, 0.5]];
+    /// let mc = PeriodicFiniteMarkovChain::new(0, vec![mix, flip], vec![0, 1], rand::thread_rng());
+    /// let pis = mc.stationary_distributions().unwrap();
+    /// assert!((pis[1][0] - 0.5).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn stationary_distributions(&self) -> Option<Vec<Array1<f64>>>
+    where
+        W: num_traits::ToPrimitive,
+    {
+        let mut pi = crate::linalg::stationary_distribution(&self.cycle_transition_probabilities(0))?;
+        let mut distributions = Vec::with_capacity(self.period());
+        for phase in 0..self.period() {
+            let next = pi.dot(&self.transition_probabilities(phase));
+            distributions.push(pi);
+            pi = next;
+        }
+        Some(distributions)
+    }
+}
+
+impl<T, W, R> Reset for PeriodicFiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    /// Restarts the chain from its initial state, at phase zero.
+    #[inline]
+    fn reset(&mut self) {
+        self.state_index = self.initial_index;
+        self.phase = 0;
+    }
+}
+
+impl<T, W, R> RandomSource for PeriodicFiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<T, W, R> State for PeriodicFiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Item = T;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state_space[self.state_index])
+    }
+
+    #[inline]
+    fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        Some(&mut self.state_space[self.state_index])
+    }
+
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        match self.state_space.iter().position(|s| *s == new_state) {
+            Some(mut state_index) => {
+                mem::swap(&mut self.state_index, &mut state_index);
+                Ok(Some(self.state_space[state_index].clone()))
+            }
+            None => Err(InvalidState::new(new_state)),
+        }
+    }
+}
+
+impl<T, W, R> Checkpoint for PeriodicFiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    /// The hidden progress is the phase.
+    type Hidden = usize;
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {
+        self.phase
+    }
+
+    /// # Panics
+    ///
+    /// If `phase` is not less than the period.
+    #[inline]
+    fn set_hidden_state(&mut self, phase: Self::Hidden) {
+        self.set_phase(phase);
+    }
+}
+
+impl<T, W, R> Iterator for PeriodicFiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.state_index = self.transition_matrices_variables[self.phase][self.state_index].sample(&mut self.rng);
+        self.phase = (self.phase + 1) % self.period();
+        self.state().cloned()
+    }
+}
+
+impl<T, W, R> StochasticProcess for PeriodicFiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type Time = ();
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<T, W, R> StateIterator for PeriodicFiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.state().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn cycle() {
+        let stay = vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 1]];
+        let up = vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]];
+        let mut mc = PeriodicFiniteMarkovChain::new(0, vec![up, stay], vec!['a', 'b', 'c'], crate::tests::rng(1));
+        let trajectory: Vec<char> = mc.by_ref().take(5).collect();
+        assert_eq!(trajectory, vec!['b', 'b', 'c', 'c', 'a']);
+        assert_eq!(mc.phase(), 1);
+        mc.reset();
+        assert_eq!((mc.state(), mc.phase()), (Some(&'a'), 0));
+    }
+
+    #[test]
+    fn stationary_distributions() {
+        let first = vec![vec![0.9, 0.1], vec![0.5, 0.5]];
+        let second = vec![vec![0.2, 0.8], vec![0.3, 0.7]];
+        let third = vec![vec![0.6, 0.4], vec![0.1, 0.9]];
+        let mc = PeriodicFiniteMarkovChain::new(0, vec![first, second, third], vec![0, 1], crate::tests::rng(2));
+        let pis = mc.stationary_distributions().unwrap();
+        assert_eq!(pis.len(), 3);
+        for phase in 0..3 {
+            let next = pis[phase].dot(&mc.transition_probabilities(phase));
+            for i in 0..2 {
+                assert_abs_diff_eq!(next[i], pis[(phase + 1) % 3][i], epsilon = 1e-12);
+            }
+            assert_abs_diff_eq!(pis[phase].sum(), 1.0, epsilon = 1e-12);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+// Structs
+use crate::errors::MatrixError;
+use ndarray::{Array2, Axis};
+
+/// Rule to turn transition counts into transition probabilities, 
+/// see [FiniteMarkovChain::from_counts].
+///
+/// [FiniteMarkovChain::from_counts]: struct.FiniteMarkovChain.html#method.from_counts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Smoothing {
+    /// Empirical frequencies. States without observed transitions stay in place.
+    None,
+    /// Adds a pseudo-count to every transition before normalizing, 
+    /// e.g. `Additive(1.0)` is Laplace smoothing.
+    Additive(f64),
+    /// Empirical frequencies. States without observed transitions move according to 
+    /// the overall frequencies of the states reached, or uniformly if there are no counts at all.
+    Backoff,
+}
+
+impl Smoothing {
+    /// Returns the smoothed transition probabilities from `counts`.
+    ///
+    /// # Errors
+    ///
+    /// If `counts` is not square, or the pseudo-count is negative or not finite.
+    #[inline]
+    pub(crate) fn probabilities(self, counts: &Array2<u64>) -> Result<Array2<f64>, MatrixError> {
+        if !counts.is_square() {
+            return Err(MatrixError::NotSquare);
+        }
+        let n = counts.nrows();
+        let mut matrix = counts.mapv(|count| count as f64);
+        match self {
+            Smoothing::None => {
+                for i in 0..n {
+                    if matrix.row(i).sum() == 0.0 {
+                        matrix[[i, i]] = 1.0;
+                    }
+                }
+            }
+            Smoothing::Additive(pseudo_count) => {
+                if !pseudo_count.is_finite() || pseudo_count < 0.0 {
+                    return Err(MatrixError::InvalidEntry(pseudo_count));
+                }
+                matrix += pseudo_count;
+                for i in 0..n {
+                    if matrix.row(i).sum() == 0.0 {
+                        matrix[[i, i]] = 1.0;
+                    }
+                }
+            }
+            Smoothing::Backoff => {
+                let mut marginal = matrix.sum_axis(Axis(0));
+                if marginal.sum() == 0.0 {
+                    marginal.fill(1.0);
+                }
+                for i in 0..n {
+                    if matrix.row(i).sum() == 0.0 {
+                        matrix.row_mut(i).assign(&marginal);
+                    }
+                }
+            }
+        }
+        Ok(crate::linalg::normalize_rows(matrix))
+    }
+}
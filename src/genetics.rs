@@ -0,0 +1,658 @@
+//! Population genetics in a population of fixed size with two types: mutant and wild type.
+//!
+//! The state of [MoranProcess] and [WrightFisher] is the number of mutants. Mutants have
+//! relative fitness `fitness` with respect to the wild type, which has fitness one.
+//! Optionally, offsprings mutate: those of a wild-type parent become mutants with probability `to_mutant`
+//! and those of a mutant parent become wild type with probability `to_wild_type`.
+//!
+//! The processes sample transitions on the fly, so simulating large populations does not
+//! need a transition matrix. Their fixation probabilities are computed exactly.
+//!
+//! # Examples
+//!
+//! A single advantageous mutant fixes with probability close to `1 - 1 / fitness`.
+//! ```
+//! # use markovian::genetics::MoranProcess;
+//! let moran = MoranProcess::new(1_000, 1, 1.5, rand::thread_rng());
+//! assert!((moran.fixation_probability() - 1. / 3.).abs() < 1e-6);
+//! ```
+//!
+//! [MoranProcess]: struct.MoranProcess.html
+//! [WrightFisher]: struct.WrightFisher.html
+
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
+use rand::{Rng, SeedableRng};
+use rand_distr::{Binomial, Distribution};
+
+// Structs
+use crate::errors::InvalidState;
+use ndarray::{Array1, Array2};
+
+// Functions
+use core::mem;
+
+/// Moran process: at each step, an individual is chosen to reproduce, with probability
+/// proportional to its fitness, and its offspring replaces an individual chosen uniformly at random.
+///
+/// # Examples
+///
+/// Steps until the mutants fix or go extinct.
+/// ```
+/// # use markovian::genetics::MoranProcess;
+/// let moran = MoranProcess::new(100, 50, 1.1, rand::thread_rng());
+/// let steps = moran.take_while(|&mutants| 0 < mutants && mutants < 100).count();
+/// println!("Fixation or extinction took {} steps", steps + 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MoranProcess<R> {
+    state: u64,
+    initial_state: u64,
+    population: u64,
+    fitness: f64,
+    to_mutant: f64,
+    to_wild_type: f64,
+    rng: R,
+}
+
+impl<R> MoranProcess<R>
+where
+    R: Rng,
+{
+    /// Constructs a new `MoranProcess<R>` in a population of size `population` with `mutants` mutants,
+    /// without mutation.
+    ///
+    /// # Panics
+    ///
+    /// If `population` is zero, `mutants` is greater than `population`, or `fitness` is not positive.
+    #[inline]
+    pub fn new(population: u64, mutants: u64, fitness: f64, rng: R) -> Self {
+        check_parameters(population, mutants, fitness);
+        MoranProcess {
+            state: mutants,
+            initial_state: mutants,
+            population,
+            fitness,
+            to_mutant: 0.,
+            to_wild_type: 0.,
+            rng,
+        }
+    }
+
+    /// Constructs a new `MoranProcess<R>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [new].
+    ///
+    /// [new]: struct.MoranProcess.html#method.new
+    #[inline]
+    pub fn with_seed(population: u64, mutants: u64, fitness: f64, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        MoranProcess::new(population, mutants, fitness, R::seed_from_u64(seed))
+    }
+
+    /// Sets the probabilities that an offspring mutates to the other type.
+    ///
+    /// # Panics
+    ///
+    /// If some probability is not in `[0, 1)`.
+    #[inline]
+    pub fn with_mutation(mut self, to_mutant: f64, to_wild_type: f64) -> Self {
+        check_mutation(to_mutant, to_wild_type);
+        self.to_mutant = to_mutant;
+        self.to_wild_type = to_wild_type;
+        self
+    }
+
+    /// Returns the size of the population.
+    #[inline]
+    pub fn population(&self) -> u64 {
+        self.population
+    }
+
+    /// Returns the relative fitness of mutants.
+    #[inline]
+    pub fn fitness(&self) -> f64 {
+        self.fitness
+    }
+
+    /// Returns the transition matrix over the number of mutants `0..=population`.
+    ///
+    /// # Remarks
+    ///
+    /// The matrix has `(population + 1)^2` entries, so it is meant for small populations.
+    #[inline]
+    pub fn transition_probabilities(&self) -> Array2<f64> {
+        let n = self.population as usize;
+        let mut p = Array2::<f64>::zeros((n + 1, n + 1));
+        for i in 0..=n {
+            let (up, down) = self.jump_probabilities(i as u64);
+            if i < n {
+                p[[i, i + 1]] = up;
+            }
+            if i > 0 {
+                p[[i, i - 1]] = down;
+            }
+            p[[i, i]] = 1. - up - down;
+        }
+        p
+    }
+
+    /// Returns, for each number of mutants in `0..=population`, the probability
+    /// that mutants fix, i.e. reach the whole population, before going extinct.
+    ///
+    /// # Remarks
+    ///
+    /// The Moran process is a birth-death chain, so the probabilities are computed
+    /// in closed form in O(population), without building the transition matrix.
+    ///
+    /// # Examples
+    ///
+    /// Neutral mutants fix with probability equal to their initial frequency.
+    /// ```
+    /// # use markovian::genetics::MoranProcess;
+    /// let moran = MoranProcess::new(4, 1, 1., rand::thread_rng());
+    /// let probabilities = moran.fixation_probabilities();
+    /// assert!((probabilities[1] - 0.25).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn fixation_probabilities(&self) -> Array1<f64> {
+        // h(i) is proportional to the sum of k < i of the product over 0 < j <= k of down(j) / up(j)
+        let n = self.population as usize;
+        let mut log_terms = Vec::with_capacity(n);
+        let mut log_term = 0.;
+        log_terms.push(log_term);
+        for j in 1..n {
+            let (up, down) = self.jump_probabilities(j as u64);
+            log_term += down.ln() - up.ln();
+            log_terms.push(log_term);
+        }
+        let max = log_terms.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mut probabilities = Array1::<f64>::zeros(n + 1);
+        for (k, log_term) in log_terms.iter().enumerate() {
+            probabilities[k + 1] = probabilities[k] + (log_term - max).exp();
+        }
+        let total = probabilities[n];
+        probabilities / total
+    }
+
+    /// Returns the probability that mutants fix from the current state.
+    ///
+    /// See [fixation_probabilities] for details.
+    ///
+    /// [fixation_probabilities]: struct.MoranProcess.html#method.fixation_probabilities
+    #[inline]
+    pub fn fixation_probability(&self) -> f64 {
+        self.fixation_probabilities()[self.state as usize]
+    }
+
+    /// Returns the probabilities of gaining and losing a mutant in one step.
+    #[inline]
+    fn jump_probabilities(&self, mutants: u64) -> (f64, f64) {
+        let q = mutant_offspring_probability(mutants, self.population, self.fitness, self.to_mutant, self.to_wild_type);
+        let frequency = mutants as f64 / self.population as f64;
+        (q * (1. - frequency), (1. - q) * frequency)
+    }
+}
+
+impl<R> Reset for MoranProcess<R> {
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state;
+    }
+}
+
+impl<R> RandomSource for MoranProcess<R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<R> State for MoranProcess<R> {
+    type Item = u64;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    /// Changes the number of mutants, as long as it does not exceed the population.
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        if new_state <= self.population {
+            Ok(Some(mem::replace(&mut self.state, new_state)))
+        } else {
+            Err(InvalidState::new(new_state))
+        }
+    }
+}
+
+impl<R> Checkpoint for MoranProcess<R>
+where
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<R> Iterator for MoranProcess<R>
+where
+    R: Rng,
+{
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (up, down) = self.jump_probabilities(self.state);
+        let u: f64 = self.rng.gen();
+        if u < up {
+            self.state += 1;
+        } else if u < up + down {
+            self.state -= 1;
+        }
+        Some(self.state)
+    }
+}
+
+impl<R> StochasticProcess for MoranProcess<R>
+where
+    R: Rng,
+{
+    type Time = ();
+    type State = u64;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<R> StateIterator for MoranProcess<R>
+where
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        Some(self.state)
+    }
+}
+
+/// Wright-Fisher process: at each generation, the whole population is replaced by offsprings,
+/// whose parents are chosen independently with probability proportional to their fitness.
+///
+/// # Examples
+///
+/// Genetic drift over ten generations.
+/// ```
+/// # use markovian::genetics::WrightFisher;
+/// let wright_fisher = WrightFisher::new(1_000_000, 500_000, 1., rand::thread_rng());
+/// for mutants in wright_fisher.take(10) {
+///     println!("{} mutants", mutants);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WrightFisher<R> {
+    state: u64,
+    initial_state: u64,
+    population: u64,
+    fitness: f64,
+    to_mutant: f64,
+    to_wild_type: f64,
+    rng: R,
+}
+
+impl<R> WrightFisher<R>
+where
+    R: Rng,
+{
+    /// Constructs a new `WrightFisher<R>` in a population of size `population` with `mutants` mutants,
+    /// without mutation.
+    ///
+    /// # Panics
+    ///
+    /// If `population` is zero, `mutants` is greater than `population`, or `fitness` is not positive.
+    #[inline]
+    pub fn new(population: u64, mutants: u64, fitness: f64, rng: R) -> Self {
+        check_parameters(population, mutants, fitness);
+        WrightFisher {
+            state: mutants,
+            initial_state: mutants,
+            population,
+            fitness,
+            to_mutant: 0.,
+            to_wild_type: 0.,
+            rng,
+        }
+    }
+
+    /// Constructs a new `WrightFisher<R>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [new].
+    ///
+    /// [new]: struct.WrightFisher.html#method.new
+    #[inline]
+    pub fn with_seed(population: u64, mutants: u64, fitness: f64, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        WrightFisher::new(population, mutants, fitness, R::seed_from_u64(seed))
+    }
+
+    /// Sets the probabilities that an offspring mutates to the other type.
+    ///
+    /// # Panics
+    ///
+    /// If some probability is not in `[0, 1)`.
+    #[inline]
+    pub fn with_mutation(mut self, to_mutant: f64, to_wild_type: f64) -> Self {
+        check_mutation(to_mutant, to_wild_type);
+        self.to_mutant = to_mutant;
+        self.to_wild_type = to_wild_type;
+        self
+    }
+
+    /// Returns the size of the population.
+    #[inline]
+    pub fn population(&self) -> u64 {
+        self.population
+    }
+
+    /// Returns the relative fitness of mutants.
+    #[inline]
+    pub fn fitness(&self) -> f64 {
+        self.fitness
+    }
+
+    /// Returns the transition matrix over the number of mutants `0..=population`.
+    ///
+    /// # Remarks
+    ///
+    /// The matrix has `(population + 1)^2` entries, so it is meant for small populations.
+    #[inline]
+    pub fn transition_probabilities(&self) -> Array2<f64> {
+        let n = self.population as usize;
+        let mut p = Array2::<f64>::zeros((n + 1, n + 1));
+        for i in 0..=n {
+            let q = mutant_offspring_probability(i as u64, self.population, self.fitness, self.to_mutant, self.to_wild_type);
+            for (j, probability) in binomial_probabilities(self.population, q).into_iter().enumerate() {
+                p[[i, j]] = probability;
+            }
+        }
+        p
+    }
+
+    /// Returns, for each number of mutants in `0..=population`, the probability
+    /// that mutants fix, i.e. reach the whole population, before going extinct.
+    ///
+    /// # Remarks
+    ///
+    /// The probabilities are computed exactly from the [transition matrix], in O(population^3).
+    ///
+    /// # Examples
+    ///
+    /// Neutral mutants fix with probability equal to their initial frequency.
+    /// ```
+    /// # use markovian::genetics::WrightFisher;
+    /// let wright_fisher = WrightFisher::new(4, 1, 1., rand::thread_rng());
+    /// let probabilities = wright_fisher.fixation_probabilities();
+    /// assert!((probabilities[1] - 0.25).abs() < 1e-12);
+    /// ```
+    ///
+    /// [transition matrix]: struct.WrightFisher.html#method.transition_probabilities
+    #[inline]
+    pub fn fixation_probabilities(&self) -> Array1<f64> {
+        let n = self.population as usize;
+        let target: Vec<bool> = (0..=n).map(|i| i == n).collect();
+        let avoid: Vec<bool> = (0..=n).map(|i| i == 0).collect();
+        crate::linalg::hitting_probabilities(&self.transition_probabilities(), &target, &avoid)
+    }
+
+    /// Returns the probability that mutants fix from the current state.
+    ///
+    /// See [fixation_probabilities] for details.
+    ///
+    /// [fixation_probabilities]: struct.WrightFisher.html#method.fixation_probabilities
+    #[inline]
+    pub fn fixation_probability(&self) -> f64 {
+        self.fixation_probabilities()[self.state as usize]
+    }
+}
+
+impl<R> Reset for WrightFisher<R> {
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state;
+    }
+}
+
+impl<R> RandomSource for WrightFisher<R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<R> State for WrightFisher<R> {
+    type Item = u64;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    /// Changes the number of mutants, as long as it does not exceed the population.
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        if new_state <= self.population {
+            Ok(Some(mem::replace(&mut self.state, new_state)))
+        } else {
+            Err(InvalidState::new(new_state))
+        }
+    }
+}
+
+impl<R> Checkpoint for WrightFisher<R>
+where
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<R> Iterator for WrightFisher<R>
+where
+    R: Rng,
+{
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let q = mutant_offspring_probability(self.state, self.population, self.fitness, self.to_mutant, self.to_wild_type);
+        self.state = Binomial::new(self.population, q)
+            .expect("The probability of a mutant offspring is in [0, 1].")
+            .sample(&mut self.rng);
+        Some(self.state)
+    }
+}
+
+impl<R> StochasticProcess for WrightFisher<R>
+where
+    R: Rng,
+{
+    type Time = ();
+    type State = u64;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<R> StateIterator for WrightFisher<R>
+where
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        Some(self.state)
+    }
+}
+
+#[inline]
+fn check_parameters(population: u64, mutants: u64, fitness: f64) {
+    assert!(population > 0, "The population must be non-empty.");
+    assert!(mutants <= population, "There can not be more mutants than individuals.");
+    assert!(fitness > 0., "The fitness must be positive.");
+}
+
+#[inline]
+fn check_mutation(to_mutant: f64, to_wild_type: f64) {
+    assert!(
+        (0. ..1.).contains(&to_mutant) && (0. ..1.).contains(&to_wild_type),
+        "Mutation probabilities must be in [0, 1)."
+    );
+}
+
+/// Returns the probability that an offspring is a mutant, after selection and mutation.
+#[inline]
+fn mutant_offspring_probability(mutants: u64, population: u64, fitness: f64, to_mutant: f64, to_wild_type: f64) -> f64 {
+    let weight = fitness * mutants as f64;
+    let parent = weight / (weight + (population - mutants) as f64);
+    parent * (1. - to_wild_type) + (1. - parent) * to_mutant
+}
+
+/// Returns the probabilities of the binomial distribution with `n` trials and success probability `q`.
+#[inline]
+fn binomial_probabilities(n: u64, q: f64) -> Vec<f64> {
+    if q <= 0. || q >= 1. {
+        let certain = if q <= 0. { 0 } else { n };
+        return (0..=n).map(|k| if k == certain { 1. } else { 0. }).collect();
+    }
+    let (log_q, log_not_q) = (q.ln(), (1. - q).ln());
+    let mut log_binomial = 0.;
+    (0..=n)
+        .map(|k| {
+            if k > 0 {
+                log_binomial += ((n - k + 1) as f64).ln() - (k as f64).ln();
+            }
+            (log_binomial + k as f64 * log_q + (n - k) as f64 * log_not_q).exp()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn moran_fixation() {
+        // Closed form without mutation: (1 - r^{-i}) / (1 - r^{-N})
+        let (n, r) = (20, 1.3);
+        let moran = MoranProcess::new(n, 1, r, crate::tests::rng(1));
+        let probabilities = moran.fixation_probabilities();
+        for i in 0..=n {
+            let expected = (1. - r.powi(-(i as i32))) / (1. - r.powi(-(n as i32)));
+            assert!((probabilities[i as usize] - expected).abs() < 1e-12);
+        }
+
+        // Large populations do not overflow
+        let moran = MoranProcess::new(100_000, 1, 0.5, crate::tests::rng(2));
+        assert_eq!(moran.fixation_probability(), 0.);
+        let moran = MoranProcess::new(100_000, 1, 2., crate::tests::rng(3));
+        assert!((moran.fixation_probability() - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn moran_steps() {
+        let moran = MoranProcess::new(10, 5, 1., crate::tests::rng(4)).with_mutation(0.1, 0.1);
+        let mut previous = 5_i64;
+        for mutants in moran.take(1_000) {
+            assert!((mutants as i64 - previous).abs() <= 1);
+            assert!(mutants <= 10);
+            previous = mutants as i64;
+        }
+    }
+
+    #[test]
+    fn wright_fisher_fixation() {
+        let wright_fisher = WrightFisher::new(10, 1, 1., crate::tests::rng(5));
+        let probabilities = wright_fisher.fixation_probabilities();
+        for i in 0..=10 {
+            assert!((probabilities[i] - i as f64 / 10.).abs() < 1e-10);
+        }
+
+        // Selection favours fixation, which matches simulations
+        let mut wright_fisher = WrightFisher::new(10, 2, 1.5, crate::tests::rng(6));
+        let expected = wright_fisher.fixation_probability();
+        assert!(expected > 0.2);
+        let samples = 4_000;
+        let fixed = (0..samples)
+            .filter(|_| {
+                wright_fisher.reset();
+                wright_fisher.by_ref().find(|&mutants| mutants == 0 || mutants == 10) == Some(10)
+            })
+            .count();
+        assert!((fixed as f64 / samples as f64 - expected).abs() < 0.03);
+    }
+
+    #[test]
+    fn binomial() {
+        let probabilities = binomial_probabilities(3, 0.5);
+        assert_eq!(probabilities.len(), 4);
+        assert!((probabilities[1] - 0.375).abs() < 1e-12);
+        assert_eq!(binomial_probabilities(2, 1.), vec![0., 0., 1.]);
+    }
+}
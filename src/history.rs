@@ -0,0 +1,298 @@
+//! Persistent histories, for non markovian processes.
+//!
+//! A [History] is an immutable list of past states where the path prefixes are shared,
+//! so cloning is O(1) and pushing a new state does not copy the previous ones.
+//! This makes `History<T>` a cheap state for [MarkovChain], 
+//! whose transitions clone the states they produce.
+//!
+//! [Record] turns a transition that only samples the next element into one that extends the history.
+//!
+//! # Examples
+//!
+//! A random walk on the integers that is attracted to zero in a non markovian fashion.
+//! ```
+//! # use markovian::{MarkovChain, history::{History, Record}, prelude::*};
+//! let transition = |history: &History<i32>| {
+//!     let last = *history.last().unwrap();
+//!     let sum: i32 = history.iter().sum();
+//!     let p = 1.0 / (sum.abs() + 1) as f64;
+//!     if sum.is_positive() {
+//!         raw_dist![(p, last + 1), (1.0 - p, last - 1)]
+//!     } else {
+//!         raw_dist![(1.0 - p, last + 1), (p, last - 1)]
+//!     }
+//! };
+//! let init_state: History<i32> = vec![0].into_iter().collect();
+//! let mut mc = MarkovChain::new(init_state, Record::new(transition), rand::thread_rng());
+//! let history = mc.nth(99).unwrap();
+//! assert_eq!(history.len(), 101);
+//! ```
+//!
+//! [History]: struct.History.html
+//! [Record]: struct.Record.html
+//! [MarkovChain]: ../struct.MarkovChain.html
+
+// Traits
+use crate::Transition;
+use core::fmt::Debug;
+use core::iter::FromIterator;
+use rand::Rng;
+
+// Structs
+use std::rc::Rc;
+
+#[derive(Debug)]
+struct Node<T> {
+    value: T,
+    previous: Option<Rc<Node<T>>>,
+}
+
+/// Persistent list of past states, sharing structure between path prefixes.
+///
+/// Cloning and pushing are O(1).
+///
+/// # Examples
+///
+/// Branching from a common past.
+/// ```
+/// # use markovian::history::History;
+/// let mut common = History::new();
+/// common.push(1);
+/// let mut left = common.clone();
+/// left.push(2);
+/// assert_eq!(common.to_vec(), vec![1]);
+/// assert_eq!(left.to_vec(), vec![1, 2]);
+/// ```
+pub struct History<T> {
+    head: Option<Rc<Node<T>>>,
+    len: usize,
+}
+
+impl<T> History<T> {
+    /// Constructs an empty history.
+    #[inline]
+    pub fn new() -> Self {
+        History { head: None, len: 0 }
+    }
+
+    /// Appends `value` as the most recent element.
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        let previous = self.head.take();
+        self.head = Some(Rc::new(Node { value, previous }));
+        self.len += 1;
+    }
+
+    /// Returns a new history with `value` as its most recent element, sharing the past with `self`.
+    #[inline]
+    pub fn pushed(&self, value: T) -> Self {
+        let mut history = self.clone();
+        history.push(value);
+        history
+    }
+
+    /// Returns the most recent element.
+    #[inline]
+    pub fn last(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    /// Returns the history without its most recent element.
+    #[inline]
+    pub fn previous(&self) -> Self {
+        match &self.head {
+            Some(node) => History {
+                head: node.previous.clone(),
+                len: self.len - 1,
+            },
+            None => History::new(),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns an iterator over the path, from the most recent element to the oldest one.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+            len: self.len,
+        }
+    }
+
+    /// Returns the path in chronological order.
+    #[inline]
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut path: Vec<T> = self.iter().cloned().collect();
+        path.reverse();
+        path
+    }
+}
+
+impl<T> Clone for History<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        History {
+            head: self.head.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Default for History<T> {
+    #[inline]
+    fn default() -> Self {
+        History::new()
+    }
+}
+
+impl<T: Debug> Debug for History<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut path: Vec<&T> = self.iter().collect();
+        path.reverse();
+        f.debug_list().entries(path).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for History<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for History<T> {}
+
+impl<T> Drop for History<T> {
+    /// Drops the unshared nodes one by one, so that long histories do not overflow the stack.
+    fn drop(&mut self) {
+        let mut next = self.head.take();
+        while let Some(node) = next {
+            match Rc::try_unwrap(node) {
+                Ok(mut node) => next = node.previous.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl<T> FromIterator<T> for History<T> {
+    /// Collects the elements in chronological order.
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut history = History::new();
+        for value in iter {
+            history.push(value);
+        }
+        history
+    }
+}
+
+impl<'a, T> IntoIterator for &'a History<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over a [History], from the most recent element to the oldest one.
+///
+/// [History]: struct.History.html
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        self.next = node.previous.as_deref();
+        self.len -= 1;
+        Some(&node.value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// Transition that extends a [History] with the next element sampled by `transition`.
+///
+/// [History]: struct.History.html
+#[derive(Debug, Clone, Copy)]
+pub struct Record<F> {
+    transition: F,
+}
+
+impl<F> Record<F> {
+    #[inline]
+    pub fn new(transition: F) -> Self {
+        Record { transition }
+    }
+}
+
+impl<T, F> Transition<History<T>, History<T>> for Record<F>
+where
+    F: Transition<History<T>, T>,
+{
+    #[inline]
+    fn sample_from<R>(&self, state: &History<T>, rng: &mut R) -> History<T>
+    where
+        R: Rng + ?Sized,
+    {
+        state.pushed(self.transition.sample_from(state, rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sharing() {
+        let mut history: History<u32> = (0..3).collect();
+        let branch = history.pushed(10);
+        history.push(3);
+        assert_eq!(history.to_vec(), vec![0, 1, 2, 3]);
+        assert_eq!(branch.to_vec(), vec![0, 1, 2, 10]);
+        assert_eq!(history.previous(), branch.previous());
+        assert_eq!(branch.iter().copied().collect::<Vec<u32>>(), vec![10, 2, 1, 0]);
+        assert_eq!(format!("{:?}", branch), "[0, 1, 2, 10]");
+
+        let long: History<u32> = (0..1_000_000).collect();
+        let prefix = long.previous();
+        drop(long);
+        assert_eq!(prefix.len(), 999_999);
+    }
+
+    #[test]
+    fn record() {
+        let transition = |history: &History<u32>| raw_dist![(1.0, history.len() as u32)];
+        let mc = crate::MarkovChain::new(History::new(), Record::new(transition), crate::tests::rng(1));
+        let history = mc.take(5).last().unwrap();
+        assert_eq!(history.to_vec(), vec![0, 1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,257 @@
+// Traits
+use crate::traits::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
+use core::fmt::Debug;
+use rand::{Rng, SeedableRng};
+use rand_distr::Distribution;
+
+// Structs
+use crate::errors::InvalidState;
+
+// Functions
+use core::mem;
+
+/// Time-inhomogeneous Markov Chain in discrete time, with arbitrary space.
+///
+/// The transition depends on the number of steps taken so far, 
+/// which is kept by the chain: the step from time `n` to time `n + 1` 
+/// samples from `transition(n, &state)`.
+///
+/// # Examples
+///
+/// A random walk whose steps get smaller with time.
+/// ```
+/// # use markovian::{InhomogeneousMarkovChain, prelude::*};
+/// let transition = |time: usize, state: &f64| {
+///     let step = 1.0 / (time + 1) as f64;
+///     raw_dist![(0.5, state + step), (0.5, state - step)]
+/// };
+/// let mut mc = InhomogeneousMarkovChain::new(0.0, transition, rand::thread_rng());
+/// assert_eq!(mc.next().unwrap().abs(), 1.0);
+/// assert_eq!(mc.time(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct InhomogeneousMarkovChain<T, F, R> {
+    state: T,
+    initial_state: T,
+    time: usize,
+    transition: F,
+    rng: R,
+}
+
+impl<T, F, D, R> InhomogeneousMarkovChain<T, F, R>
+where
+    T: Clone,
+    F: Fn(usize, &T) -> D,
+    D: Distribution<T>,
+    R: Rng,
+{
+    /// Constructs a new chain at time zero.
+    #[inline]
+    pub fn new(state: T, transition: F, rng: R) -> Self {
+        InhomogeneousMarkovChain {
+            initial_state: state.clone(),
+            state,
+            time: 0,
+            transition,
+            rng,
+        }
+    }
+
+    /// Constructs a new `InhomogeneousMarkovChain<T, F, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(state: T, transition: F, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        InhomogeneousMarkovChain::new(state, transition, R::seed_from_u64(seed))
+    }
+}
+
+impl<T, F, R> InhomogeneousMarkovChain<T, F, R> {
+    /// Returns the number of steps taken since the start, or the last reset.
+    #[inline]
+    pub fn time(&self) -> usize {
+        self.time
+    }
+
+    /// Sets the number of steps taken, so that the next step uses the transition at time `time`.
+    #[inline]
+    pub fn set_time(&mut self, time: usize) {
+        self.time = time;
+    }
+}
+
+impl<T, F, R> Reset for InhomogeneousMarkovChain<T, F, R>
+where
+    T: Clone,
+{
+    /// Restarts the chain from its initial state, at time zero.
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+        self.time = 0;
+    }
+}
+
+impl<T, F, R> RandomSource for InhomogeneousMarkovChain<T, F, R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<T, F, R> State for InhomogeneousMarkovChain<T, F, R>
+where
+    T: Debug + Clone,
+{
+    type Item = T;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    #[inline]
+    fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        Some(&mut self.state)
+    }
+
+    /// Changes the state, keeping the current time.
+    #[inline]
+    fn set_state(
+        &mut self,
+        mut new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        mem::swap(&mut self.state, &mut new_state);
+        Ok(Some(new_state))
+    }
+}
+
+impl<T, F, R> Checkpoint for InhomogeneousMarkovChain<T, F, R>
+where
+    T: Debug + Clone,
+    R: Rng,
+{
+    /// The hidden progress is the time, that is, the number of steps taken.
+    type Hidden = usize;
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {
+        self.time
+    }
+
+    #[inline]
+    fn set_hidden_state(&mut self, time: Self::Hidden) {
+        self.time = time;
+    }
+
+    #[inline]
+    fn progress(&self) -> (usize, f64) {
+        (self.time, 0.)
+    }
+}
+
+impl<T, F, D, R> Iterator for InhomogeneousMarkovChain<T, F, R>
+where
+    T: Debug + Clone,
+    F: Fn(usize, &T) -> D,
+    D: Distribution<T>,
+    R: Rng,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance();
+        self.state().cloned()
+    }
+}
+
+impl<T, F, D, R> StochasticProcess for InhomogeneousMarkovChain<T, F, R>
+where
+    T: Debug + Clone,
+    F: Fn(usize, &T) -> D,
+    D: Distribution<T>,
+    R: Rng,
+{
+    type Time = ();
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<T, F, D, R> StateIterator for InhomogeneousMarkovChain<T, F, R>
+where
+    T: Debug + Clone,
+    F: Fn(usize, &T) -> D,
+    D: Distribution<T>,
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.state().cloned()
+    }
+
+    #[inline]
+    fn advance(&mut self) -> bool {
+        self.state = (self.transition)(self.time, &self.state).sample(&mut self.rng);
+        self.time += 1;
+        true
+    }
+}
+
+impl<T, F, D, R> Distribution<T> for InhomogeneousMarkovChain<T, F, R>
+where
+    T: Debug + Clone,
+    F: Fn(usize, &T) -> D,
+    D: Distribution<T>,
+    R: Rng,
+{
+    /// Sample a possible next state, at the current time.
+    #[inline]
+    fn sample<R2>(&self, rng: &mut R2) -> T
+    where
+        R2: Rng + ?Sized,
+    {
+        (self.transition)(self.time, &self.state).sample(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::Raw;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn time() {
+        let transition = |time: usize, state: &usize| Raw::new(vec![(1.0, state + time)]);
+        let mut mc = InhomogeneousMarkovChain::new(0, transition, crate::tests::rng(1));
+        let trajectory: Vec<usize> = mc.by_ref().take(5).collect();
+        assert_eq!(trajectory, vec![0, 1, 3, 6, 10]);
+        assert_eq!(mc.time(), 5);
+
+        mc.set_state(0).unwrap();
+        assert_eq!(mc.next(), Some(5));
+        mc.reset();
+        assert_eq!((mc.time(), mc.state()), (0, Some(&0)));
+    }
+}
@@ -170,21 +170,78 @@
 //! mc.next();
 //! assert_eq!(mc.state().unwrap().len(), 2);
 //! ```
+//!
+//! Long histories are better updated in place, see [MarkovChainInPlace], or shared, see [history].
+//!
+//! [MarkovChainInPlace]: struct.MarkovChainInPlace.html
+//! [history]: history/index.html
 //! 
 pub use self::continuous_finite_markov_chain::ContFiniteMarkovChain;
-pub use self::finite_markov_chain::FiniteMarkovChain;
+pub use self::dyn_process::DynProcess;
+pub use self::finite_markov_chain::{CoverTimes, FiniteMarkovChain, JumpChain, PeriodicFiniteMarkovChain, Smoothing, FAST_FORWARD_THRESHOLD};
+pub use self::inhomogeneous_markov_chain::InhomogeneousMarkovChain;
+pub use self::map_markov_chain::MapMarkovChain;
 pub use self::markov_chain::MarkovChain;
+pub use self::markov_chain_in_place::MarkovChainInPlace;
+pub use self::random_environment_chain::{EnvironmentMode, RandomEnvironmentChain};
+pub use self::regime_switching::RegimeSwitching;
 pub use self::timed_markov_chain::TimedMarkovChain;
-pub use self::traits::{State, StateIterator, Transition};
+pub use self::with_memory::WithMemory;
+pub use self::traits::{Checkpoint, DistributionOnce, Period, RandomIterator, RandomSource, Reset, Snapshot, State, StateIterator, StochasticProcess, TimedIterator, Transition, TransitionInPlace, TransitionOnce};
 
 /// Generating random trajectories from stochactic processes
 pub mod processes;
+/// Iterator adapters returned by the traits of this crate.
+pub mod adapters;
+pub mod absorption;
+pub mod automata;
+pub mod compare;
+pub mod density_dependent;
+pub mod epidemics;
+pub mod estimation;
+pub mod exclusion;
+pub mod experiments;
+pub mod export;
+pub mod filtering;
+pub mod genetics;
+pub mod history;
+pub mod matrix;
+pub mod metastability;
+pub mod mixing;
+pub mod model_checking;
+pub mod montecarlo;
+pub mod msm;
+pub mod pdmp;
+pub mod petri;
+pub mod qbd;
+pub mod queueing;
+pub mod regenerative;
+pub mod replay;
+pub mod runner;
+pub mod spatial;
+pub mod splitting;
+pub mod urns;
+/// Generating sequences from Markov models estimated from data.
+pub mod sequence;
+/// Statistics of trajectories, computed on the fly.
+pub mod stats;
+/// Visualization of processes, enabled by the `viz` feature.
+#[cfg(feature = "viz")]
+pub mod viz;
 mod continuous_finite_markov_chain;
+mod dyn_process;
 mod finite_markov_chain;
+mod inhomogeneous_markov_chain;
+mod map_markov_chain;
 mod markov_chain;
+mod markov_chain_in_place;
+mod random_environment_chain;
+mod regime_switching;
 mod timed_markov_chain;
+mod with_memory;
 mod traits;
 mod macros;
+mod linalg;
 
 /// Ease interoperability with rand_distr crate.
 pub mod distributions;
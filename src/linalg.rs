@@ -0,0 +1,510 @@
+//! Dense linear algebra over `f64`, used for exact computations on finite chains.
+
+use ndarray::{Array1, Array2};
+
+/// Returns `exp(q * time)` for a generator matrix `q`, i.e. a matrix with non-negative 
+/// off-diagonal entries and rows summing up to zero.
+///
+/// The exponential is computed by uniformization, together with scaling and squaring 
+/// so that the Poisson weights of the series never underflow. 
+/// The result has non-negative entries and rows summing up to one.
+pub(crate) fn expm_generator(q: &Array2<f64>, time: f64) -> Array2<f64> {
+    normalize_rows(expm_metzler(q, time))
+}
+
+/// Returns `exp(q * time)` for a matrix `q` with non-negative off-diagonal entries.
+///
+/// The exponential is computed by uniformization, as in [expm_generator], without normalizing rows.
+///
+/// [expm_generator]: fn.expm_generator.html
+pub(crate) fn expm_metzler(q: &Array2<f64>, time: f64) -> Array2<f64> {
+    let n = q.nrows();
+    if time == 0.0 || q.iter().all(|&x| x == 0.0) {
+        return Array2::eye(n);
+    }
+    let lambda = (0..n).map(|i| -q[[i, i]]).fold(0.0, f64::max);
+    // Any positive rate is valid if the diagonal is non-negative
+    let lambda = if lambda > 0.0 { lambda } else { 1.0 };
+    let mut squarings = 0;
+    let mut scaled_time = time;
+    while lambda * scaled_time > 1.0 {
+        scaled_time /= 2.0;
+        squarings += 1;
+    }
+    let jump = Array2::eye(n) + q / lambda;
+    let rate = lambda * scaled_time;
+
+    let mut weight = (-rate).exp();
+    let mut power: Array2<f64> = Array2::eye(n);
+    let mut result = &power * weight;
+    let mut k = 0;
+    while weight > f64::EPSILON * f64::EPSILON {
+        k += 1;
+        weight *= rate / k as f64;
+        power = power.dot(&jump);
+        result = result + &power * weight;
+    }
+    for _ in 0..squarings {
+        result = result.dot(&result);
+    }
+    result
+}
+
+/// Divides each row by its sum, so that rows sum up to one.
+pub(crate) fn normalize_rows(mut matrix: Array2<f64>) -> Array2<f64> {
+    for mut row in matrix.genrows_mut() {
+        let total: f64 = row.sum();
+        if total > 0.0 {
+            row /= total;
+        }
+    }
+    matrix
+}
+
+/// Solves the linear system `a x = b` by Gaussian elimination with partial pivoting.
+///
+/// Returns `None` if `a` is singular.
+pub(crate) fn solve(mut a: Array2<f64>, mut b: Array1<f64>) -> Option<Array1<f64>> {
+    let n = a.nrows();
+    for col in 0..n {
+        let pivot = (col..n).fold(col, |best, row| if a[[row, col]].abs() > a[[best, col]].abs() { row } else { best });
+        if a[[pivot, col]].abs() < 1e-300 {
+            return None;
+        }
+        if pivot != col {
+            for k in 0..n {
+                a.swap([pivot, k], [col, k]);
+            }
+            b.swap(pivot, col);
+        }
+        for row in (col + 1)..n {
+            let factor = a[[row, col]] / a[[col, col]];
+            if factor != 0.0 {
+                for k in col..n {
+                    a[[row, k]] -= factor * a[[col, k]];
+                }
+                b[row] -= factor * b[col];
+            }
+        }
+    }
+    let mut x = Array1::zeros(n);
+    for row in (0..n).rev() {
+        let rest: f64 = ((row + 1)..n).map(|k| a[[row, k]] * x[k]).sum();
+        x[row] = (b[row] - rest) / a[[row, row]];
+    }
+    Some(x)
+}
+
+/// Returns the unique stationary distribution of the stochastic matrix `p`,
+/// or `None` if it is not unique.
+pub(crate) fn stationary_distribution(p: &Array2<f64>) -> Option<Array1<f64>> {
+    // π (I - P + 1 1^T) = 1^T
+    let n = p.nrows();
+    let a = Array2::from_shape_fn((n, n), |(i, j)| {
+        let identity = if i == j { 1.0 } else { 0.0 };
+        identity - p[[j, i]] + 1.0
+    });
+    solve(a, Array1::ones(n))
+}
+
+/// Returns the principal logarithm of `a`, by inverse scaling and squaring: 
+/// square roots are taken, by the Denman-Beavers iteration, until the matrix is close 
+/// to the identity, where the series of `log(I + x)` converges fast.
+///
+/// Returns `None` if the iterations do not converge, which happens when
+/// `a` has eigenvalues on the closed negative real axis.
+pub(crate) fn logm(a: &Array2<f64>) -> Option<Array2<f64>> {
+    let n = a.nrows();
+    let identity: Array2<f64> = Array2::eye(n);
+    let norm = |x: &Array2<f64>| x.genrows().into_iter().map(|row| row.iter().map(|v| v.abs()).sum::<f64>()).fold(0.0, f64::max);
+    let mut root = a.clone();
+    let mut square_roots = 0;
+    while norm(&(&root - &identity)) > 0.25 {
+        if square_roots == 64 {
+            return None;
+        }
+        // Denman-Beavers
+        let mut y = root;
+        let mut z = identity.clone();
+        let mut converged = false;
+        for _ in 0..100 {
+            let y_inverse = inverse(y.clone())?;
+            let z_inverse = inverse(z.clone())?;
+            let next = (&y + &z_inverse) / 2.0;
+            z = (&z + &y_inverse) / 2.0;
+            let change = norm(&(&next - &y));
+            y = next;
+            if change <= 1e-12 * norm(&y) {
+                converged = true;
+                break;
+            }
+        }
+        if !converged || !y.iter().all(|v| v.is_finite()) {
+            return None;
+        }
+        root = y;
+        square_roots += 1;
+    }
+
+    // log(I + x) = x - x^2 / 2 + x^3 / 3 - ...
+    let x = &root - &identity;
+    let mut power = x.clone();
+    let mut result = x.clone();
+    let mut k = 1;
+    while norm(&power) > f64::EPSILON * 1e-3 && k < 200 {
+        k += 1;
+        power = power.dot(&x);
+        let sign = if k % 2 == 0 { -1.0 } else { 1.0 };
+        result = result + &power * (sign / k as f64);
+    }
+    Some(result * 2_f64.powi(square_roots))
+}
+
+/// Returns the inverse of `a` by Gauss-Jordan elimination with partial pivoting.
+///
+/// Returns `None` if `a` is singular.
+pub(crate) fn inverse(mut a: Array2<f64>) -> Option<Array2<f64>> {
+    let n = a.nrows();
+    let mut inverse = Array2::eye(n);
+    for col in 0..n {
+        let pivot = (col..n).fold(col, |best, row| if a[[row, col]].abs() > a[[best, col]].abs() { row } else { best });
+        if a[[pivot, col]].abs() < 1e-300 {
+            return None;
+        }
+        if pivot != col {
+            for k in 0..n {
+                a.swap([pivot, k], [col, k]);
+                inverse.swap([pivot, k], [col, k]);
+            }
+        }
+        let diagonal = a[[col, col]];
+        for k in 0..n {
+            a[[col, k]] /= diagonal;
+            inverse[[col, k]] /= diagonal;
+        }
+        for row in (0..n).filter(|&row| row != col) {
+            let factor = a[[row, col]];
+            if factor != 0.0 {
+                for k in 0..n {
+                    a[[row, k]] -= factor * a[[col, k]];
+                    inverse[[row, k]] -= factor * inverse[[col, k]];
+                }
+            }
+        }
+    }
+    Some(inverse)
+}
+
+/// Returns the determinant of `a` by Gaussian elimination with partial pivoting.
+pub(crate) fn determinant(mut a: Array2<f64>) -> f64 {
+    let n = a.nrows();
+    let mut determinant = 1.0;
+    for col in 0..n {
+        let pivot = (col..n).fold(col, |best, row| if a[[row, col]].abs() > a[[best, col]].abs() { row } else { best });
+        if a[[pivot, col]] == 0.0 {
+            return 0.0;
+        }
+        if pivot != col {
+            for k in 0..n {
+                a.swap([pivot, k], [col, k]);
+            }
+            determinant = -determinant;
+        }
+        determinant *= a[[col, col]];
+        for row in (col + 1)..n {
+            let factor = a[[row, col]] / a[[col, col]];
+            if factor != 0.0 {
+                for k in col..n {
+                    a[[row, k]] -= factor * a[[col, k]];
+                }
+            }
+        }
+    }
+    determinant
+}
+
+/// Returns the eigenvalues of the symmetric matrix `a`, together with a matrix whose columns 
+/// are the corresponding orthonormal eigenvectors, by the cyclic Jacobi method.
+pub(crate) fn symmetric_eigen(mut a: Array2<f64>) -> (Array1<f64>, Array2<f64>) {
+    let n = a.nrows();
+    let mut vectors: Array2<f64> = Array2::eye(n);
+    let scale: f64 = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    for _ in 0..100 {
+        let off_diagonal: f64 = a.indexed_iter().filter(|((i, j), _)| i != j).map(|(_, x)| x * x).sum::<f64>().sqrt();
+        if off_diagonal <= f64::EPSILON * scale {
+            break;
+        }
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[[p, q]] == 0.0 {
+                    continue;
+                }
+                // Rotation that annihilates a[[p, q]]
+                let theta = (a[[q, q]] - a[[p, p]]) / (2.0 * a[[p, q]]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (akp, akq) = (a[[k, p]], a[[k, q]]);
+                    a[[k, p]] = c * akp - s * akq;
+                    a[[k, q]] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (a[[p, k]], a[[q, k]]);
+                    a[[p, k]] = c * apk - s * aqk;
+                    a[[q, k]] = s * apk + c * aqk;
+                }
+                for k in 0..n {
+                    let (vkp, vkq) = (vectors[[k, p]], vectors[[k, q]]);
+                    vectors[[k, p]] = c * vkp - s * vkq;
+                    vectors[[k, q]] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    (a.diag().to_owned(), vectors)
+}
+
+/// Returns the eigenvalues of the transition matrix `p`, in decreasing order, together with a matrix 
+/// whose columns are the corresponding right eigenvectors, normalized with respect to the 
+/// stationary distribution `pi`, which must be positive.
+///
+/// Eigenvectors are computed for `D^{1/2} p D^{-1/2}`, where `D` is the diagonal matrix of `pi`, 
+/// which is symmetric if `p` is reversible. Otherwise, its symmetric part is used, i.e. 
+/// the result corresponds to the additive reversibilization of `p`.
+pub(crate) fn reversible_eigen(p: &Array2<f64>, pi: &Array1<f64>) -> (Array1<f64>, Array2<f64>) {
+    let n = p.nrows();
+    let sqrt_pi = pi.mapv(f64::sqrt);
+    let similar = Array2::from_shape_fn((n, n), |(i, j)| sqrt_pi[i] * p[[i, j]] / sqrt_pi[j]);
+    let (values, vectors) = symmetric_eigen((&similar + &similar.t()) / 2.0);
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| values[b].partial_cmp(&values[a]).expect("Eigenvalues are finite."));
+    let sorted_values = order.iter().map(|&k| values[k]).collect();
+    let right_vectors = Array2::from_shape_fn((n, n), |(i, k)| vectors[[i, order[k]]] / sqrt_pi[i]);
+    (sorted_values, right_vectors)
+}
+
+/// Returns `true` if the only non-zero entries of `a` are in the diagonal and next to it.
+pub(crate) fn is_tridiagonal(a: &Array2<f64>) -> bool {
+    a.indexed_iter().all(|((i, j), &value)| value == 0.0 || (i as isize - j as isize).abs() <= 1)
+}
+
+/// Solves the linear system `a x = b` for a tridiagonal matrix `a`, by the Thomas algorithm, in O(n).
+///
+/// Returns `None` if some pivot vanishes, which does not happen for diagonally dominant non-singular matrices.
+pub(crate) fn solve_tridiagonal(a: &Array2<f64>, b: &Array1<f64>) -> Option<Array1<f64>> {
+    let n = a.nrows();
+    let mut upper = Array1::zeros(n);
+    let mut x = Array1::zeros(n);
+    for i in 0..n {
+        let lower = if i > 0 { a[[i, i - 1]] } else { 0.0 };
+        let previous_upper = if i > 0 { upper[i - 1] } else { 0.0 };
+        let previous_x = if i > 0 { x[i - 1] } else { 0.0 };
+        let pivot = a[[i, i]] - lower * previous_upper;
+        if pivot.abs() < 1e-300 {
+            return None;
+        }
+        if i + 1 < n {
+            upper[i] = a[[i, i + 1]] / pivot;
+        }
+        x[i] = (b[i] - lower * previous_x) / pivot;
+    }
+    for i in (0..n.saturating_sub(1)).rev() {
+        x[i] -= upper[i] * x[i + 1];
+    }
+    Some(x)
+}
+
+/// Solves the linear system `a x = b`, in O(n) if `a` is tridiagonal, as for birth-death chains,
+/// and by Gaussian elimination otherwise.
+///
+/// Returns `None` if `a` is singular.
+pub(crate) fn solve_structured(a: Array2<f64>, b: Array1<f64>) -> Option<Array1<f64>> {
+    if is_tridiagonal(&a) {
+        if let Some(x) = solve_tridiagonal(&a, &b) {
+            return Some(x);
+        }
+    }
+    solve(a, b)
+}
+
+/// Returns, for each state, the expected total cost until hitting some state in `target`,
+/// for the transition matrix `p`, where `cost(i, j)` is the cost of a transition from `i` to `j`.
+///
+/// States in `target` have zero cost. States from which hitting `target` has probability
+/// less than one have infinite cost.
+pub(crate) fn expected_cost_to_target<F>(p: &Array2<f64>, target: &[bool], cost: F) -> Array1<f64>
+where
+    F: Fn(usize, usize) -> f64,
+{
+    let n = p.nrows();
+    let may_hit = backward_reachable(p, target);
+    let never_hit: Vec<bool> = may_hit.iter().map(|&b| !b).collect();
+    let infinite = backward_reachable(p, &never_hit);
+
+    let transient: Vec<usize> = (0..n).filter(|&i| !target[i] && !infinite[i]).collect();
+    let m = transient.len();
+    let a = Array2::from_shape_fn((m, m), |(k, l)| {
+        let identity = if k == l { 1.0 } else { 0.0 };
+        identity - p[[transient[k], transient[l]]]
+    });
+    let b = Array1::from_shape_fn(m, |k| {
+        let i = transient[k];
+        (0..n).filter(|&j| p[[i, j]] > 0.0).map(|j| p[[i, j]] * cost(i, j)).sum()
+    });
+    let solution = solve_structured(a, b)
+        .expect("Transient states hit the target with probability one.");
+
+    let mut costs = Array1::from_shape_fn(n, |i| if infinite[i] { f64::INFINITY } else { 0.0 });
+    for (k, &i) in transient.iter().enumerate() {
+        costs[i] = solution[k];
+    }
+    costs
+}
+
+/// Returns the indexes of states from which some state in `target` is reachable
+/// through transitions with positive probability, including `target` itself.
+pub(crate) fn backward_reachable(p: &Array2<f64>, target: &[bool]) -> Vec<bool> {
+    let n = p.nrows();
+    let mut reachable = target.to_vec();
+    let mut stack: Vec<usize> = (0..n).filter(|&i| target[i]).collect();
+    while let Some(j) = stack.pop() {
+        for i in 0..n {
+            if !reachable[i] && p[[i, j]] > 0.0 {
+                reachable[i] = true;
+                stack.push(i);
+            }
+        }
+    }
+    reachable
+}
+
+/// Returns, for each state, the probability of hitting some state in `target`
+/// before any state in `avoid`, for the transition matrix `p`.
+///
+/// States in `target` have probability one, unless they are also in `avoid`.
+pub(crate) fn hitting_probabilities(p: &Array2<f64>, target: &[bool], avoid: &[bool]) -> Array1<f64> {
+    let n = p.nrows();
+    let stopped: Vec<bool> = (0..n).map(|i| target[i] || avoid[i]).collect();
+    let mut stopped_p = p.clone();
+    for i in (0..n).filter(|&i| stopped[i]) {
+        for j in 0..n {
+            stopped_p[[i, j]] = if i == j { 1.0 } else { 0.0 };
+        }
+    }
+    let goal: Vec<bool> = (0..n).map(|i| target[i] && !avoid[i]).collect();
+    let may_hit = backward_reachable(&stopped_p, &goal);
+
+    let unknown: Vec<usize> = (0..n).filter(|&i| !stopped[i] && may_hit[i]).collect();
+    let m = unknown.len();
+    let a = Array2::from_shape_fn((m, m), |(k, l)| {
+        let identity = if k == l { 1.0 } else { 0.0 };
+        identity - p[[unknown[k], unknown[l]]]
+    });
+    let b = Array1::from_shape_fn(m, |k| (0..n).filter(|&j| goal[j]).map(|j| p[[unknown[k], j]]).sum());
+    let solution = solve_structured(a, b).expect("States that may hit the target leave the unknown states with positive probability.");
+
+    let mut probabilities = Array1::from_shape_fn(n, |i| if goal[i] { 1.0 } else { 0.0 });
+    for (k, &i) in unknown.iter().enumerate() {
+        probabilities[i] = solution[k];
+    }
+    probabilities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn logarithm() {
+        let q = array![[-1., 0.5, 0.5], [0.2, -0.4, 0.2], [0., 3., -3.]];
+        for &time in &[0.1, 1., 2.] {
+            let log = logm(&expm_generator(&q, time)).unwrap();
+            assert!((log / time - &q).iter().all(|x| x.abs() < 1e-8));
+        }
+        assert!(logm(&array![[0., 1.], [1., 0.]]).is_none());
+    }
+
+    #[test]
+    fn two_states() {
+        // Closed form: p_01(t) = a / (a + b) * (1 - exp(-(a + b) t))
+        let (a, b, t) = (2.0, 3.0, 0.7);
+        let q = array![[-a, a], [b, -b]];
+        let p = expm_generator(&q, t);
+        let expected = a / (a + b) * (1.0 - (-(a + b) * t).exp());
+
+        assert!((p[[0, 1]] - expected).abs() < 1e-12);
+        assert!((p[[0, 0]] + p[[0, 1]] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn large_time() {
+        let q = array![[-1000.0, 1000.0], [1000.0, -1000.0]];
+        let p = expm_generator(&q, 10.0);
+
+        assert!((p[[0, 0]] - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn solve_system() {
+        let a = array![[0.0, 2.0], [1.0, 1.0]];
+        let x = solve(a, array![4.0, 3.0]).unwrap();
+
+        assert!((x[0] - 1.0).abs() < 1e-12);
+        assert!((x[1] - 2.0).abs() < 1e-12);
+        assert!(solve(array![[1.0, 1.0], [1.0, 1.0]], array![1.0, 1.0]).is_none());
+    }
+
+    #[test]
+    fn gambler_ruin() {
+        // Fair walk on 0..=4: hitting 4 before 0 from i has probability i / 4
+        let p = array![
+            [1.0, 0.0, 0.0, 0.0, 0.0],
+            [0.5, 0.0, 0.5, 0.0, 0.0],
+            [0.0, 0.5, 0.0, 0.5, 0.0],
+            [0.0, 0.0, 0.5, 0.0, 0.5],
+            [0.0, 0.0, 0.0, 0.0, 1.0],
+        ];
+        let target = [false, false, false, false, true];
+        let avoid = [true, false, false, false, false];
+        let h = hitting_probabilities(&p, &target, &avoid);
+        for i in 0..5 {
+            assert!((h[i] - i as f64 / 4.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn tridiagonal() {
+        let a = array![[2.0, -1.0, 0.0], [-1.0, 2.0, -1.0], [0.0, -1.0, 2.0]];
+        let b = array![1.0, 0.0, 1.0];
+        assert!(is_tridiagonal(&a));
+        assert!(!is_tridiagonal(&array![[1.0, 0.0, 1.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]));
+
+        let x = solve_tridiagonal(&a, &b).unwrap();
+        let expected = solve(a, b).unwrap();
+        for i in 0..3 {
+            assert!((x[i] - expected[i]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn invert() {
+        let a = array![[0.0, 2.0], [1.0, 1.0]];
+        let product = a.dot(&inverse(a.clone()).unwrap());
+
+        assert!((&product - &Array2::<f64>::eye(2)).iter().all(|x| x.abs() < 1e-12));
+        assert!(inverse(array![[1.0, 1.0], [1.0, 1.0]]).is_none());
+    }
+
+    #[test]
+    fn eigen() {
+        let a = array![[2.0, 1.0, 0.0], [1.0, 2.0, 1.0], [0.0, 1.0, 2.0]];
+        let (values, vectors) = symmetric_eigen(a.clone());
+        let reconstructed = vectors.dot(&Array2::from_diag(&values)).dot(&vectors.t());
+
+        assert!((&reconstructed - &a).iter().all(|x| x.abs() < 1e-12));
+        assert!((values.iter().product::<f64>() - determinant(a)).abs() < 1e-12);
+        assert_eq!(determinant(array![[0.0, 1.0], [1.0, 0.0]]), -1.0);
+    }
+}
@@ -21,13 +21,7 @@
 #[macro_export]
 macro_rules! raw_dist {
     ( $( $x:expr ),* ) => {
-        {
-            let mut temp_vec = Vec::new();
-            $(
-                temp_vec.push($x);
-            )*
-            $crate::distributions::Raw::new(temp_vec)
-        }
+        $crate::distributions::Raw::new(vec![$( $x ),*])
     };
 }
 
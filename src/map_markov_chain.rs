@@ -0,0 +1,489 @@
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::cmp::Ordering;
+use rand::{Rng, SeedableRng};
+use rand_distr::{weighted_alias::{WeightedAliasIndex, AliasableWeight}, Uniform, Distribution};
+
+// Structs
+use crate::errors::InvalidState;
+use rand_distr::WeightedError;
+use std::collections::{hash_map::Entry, HashMap};
+
+// Functions
+use core::mem;
+
+/// Outgoing transitions of a state.
+#[derive(Debug, Clone)]
+struct Row<T, W>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+{
+    targets: Vec<T>,
+    weights: Vec<W>,
+    alias: Option<WeightedAliasIndex<W>>,
+}
+
+impl<T, W> Row<T, W>
+where
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+{
+    #[inline]
+    fn new() -> Self {
+        Row {
+            targets: Vec::new(),
+            weights: Vec::new(),
+            alias: None,
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, to: T, weight: W) -> Result<(), WeightedError>
+    where
+        T: PartialEq,
+    {
+        if let None | Some(Ordering::Less) = weight.partial_cmp(&W::ZERO) {
+            return Err(WeightedError::InvalidWeight);
+        }
+        match self.targets.iter().position(|t| *t == to) {
+            Some(j) => {
+                if weight > W::MAX - self.weights[j] {
+                    return Err(WeightedError::InvalidWeight);
+                }
+                self.weights[j] += weight;
+            }
+            None => {
+                self.targets.push(to);
+                self.weights.push(weight);
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn rebuild(&mut self) -> Result<(), WeightedError> {
+        self.alias = match WeightedAliasIndex::new(self.weights.clone()) {
+            Ok(alias) => Some(alias),
+            Err(WeightedError::NoItem) | Err(WeightedError::AllWeightsZero) => None,
+            Err(e) => return Err(e),
+        };
+        Ok(())
+    }
+}
+
+/// Markov Chain in discrete time, with a state space discovered on the fly.
+///
+/// States are stored in a `HashMap`, so they do not need to be indexed
+/// beforehand, and both states and transitions can be inserted at any time.
+/// The iterator stops if the current state has no outgoing transitions.
+///
+/// # Costs
+///
+/// **Insertion of a transition**: O(d), where d is the number of transitions
+/// leaving the same state.
+///
+/// **Sample**: O(1).
+///
+/// # Examples
+///
+/// A random walk on a cycle of words.
+/// ```
+/// # use markovian::{MapMarkovChain, State};
+/// let mut mc = MapMarkovChain::new("rock", rand::thread_rng());
+/// mc.add_transition("rock", "paper", 1.).unwrap();
+/// mc.add_transition("paper", "scissors", 1.).unwrap();
+/// mc.add_transition("scissors", "rock", 1.).unwrap();
+/// assert_eq!(mc.next(), Some("paper"));
+/// assert_eq!(mc.next(), Some("scissors"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MapMarkovChain<T, W, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    state: T,
+    initial_state: T,
+    transitions: HashMap<T, Row<T, W>>,
+    rng: R,
+}
+
+impl<T, W, R> MapMarkovChain<T, W, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    /// Constructs a new `MapMarkovChain<T, W, R>` whose only state is `state`.
+    #[inline]
+    pub fn new(state: T, rng: R) -> Self {
+        let mut transitions = HashMap::new();
+        transitions.insert(state.clone(), Row::new());
+        MapMarkovChain {
+            initial_state: state.clone(),
+            state,
+            transitions,
+            rng,
+        }
+    }
+
+    /// Constructs a new `MapMarkovChain<T, W, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(state: T, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        MapMarkovChain::new(state, R::seed_from_u64(seed))
+    }
+
+    /// Constructs a new `MapMarkovChain<T, W, R>` from weighted transitions `(from, to, weight)`.
+    ///
+    /// Weights of repeated transitions are added up.
+    ///
+    /// # Errors
+    ///
+    /// If any weight is negative or the weights leaving a state overflow.
+    ///
+    /// # Examples
+    ///
+    /// A chain that alternates between two states.
+    /// ```
+    /// # use markovian::{MapMarkovChain, State};
+    /// let mc = MapMarkovChain::from_transitions('a', vec![('a', 'b', 1), ('b', 'a', 1)], rand::thread_rng()).unwrap();
+    /// assert_eq!(mc.take(4).collect::<String>(), "baba");
+    /// ```
+    #[inline]
+    pub fn from_transitions<I>(state: T, transitions: I, rng: R) -> Result<Self, WeightedError>
+    where
+        I: IntoIterator<Item = (T, T, W)>,
+    {
+        let mut mc = MapMarkovChain::new(state, rng);
+        for (from, to, weight) in transitions {
+            mc.push_transition(from, to, weight)?;
+        }
+        for row in mc.transitions.values_mut() {
+            row.rebuild()?;
+        }
+        Ok(mc)
+    }
+
+    /// Inserts `state` in the state space, without transitions.
+    ///
+    /// Returns `false` if the state was already present.
+    #[inline]
+    pub fn add_state(&mut self, state: T) -> bool {
+        match self.transitions.entry(state) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(Row::new());
+                true
+            }
+        }
+    }
+
+    /// Adds `weight` to the transition from `from` to `to`, inserting both states if needed.
+    ///
+    /// # Errors
+    ///
+    /// If the resulting weights leaving `from` are invalid, e.g. negative or too large.
+    /// In that case, the chain is left unchanged.
+    #[inline]
+    pub fn add_transition(&mut self, from: T, to: T, weight: W) -> Result<(), WeightedError> {
+        let mut row = match self.transitions.get(&from) {
+            Some(row) => Row {
+                targets: row.targets.clone(),
+                weights: row.weights.clone(),
+                alias: None,
+            },
+            None => Row::new(),
+        };
+        row.push(to.clone(), weight)?;
+        row.rebuild()?;
+        self.add_state(to);
+        self.transitions.insert(from, row);
+        Ok(())
+    }
+
+    #[inline]
+    fn push_transition(&mut self, from: T, to: T, weight: W) -> Result<(), WeightedError> {
+        self.add_state(to.clone());
+        self.transitions.entry(from).or_insert_with(Row::new).push(to, weight)
+    }
+
+    /// Returns `true` if `state` belongs to the state space.
+    #[inline]
+    pub fn contains_state(&self, state: &T) -> bool {
+        self.transitions.contains_key(state)
+    }
+
+    /// Returns the size of the state space.
+    #[inline]
+    pub fn nstates(&self) -> usize {
+        self.transitions.len()
+    }
+
+    /// Returns an iterator over the state space, in arbitrary order.
+    #[inline]
+    pub fn states(&self) -> impl Iterator<Item = &T> {
+        self.transitions.keys()
+    }
+
+    /// Returns the transitions leaving `state` together with their weights, if `state` is known.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use markovian::MapMarkovChain;
+    /// let mut mc = MapMarkovChain::new(0, rand::thread_rng());
+    /// mc.add_transition(0, 1, 2).unwrap();
+    /// mc.add_transition(0, 1, 3).unwrap();
+    /// assert_eq!(mc.transitions_from(&0).unwrap().collect::<Vec<_>>(), vec![(&1, &5)]);
+    /// ```
+    #[inline]
+    pub fn transitions_from(&self, state: &T) -> Option<impl Iterator<Item = (&T, &W)>> {
+        self.transitions.get(state)
+            .map(|row| row.targets.iter().zip(row.weights.iter()))
+    }
+
+    /// Returns `true` if the current state has at least one transition with positive weight.
+    #[inline]
+    pub fn can_continue(&self) -> bool {
+        self.transitions[&self.state].alias.is_some()
+    }
+}
+
+impl<T, W, R> Reset for MapMarkovChain<T, W, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+    }
+}
+
+impl<T, W, R> RandomSource for MapMarkovChain<T, W, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<T, W, R> State for MapMarkovChain<T, W, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    type Item = T;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    #[inline]
+    fn set_state(
+        &mut self,
+        mut new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        if !self.contains_state(&new_state) {
+            return Err(InvalidState::new(new_state));
+        }
+        mem::swap(&mut self.state, &mut new_state);
+        Ok(Some(new_state))
+    }
+}
+
+impl<T, W, R> Checkpoint for MapMarkovChain<T, W, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<T, W, R> Iterator for MapMarkovChain<T, W, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = &self.transitions[&self.state];
+        let index = row.alias.as_ref()?.sample(&mut self.rng);
+        self.state = row.targets[index].clone();
+        self.state().cloned()
+    }
+}
+
+impl<T, W, R> StochasticProcess for MapMarkovChain<T, W, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    type Time = ();
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<T, W, R> StateIterator for MapMarkovChain<T, W, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.state().cloned()
+    }
+
+    #[inline]
+    fn advance(&mut self) -> bool {
+        let row = &self.transitions[&self.state];
+        match row.alias.as_ref() {
+            Some(alias) => {
+                self.state = row.targets[alias.sample(&mut self.rng)].clone();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<T, W, R> Distribution<T> for MapMarkovChain<T, W, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    W: AliasableWeight + Debug + Clone,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    /// Sample a possible next state.
+    ///
+    /// # Panics
+    ///
+    /// If the current state has no transitions with positive weight.
+    #[inline]
+    fn sample<R2>(&self, rng: &mut R2) -> T
+    where
+        R2: Rng + ?Sized,
+    {
+        let row = &self.transitions[&self.state];
+        let index = row.alias.as_ref()
+            .expect("The current state has no transitions.")
+            .sample(rng);
+        row.targets[index].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn construction() {
+        let mut mc = MapMarkovChain::new(0, crate::tests::rng(1));
+        assert!(!mc.can_continue());
+        assert_eq!(mc.next(), None);
+
+        mc.add_transition(0, 1, 1.).unwrap();
+        assert_eq!(mc.nstates(), 2);
+        assert!(mc.add_state(2));
+        assert!(!mc.add_state(2));
+        assert_eq!(mc.nstates(), 3);
+        assert!(mc.add_transition(1, 2, -1.).is_err());
+        assert_eq!(mc.transitions_from(&1).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn invalid_transition() {
+        let mut mc = MapMarkovChain::new(0, crate::tests::rng(5));
+        mc.add_transition(0, 1, u32::MAX).unwrap();
+        assert_eq!(mc.add_transition(0, 2, u32::MAX), Err(WeightedError::InvalidWeight));
+        assert!(!mc.contains_state(&2));
+        assert_eq!(mc.transitions_from(&0).unwrap().collect::<Vec<_>>(), vec![(&1, &u32::MAX)]);
+        assert_eq!(mc.next(), Some(1));
+
+        // Adding to an existing transition must not overflow
+        assert_eq!(mc.add_transition(0, 1, u32::MAX), Err(WeightedError::InvalidWeight));
+        assert_eq!(mc.transitions_from(&0).unwrap().collect::<Vec<_>>(), vec![(&1, &u32::MAX)]);
+    }
+
+    #[test]
+    fn zero_weights() {
+        let mut mc = MapMarkovChain::from_transitions(0, vec![(0, 1, 0)], crate::tests::rng(2)).unwrap();
+        assert!(!mc.can_continue());
+        mc.add_transition(0, 1, 1).unwrap();
+        assert_eq!(mc.next(), Some(1));
+    }
+
+    #[test]
+    fn sampling_stability() {
+        let rng = crate::tests::rng(3);
+        let transitions = vec![(0, 1, 1.), (0, 2, 1.), (1, 0, 1.), (2, 0, 1.)];
+        let mc = MapMarkovChain::from_transitions(0, transitions, rng).unwrap();
+        let sample: Vec<u32> = mc.trajectory().take(100).collect();
+        for window in sample.windows(2) {
+            match window[0] {
+                0 => assert!(window[1] == 1 || window[1] == 2),
+                _ => assert_eq!(window[1], 0),
+            }
+        }
+    }
+
+    #[test]
+    fn change_state() {
+        let mut mc = MapMarkovChain::<_, f64, _>::new('a', crate::tests::rng(4));
+        assert!(mc.set_state('b').is_err());
+        mc.add_state('b');
+        assert_eq!(mc.set_state('b').unwrap(), Some('a'));
+    }
+}
@@ -1,8 +1,8 @@
 // Traits
 use rand_distr::Distribution;
-use crate::traits::{State, StateIterator, Transition};
+use crate::traits::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess, Transition, TransitionOnce};
 use core::fmt::Debug;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 
 // Structs
 use crate::errors::InvalidState;
@@ -32,6 +32,8 @@ use core::mem;
 #[derive(Debug, Clone)]
 pub struct MarkovChain<T, F, R> {
     state: T,
+    // Kept once the chain leaves its initial state
+    initial_state: Option<T>,
     transition: F,
     rng: R,
 }
@@ -45,10 +47,125 @@ where
     pub fn new(state: T, transition: F, rng: R) -> Self {
         MarkovChain {
             state,
+            initial_state: None,
             transition,
             rng,
         }
     }
+
+    /// Constructs a new `MarkovChain<T, F, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(state: T, transition: F, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        MarkovChain::new(state, transition, R::seed_from_u64(seed))
+    }
+
+    /// Advances the chain one step with a one-off `transition`, instead of its own, 
+    /// and returns the new state.
+    ///
+    /// Since `transition` is consumed, it can move the values it owns into the new state.
+    ///
+    /// # Examples
+    ///
+    /// Consuming precomputed increments, without cloning them.
+    /// ```
+    /// # use markovian::{MarkovChain, prelude::*};
+    /// # use rand::Rng;
+    /// struct Given(Vec<String>);
+    /// impl DistributionOnce<Vec<String>> for Given {
+    ///     fn sample_once<R: Rng + ?Sized>(self, _: &mut R) -> Vec<String> {
+    ///         self.0
+    ///     }
+    /// }
+    /// let transition = |state: &Vec<String>| raw_dist![(1.0, state.clone())];
+    /// let mut mc = MarkovChain::new(Vec::new(), transition, rand::thread_rng());
+    /// for word in vec![String::from("a"), String::from("b")] {
+    ///     mc.step_once(move |state: &Vec<String>| {
+    ///         let mut next = state.clone();
+    ///         next.push(word);
+    ///         Given(next)
+    ///     });
+    /// }
+    /// assert_eq!(mc.state(), Some(&vec![String::from("a"), String::from("b")]));
+    /// ```
+    #[inline]
+    pub fn step_once<G>(&mut self, transition: G) -> T
+    where
+        T: Clone,
+        G: TransitionOnce<T, T>,
+    {
+        self.keep_initial_state();
+        self.state = transition.sample_once_from(&self.state, &mut self.rng);
+        self.state.clone()
+    }
+}
+
+impl<'a, T, F, R> MarkovChain<T, F, &'a mut R>
+where
+    R: Rng + ?Sized,
+    F: Transition<T, T>,
+{
+    /// Constructs a new `MarkovChain<T, F, &mut R>` that borrows its random number generator.
+    ///
+    /// # Examples
+    ///
+    /// Two chains sharing one random number generator, one after the other.
+    /// ```
+    /// # use markovian::{MarkovChain, prelude::*};
+    /// let mut rng = rand::thread_rng();
+    /// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+    /// let first: Vec<i32> = MarkovChain::new_with_rng_mut(0, transition, &mut rng).take(5).collect();
+    /// let second: Vec<i32> = MarkovChain::new_with_rng_mut(0, transition, &mut rng).take(5).collect();
+    /// ```
+    #[inline]
+    pub fn new_with_rng_mut(state: T, transition: F, rng: &'a mut R) -> Self {
+        MarkovChain::new(state, transition, rng)
+    }
+}
+
+impl<T, F, R> MarkovChain<T, F, R>
+where
+    T: Clone,
+{
+    #[inline]
+    fn keep_initial_state(&mut self) {
+        if self.initial_state.is_none() {
+            self.initial_state = Some(self.state.clone());
+        }
+    }
+}
+
+impl<T, F, R> Reset for MarkovChain<T, F, R> {
+    #[inline]
+    fn reset(&mut self) {
+        if let Some(initial_state) = self.initial_state.take() {
+            self.state = initial_state;
+        }
+    }
+}
+
+impl<T, F, R> RandomSource for MarkovChain<T, F, R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
 }
 
 impl<T, F, R> State for MarkovChain<T, F, R>
@@ -64,6 +181,7 @@ where
 
     #[inline]
     fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        self.keep_initial_state();
         Some(&mut self.state)
     }
 
@@ -72,11 +190,26 @@ where
         &mut self,
         mut new_state: Self::Item,
     ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        self.keep_initial_state();
         mem::swap(&mut self.state, &mut new_state);
         Ok(Some(new_state))
     }
 }
 
+impl<T, F, R> Checkpoint for MarkovChain<T, F, R>
+where
+    T: Debug + Clone,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
 impl<T, F, R> Iterator for MarkovChain<T, F, R>
 where
     T: Debug + Clone,
@@ -87,11 +220,27 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        self.keep_initial_state();
         self.state = self.transition.sample_from(&self.state, &mut self.rng);
         self.state().cloned()
     }
 }
 
+impl<T, F, R> StochasticProcess for MarkovChain<T, F, R>
+where
+    T: Debug + Clone,
+    F: Transition<T, T>,
+    R: Rng,
+{
+    type Time = ();
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
 impl<T, F, R> StateIterator for MarkovChain<T, F, R>
 where
     T: Debug + Clone,
@@ -102,6 +251,13 @@ where
     fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
         self.state().cloned()
     }
+
+    #[inline]
+    fn advance(&mut self) -> bool {
+        self.keep_initial_state();
+        self.state = self.transition.sample_from(&self.state, &mut self.rng);
+        true
+    }
 }
 
 impl<T, F, R> Distribution<T> for MarkovChain<T, F, R>
@@ -147,6 +303,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn advance() {
+        let transition = |state: &u64| Raw::new(vec![(0.5, state + 1), (0.5, state + 2)]);
+        let expected: Vec<u64> = MarkovChain::new(0, transition, crate::tests::rng(4)).take(10).collect();
+        let mut mc = MarkovChain::new(0, transition, crate::tests::rng(4));
+        for x in expected {
+            assert!(mc.advance());
+            assert_eq!(mc.state(), Some(&x));
+        }
+    }
+
     #[test]
     fn value_stability() {
         let rng = crate::tests::rng(3);
@@ -158,6 +325,31 @@ mod tests {
         assert_eq!(sample, expected);
     }
 
+    #[test]
+    fn borrowed_rng() {
+        // Transitions need not be Clone
+        struct Counting<'a> {
+            calls: &'a core::cell::Cell<usize>,
+        }
+        impl Transition<u64, u64> for Counting<'_> {
+            fn sample_from<R>(&self, state: &u64, rng: &mut R) -> u64
+            where
+                R: Rng + ?Sized,
+            {
+                self.calls.set(self.calls.get() + 1);
+                Raw::new(vec![(1.0, state + 1)]).sample(rng)
+            }
+        }
+
+        let mut rng = crate::tests::rng(5);
+        let calls = core::cell::Cell::new(0);
+        let sample: Vec<u64> = MarkovChain::new_with_rng_mut(0, Counting { calls: &calls }, &mut rng).take(3).collect();
+        assert_eq!(sample, vec![1, 2, 3]);
+        let mc = crate::FiniteMarkovChain::new_with_rng_mut(0, vec![vec![0., 1.], vec![1., 0.]], vec![0, 1], &mut rng);
+        assert_eq!(mc.take(3).collect::<Vec<u64>>(), vec![1, 0, 1]);
+        assert_eq!(calls.get(), 3);
+    }
+
     #[test]
     fn construction() {
         let rng = crate::tests::rng(4);
@@ -168,4 +360,39 @@ mod tests {
 
         assert_eq!(sample, expected);
     }
+
+    #[test]
+    fn reset() {
+        let transition = |state: &u64| Raw::new(vec![(1.0, state + 1)]);
+        let mut mc = MarkovChain::new(0, transition, crate::tests::rng(6));
+        mc.reset();
+        assert_eq!(mc.state(), Some(&0));
+        mc.nth(2);
+        mc.set_state(10).unwrap();
+        mc.reset();
+        assert_eq!(mc.take(2).collect::<Vec<u64>>(), vec![1, 2]);
+
+        // States need not be Clone to construct and reset a chain
+        struct Token;
+        struct Stay;
+        impl Distribution<Token> for Stay {
+            fn sample<R: Rng + ?Sized>(&self, _: &mut R) -> Token {
+                Token
+            }
+        }
+        let mut mc = MarkovChain::new(Token, |_: &Token| Stay, crate::tests::rng(7));
+        mc.reset();
+    }
+
+    #[test]
+    fn fork() {
+        let transition = |_: &f64| rand_distr::StandardNormal;
+        let mut mc = MarkovChain::<f64, _, rand_pcg::Pcg64>::with_seed(0., transition, 5);
+        let forked = mc.fork();
+        let cloned = mc.clone();
+        let sample: Vec<f64> = mc.take(4).collect();
+
+        assert_eq!(sample, cloned.take(4).collect::<Vec<f64>>());
+        assert_ne!(sample, forked.take(4).collect::<Vec<f64>>());
+    }
 }
@@ -0,0 +1,235 @@
+// Traits
+use crate::traits::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess, TransitionInPlace};
+use core::fmt::Debug;
+use rand::{Rng, SeedableRng};
+
+// Structs
+use crate::errors::InvalidState;
+
+// Functions
+use core::mem;
+
+/// Markov Chain in discrete time, with arbitrary space, whose state is updated in place.
+///
+/// # Remarks
+///
+/// Iterating clones the state at every step. To avoid any copy, 
+/// use [advance] and read the current state with [state].
+///
+/// # Examples
+///
+/// A non markovian random walk, that is attracted to zero, with its whole history as state.
+/// ```
+/// # use markovian::{MarkovChainInPlace, TransitionInPlace, prelude::*};
+/// # use rand::Rng;
+/// struct Attracted;
+/// impl TransitionInPlace<Vec<i32>> for Attracted {
+///     fn step_in_place<R>(&self, state: &mut Vec<i32>, rng: &mut R)
+///     where
+///         R: Rng + ?Sized,
+///     {
+///         let sum: i32 = state.iter().sum();
+///         let last = state[state.len() - 1];
+///         let p = 1.0 / (sum.abs() + 1) as f64;
+///         let up = if sum.is_positive() { rng.gen_bool(p) } else { !rng.gen_bool(p) };
+///         state.push(if up { last + 1 } else { last - 1 });
+///     }
+/// }
+/// let mut mc = MarkovChainInPlace::new(vec![0], Attracted, rand::thread_rng());
+/// for _ in 0..1000 {
+///     mc.advance();
+/// }
+/// assert_eq!(mc.state().unwrap().len(), 1001);
+/// ```
+///
+/// [advance]: trait.StateIterator.html#method.advance
+/// [state]: trait.State.html#method.state
+#[derive(Debug, Clone)]
+pub struct MarkovChainInPlace<T, F, R> {
+    state: T,
+    initial_state: T,
+    transition: F,
+    rng: R,
+}
+
+impl<T, F, R> MarkovChainInPlace<T, F, R>
+where
+    T: Clone,
+    R: Rng,
+    F: TransitionInPlace<T>,
+{
+    #[inline]
+    pub fn new(state: T, transition: F, rng: R) -> Self {
+        MarkovChainInPlace {
+            initial_state: state.clone(),
+            state,
+            transition,
+            rng,
+        }
+    }
+
+    /// Constructs a new `MarkovChainInPlace<T, F, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(state: T, transition: F, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        MarkovChainInPlace::new(state, transition, R::seed_from_u64(seed))
+    }
+}
+
+impl<T, F, R> Reset for MarkovChainInPlace<T, F, R>
+where
+    T: Clone,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state.clone_from(&self.initial_state);
+    }
+}
+
+impl<T, F, R> RandomSource for MarkovChainInPlace<T, F, R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<T, F, R> State for MarkovChainInPlace<T, F, R>
+where
+    T: Debug + Clone,
+{
+    type Item = T;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    #[inline]
+    fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        Some(&mut self.state)
+    }
+
+    #[inline]
+    fn set_state(
+        &mut self,
+        mut new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        mem::swap(&mut self.state, &mut new_state);
+        Ok(Some(new_state))
+    }
+}
+
+impl<T, F, R> Checkpoint for MarkovChainInPlace<T, F, R>
+where
+    T: Debug + Clone,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<T, F, R> Iterator for MarkovChainInPlace<T, F, R>
+where
+    T: Debug + Clone,
+    F: TransitionInPlace<T>,
+    R: Rng,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.transition.step_in_place(&mut self.state, &mut self.rng);
+        self.state().cloned()
+    }
+}
+
+impl<T, F, R> StochasticProcess for MarkovChainInPlace<T, F, R>
+where
+    T: Debug + Clone,
+    F: TransitionInPlace<T>,
+    R: Rng,
+{
+    type Time = ();
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<T, F, R> StateIterator for MarkovChainInPlace<T, F, R>
+where
+    T: Debug + Clone,
+    F: TransitionInPlace<T>,
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.state().cloned()
+    }
+
+    #[inline]
+    fn advance(&mut self) -> bool {
+        self.transition.step_in_place(&mut self.state, &mut self.rng);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    struct Count;
+
+    impl TransitionInPlace<Vec<u32>> for Count {
+        fn step_in_place<R>(&self, state: &mut Vec<u32>, rng: &mut R)
+        where
+            R: Rng + ?Sized,
+        {
+            let last = state[state.len() - 1];
+            state.push(last + rng.gen_range(1..3));
+        }
+    }
+
+    #[test]
+    fn history() {
+        let mut mc = MarkovChainInPlace::new(vec![0], Count, crate::tests::rng(1));
+        let trajectory: Vec<Vec<u32>> = mc.by_ref().take(3).collect();
+        assert_eq!(trajectory[2].len(), 4);
+        assert_eq!(&trajectory[2][..3], &trajectory[1][..]);
+
+        for _ in 0..10 {
+            assert!(mc.advance());
+        }
+        let state = mc.state().unwrap();
+        assert_eq!(state.len(), 14);
+        assert!(state.windows(2).all(|w| w[1] > w[0] && w[1] <= w[0] + 2));
+
+        mc.reset();
+        assert_eq!(mc.state(), Some(&vec![0]));
+    }
+}
@@ -0,0 +1,440 @@
+//! Checks and normalizations of non-negative matrices, before constructing chains from them.
+//!
+//! Matrices estimated from data rarely have rows summing up to one exactly.
+//! [normalize_rows] projects a non-negative matrix onto a stochastic one, and
+//! [sinkhorn_normalize] onto a doubly stochastic one, by scaling rows and columns.
+//!
+//! # Examples
+//!
+//! A doubly stochastic chain from counts.
+//! ```
+//! # use ndarray::array;
+//! # use markovian::{FiniteMarkovChain, matrix::{is_doubly_stochastic, sinkhorn_normalize}};
+//! let counts = array![[3., 1.], [2., 5.]];
+//! let transition = sinkhorn_normalize(&counts, 1e-12).unwrap();
+//! assert!(is_doubly_stochastic(&transition, 1e-9));
+//! let mc = FiniteMarkovChain::from((0, transition, rand::thread_rng()));
+//! ```
+//!
+//! Generators of chains in continuous time are kept valid by the [Generator] newtype.
+//!
+//! [normalize_rows]: fn.normalize_rows.html
+//! [sinkhorn_normalize]: fn.sinkhorn_normalize.html
+//! [Generator]: struct.Generator.html
+
+// Traits
+use num_traits::float::Float;
+use rand::Rng;
+use rand_distr::{weighted_alias::AliasableWeight, Distribution, Exp1};
+
+// Structs
+use crate::errors::{EmbeddingError, MatrixError};
+use crate::ContFiniteMarkovChain;
+use ndarray::{Array2, Axis};
+
+/// Maximum number of iterations of [sinkhorn_normalize].
+///
+/// [sinkhorn_normalize]: fn.sinkhorn_normalize.html
+pub const SINKHORN_MAX_ITERATIONS: usize = 100_000;
+
+/// Returns `true` if `matrix` is square, has non-negative entries and its rows sum up to one,
+/// up to `tol`.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use markovian::matrix::is_stochastic;
+/// assert!(is_stochastic(&array![[0.5, 0.5], [0., 1.]], 1e-12));
+/// assert!(!is_stochastic(&array![[0.5, 0.6], [0., 1.]], 1e-12));
+/// ```
+#[inline]
+pub fn is_stochastic(matrix: &Array2<f64>, tol: f64) -> bool {
+    matrix.is_square()
+        && matrix.iter().all(|&x| x >= 0.)
+        && matrix.sum_axis(Axis(1)).iter().all(|sum| (sum - 1.).abs() <= tol)
+}
+
+/// Returns `true` if `matrix` is stochastic and its columns also sum up to one, up to `tol`.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use markovian::matrix::is_doubly_stochastic;
+/// assert!(is_doubly_stochastic(&array![[0.5, 0.5], [0.5, 0.5]], 1e-12));
+/// assert!(!is_doubly_stochastic(&array![[0.5, 0.5], [0., 1.]], 1e-12));
+/// ```
+#[inline]
+pub fn is_doubly_stochastic(matrix: &Array2<f64>, tol: f64) -> bool {
+    is_stochastic(matrix, tol) && matrix.sum_axis(Axis(0)).iter().all(|sum| (sum - 1.).abs() <= tol)
+}
+
+/// Returns the stochastic matrix obtained by dividing each row of `matrix` by its sum.
+///
+/// # Errors
+///
+/// If `matrix` is not square, has a negative or non-finite entry, or has a row of zeros.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use markovian::matrix::normalize_rows;
+/// assert_eq!(normalize_rows(&array![[1., 3.], [2., 2.]]), Ok(array![[0.25, 0.75], [0.5, 0.5]]));
+/// ```
+#[inline]
+pub fn normalize_rows(matrix: &Array2<f64>) -> Result<Array2<f64>, MatrixError> {
+    check(matrix)?;
+    if matrix.sum_axis(Axis(1)).iter().any(|&sum| sum == 0.) {
+        return Err(MatrixError::ZeroLine);
+    }
+    Ok(crate::linalg::normalize_rows(matrix.clone()))
+}
+
+/// Returns the doubly stochastic matrix obtained by scaling the rows and columns of `matrix`,
+/// by the Sinkhorn-Knopp algorithm: rows and columns are normalized alternately
+/// until all their sums are within `tol` of one.
+///
+/// The result is the unique doubly stochastic matrix of the form `D1 * matrix * D2`,
+/// for diagonal matrices `D1` and `D2`, which exists if `matrix` has total support,
+/// for example, if all its entries are positive.
+///
+/// # Errors
+///
+/// If `matrix` is not square, has a negative or non-finite entry, has a row or a column of zeros,
+/// or the algorithm does not converge within [SINKHORN_MAX_ITERATIONS] iterations,
+/// which happens for matrices without total support.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use markovian::matrix::{sinkhorn_normalize, is_doubly_stochastic};
+/// let matrix = sinkhorn_normalize(&array![[1., 2., 3.], [1., 1., 1.], [5., 1., 1.]], 1e-12).unwrap();
+/// assert!(is_doubly_stochastic(&matrix, 1e-9));
+/// ```
+///
+/// [SINKHORN_MAX_ITERATIONS]: constant.SINKHORN_MAX_ITERATIONS.html
+#[inline]
+pub fn sinkhorn_normalize(matrix: &Array2<f64>, tol: f64) -> Result<Array2<f64>, MatrixError> {
+    check(matrix)?;
+    if matrix.sum_axis(Axis(1)).iter().chain(matrix.sum_axis(Axis(0)).iter()).any(|&sum| sum == 0.) {
+        return Err(MatrixError::ZeroLine);
+    }
+    let mut result = matrix.clone();
+    for _ in 0..SINKHORN_MAX_ITERATIONS {
+        result = crate::linalg::normalize_rows(result);
+        let column_sums = result.sum_axis(Axis(0));
+        if column_sums.iter().all(|sum| (sum - 1.).abs() <= tol) {
+            return Ok(result);
+        }
+        for (mut column, sum) in result.gencolumns_mut().into_iter().zip(column_sums.iter()) {
+            column /= *sum;
+        }
+    }
+    Err(MatrixError::NotConverged(SINKHORN_MAX_ITERATIONS))
+}
+
+/// Generator matrix of a chain in continuous time: off-diagonal entries are non-negative
+/// finite rates and rows sum up to zero.
+///
+/// Operations keep these invariants, so that the result is always a valid generator.
+///
+/// # Examples
+///
+/// Speeding up a chain and adding a drift to the right.
+/// ```
+/// # use ndarray::array;
+/// # use markovian::matrix::Generator;
+/// let q = Generator::new(array![[0., 1.], [2., 0.]]).unwrap();
+/// let drift = Generator::new(array![[0., 3.], [0., 0.]]).unwrap();
+/// let faster = q.scaled(2.).unwrap().superposed(&drift).unwrap();
+/// assert_eq!(faster.matrix(), &array![[-5., 5.], [4., -4.]]);
+/// let mc = faster.chain(0, vec!['a', 'b'], rand::thread_rng());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generator {
+    matrix: Array2<f64>,
+}
+
+impl Generator {
+    /// Constructs a new `Generator` with the off-diagonal rates of `rates`.
+    ///
+    /// The diagonal of `rates` is ignored and replaced so that rows sum up to zero.
+    ///
+    /// # Errors
+    ///
+    /// If `rates` is not square, or has a negative or non-finite off-diagonal entry.
+    #[inline]
+    pub fn new(mut rates: Array2<f64>) -> Result<Self, MatrixError> {
+        if !rates.is_square() {
+            return Err(MatrixError::NotSquare);
+        }
+        for i in 0..rates.nrows() {
+            rates[[i, i]] = 0.;
+        }
+        check(&rates)?;
+        let exit_rates = rates.sum_axis(Axis(1));
+        for (i, exit_rate) in exit_rates.iter().enumerate() {
+            rates[[i, i]] = -exit_rate;
+        }
+        Ok(Generator { matrix: rates })
+    }
+
+    /// Constructs the generator of the chain in continuous time that jumps at `rate` 
+    /// according to the transition matrix `transition`, i.e. `rate * (P - I)`.
+    ///
+    /// Rows of `transition` are normalized and jumps from a state to itself are ignored.
+    ///
+    /// # Errors
+    ///
+    /// If `transition` is not square, has a negative or non-finite entry, or has a row of zeros, 
+    /// or `rate` is negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::matrix::Generator;
+    /// let q = Generator::embed(&array![[0.5, 0.5], [1., 0.]], 2.).unwrap();
+    /// assert_eq!(q.matrix(), &array![[-1., 1.], [2., -2.]]);
+    /// ```
+    #[inline]
+    pub fn embed(transition: &Array2<f64>, rate: f64) -> Result<Self, MatrixError> {
+        let transition = normalize_rows(transition)?;
+        Generator::new(transition)?.scaled(rate)
+    }
+
+    /// Returns the generator of the chain whose time runs `factor` times faster.
+    ///
+    /// # Errors
+    ///
+    /// If `factor` is negative or not finite.
+    #[inline]
+    pub fn scaled(&self, factor: f64) -> Result<Self, MatrixError> {
+        if !factor.is_finite() || factor < 0. {
+            return Err(MatrixError::InvalidEntry(factor));
+        }
+        Ok(Generator { matrix: &self.matrix * factor })
+    }
+
+    /// Returns the generator whose rates are the sum of the rates of `self` and `other`,
+    /// i.e. both kinds of transitions compete.
+    ///
+    /// # Errors
+    ///
+    /// If the generators have different dimensions.
+    #[inline]
+    pub fn superposed(&self, other: &Generator) -> Result<Self, MatrixError> {
+        if self.nstates() != other.nstates() {
+            return Err(MatrixError::DimensionMismatch);
+        }
+        Ok(Generator { matrix: &self.matrix + &other.matrix })
+    }
+
+    /// Returns the convex combination of the generators, with weight `weight` on `other`.
+    ///
+    /// # Errors
+    ///
+    /// If the generators have different dimensions, or `weight` is not in [0, 1].
+    #[inline]
+    pub fn mixed(&self, other: &Generator, weight: f64) -> Result<Self, MatrixError> {
+        if !(0. ..=1.).contains(&weight) {
+            return Err(MatrixError::InvalidEntry(weight));
+        }
+        self.scaled(1. - weight)?.superposed(&other.scaled(weight)?)
+    }
+
+    #[inline]
+    pub fn matrix(&self) -> &Array2<f64> {
+        &self.matrix
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> Array2<f64> {
+        self.matrix
+    }
+
+    #[inline]
+    pub fn nstates(&self) -> usize {
+        self.matrix.nrows()
+    }
+
+    /// Returns the rate of leaving each state.
+    #[inline]
+    pub fn exit_rates(&self) -> ndarray::Array1<f64> {
+        -self.matrix.diag().to_owned()
+    }
+
+    /// Returns the matrix of transition probabilities P(t) = exp(Q t).
+    #[inline]
+    pub fn transition_probabilities(&self, time: f64) -> Array2<f64> {
+        crate::linalg::expm_generator(&self.matrix, time)
+    }
+
+    /// Returns the chain with this generator, starting at the state of index `state_index`.
+    ///
+    /// States that are never left have weight one on a jump to themselves,
+    /// which does not change the law of the chain.
+    ///
+    /// # Panics
+    ///
+    /// If `state_space` has not as many elements as states of the generator.
+    #[inline]
+    pub fn chain<T, R>(&self, state_index: usize, state_space: Vec<T>, rng: R) -> ContFiniteMarkovChain<T, f64, R>
+    where
+        R: Rng,
+    {
+        assert_eq!(state_space.len(), self.nstates(), "The state space must have as many elements as states.");
+        let transition_weights = self.matrix.genrows()
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let mut weights = row.to_vec();
+                weights[i] = if row[i] == 0. { 1. } else { 0. };
+                weights
+            })
+            .collect();
+        ContFiniteMarkovChain::new(state_index, transition_weights, state_space, rng)
+    }
+}
+
+impl<T, W, R> From<&ContFiniteMarkovChain<T, W, R>> for Generator
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+    R: Rng,
+{
+    #[inline]
+    fn from(mc: &ContFiniteMarkovChain<T, W, R>) -> Self {
+        Generator { matrix: mc.generator().mapv(|w| w.to_f64().unwrap()) }
+    }
+}
+
+impl From<Generator> for Array2<f64> {
+    #[inline]
+    fn from(generator: Generator) -> Self {
+        generator.matrix
+    }
+}
+
+/// Tolerance under which negative rates of a matrix logarithm are considered rounding errors.
+pub const EMBEDDING_TOLERANCE: f64 = 1e-10;
+
+/// How to turn the logarithm of a transition matrix into a generator, when it has negative rates,
+/// see [FiniteMarkovChain::embed_as_ctmc].
+///
+/// Both regularizations are from Israel, Rosenthal and Wei (2001),
+/// "Finding generators for Markov chains via empirical transition matrices".
+///
+/// [FiniteMarkovChain::embed_as_ctmc]: ../struct.FiniteMarkovChain.html#method.embed_as_ctmc
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Regularization {
+    /// Negative rates are an error.
+    None,
+    /// Negative rates are set to zero and the diagonal is adjusted so that rows sum up to zero.
+    Diagonal,
+    /// Negative rates are set to zero and the excess is removed from the other entries
+    /// of the row, proportionally to their absolute values.
+    Weighted,
+}
+
+impl Regularization {
+    /// Returns the generator obtained from the logarithm `log` of a transition matrix.
+    #[inline]
+    pub(crate) fn generator(self, mut log: Array2<f64>) -> Result<Generator, EmbeddingError> {
+        let n = log.nrows();
+        let most_negative = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| log[[i, j]])
+            .fold(0.0, f64::min);
+        if most_negative < -EMBEDDING_TOLERANCE {
+            match self {
+                Regularization::None => return Err(EmbeddingError::NotEmbeddable(most_negative)),
+                Regularization::Diagonal => (),
+                Regularization::Weighted => {
+                    for i in 0..n {
+                        let off_diagonal = (0..n).filter(|&j| j != i).map(|j| log[[i, j]]);
+                        let positive: f64 = off_diagonal.clone().filter(|&q| q > 0.).sum();
+                        let negative: f64 = -off_diagonal.filter(|&q| q < 0.).sum::<f64>();
+                        let total = log[[i, i]].abs() + positive;
+                        if total > 0. {
+                            for j in 0..n {
+                                if j == i || log[[i, j]] > 0. {
+                                    log[[i, j]] -= negative * log[[i, j]].abs() / total;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        for i in 0..n {
+            for j in 0..n {
+                if i != j && log[[i, j]] < 0. {
+                    log[[i, j]] = 0.;
+                }
+            }
+        }
+        Generator::new(log).map_err(|_| EmbeddingError::NoLogarithm)
+    }
+}
+
+/// Checks that `matrix` is square with non-negative finite entries.
+#[inline]
+fn check(matrix: &Array2<f64>) -> Result<(), MatrixError> {
+    if !matrix.is_square() {
+        return Err(MatrixError::NotSquare);
+    }
+    match matrix.iter().find(|x| !x.is_finite() || **x < 0.) {
+        Some(&x) => Err(MatrixError::InvalidEntry(x)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sinkhorn() {
+        let matrix = array![[1., 2.], [3., 4.]];
+        let normalized = sinkhorn_normalize(&matrix, 1e-14).unwrap();
+        assert!(is_doubly_stochastic(&normalized, 1e-12));
+        // Scaling preserves the cross ratio
+        let cross_ratio = |m: &Array2<f64>| m[[0, 0]] * m[[1, 1]] / (m[[0, 1]] * m[[1, 0]]);
+        assert!((cross_ratio(&normalized) - cross_ratio(&matrix)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(normalize_rows(&array![[1., 2., 3.]]), Err(MatrixError::NotSquare));
+        assert_eq!(normalize_rows(&array![[1., -2.], [1., 1.]]), Err(MatrixError::InvalidEntry(-2.)));
+        assert_eq!(normalize_rows(&array![[0., 0.], [1., 1.]]), Err(MatrixError::ZeroLine));
+        assert_eq!(sinkhorn_normalize(&array![[1., 0.], [1., 0.]], 1e-12), Err(MatrixError::ZeroLine));
+        // No total support: the normalization only converges in the limit
+        assert_eq!(
+            sinkhorn_normalize(&array![[1., 1.], [0., 1.]], 1e-12),
+            Err(MatrixError::NotConverged(SINKHORN_MAX_ITERATIONS))
+        );
+    }
+
+    #[test]
+    fn generator() {
+        assert_eq!(Generator::new(array![[1., 2., 3.]]), Err(MatrixError::NotSquare));
+        assert_eq!(Generator::new(array![[0., -1.], [1., 0.]]), Err(MatrixError::InvalidEntry(-1.)));
+        let q = Generator::new(array![[7., 1.], [2., 0.]]).unwrap();
+        assert_eq!(q.matrix(), &array![[-1., 1.], [2., -2.]]);
+        assert_eq!(q.exit_rates(), array![1., 2.]);
+        assert_eq!(q.scaled(-1.), Err(MatrixError::InvalidEntry(-1.)));
+        let other = Generator::new(array![[0., 3.], [0., 0.]]).unwrap();
+        assert_eq!(q.mixed(&other, 0.5).unwrap().matrix(), &array![[-2., 2.], [1., -1.]]);
+        assert_eq!(q.superposed(&Generator::new(Array2::zeros((3, 3))).unwrap()), Err(MatrixError::DimensionMismatch));
+
+        let mc = other.chain(0, vec![0, 1], crate::tests::rng(1));
+        assert_eq!(Generator::from(&mc), other);
+    }
+}
@@ -0,0 +1,292 @@
+//! Metastable sets of finite chains, by PCCA with the inner simplex algorithm.
+//!
+//! A chain is metastable if its states split into a few sets that the chain rarely leaves,
+//! as the conformations of a molecule. Such sets are identified by the sign structure of the
+//! eigenvectors of the transition matrix with eigenvalues close to one.
+//!
+//! [pcca] implements the Perron cluster analysis of Deuflhard and Weber with the
+//! inner simplex algorithm: each state is given a membership to each metastable set, and
+//! the chain is coarse-grained to a chain between the sets.
+//!
+//! # Remarks
+//!
+//! Eigenvectors are computed for the transition matrix symmetrized with respect to the stationary
+//! distribution, which is exact for reversible chains. For non-reversible chains, the additive
+//! reversibilization of the chain is analysed instead.
+//!
+//! Memberships are the coordinates of states with respect to the vertices found by the
+//! inner simplex algorithm, clipped at zero and renormalized. They are not optimized further,
+//! as PCCA+ does, so they are less robust when the metastable sets are not well separated.
+//!
+//! # Examples
+//!
+//! Two groups of states, weakly coupled.
+//! ```
+//! # use ndarray::array;
+//! # use markovian::{FiniteMarkovChain, metastability::pcca};
+//! let p = array![
+//!     [0.5, 0.49, 0.01, 0.0],
+//!     [0.49, 0.5, 0.0, 0.01],
+//!     [0.01, 0.0, 0.5, 0.49],
+//!     [0.0, 0.01, 0.49, 0.5],
+//! ];
+//! let mc = FiniteMarkovChain::from((0, p, rand::thread_rng()));
+//! let metastable = pcca(&mc, 2).unwrap();
+//! let sets = metastable.assignments();
+//! assert_eq!(sets[0], sets[1]);
+//! assert_eq!(sets[2], sets[3]);
+//! assert_ne!(sets[0], sets[2]);
+//! ```
+//!
+//! [pcca]: fn.pcca.html
+
+// Traits
+use core::fmt::Debug;
+use num_traits::ToPrimitive;
+use rand::Rng;
+use rand_distr::{weighted_alias::AliasableWeight, Uniform};
+
+// Structs
+use crate::errors::MetastabilityError;
+use crate::FiniteMarkovChain;
+use ndarray::{Array1, Array2};
+
+/// Metastable sets of a finite chain, computed by [pcca].
+///
+/// [pcca]: fn.pcca.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metastability {
+    eigenvalues: Array1<f64>,
+    memberships: Array2<f64>,
+    assignments: Vec<usize>,
+    stationary_weights: Array1<f64>,
+    coarse_transition_matrix: Array2<f64>,
+}
+
+impl Metastability {
+    /// Returns the number of metastable sets.
+    #[inline]
+    pub fn nsets(&self) -> usize {
+        self.memberships.ncols()
+    }
+
+    /// Returns the largest eigenvalues of the transition matrix, one per metastable set, in decreasing order.
+    ///
+    /// A large gap after the last one indicates that the number of sets is well chosen.
+    #[inline]
+    pub fn eigenvalues(&self) -> &Array1<f64> {
+        &self.eigenvalues
+    }
+
+    /// Returns the membership of each state, by rows, to each metastable set, by columns.
+    ///
+    /// Memberships are non-negative and sum up to one for each state.
+    #[inline]
+    pub fn memberships(&self) -> &Array2<f64> {
+        &self.memberships
+    }
+
+    /// Returns the metastable set of each state, the one with the largest membership.
+    #[inline]
+    pub fn assignments(&self) -> &[usize] {
+        &self.assignments
+    }
+
+    /// Returns the states assigned to each metastable set.
+    #[inline]
+    pub fn sets(&self) -> Vec<Vec<usize>> {
+        let mut sets = vec![Vec::new(); self.nsets()];
+        for (state, &set) in self.assignments.iter().enumerate() {
+            sets[set].push(state);
+        }
+        sets
+    }
+
+    /// Returns the stationary probability of each metastable set.
+    #[inline]
+    pub fn stationary_weights(&self) -> &Array1<f64> {
+        &self.stationary_weights
+    }
+
+    /// Returns the transition matrix between metastable sets: the probability that the chain,
+    /// started from the stationary distribution restricted to a set, is in each set after one step.
+    #[inline]
+    pub fn coarse_transition_matrix(&self) -> &Array2<f64> {
+        &self.coarse_transition_matrix
+    }
+
+    /// Returns the coarse-grained chain between metastable sets, started at `set`.
+    ///
+    /// # Panics
+    ///
+    /// If `set` is not a metastable set.
+    #[inline]
+    pub fn coarse_chain<R>(&self, set: usize, rng: R) -> FiniteMarkovChain<usize, f64, R>
+    where
+        R: Rng,
+    {
+        assert!(set < self.nsets(), "The initial set must be a metastable set.");
+        FiniteMarkovChain::from((set, self.coarse_transition_matrix.clone(), rng))
+    }
+}
+
+/// Identifies `nsets` metastable sets of `mc` by PCCA with the inner simplex algorithm.
+///
+/// # Costs
+///
+/// O(n^3), where n is the size of the state space.
+///
+/// # Errors
+///
+/// If `nsets` is zero or more than the number of states, or the chain is not irreducible.
+#[inline]
+pub fn pcca<T, W, R>(mc: &FiniteMarkovChain<T, W, R>, nsets: usize) -> Result<Metastability, MetastabilityError>
+where
+    T: Debug + PartialEq + Clone,
+    W: AliasableWeight + Debug + Clone + ToPrimitive,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    let n = mc.nstates();
+    if nsets == 0 || nsets > n {
+        return Err(MetastabilityError::InvalidNumberOfSets(nsets));
+    }
+    let p = mc.transition_probabilities();
+    let pi = mc.stationary_distribution().ok_or(MetastabilityError::NotIrreducible)?;
+    if pi.iter().any(|&x| x <= 0.) {
+        return Err(MetastabilityError::NotIrreducible);
+    }
+
+    let (values, vectors) = crate::linalg::reversible_eigen(&p, &pi);
+    let eigenvalues = values.slice(ndarray::s![..nsets]).to_owned();
+    // Right eigenvectors, the first one constant
+    let mut x = vectors.slice(ndarray::s![.., ..nsets]).to_owned();
+    x.column_mut(0).fill(1.);
+
+    let representatives = inner_simplex(&x);
+    let vertices = Array2::from_shape_fn((nsets, nsets), |(i, k)| x[[representatives[i], k]]);
+    let transformation = crate::linalg::inverse(vertices).ok_or(MetastabilityError::Degenerate)?;
+    let memberships = memberships(&x.dot(&transformation));
+
+    let assignments: Vec<usize> = memberships
+        .genrows()
+        .into_iter()
+        .map(|row| (0..nsets).fold(0, |best, k| if row[k] > row[best] { k } else { best }))
+        .collect();
+    let mut stationary_weights = Array1::zeros(nsets);
+    let mut flows = Array2::zeros((nsets, nsets));
+    for i in 0..n {
+        stationary_weights[assignments[i]] += pi[i];
+        for j in 0..n {
+            flows[[assignments[i], assignments[j]]] += pi[i] * p[[i, j]];
+        }
+    }
+    if stationary_weights.iter().any(|&weight| weight == 0.) {
+        return Err(MetastabilityError::Degenerate);
+    }
+    let coarse_transition_matrix = crate::linalg::normalize_rows(flows);
+
+    Ok(Metastability {
+        eigenvalues,
+        memberships,
+        assignments,
+        stationary_weights,
+        coarse_transition_matrix,
+    })
+}
+
+/// Returns the memberships given by the coordinates `chi`, clipped at zero and renormalized.
+///
+/// States whose coordinates are all non-positive fully belong to the set of their largest coordinate.
+#[inline]
+fn memberships(chi: &Array2<f64>) -> Array2<f64> {
+    let mut memberships = chi.mapv(|c| c.max(0.));
+    for (mut row, coordinates) in memberships.genrows_mut().into_iter().zip(chi.genrows()) {
+        let total = row.sum();
+        if total > 0. {
+            row /= total;
+        } else {
+            let best = (0..coordinates.len()).fold(0, |best, k| if coordinates[k] > coordinates[best] { k } else { best });
+            row[best] = 1.;
+        }
+    }
+    memberships
+}
+
+/// Returns the indexes of the rows of `x` that are the vertices of the simplex
+/// spanned by all rows, by the inner simplex algorithm.
+#[inline]
+fn inner_simplex(x: &Array2<f64>) -> Vec<usize> {
+    let (n, k) = x.dim();
+    let norm = |row: ndarray::ArrayView1<f64>| row.dot(&row).sqrt();
+    let farthest = |y: &Array2<f64>| (0..n).fold(0, |best, i| if norm(y.row(i)) > norm(y.row(best)) { i } else { best });
+
+    let first = farthest(x);
+    let mut representatives = vec![first];
+    let origin = x.row(first).to_owned();
+    let mut y = x - &origin;
+    for _ in 1..k {
+        let next = farthest(&y);
+        representatives.push(next);
+        let direction = y.row(next).to_owned();
+        let length = norm(direction.view());
+        if length == 0. {
+            break;
+        }
+        let direction = direction / length;
+        for mut row in y.genrows_mut() {
+            let projection = row.dot(&direction);
+            row.scaled_add(-projection, &direction);
+        }
+    }
+    representatives
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn three_wells() {
+        // Three groups of two states, coupled with probability epsilon
+        let epsilon = 0.01;
+        let group = |i: usize| i / 2;
+        let p = Array2::from_shape_fn((6, 6), |(i, j)| {
+            if group(i) == group(j) {
+                (1. - epsilon) / 2.
+            } else if (group(i) + 1) % 3 == group(j) || (group(j) + 1) % 3 == group(i) {
+                epsilon / 4.
+            } else {
+                0.
+            }
+        });
+        let mc = FiniteMarkovChain::from((0, p, crate::tests::rng(1)));
+        let metastable = pcca(&mc, 3).unwrap();
+
+        let mut sets = metastable.sets();
+        sets.sort();
+        assert_eq!(sets, vec![vec![0, 1], vec![2, 3], vec![4, 5]]);
+        assert!(metastable.eigenvalues().iter().all(|&lambda| lambda > 0.95));
+        for row in metastable.memberships().genrows() {
+            assert_abs_diff_eq!(row.sum(), 1., epsilon = 1e-12);
+            assert!(row.iter().any(|&chi| chi > 0.99));
+        }
+        let coarse = metastable.coarse_transition_matrix();
+        for k in 0..3 {
+            assert_abs_diff_eq!(coarse[[k, k]], 1. - epsilon, epsilon = 1e-12);
+            assert_abs_diff_eq!(metastable.stationary_weights()[k], 1. / 3., epsilon = 1e-12);
+        }
+        assert_eq!(metastable.coarse_chain(0, crate::tests::rng(2)).nstates(), 3);
+
+        assert_eq!(pcca(&mc, 7).unwrap_err(), MetastabilityError::InvalidNumberOfSets(7));
+    }
+
+    #[test]
+    fn memberships() {
+        let chi = ndarray::array![[0.6, 0.6, -0.2], [-0.1, -0.3, -0.2]];
+        let memberships = super::memberships(&chi);
+        assert_eq!(memberships, ndarray::array![[0.5, 0.5, 0.], [1., 0., 0.]]);
+    }
+}
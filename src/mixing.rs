@@ -0,0 +1,285 @@
+//! Distances to stationarity of finite chains, and strong stationary times.
+//!
+//! For a finite chain with transition matrix `P` and stationary distribution `π`,
+//! the distances to stationarity after `t` steps, from the worst initial state, are
+//! - the total variation distance `d(t) = max_x ||P^t(x, ·) - π||_TV`, and
+//! - the separation distance `sep(t) = max_{x, y} (1 - P^t(x, y) / π(y))`.
+//!
+//! Both are computed exactly from the `t`-step transition matrices, and `d(t) <= sep(t)`.
+//!
+//! A strong stationary time `τ` is a randomized stopping time such that the state at time `τ`
+//! is stationary and independent of `τ`. Then, `sep(t) <= P(τ > t)`.
+//! Constructions of strong stationary times can be checked against the exact separation distance,
+//! or used to bound it when the state space is too large, by [strong_stationary_time].
+//!
+//! Non-reversible samplers that mix faster than reversible walks are constructed in [accelerated].
+//!
+//! # Examples
+//!
+//! Cutoff of the lazy random walk on the hypercube `{0, 1}^4`:
+//! the distances drop from almost one to almost zero in a window of order `n` around `n log(n) / 2`.
+//! ```
+//! # use ndarray::Array2;
+//! # use markovian::{FiniteMarkovChain, mixing::{separation_profile, total_variation_profile}};
+//! let n = 4;
+//! let p = Array2::from_shape_fn((1 << n, 1 << n), |(x, y)| {
+//!     match (x ^ y).count_ones() {
+//!         0 => 0.5,
+//!         1 => 0.5 / n as f64,
+//!         _ => 0.,
+//!     }
+//! });
+//! let mc = FiniteMarkovChain::from((0, p, rand::thread_rng()));
+//! let separation = separation_profile(&mc, 30).unwrap();
+//! let total_variation = total_variation_profile(&mc, 30).unwrap();
+//! for t in 0..=30 {
+//!     assert!(total_variation[t] <= separation[t] + 1e-12);
+//! }
+//! ```
+//!
+//! [strong_stationary_time]: fn.strong_stationary_time.html
+//! [accelerated]: accelerated/index.html
+
+pub mod accelerated;
+
+// Traits
+use core::fmt::Debug;
+use num_traits::ToPrimitive;
+use rand::Rng;
+use rand_distr::{weighted_alias::AliasableWeight, Uniform};
+
+// Structs
+use crate::montecarlo::FirstPassage;
+use crate::FiniteMarkovChain;
+use ndarray::{Array1, Array2};
+
+// Functions
+use crate::montecarlo::first_passage;
+
+/// Returns the separation distance of `mc` after `t` steps, from the worst initial state,
+/// or `None` if the stationary distribution is not unique.
+///
+/// States with zero stationary probability are not taken into account.
+///
+/// # Costs
+///
+/// O(s^3 log t), where s is the size of the state space.
+///
+/// # Examples
+///
+/// A chain that refreshes to a uniform state with probability one half.
+/// ```
+/// # use ndarray::array;
+/// # use markovian::{FiniteMarkovChain, mixing::separation_distance};
+/// let mc = FiniteMarkovChain::from((0, array![[0.75, 0.25], [0.25, 0.75]], rand::thread_rng()));
+/// assert!((separation_distance(&mc, 3).unwrap() - 0.125).abs() < 1e-12);
+/// ```
+#[inline]
+pub fn separation_distance<T, W, R>(mc: &FiniteMarkovChain<T, W, R>, t: usize) -> Option<f64>
+where
+    T: Debug + PartialEq + Clone,
+    W: AliasableWeight + Debug + Clone + ToPrimitive,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    let pi = mc.stationary_distribution()?;
+    Some(separation(&power(mc.transition_probabilities(), t), &pi))
+}
+
+/// Returns the total variation distance to stationarity of `mc` after `t` steps, from the worst initial state,
+/// or `None` if the stationary distribution is not unique.
+///
+/// # Costs
+///
+/// O(s^3 log t), where s is the size of the state space.
+#[inline]
+pub fn total_variation_distance<T, W, R>(mc: &FiniteMarkovChain<T, W, R>, t: usize) -> Option<f64>
+where
+    T: Debug + PartialEq + Clone,
+    W: AliasableWeight + Debug + Clone + ToPrimitive,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    let pi = mc.stationary_distribution()?;
+    Some(total_variation(&power(mc.transition_probabilities(), t), &pi))
+}
+
+/// Returns the separation distances of `mc` after `0..=t_max` steps,
+/// or `None` if the stationary distribution is not unique.
+///
+/// # Costs
+///
+/// O(s^3 t_max), where s is the size of the state space.
+#[inline]
+pub fn separation_profile<T, W, R>(mc: &FiniteMarkovChain<T, W, R>, t_max: usize) -> Option<Vec<f64>>
+where
+    T: Debug + PartialEq + Clone,
+    W: AliasableWeight + Debug + Clone + ToPrimitive,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    let pi = mc.stationary_distribution()?;
+    Some(profile(&mc.transition_probabilities(), t_max, |p_t| separation(p_t, &pi)))
+}
+
+/// Returns the total variation distances to stationarity of `mc` after `0..=t_max` steps,
+/// or `None` if the stationary distribution is not unique.
+///
+/// # Costs
+///
+/// O(s^3 t_max), where s is the size of the state space.
+#[inline]
+pub fn total_variation_profile<T, W, R>(mc: &FiniteMarkovChain<T, W, R>, t_max: usize) -> Option<Vec<f64>>
+where
+    T: Debug + PartialEq + Clone,
+    W: AliasableWeight + Debug + Clone + ToPrimitive,
+    Uniform<W>: Debug + Clone,
+    R: Rng,
+{
+    let pi = mc.stationary_distribution()?;
+    Some(profile(&mc.transition_probabilities(), t_max, |p_t| total_variation(p_t, &pi)))
+}
+
+/// Simulates `n` trajectories, each one produced by `chain_factory`, until the stopping rule
+/// produced by `rule_factory` for that trajectory stops, and returns the stopping times.
+///
+/// A stopping rule is called with each element yielded, in order, and returns `true` to stop.
+/// It can keep track of the trajectory so far, or of auxiliary randomness included in the elements.
+/// If the rule defines a strong stationary time `τ`, the survival function of the result,
+/// `P(τ > t)`, bounds the separation distance `sep(t)` from the initial state.
+///
+/// The stopping time is the first step `k >= 1` at which the rule stops.
+/// Trajectories are censored if the rule does not stop within `max_steps` steps, or if they end before.
+/// This is the [first_passage] of the decisions of the rule to `true`.
+///
+/// # Examples
+///
+/// The top-to-random shuffle of three cards: the time one step after the original bottom card
+/// reaches the top is a strong stationary time.
+/// ```
+/// # use markovian::{MarkovChain, mixing::strong_stationary_time};
+/// struct TopToRandom;
+/// impl markovian::Transition<Vec<u8>, Vec<u8>> for TopToRandom {
+///     fn sample_from<R: rand::Rng + ?Sized>(&self, deck: &Vec<u8>, rng: &mut R) -> Vec<u8> {
+///         let mut next = deck[1..].to_vec();
+///         next.insert(rng.gen_range(0..deck.len()), deck[0]);
+///         next
+///     }
+/// }
+/// let factory = || MarkovChain::new(vec![0, 1, 2], TopToRandom, rand::thread_rng());
+/// let rule_factory = || {
+///     let mut on_top = false;
+///     move |deck: &Vec<u8>| {
+///         let stop = on_top;
+///         on_top = deck[0] == 2;
+///         stop
+///     }
+/// };
+/// let times = strong_stationary_time(factory, rule_factory, 1_000, 100);
+/// println!("sep(3) <= {}", times.survival_at(3));
+/// ```
+///
+/// [first_passage]: ../montecarlo/fn.first_passage.html
+#[inline]
+pub fn strong_stationary_time<C, I, S, G>(mut chain_factory: C, mut rule_factory: S, n: usize, max_steps: usize) -> FirstPassage
+where
+    C: FnMut() -> I,
+    I: Iterator,
+    S: FnMut() -> G,
+    G: FnMut(&I::Item) -> bool,
+{
+    let stops = || {
+        let mut rule = rule_factory();
+        chain_factory().map(move |item| rule(&item))
+    };
+    first_passage(stops, |&stop: &bool| stop, n, max_steps)
+}
+
+/// Returns `p^t`, by repeated squaring.
+#[inline]
+fn power(mut p: Array2<f64>, mut t: usize) -> Array2<f64> {
+    let mut result = Array2::eye(p.nrows());
+    while t > 0 {
+        if t % 2 == 1 {
+            result = result.dot(&p);
+        }
+        t /= 2;
+        if t > 0 {
+            p = p.dot(&p);
+        }
+    }
+    result
+}
+
+/// Returns the values of `distance` at `p^t`, for `t` in `0..=t_max`.
+#[inline]
+fn profile<F>(p: &Array2<f64>, t_max: usize, distance: F) -> Vec<f64>
+where
+    F: Fn(&Array2<f64>) -> f64,
+{
+    let mut p_t = Array2::eye(p.nrows());
+    let mut distances = Vec::with_capacity(t_max + 1);
+    distances.push(distance(&p_t));
+    for _ in 0..t_max {
+        p_t = p_t.dot(p);
+        distances.push(distance(&p_t));
+    }
+    distances
+}
+
+/// Returns the separation distance between the rows of `p_t` and `pi`, from the worst row.
+#[inline]
+fn separation(p_t: &Array2<f64>, pi: &Array1<f64>) -> f64 {
+    p_t.indexed_iter()
+        .filter(|((_, y), _)| pi[*y] > 0.)
+        .map(|((_, y), &p)| 1. - p / pi[y])
+        .fold(0., f64::max)
+}
+
+/// Returns the total variation distance between the rows of `p_t` and `pi`, from the worst row.
+#[inline]
+fn total_variation(p_t: &Array2<f64>, pi: &Array1<f64>) -> f64 {
+    p_t.genrows()
+        .into_iter()
+        .map(|row| row.iter().zip(pi.iter()).map(|(p, q)| (p - q).abs()).sum::<f64>() / 2.)
+        .fold(0., f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{distributions::Raw, MarkovChain};
+    use approx::assert_abs_diff_eq;
+    use ndarray::Array2;
+
+    #[test]
+    fn refreshing_chain() {
+        // With probability p, the state is refreshed uniformly: sep(t) = (1 - p)^t and d(t) = (1 - p)^t (1 - 1 / n)
+        let (n, p) = (3, 0.4);
+        let matrix = Array2::from_shape_fn((n, n), |(x, y)| p / n as f64 + if x == y { 1. - p } else { 0. });
+        let mc = FiniteMarkovChain::from((0, matrix, crate::tests::rng(1)));
+        let separation = separation_profile(&mc, 10).unwrap();
+        let total_variation = total_variation_profile(&mc, 10).unwrap();
+        for t in 0..=10 {
+            let tail = (1. - p).powi(t as i32);
+            assert_abs_diff_eq!(separation[t], tail, epsilon = 1e-12);
+            assert_abs_diff_eq!(total_variation[t], tail * (1. - 1. / n as f64), epsilon = 1e-12);
+            assert_abs_diff_eq!(separation_distance(&mc, t).unwrap(), tail, epsilon = 1e-12);
+            assert_abs_diff_eq!(total_variation_distance(&mc, t).unwrap(), total_variation[t], epsilon = 1e-12);
+        }
+
+        // The first refresh is a strong stationary time, with P(τ > t) = sep(t)
+        let transition = |&(_, x): &(bool, usize)| {
+            let mut outcomes = vec![(1. - p, (false, x))];
+            outcomes.extend((0..n).map(|y| (p / n as f64, (true, y))));
+            Raw::new(outcomes)
+        };
+        let mut rng = crate::tests::rng(2);
+        let factory = || MarkovChain::new((false, 0), transition, crate::tests::rng(rng.gen()));
+        let times = strong_stationary_time(factory, || |&(refreshed, _): &(bool, usize)| refreshed, 5_000, 100);
+        assert_eq!(times.ncensored(), 0);
+        for (t, distance) in separation.iter().enumerate().take(5) {
+            assert_abs_diff_eq!(times.survival_at(t), distance, epsilon = 0.02);
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! Lifted chains, non-reversible samplers that mix faster than their reversible counterparts.
+//!
+//! A lifted chain augments each state with a direction of motion. It keeps moving in its
+//! direction while moves are accepted, and reverses it on rejections, so that it sweeps the state
+//! space ballistically instead of diffusively. Directions are refreshed at random, with a small
+//! probability, to make the chain irreducible and aperiodic.
+//!
+//! The stationary distribution of a lifted chain is the target distribution times the uniform
+//! distribution of directions, so that the marginal on states is the target. For a uniform target on
+//! a cycle or a path of `n` states and refresh probability of order `1 / n`, the lifted chain mixes
+//! in order `n` steps, while the reversible random walk needs order `n^2` steps,
+//! as shown by Diaconis, Holmes and Neal, and by Chen, Lovász and Pak.
+//!
+//! # Examples
+//!
+//! Mixing of the lifted and reversible walks on a cycle.
+//! ```
+//! # use markovian::mixing::{accelerated::{lifted_cycle, reversible_cycle}, total_variation_profile};
+//! let n = 21;
+//! let weights = vec![1.; n];
+//! let lifted = lifted_cycle(&weights, 1. / n as f64, rand::thread_rng());
+//! let reversible = reversible_cycle(&weights, rand::thread_rng());
+//! let mixing_time = |profile: Vec<f64>| profile.iter().position(|&d| d < 0.25).unwrap();
+//! assert!(
+//!     mixing_time(total_variation_profile(&lifted, 1_000).unwrap())
+//!         < mixing_time(total_variation_profile(&reversible, 1_000).unwrap())
+//! );
+//! ```
+
+// Traits
+use rand::Rng;
+
+// Structs
+use crate::FiniteMarkovChain;
+use ndarray::Array2;
+
+/// Direction of motion of a lifted chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Towards increasing states.
+    Forward,
+    /// Towards decreasing states.
+    Backward,
+}
+
+impl Direction {
+    /// Returns the opposite direction.
+    #[inline]
+    pub fn reversed(self) -> Self {
+        match self {
+            Direction::Forward => Direction::Backward,
+            Direction::Backward => Direction::Forward,
+        }
+    }
+}
+
+/// Returns the lifted Metropolis chain on the path `0..weights.len()`, with stationary distribution
+/// proportional to `weights` on positions, started at `(0, Direction::Forward)`.
+///
+/// From `(x, d)`, with probability `refresh`, the direction is drawn uniformly at random.
+/// Otherwise, the move to the neighbour of `x` in direction `d` is accepted
+/// with the Metropolis probability, and if it is rejected, the direction is reversed.
+/// Moves beyond the ends of the path are always rejected.
+///
+/// # Panics
+///
+/// If `weights` is empty or has a non-positive or non-finite weight, or `refresh` is not a probability.
+///
+/// # Examples
+///
+/// ```
+/// # use markovian::mixing::accelerated::{lifted_line, Direction};
+/// let mc = lifted_line(&[1., 2., 3.], 0.1, rand::thread_rng());
+/// assert_eq!(mc.state_space()[0], (0, Direction::Forward));
+/// let pi = mc.stationary_distribution().unwrap();
+/// assert!((pi[4] + pi[5] - 0.5).abs() < 1e-12);
+/// ```
+#[inline]
+pub fn lifted_line<R>(weights: &[f64], refresh: f64, rng: R) -> FiniteMarkovChain<(usize, Direction), f64, R>
+where
+    R: Rng,
+{
+    lifted(weights, refresh, false, rng)
+}
+
+/// Returns the lifted Metropolis chain on the cycle `0..weights.len()`, with stationary distribution
+/// proportional to `weights` on positions, started at `(0, Direction::Forward)`.
+///
+/// As [lifted_line], but the neighbours of the last position and the first one are each other.
+///
+/// # Panics
+///
+/// If `weights` is empty or has a non-positive or non-finite weight, or `refresh` is not a probability.
+///
+/// [lifted_line]: fn.lifted_line.html
+#[inline]
+pub fn lifted_cycle<R>(weights: &[f64], refresh: f64, rng: R) -> FiniteMarkovChain<(usize, Direction), f64, R>
+where
+    R: Rng,
+{
+    lifted(weights, refresh, true, rng)
+}
+
+/// Returns the lifted walk on the torus `{0, .., side - 1}^dim`, with uniform stationary distribution
+/// on positions, started at the origin moving forward along the first axis.
+///
+/// Directions are an axis and a sign. From `(x, (axis, d))`, with probability `refresh`,
+/// the direction is drawn uniformly at random among the `2 dim` directions.
+/// Otherwise, the chain moves to the neighbour of `x` in that direction.
+///
+/// # Panics
+///
+/// If `side` or `dim` is zero, or `refresh` is not a probability.
+///
+/// # Costs
+///
+/// The state space has `2 dim side^dim` states.
+#[inline]
+pub fn lifted_torus<R>(side: usize, dim: usize, refresh: f64, rng: R) -> FiniteMarkovChain<(Vec<usize>, (usize, Direction)), f64, R>
+where
+    R: Rng,
+{
+    assert!(side > 0 && dim > 0, "The torus must have positive side and dimension.");
+    assert_refresh(refresh);
+    let npositions = side.pow(dim as u32);
+    let ndirections = 2 * dim;
+    let position = |mut index: usize| -> Vec<usize> {
+        (0..dim)
+            .map(|_| {
+                let coordinate = index % side;
+                index /= side;
+                coordinate
+            })
+            .collect()
+    };
+    let direction = |k: usize| (k / 2, if k % 2 == 0 { Direction::Forward } else { Direction::Backward });
+    let step = |x: usize, (axis, d): (usize, Direction)| {
+        let stride = side.pow(axis as u32);
+        let coordinate = (x / stride) % side;
+        let moved = match d {
+            Direction::Forward => (coordinate + 1) % side,
+            Direction::Backward => (coordinate + side - 1) % side,
+        };
+        x - coordinate * stride + moved * stride
+    };
+
+    let mut p = Array2::<f64>::zeros((npositions * ndirections, npositions * ndirections));
+    for x in 0..npositions {
+        for k in 0..ndirections {
+            let i = x * ndirections + k;
+            for l in 0..ndirections {
+                p[[i, x * ndirections + l]] += refresh / ndirections as f64;
+            }
+            p[[i, step(x, direction(k)) * ndirections + k]] += 1. - refresh;
+        }
+    }
+    let state_space = (0..npositions * ndirections)
+        .map(|i| (position(i / ndirections), direction(i % ndirections)))
+        .collect();
+    FiniteMarkovChain::from((0, p, state_space, rng))
+}
+
+/// Returns the reversible Metropolis chain on the path `0..weights.len()`, with stationary distribution
+/// proportional to `weights`, started at `0`.
+///
+/// It proposes each neighbour with probability one half, and is the reversible counterpart of [lifted_line].
+///
+/// # Panics
+///
+/// If `weights` is empty or has a non-positive or non-finite weight.
+///
+/// [lifted_line]: fn.lifted_line.html
+#[inline]
+pub fn reversible_line<R>(weights: &[f64], rng: R) -> FiniteMarkovChain<usize, f64, R>
+where
+    R: Rng,
+{
+    reversible(weights, false, rng)
+}
+
+/// Returns the reversible Metropolis chain on the cycle `0..weights.len()`, with stationary distribution
+/// proportional to `weights`, started at `0`.
+///
+/// It proposes each neighbour with probability one half, and is the reversible counterpart of [lifted_cycle].
+/// For uniform weights, it is periodic if the cycle has an even number of states.
+///
+/// # Panics
+///
+/// If `weights` is empty or has a non-positive or non-finite weight.
+///
+/// [lifted_cycle]: fn.lifted_cycle.html
+#[inline]
+pub fn reversible_cycle<R>(weights: &[f64], rng: R) -> FiniteMarkovChain<usize, f64, R>
+where
+    R: Rng,
+{
+    reversible(weights, true, rng)
+}
+
+#[inline]
+fn lifted<R>(weights: &[f64], refresh: f64, periodic: bool, rng: R) -> FiniteMarkovChain<(usize, Direction), f64, R>
+where
+    R: Rng,
+{
+    assert_weights(weights);
+    assert_refresh(refresh);
+    let n = weights.len();
+    let index = |x: usize, d: Direction| 2 * x + if d == Direction::Forward { 0 } else { 1 };
+
+    let mut p = Array2::<f64>::zeros((2 * n, 2 * n));
+    for x in 0..n {
+        for &d in &[Direction::Forward, Direction::Backward] {
+            let i = index(x, d);
+            p[[i, index(x, Direction::Forward)]] += refresh / 2.;
+            p[[i, index(x, Direction::Backward)]] += refresh / 2.;
+            let acceptance = match neighbour(x, d, n, periodic) {
+                Some(y) => {
+                    let acceptance = (weights[y] / weights[x]).min(1.);
+                    p[[i, index(y, d)]] += (1. - refresh) * acceptance;
+                    acceptance
+                }
+                None => 0.,
+            };
+            p[[i, index(x, d.reversed())]] += (1. - refresh) * (1. - acceptance);
+        }
+    }
+    let state_space = (0..n)
+        .flat_map(|x| vec![(x, Direction::Forward), (x, Direction::Backward)])
+        .collect();
+    FiniteMarkovChain::from((0, p, state_space, rng))
+}
+
+#[inline]
+fn reversible<R>(weights: &[f64], periodic: bool, rng: R) -> FiniteMarkovChain<usize, f64, R>
+where
+    R: Rng,
+{
+    assert_weights(weights);
+    let n = weights.len();
+    let mut proposal = Array2::<f64>::zeros((n, n));
+    for x in 0..n {
+        for &d in &[Direction::Forward, Direction::Backward] {
+            let y = neighbour(x, d, n, periodic).unwrap_or(x);
+            proposal[[x, y]] += 0.5;
+        }
+    }
+    FiniteMarkovChain::from((0, proposal, rng)).metropolize(weights)
+}
+
+/// Returns the neighbour of `x` in direction `d`, or `None` if it is beyond the ends of a path.
+#[inline]
+fn neighbour(x: usize, d: Direction, n: usize, periodic: bool) -> Option<usize> {
+    match d {
+        Direction::Forward if x + 1 < n => Some(x + 1),
+        Direction::Backward if x > 0 => Some(x - 1),
+        Direction::Forward if periodic => Some(0),
+        Direction::Backward if periodic => Some(n - 1),
+        _ => None,
+    }
+}
+
+#[inline]
+fn assert_weights(weights: &[f64]) {
+    assert!(
+        !weights.is_empty() && weights.iter().all(|&w| w > 0. && w.is_finite()),
+        "Weights must be positive and finite."
+    );
+}
+
+#[inline]
+fn assert_refresh(refresh: f64) {
+    assert!((0. ..=1.).contains(&refresh), "The refresh probability must be in [0, 1].");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mixing::total_variation_profile;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn stationary_marginals() {
+        let weights = [1., 3., 2., 4.];
+        let total: f64 = weights.iter().sum();
+        for mc in [lifted_line(&weights, 0.2, crate::tests::rng(1)), lifted_cycle(&weights, 0.2, crate::tests::rng(2))] {
+            let pi = mc.stationary_distribution().unwrap();
+            for (x, w) in weights.iter().enumerate() {
+                assert_abs_diff_eq!(pi[2 * x], w / total / 2., epsilon = 1e-12);
+                assert_abs_diff_eq!(pi[2 * x + 1], w / total / 2., epsilon = 1e-12);
+            }
+        }
+        let pi = reversible_line(&weights, crate::tests::rng(3)).stationary_distribution().unwrap();
+        for (x, w) in weights.iter().enumerate() {
+            assert_abs_diff_eq!(pi[x], w / total, epsilon = 1e-12);
+        }
+
+        let torus = lifted_torus(3, 2, 0.1, crate::tests::rng(4));
+        assert_eq!(torus.nstates(), 36);
+        let pi = torus.stationary_distribution().unwrap();
+        assert!(pi.iter().all(|&p| (p - 1. / 36.).abs() < 1e-12));
+    }
+
+    #[test]
+    fn faster_mixing() {
+        // Order n against order n^2 steps to mix
+        let n = 31;
+        let weights = vec![1.; n];
+        let mixing_time = |profile: Vec<f64>| profile.iter().position(|&d| d < 0.25).unwrap();
+        let lifted = mixing_time(total_variation_profile(&lifted_cycle(&weights, 1. / n as f64, crate::tests::rng(1)), 2_000).unwrap());
+        let reversible = mixing_time(total_variation_profile(&reversible_cycle(&weights, crate::tests::rng(2)), 2_000).unwrap());
+        assert!(2 * lifted < reversible, "lifted: {}, reversible: {}", lifted, reversible);
+    }
+}
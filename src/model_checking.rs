@@ -0,0 +1,241 @@
+//! Exact reachability probabilities, as in probabilistic model checkers.
+//!
+//! The probability of eventually reaching a set of target states is the minimal
+//! solution of a linear system, while bounded reachability, within a number of steps
+//! or an amount of time, is computed by iterating the transition matrix or by
+//! uniformization. These exact answers are useful to validate simulations.
+//!
+//! # Examples
+//!
+//! Gambler's ruin: probability of reaching 3 before 0, starting from every fortune.
+//! ```
+//! # use ndarray::array;
+//! # use markovian::{FiniteMarkovChain, model_checking::Reachability};
+//! let transition_matrix = array![
+//!     [1.0, 0.0, 0.0, 0.0],
+//!     [0.5, 0.0, 0.5, 0.0],
+//!     [0.0, 0.5, 0.0, 0.5],
+//!     [0.0, 0.0, 0.0, 1.0],
+//! ];
+//! let mc = FiniteMarkovChain::from((1, transition_matrix, rand::thread_rng()));
+//! let probabilities = mc.prob_reach(&[3], None).unwrap();
+//! assert!((probabilities[1] - 1. / 3.).abs() < 1e-12);
+//! let probabilities = mc.prob_reach(&[3], Some(2)).unwrap();
+//! assert!((probabilities[1] - 1. / 4.).abs() < 1e-12);
+//! ```
+
+// Traits
+use core::fmt::Debug;
+use num_traits::Float;
+use rand::Rng;
+use rand_distr::{weighted_alias::AliasableWeight, Distribution, Uniform};
+
+// Structs
+use crate::errors::InvalidState;
+use crate::{ContFiniteMarkovChain, FiniteMarkovChain};
+use ndarray::{Array1, Array2};
+use rand_distr::Exp1;
+
+/// Probabilities of reaching a set of states.
+pub trait Reachability {
+    type State: Debug;
+
+    /// Returns, for each state index, the probability of reaching some state in `target`
+    /// starting from that state.
+    ///
+    /// With `horizon` equal to `Some(n)`, only the first `n` jumps are considered.
+    /// Otherwise, the probability of eventually reaching `target` is returned.
+    ///
+    /// # Errors
+    ///
+    /// If some state in `target` is not in the state space.
+    fn prob_reach(&self, target: &[Self::State], horizon: Option<usize>) -> Result<Array1<f64>, InvalidState<Self::State>>;
+}
+
+/// Probabilities of reaching a set of states within a time horizon, for continuous time processes.
+pub trait TimedReachability: Reachability {
+    /// Returns, for each state index, the probability of reaching some state in `target`
+    /// within time `time`, starting from that state.
+    ///
+    /// # Errors
+    ///
+    /// If some state in `target` is not in the state space.
+    fn prob_reach_within_time(&self, target: &[Self::State], time: f64) -> Result<Array1<f64>, InvalidState<Self::State>>;
+}
+
+impl<T, W, R> Reachability for FiniteMarkovChain<T, W, R>
+where
+    W: AliasableWeight + Debug + Clone + num_traits::ToPrimitive,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type State = T;
+
+    #[inline]
+    fn prob_reach(&self, target: &[T], horizon: Option<usize>) -> Result<Array1<f64>, InvalidState<T>> {
+        let target = indicator(self.state_space(), target)?;
+        Ok(reach_probabilities(&self.transition_probabilities(), &target, horizon))
+    }
+}
+
+impl<T, W, R> Reachability for ContFiniteMarkovChain<T, W, R>
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    type State = T;
+
+    /// The horizon counts jumps of the chain.
+    #[inline]
+    fn prob_reach(&self, target: &[T], horizon: Option<usize>) -> Result<Array1<f64>, InvalidState<T>> {
+        let target = indicator(self.state_space(), target)?;
+        Ok(reach_probabilities(&jump_matrix(&generator(self)), &target, horizon))
+    }
+}
+
+impl<T, W, R> TimedReachability for ContFiniteMarkovChain<T, W, R>
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+{
+    #[inline]
+    fn prob_reach_within_time(&self, target: &[T], time: f64) -> Result<Array1<f64>, InvalidState<T>> {
+        let target = indicator(self.state_space(), target)?;
+        let mut q = generator(self);
+        for (i, _) in target.iter().enumerate().filter(|(_, &is_target)| is_target) {
+            q.row_mut(i).fill(0.0);
+        }
+        let p = crate::linalg::expm_generator(&q, time);
+        Ok(p.genrows()
+            .into_iter()
+            .map(|row| row.iter().zip(&target).filter(|(_, &is_target)| is_target).map(|(p, _)| p).sum())
+            .collect())
+    }
+}
+
+/// Returns the indicator of `target` over the state space.
+#[inline]
+fn indicator<T>(state_space: &[T], target: &[T]) -> Result<Vec<bool>, InvalidState<T>>
+where
+    T: Debug + PartialEq + Clone,
+{
+    let mut indicator = vec![false; state_space.len()];
+    for state in target {
+        match state_space.iter().position(|s| s == state) {
+            Some(i) => indicator[i] = true,
+            None => return Err(InvalidState::new(state.clone())),
+        }
+    }
+    Ok(indicator)
+}
+
+/// Returns the generator of `mc` in `f64` precision.
+#[inline]
+fn generator<T, W, R>(mc: &ContFiniteMarkovChain<T, W, R>) -> Array2<f64>
+where
+    W: Float + AliasableWeight,
+    Exp1: Distribution<W>,
+    R: Rng,
+{
+    mc.generator().mapv(|w| w.to_f64().unwrap())
+}
+
+/// Returns the transition matrix of the jump chain of the generator `q`.
+/// States without exit rate are absorbing.
+#[inline]
+fn jump_matrix(q: &Array2<f64>) -> Array2<f64> {
+    let n = q.nrows();
+    let mut p = q.clone();
+    for i in 0..n {
+        let rate = -q[[i, i]];
+        if rate > 0.0 {
+            p[[i, i]] = 0.0;
+            p.row_mut(i).mapv_inplace(|x| x / rate);
+        } else {
+            p.row_mut(i).fill(0.0);
+            p[[i, i]] = 1.0;
+        }
+    }
+    p
+}
+
+/// Returns the probabilities of reaching `target`, within `horizon` steps if any,
+/// for the chain with transition matrix `p`.
+#[inline]
+fn reach_probabilities(p: &Array2<f64>, target: &[bool], horizon: Option<usize>) -> Array1<f64> {
+    let n = p.nrows();
+    let start = Array1::from_shape_fn(n, |i| if target[i] { 1.0 } else { 0.0 });
+    match horizon {
+        Some(steps) => {
+            let mut x = start;
+            for _ in 0..steps {
+                let next = p.dot(&x);
+                x = Array1::from_shape_fn(n, |i| if target[i] { 1.0 } else { next[i] });
+            }
+            x
+        }
+        None => {
+            let reachable = crate::linalg::backward_reachable(p, target);
+            let unknown: Vec<usize> = (0..n).filter(|&i| reachable[i] && !target[i]).collect();
+            let m = unknown.len();
+            let a = Array2::from_shape_fn((m, m), |(k, l)| {
+                let identity = if k == l { 1.0 } else { 0.0 };
+                identity - p[[unknown[k], unknown[l]]]
+            });
+            let b = Array1::from_shape_fn(m, |k| {
+                (0..n).filter(|&j| target[j]).map(|j| p[[unknown[k], j]]).sum()
+            });
+            let solution = crate::linalg::solve(a, b)
+                .expect("Every transient state reaches the target with positive probability.");
+            let mut x = start;
+            for (k, &i) in unknown.iter().enumerate() {
+                x[i] = solution[k].clamp(0.0, 1.0);
+            }
+            x
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn discrete() {
+        let transition_matrix = vec![
+            vec![1.0, 0.0, 0.0],
+            vec![0.3, 0.2, 0.5],
+            vec![0.0, 0.0, 1.0],
+        ];
+        let mc = FiniteMarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(1, transition_matrix, vec!['a', 'b', 'c'], 1);
+        let probabilities = mc.prob_reach(&['c'], None).unwrap();
+
+        assert_abs_diff_eq!(probabilities[0], 0.);
+        assert_abs_diff_eq!(probabilities[1], 0.5 / 0.8, epsilon = 1e-12);
+        assert_abs_diff_eq!(probabilities[2], 1.);
+
+        let probabilities = mc.prob_reach(&['c'], Some(2)).unwrap();
+        assert_abs_diff_eq!(probabilities[1], 0.5 + 0.2 * 0.5, epsilon = 1e-12);
+        assert_eq!(mc.prob_reach(&['d'], None), Err(InvalidState::new('d')));
+    }
+
+    #[test]
+    fn continuous() {
+        let rate = 2.0;
+        let mc = ContFiniteMarkovChain::new(0, vec![vec![0., rate], vec![0., 1.]], vec![0, 1], crate::tests::rng(1));
+        let time = 0.3;
+        let probabilities = mc.prob_reach_within_time(&[1], time).unwrap();
+
+        assert_abs_diff_eq!(probabilities[0], 1. - (-rate * time).exp(), epsilon = 1e-12);
+        assert_eq!(mc.prob_reach(&[1], None).unwrap(), array![1., 1.]);
+        assert_eq!(mc.prob_reach(&[1], Some(0)).unwrap(), array![0., 1.]);
+    }
+}
@@ -0,0 +1,681 @@
+//! Variance reduction for Monte Carlo estimates over trajectories.
+//!
+//! Antithetic trajectories are produced by [antithetic_estimate] and known expectations,
+//! like those computed exactly for finite chains, can be used by [control_variate].
+//! Initial states can be sampled by strata with [stratified_estimate].
+//! Censored hitting times are handled by [first_passage].
+//!
+//! # Examples
+//!
+//! Expected maximum of a random walk over 20 steps, with antithetic trajectories.
+//! ```
+//! # use rand::SeedableRng;
+//! # use markovian::{MarkovChain, prelude::*, montecarlo::{antithetic_estimate, Antithetic}};
+//! let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+//! let rng = Antithetic::new(rand_pcg::Pcg64::seed_from_u64(1));
+//! let mc = MarkovChain::new(0, transition, rng);
+//! let estimate = antithetic_estimate(mc, 1_000, |path| path.take(20).max().unwrap() as f64);
+//! println!("Expected maximum: {} ± {}", estimate.mean, estimate.standard_error);
+//! ```
+//!
+//! [antithetic_estimate]: fn.antithetic_estimate.html
+//! [control_variate]: fn.control_variate.html
+//! [first_passage]: fn.first_passage.html
+//! [stratified_estimate]: fn.stratified_estimate.html
+
+// Traits
+use crate::{stats::Welford, RandomSource, State};
+use core::fmt::Debug;
+use rand::{seq::SliceRandom, Rng, RngCore, SeedableRng};
+
+// Structs
+use crate::errors::InvalidState;
+
+/// Monte Carlo estimate of an expectation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    /// Estimate of the expectation.
+    pub mean: f64,
+    /// Standard error of `mean`.
+    pub standard_error: f64,
+    /// Number of independent samples used.
+    pub samples: usize,
+}
+
+impl From<Welford> for Estimate {
+    #[inline]
+    fn from(statistics: Welford) -> Self {
+        Estimate {
+            mean: statistics.mean(),
+            standard_error: statistics.standard_error(),
+            samples: statistics.count(),
+        }
+    }
+}
+
+/// Random number generator that can produce the antithetic values of an inner generator.
+///
+/// When flipped, every random word `x` of the inner generator is replaced by its bitwise
+/// complement `!x`, so that uniform samples `u` become (up to rounding) `1 - u`.
+/// Samples by inversion, like those of `raw_dist!` or exponential waiting times,
+/// are then negatively correlated with the original ones.
+#[derive(Debug, Clone)]
+pub struct Antithetic<R> {
+    rng: R,
+    flipped: bool,
+}
+
+impl<R> Antithetic<R> {
+    /// Constructs a new `Antithetic` producing the values of `rng`.
+    #[inline]
+    pub fn new(rng: R) -> Self {
+        Antithetic { rng, flipped: false }
+    }
+
+    /// Constructs a new `Antithetic` producing the antithetic values of `rng`.
+    #[inline]
+    pub fn flipped(rng: R) -> Self {
+        Antithetic { rng, flipped: true }
+    }
+
+    /// Returns `true` if the antithetic values are produced.
+    #[inline]
+    pub fn is_flipped(&self) -> bool {
+        self.flipped
+    }
+}
+
+impl<R> RngCore for Antithetic<R>
+where
+    R: RngCore,
+{
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let word = self.rng.next_u32();
+        if self.flipped { !word } else { word }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let word = self.rng.next_u64();
+        if self.flipped { !word } else { word }
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+        if self.flipped {
+            for byte in dest.iter_mut() {
+                *byte = !*byte;
+            }
+        }
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl<R> SeedableRng for Antithetic<R>
+where
+    R: SeedableRng,
+{
+    type Seed = R::Seed;
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Antithetic::new(R::from_seed(seed))
+    }
+}
+
+/// Estimates the expectation of `f` over trajectories of `process` from `pairs` pairs
+/// of antithetic trajectories.
+///
+/// For each pair, `process` is cloned twice and both clones get a random number generator
+/// seeded with the same seed, taken from the random number generator of `process`,
+/// but the second one is flipped. Then, `f` receives each clone as an iterator and
+/// the average of both values is one sample of the estimate.
+///
+/// The variance is reduced when `f` is a monotone functional of the trajectory,
+/// for example the final position or the maximum of a random walk.
+///
+/// # Panics
+///
+/// If `pairs` is zero.
+#[inline]
+pub fn antithetic_estimate<P, T, R, F>(mut process: P, pairs: usize, mut f: F) -> Estimate
+where
+    P: Iterator<Item = T> + RandomSource<Rng = Antithetic<R>> + Clone,
+    R: RngCore + SeedableRng,
+    F: FnMut(&mut dyn Iterator<Item = T>) -> f64,
+{
+    assert!(pairs > 0, "There must be at least one pair of trajectories.");
+    let mut statistics = Welford::new();
+    for _ in 0..pairs {
+        let seed: u64 = process.rng_mut().gen();
+        let mut original = process.clone();
+        original.set_rng(Antithetic::new(R::seed_from_u64(seed)));
+        let mut antithetic = process.clone();
+        antithetic.set_rng(Antithetic::flipped(R::seed_from_u64(seed)));
+        statistics.push((f(&mut original) + f(&mut antithetic)) / 2.);
+    }
+    statistics.into()
+}
+
+/// Estimates an expectation from `samples`, using `control_samples` of a quantity whose
+/// expectation `control_mean` is known exactly, and which were sampled jointly with `samples`.
+///
+/// The estimate is the mean of `y - β (c - control_mean)` over pairs of samples `y` and controls `c`,
+/// where the coefficient `β`, which minimizes the variance, is estimated from the samples.
+/// The more correlated the controls are with the samples, the larger the variance reduction.
+///
+/// # Panics
+///
+/// If `samples` and `control_samples` have different lengths, or there are less than two samples.
+///
+/// # Examples
+///
+/// Expected maximum of a random walk over 10 steps, controlled by its final position,
+/// whose expectation is computed exactly by [n_step_expectation].
+/// ```
+/// # use ndarray::Array2;
+/// # use rand::SeedableRng;
+/// # use markovian::{FiniteMarkovChain, RandomSource, montecarlo::control_variate};
+/// let transition = Array2::from_shape_fn((21, 21), |(i, j)| {
+///     if (i as i32 - j as i32).abs() == 1 || (i == j && (i == 0 || i == 20)) { 1. } else { 0. }
+/// });
+/// let mut mc = FiniteMarkovChain::from((10, transition, rand_pcg::Pcg64::seed_from_u64(1)));
+/// let control_mean = mc.n_step_expectation(10, |&state| state as f64);
+/// let (samples, controls): (Vec<f64>, Vec<f64>) = (0..1_000)
+///     .map(|_| {
+///         let path: Vec<usize> = mc.fork().take(10).collect();
+///         (*path.iter().max().unwrap() as f64, path[9] as f64)
+///     })
+///     .unzip();
+/// let estimate = control_variate(&samples, &controls, control_mean);
+/// println!("Expected maximum: {} ± {}", estimate.mean, estimate.standard_error);
+/// ```
+///
+/// [n_step_expectation]: ../struct.FiniteMarkovChain.html#method.n_step_expectation
+#[inline]
+pub fn control_variate(samples: &[f64], control_samples: &[f64], control_mean: f64) -> Estimate {
+    assert_eq!(samples.len(), control_samples.len(), "There must be one control for each sample.");
+    assert!(samples.len() > 1, "There must be at least two samples.");
+    let n = samples.len() as f64;
+    let sample_mean = samples.iter().sum::<f64>() / n;
+    let control_sample_mean = control_samples.iter().sum::<f64>() / n;
+    let (covariance, control_variance) = samples.iter()
+        .zip(control_samples)
+        .fold((0., 0.), |(covariance, variance), (y, c)| {
+            let dc = c - control_sample_mean;
+            (covariance + (y - sample_mean) * dc, variance + dc * dc)
+        });
+    let beta = if control_variance > 0. { covariance / control_variance } else { 0. };
+    samples.iter()
+        .zip(control_samples)
+        .map(|(y, c)| y - beta * (c - control_mean))
+        .collect::<Welford>()
+        .into()
+}
+
+/// Estimates the expectation of `f` over trajectories of `process` started from a random
+/// initial state, by stratified sampling over the initial state.
+///
+/// Each stratum is given as `(weight, initial_state, trajectories)`: the probability of the
+/// initial state, the initial state and the number of trajectories to simulate from it.
+/// Weights are normalized. Each trajectory is a [fork] of `process` whose state is
+/// changed to the initial state of its stratum, and `f` receives it as an iterator.
+///
+/// The estimate is the weighted sum of the means of each stratum, which removes the
+/// variance due to the initial state. The number of samples of the estimate is the total
+/// number of trajectories.
+///
+/// # Errors
+///
+/// If `process` does not accept some initial state.
+///
+/// # Panics
+///
+/// If some stratum has less than two trajectories, some weight is negative,
+/// or all weights are zero.
+///
+/// # Examples
+///
+/// Expected position after 10 steps of a random walk started uniformly in {0, 5, 10}.
+/// ```
+/// # use markovian::{MarkovChain, prelude::*, montecarlo::stratified_estimate};
+/// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+/// let mc = MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 1);
+/// let strata = vec![(1., 0, 100), (1., 5, 100), (1., 10, 100)];
+/// let estimate = stratified_estimate(mc, strata, |path| path.nth(9).unwrap() as f64).unwrap();
+/// assert!((estimate.mean - 5.).abs() < 4. * estimate.standard_error);
+/// ```
+///
+/// [fork]: ../trait.RandomSource.html#method.fork
+#[inline]
+pub fn stratified_estimate<P, T, I, F>(mut process: P, strata: I, mut f: F) -> Result<Estimate, InvalidState<T>>
+where
+    P: Iterator<Item = T> + State<Item = T> + RandomSource + Clone,
+    P::Rng: SeedableRng,
+    T: Debug + Clone,
+    I: IntoIterator<Item = (f64, T, usize)>,
+    F: FnMut(&mut dyn Iterator<Item = T>) -> f64,
+{
+    let mut strata_statistics = Vec::new();
+    for (weight, initial_state, trajectories) in strata {
+        assert!(weight >= 0., "Weights must be non-negative.");
+        assert!(trajectories > 1, "Each stratum must have at least two trajectories.");
+        let mut statistics = Welford::new();
+        for _ in 0..trajectories {
+            let mut trajectory = process.fork();
+            trajectory.set_state(initial_state.clone())?;
+            statistics.push(f(&mut trajectory));
+        }
+        strata_statistics.push((weight, statistics));
+    }
+    let total_weight: f64 = strata_statistics.iter().map(|(weight, _)| weight).sum();
+    assert!(total_weight > 0., "Some weight must be positive.");
+    let (mean, variance, samples) = strata_statistics.iter()
+        .fold((0., 0., 0), |(mean, variance, samples), (weight, statistics)| {
+            let w = weight / total_weight;
+            (
+                mean + w * statistics.mean(),
+                variance + w * w * statistics.variance() / statistics.count() as f64,
+                samples + statistics.count(),
+            )
+        });
+    Ok(Estimate {
+        mean,
+        standard_error: variance.sqrt(),
+        samples,
+    })
+}
+
+/// Returns `n` points of the unit hypercube of dimension `dimension`, by Latin hypercube sampling.
+///
+/// In every coordinate, each of the `n` intervals `[i / n, (i + 1) / n)` contains exactly one point.
+/// Points can be mapped to initial states or parameters, for example by inverse distribution functions,
+/// to cover their range better than independent samples.
+///
+/// # Examples
+///
+/// Ten pairs of parameters, uniform in [0, 1] x [1, 3].
+/// ```
+/// # use markovian::montecarlo::latin_hypercube;
+/// let points = latin_hypercube(10, 2, &mut rand::thread_rng());
+/// let parameters: Vec<(f64, f64)> = points.iter().map(|x| (x[0], 1. + 2. * x[1])).collect();
+/// assert_eq!(parameters.len(), 10);
+/// ```
+#[inline]
+pub fn latin_hypercube<R>(n: usize, dimension: usize, rng: &mut R) -> Vec<Vec<f64>>
+where
+    R: Rng + ?Sized,
+{
+    let mut points = vec![vec![0.; dimension]; n];
+    let mut strata: Vec<usize> = (0..n).collect();
+    for coordinate in 0..dimension {
+        strata.shuffle(rng);
+        for (point, stratum) in points.iter_mut().zip(&strata) {
+            point[coordinate] = (*stratum as f64 + rng.gen::<f64>()) / n as f64;
+        }
+    }
+    points
+}
+
+/// Estimates the value of the current state of `process`, as the mean of `value_fn`
+/// over `rollouts` hypothetical paths of `horizon` steps.
+///
+/// Rollouts are simulated by clones of `process`, whose random number generators are seeded
+/// by the random number generator of `process`, so that `process` itself does not move.
+/// The path given to `value_fn` does not include the current state.
+///
+/// # Panics
+///
+/// If `rollouts` is zero.
+///
+/// # Examples
+///
+/// Discounted reward of a random walk, with reward one for every step above zero.
+/// ```
+/// # use markovian::{MarkovChain, prelude::*, montecarlo::evaluate_rollouts};
+/// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+/// let mut mc = MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 1);
+/// let value = |path: &[i32]| {
+///     path.iter()
+///         .enumerate()
+///         .map(|(t, &state)| if state > 0 { 0.9_f64.powi(t as i32) } else { 0. })
+///         .sum::<f64>()
+/// };
+/// let estimate = evaluate_rollouts(&mut mc, value, 1_000, 20);
+/// println!("Value: {} ± {}", estimate.mean, estimate.standard_error);
+/// assert_eq!(mc.state(), Some(&0));
+/// ```
+#[inline]
+pub fn evaluate_rollouts<P, T, V>(process: &mut P, value_fn: V, rollouts: usize, horizon: usize) -> Estimate
+where
+    P: Iterator<Item = T> + RandomSource + Clone,
+    P::Rng: SeedableRng,
+    V: Fn(&[T]) -> f64,
+{
+    assert!(rollouts > 0, "There must be at least one rollout.");
+    rollout_seeds(process, rollouts)
+        .into_iter()
+        .map(|seed| rollout_value(process, &value_fn, horizon, seed))
+        .collect::<Welford>()
+        .into()
+}
+
+/// Estimates the value of the current state of `process` as [evaluate_rollouts],
+/// simulating rollouts in parallel.
+///
+/// Results are the same as those of [evaluate_rollouts].
+///
+/// # Panics
+///
+/// If `rollouts` is zero.
+///
+/// [evaluate_rollouts]: fn.evaluate_rollouts.html
+#[cfg(feature = "rayon")]
+#[inline]
+pub fn evaluate_rollouts_parallel<P, T, V>(process: &mut P, value_fn: V, rollouts: usize, horizon: usize) -> Estimate
+where
+    P: Iterator<Item = T> + RandomSource + Clone + Sync,
+    P::Rng: SeedableRng,
+    V: Fn(&[T]) -> f64 + Sync,
+{
+    use rayon::prelude::*;
+    assert!(rollouts > 0, "There must be at least one rollout.");
+    let seeds = rollout_seeds(process, rollouts);
+    let process: &P = process;
+    let values: Vec<f64> = seeds
+        .into_par_iter()
+        .map(|seed| rollout_value(process, &value_fn, horizon, seed))
+        .collect();
+    values.into_iter().collect::<Welford>().into()
+}
+
+/// Hitting times of a target by independent trajectories, some of which may be censored.
+///
+/// This struct is created by [first_passage].
+///
+/// [first_passage]: fn.first_passage.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirstPassage {
+    samples: Vec<(usize, bool)>,
+}
+
+impl FirstPassage {
+    /// Returns, for each trajectory, the number of steps observed and whether the target was hit at the last one.
+    ///
+    /// Trajectories that did not hit the target are censored: their hitting time is
+    /// only known to be greater than the number of steps observed.
+    #[inline]
+    pub fn samples(&self) -> &[(usize, bool)] {
+        &self.samples
+    }
+
+    /// Returns the hitting times of the trajectories that hit the target.
+    #[inline]
+    pub fn hitting_times(&self) -> Vec<usize> {
+        self.samples.iter().filter(|(_, hit)| *hit).map(|(time, _)| *time).collect()
+    }
+
+    /// Returns the number of censored trajectories.
+    #[inline]
+    pub fn ncensored(&self) -> usize {
+        self.samples.iter().filter(|(_, hit)| !*hit).count()
+    }
+
+    /// Returns the Kaplan-Meier estimate of the survival function `P(τ > t)`, where `τ` is the hitting time,
+    /// as pairs `(t, survival)` at each time `t` some trajectory hit the target.
+    ///
+    /// The survival function is constant between those times. A trajectory censored at time `t`
+    /// counts as being at risk up to time `t`, included.
+    #[inline]
+    pub fn survival(&self) -> Vec<(usize, f64)> {
+        let mut samples = self.samples.clone();
+        // Hits before censorings at the same time
+        samples.sort_unstable_by_key(|&(time, hit)| (time, !hit));
+        let mut at_risk = samples.len();
+        let mut survival = 1.;
+        let mut curve: Vec<(usize, f64)> = Vec::new();
+        let mut index = 0;
+        while index < samples.len() {
+            let time = samples[index].0;
+            let same_time = samples[index..].iter().take_while(|(t, _)| *t == time).count();
+            let hits = samples[index..index + same_time].iter().filter(|(_, hit)| *hit).count();
+            if hits > 0 {
+                survival *= 1. - hits as f64 / at_risk as f64;
+                curve.push((time, survival));
+            }
+            at_risk -= same_time;
+            index += same_time;
+        }
+        curve
+    }
+
+    /// Returns the Kaplan-Meier estimate of `P(τ > time)`, where `τ` is the hitting time.
+    #[inline]
+    pub fn survival_at(&self, time: usize) -> f64 {
+        self.survival()
+            .into_iter()
+            .take_while(|(t, _)| *t <= time)
+            .last()
+            .map_or(1., |(_, survival)| survival)
+    }
+
+    /// Returns the Kaplan-Meier estimate of the restricted mean `E[min(τ, horizon)]`,
+    /// where `τ` is the hitting time, i.e. the sum of the survival function over `0..horizon`.
+    ///
+    /// # Remarks
+    ///
+    /// Beyond the largest time observed, the survival function is extended as a constant.
+    #[inline]
+    pub fn restricted_mean(&self, horizon: usize) -> f64 {
+        let mut mean = 0.;
+        let mut previous = (0, 1.);
+        for (time, survival) in self.survival() {
+            if time >= horizon {
+                break;
+            }
+            mean += (time - previous.0) as f64 * previous.1;
+            previous = (time, survival);
+        }
+        mean + (horizon - previous.0) as f64 * previous.1
+    }
+}
+
+/// Simulates `n` trajectories, each one produced by `chain_factory`, until they hit `target`,
+/// and returns their hitting times.
+///
+/// The hitting time is the first step `k >= 1` such that the `k`-th element yielded satisfies `target`.
+/// Trajectories are censored if they do not hit the target within `max_steps` steps,
+/// or if they end before.
+///
+/// # Examples
+///
+/// Hitting time of 3 by a random walk started at 0.
+/// ```
+/// # use markovian::{MarkovChain, prelude::*, montecarlo::first_passage};
+/// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+/// let factory = || MarkovChain::new(0, transition, rand::thread_rng());
+/// let passage = first_passage(factory, |state: &i32| *state == 3, 1_000, 100);
+/// println!("P(τ > 50) = {}", passage.survival_at(50));
+/// println!("{} trajectories were censored", passage.ncensored());
+/// ```
+#[inline]
+pub fn first_passage<C, I, A>(mut chain_factory: C, target: A, n: usize, max_steps: usize) -> FirstPassage
+where
+    C: FnMut() -> I,
+    I: Iterator,
+    A: Fn(&I::Item) -> bool,
+{
+    let samples = (0..n)
+        .map(|_| {
+            let mut observed = 0;
+            for item in chain_factory().take(max_steps) {
+                observed += 1;
+                if target(&item) {
+                    return (observed, true);
+                }
+            }
+            (observed, false)
+        })
+        .collect();
+    FirstPassage { samples }
+}
+
+/// Returns seeds for `rollouts` rollouts, taken from the random number generator of `process`.
+#[inline]
+fn rollout_seeds<P>(process: &mut P, rollouts: usize) -> Vec<u64>
+where
+    P: RandomSource,
+{
+    (0..rollouts).map(|_| process.rng_mut().gen()).collect()
+}
+
+/// Returns the value of a rollout of `horizon` steps of a clone of `process` seeded by `seed`.
+#[inline]
+fn rollout_value<P, T, V>(process: &P, value_fn: &V, horizon: usize, seed: u64) -> f64
+where
+    P: Iterator<Item = T> + RandomSource + Clone,
+    P::Rng: SeedableRng,
+    V: Fn(&[T]) -> f64,
+{
+    let mut rollout = process.clone();
+    rollout.set_rng(P::Rng::seed_from_u64(seed));
+    let path: Vec<T> = rollout.take(horizon).collect();
+    value_fn(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn flipped() {
+        let mut rng = Antithetic::new(crate::tests::rng(1));
+        let mut flipped = Antithetic::flipped(crate::tests::rng(1));
+        for _ in 0..100 {
+            let u: f64 = rng.gen();
+            let v: f64 = flipped.gen();
+            assert_abs_diff_eq!(u + v, 1., epsilon = 1e-15);
+        }
+    }
+
+    #[test]
+    fn variance_reduction() {
+        let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+        let mc = crate::MarkovChain::<_, _, Antithetic<rand_pcg::Pcg64>>::with_seed(0, transition, 2);
+        let estimate = antithetic_estimate(mc, 100, |path| path.nth(9).unwrap() as f64);
+
+        // Each antithetic pair of symmetric walks ends at opposite positions
+        assert_abs_diff_eq!(estimate.mean, 0.);
+        assert_abs_diff_eq!(estimate.standard_error, 0.);
+        assert_eq!(estimate.samples, 100);
+    }
+
+    #[test]
+    fn control() {
+        let mut rng = crate::tests::rng(3);
+        let controls: Vec<f64> = (0..1_000).map(|_| rng.gen()).collect();
+        let samples: Vec<f64> = controls.iter().map(|c| 2. * c + 1.).collect();
+        let estimate = control_variate(&samples, &controls, 0.5);
+
+        // Perfectly correlated controls give the exact expectation
+        assert_abs_diff_eq!(estimate.mean, 2., epsilon = 1e-12);
+        assert_abs_diff_eq!(estimate.standard_error, 0., epsilon = 1e-12);
+    }
+
+    #[test]
+    fn n_step_control() {
+        let transition = ndarray::array![[0.5, 0.5, 0.], [0.25, 0.5, 0.25], [0., 0.5, 0.5]];
+        let mut mc = crate::FiniteMarkovChain::from((0, transition, rand_pcg::Pcg64::seed_from_u64(4)));
+        let control_mean = mc.n_step_expectation(5, |&state| state as f64);
+        let (samples, controls): (Vec<f64>, Vec<f64>) = (0..2_000)
+            .map(|_| {
+                let path: Vec<usize> = mc.fork().take(5).collect();
+                (path.iter().sum::<usize>() as f64, path[4] as f64)
+            })
+            .unzip();
+        let plain: Welford = samples.iter().copied().collect();
+        let estimate = control_variate(&samples, &controls, control_mean);
+
+        assert!(estimate.standard_error < plain.standard_error());
+        let exact: f64 = (1..=5).map(|n| mc.n_step_expectation(n, |&state| state as f64)).sum();
+        assert!((estimate.mean - exact).abs() < 4. * estimate.standard_error);
+    }
+
+    #[test]
+    fn stratified() {
+        let transition = |state: &i32| raw_dist![(1.0, *state)];
+        let mc = crate::MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 5);
+        let strata = vec![(0.25, 4, 2), (0.75, 8, 10)];
+        let estimate = stratified_estimate(mc, strata, |path| path.next().unwrap() as f64).unwrap();
+
+        // Constant trajectories have no variance within strata
+        assert_abs_diff_eq!(estimate.mean, 7.);
+        assert_abs_diff_eq!(estimate.standard_error, 0.);
+        assert_eq!(estimate.samples, 12);
+    }
+
+    #[test]
+    fn latin() {
+        let points = latin_hypercube(20, 3, &mut crate::tests::rng(6));
+        for coordinate in 0..3 {
+            let mut strata: Vec<usize> = points.iter().map(|x| (x[coordinate] * 20.) as usize).collect();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..20).collect::<Vec<usize>>());
+        }
+    }
+
+    #[test]
+    fn rollouts() {
+        let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+        let mut mc = crate::MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 7);
+        let estimate = evaluate_rollouts(&mut mc, |path: &[i32]| path[9] as f64, 2_000, 10);
+
+        assert_eq!(mc.state(), Some(&0));
+        assert_eq!(estimate.samples, 2_000);
+        assert!(estimate.mean.abs() < 4. * estimate.standard_error);
+        #[cfg(feature = "rayon")]
+        {
+            let mut mc = crate::MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 7);
+            assert_eq!(evaluate_rollouts_parallel(&mut mc, |path: &[i32]| path[9] as f64, 2_000, 10), estimate);
+        }
+    }
+
+    #[test]
+    fn kaplan_meier() {
+        let passage = FirstPassage { samples: vec![(1, true), (2, false), (3, true), (3, true), (5, false)] };
+
+        assert_eq!(passage.hitting_times(), vec![1, 3, 3]);
+        assert_eq!(passage.ncensored(), 2);
+        let survival = passage.survival();
+        assert_eq!(survival.len(), 2);
+        assert_abs_diff_eq!(survival[0].1, 0.8);
+        // Three at risk at time 3, after one hit and one censoring
+        assert_abs_diff_eq!(survival[1].1, 0.8 / 3.);
+        assert_abs_diff_eq!(passage.survival_at(2), 0.8);
+        assert_abs_diff_eq!(passage.restricted_mean(4), 1. + 2. * 0.8 + 0.8 / 3.);
+    }
+
+    #[test]
+    fn hitting() {
+        // Geometric hitting time with success probability 1/2
+        let mut rng = crate::tests::rng(8);
+        let factory = || {
+            let seed = rng.gen();
+            crate::MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(false, |_: &bool| raw_dist![(0.5, true), (0.5, false)], seed)
+        };
+        let passage = first_passage(factory, |state: &bool| *state, 4_000, 3);
+
+        assert!((passage.survival_at(2) - 0.25).abs() < 0.03);
+        assert!((passage.ncensored() as f64 / 4_000. - 0.125).abs() < 0.02);
+        assert!(passage.samples().iter().all(|&(time, hit)| hit || time == 3));
+    }
+}
@@ -0,0 +1,607 @@
+//! Markov state models: finite chains estimated from trajectories of continuous states.
+//!
+//! The pipeline of an [MsmBuilder] is
+//! 1. discretize each observation into one of finitely many states, with a user-supplied function,
+//!    for example the nearest center found by [KMeans], behind the `kmeans` feature,
+//! 2. count the transitions between states `lag` observations apart,
+//! 3. estimate the transition matrix at that lag, by default reversible, i.e. in detailed balance.
+//!
+//! The resulting [Msm] reports the implied timescales of the dynamics, which should not
+//! depend on the lag if the discretized process is Markovian at that lag.
+//!
+//! # Examples
+//!
+//! A particle hopping between two wells, around `-1` and `1`.
+//! ```
+//! # use markovian::msm::MsmBuilder;
+//! let trajectory = vec![-1.1, -0.9, -1.0, 0.9, 1.1, 1.0, 0.8, -0.9, -1.2, -1.0];
+//! let msm = MsmBuilder::new(|x: &f64| if *x < 0. { 0 } else { 1 })
+//!     .with_lag(1)
+//!     .build(&[trajectory])
+//!     .unwrap();
+//! assert_eq!(msm.nstates(), 2);
+//! println!("The slowest timescale is {}", msm.implied_timescales()[0]);
+//! ```
+//!
+//! [MsmBuilder]: struct.MsmBuilder.html
+//! [Msm]: struct.Msm.html
+//! [KMeans]: struct.KMeans.html
+
+// Traits
+use rand::Rng;
+
+// Structs
+use crate::errors::MsmError;
+use crate::FiniteMarkovChain;
+use ndarray::{Array1, Array2};
+
+/// Relative change of the reversible estimate below which its iterations stop.
+const REVERSIBLE_TOLERANCE: f64 = 1e-12;
+
+/// Maximum number of iterations of the reversible estimate.
+const REVERSIBLE_ITERATIONS: usize = 100_000;
+
+/// Builder of Markov state models from trajectories of observations.
+#[derive(Debug, Clone)]
+pub struct MsmBuilder<F> {
+    discretize: F,
+    lag: usize,
+    reversible: bool,
+}
+
+impl<F> MsmBuilder<F> {
+    /// Constructs a new `MsmBuilder<F>`, where `discretize` maps each observation to the index of its state,
+    /// with lag one and reversible estimation.
+    #[inline]
+    pub fn new(discretize: F) -> Self {
+        MsmBuilder {
+            discretize,
+            lag: 1,
+            reversible: true,
+        }
+    }
+
+    /// Counts transitions between observations `lag` steps apart.
+    ///
+    /// # Panics
+    ///
+    /// If `lag` is zero.
+    #[inline]
+    pub fn with_lag(mut self, lag: usize) -> Self {
+        assert!(lag > 0, "The lag must be positive.");
+        self.lag = lag;
+        self
+    }
+
+    /// Estimates a reversible chain, by maximum likelihood under detailed balance,
+    /// or the chain given by the empirical transition frequencies.
+    #[inline]
+    pub fn with_reversible(mut self, reversible: bool) -> Self {
+        self.reversible = reversible;
+        self
+    }
+
+    /// Returns the lag.
+    #[inline]
+    pub fn lag(&self) -> usize {
+        self.lag
+    }
+
+    /// Returns the matrix of transitions between states `lag` steps apart,
+    /// with as many states as the largest index observed plus one.
+    #[inline]
+    pub fn count<X>(&self, trajectories: &[Vec<X>]) -> Array2<u64>
+    where
+        F: Fn(&X) -> usize,
+    {
+        let discrete: Vec<Vec<usize>> = trajectories
+            .iter()
+            .map(|trajectory| trajectory.iter().map(&self.discretize).collect())
+            .collect();
+        let n = discrete.iter().flatten().max().map_or(0, |&max| max + 1);
+        let mut counts = Array2::zeros((n, n));
+        for trajectory in &discrete {
+            for (from, to) in trajectory.iter().zip(trajectory.iter().skip(self.lag)) {
+                counts[[*from, *to]] += 1;
+            }
+        }
+        counts
+    }
+
+    /// Discretizes `trajectories`, counts their transitions and estimates the Markov state model.
+    ///
+    /// # Errors
+    ///
+    /// If no transitions are observed, or some state is never left nor entered,
+    /// or, for non-reversible estimation, never left.
+    #[inline]
+    pub fn build<X>(&self, trajectories: &[Vec<X>]) -> Result<Msm, MsmError>
+    where
+        F: Fn(&X) -> usize,
+    {
+        let counts = self.count(trajectories);
+        if counts.iter().all(|&c| c == 0) {
+            return Err(MsmError::NoTransitions);
+        }
+        let n = counts.nrows();
+        let counts_f64 = counts.mapv(|c| c as f64);
+        let outgoing = counts_f64.sum_axis(ndarray::Axis(1));
+        let incoming = counts_f64.sum_axis(ndarray::Axis(0));
+        let transition_matrix = if self.reversible {
+            if let Some(i) = (0..n).find(|&i| outgoing[i] + incoming[i] == 0.) {
+                return Err(MsmError::UnvisitedState(i));
+            }
+            reversible_estimate(&counts_f64)
+        } else {
+            if let Some(i) = (0..n).find(|&i| outgoing[i] == 0.) {
+                return Err(MsmError::UnvisitedState(i));
+            }
+            crate::linalg::normalize_rows(counts_f64)
+        };
+        let stationary = crate::linalg::stationary_distribution(&transition_matrix).ok_or(MsmError::NotConnected)?;
+        if stationary.iter().any(|&p| p <= 0.) {
+            return Err(MsmError::NotConnected);
+        }
+        let (eigenvalues, _) = crate::linalg::reversible_eigen(&transition_matrix, &stationary);
+        Ok(Msm {
+            lag: self.lag,
+            counts,
+            transition_matrix,
+            stationary,
+            eigenvalues,
+        })
+    }
+
+    /// Returns the implied timescales of the models built at each lag of `lags`, see [implied_timescales].
+    ///
+    /// If the discretized process is Markovian from some lag on, the timescales are constant from that lag on.
+    ///
+    /// # Errors
+    ///
+    /// If the model can not be built at some lag, see [build].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use markovian::msm::MsmBuilder;
+    /// let trajectory: Vec<usize> = (0..1_000).map(|t| (t / 10) % 2).collect();
+    /// let timescales = MsmBuilder::new(|x: &usize| *x).implied_timescales(&[trajectory], &[1, 2, 4]).unwrap();
+    /// assert_eq!(timescales.len(), 3);
+    /// ```
+    ///
+    /// [implied_timescales]: struct.Msm.html#method.implied_timescales
+    /// [build]: struct.MsmBuilder.html#method.build
+    #[inline]
+    pub fn implied_timescales<X>(&self, trajectories: &[Vec<X>], lags: &[usize]) -> Result<Vec<Vec<f64>>, MsmError>
+    where
+        F: Fn(&X) -> usize + Clone,
+    {
+        lags.iter()
+            .map(|&lag| Ok(self.clone().with_lag(lag).build(trajectories)?.implied_timescales()))
+            .collect()
+    }
+
+    /// Compares, for each set of states in `sets`, the probability of being in the set `k` lags after
+    /// starting from the stationary distribution restricted to the set, as predicted by the model at the lag,
+    /// `P(lag)^k`, and as estimated directly at lag `k * lag`, `P(k lag)`, for `k` in `0..=steps`.
+    ///
+    /// # Errors
+    ///
+    /// If the model can not be built at some multiple of the lag, see [build].
+    ///
+    /// # Panics
+    ///
+    /// If some set is empty or contains a state that is not observed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use markovian::msm::MsmBuilder;
+    /// let trajectory: Vec<usize> = (0..1_000).map(|t| (t / 10) % 2).collect();
+    /// let test = MsmBuilder::new(|x: &usize| *x).chapman_kolmogorov_test(&[trajectory], &[vec![0]], 3).unwrap();
+    /// println!("Largest discrepancy: {}", test.max_discrepancy());
+    /// ```
+    ///
+    /// [build]: struct.MsmBuilder.html#method.build
+    #[inline]
+    pub fn chapman_kolmogorov_test<X>(
+        &self,
+        trajectories: &[Vec<X>],
+        sets: &[Vec<usize>],
+        steps: usize,
+    ) -> Result<ChapmanKolmogorov, MsmError>
+    where
+        F: Fn(&X) -> usize + Clone,
+    {
+        let model = self.build(trajectories)?;
+        let n = model.nstates();
+        assert!(
+            sets.iter().all(|set| !set.is_empty() && set.iter().all(|&i| i < n)),
+            "Sets must be non-empty and contain only observed states."
+        );
+        let initial: Vec<Array1<f64>> = sets
+            .iter()
+            .map(|set| {
+                let mut distribution = Array1::zeros(n);
+                for &i in set {
+                    distribution[i] = model.stationary[i];
+                }
+                let total = distribution.sum();
+                distribution / total
+            })
+            .collect();
+        let in_set = |distribution: &Array1<f64>, set: &[usize]| set.iter().map(|&i| distribution[i]).sum::<f64>();
+
+        let mut predicted = Array2::zeros((sets.len(), steps + 1));
+        let mut estimated = Array2::zeros((sets.len(), steps + 1));
+        let mut standard_errors = Array2::zeros((sets.len(), steps + 1));
+        let mut distributions = initial.clone();
+        predicted.column_mut(0).fill(1.);
+        estimated.column_mut(0).fill(1.);
+        for k in 1..=steps {
+            let direct = self.clone().with_lag(k * self.lag).build(trajectories)?;
+            for (s, set) in sets.iter().enumerate() {
+                distributions[s] = distributions[s].dot(&model.transition_matrix);
+                predicted[[s, k]] = in_set(&distributions[s], set);
+                let p = in_set(&initial[s].dot(&direct.transition_matrix), set);
+                estimated[[s, k]] = p;
+                let observed: u64 = set.iter().map(|&i| direct.counts.row(i).sum()).sum();
+                standard_errors[[s, k]] = if observed > 0 { (p * (1. - p) / observed as f64).sqrt() } else { f64::INFINITY };
+            }
+        }
+        Ok(ChapmanKolmogorov {
+            lag: self.lag,
+            predicted,
+            estimated,
+            standard_errors,
+        })
+    }
+}
+
+/// Markov state model: a finite chain estimated at a lag from discretized trajectories.
+///
+/// This struct is created by the [build] method of [MsmBuilder].
+///
+/// [build]: struct.MsmBuilder.html#method.build
+/// [MsmBuilder]: struct.MsmBuilder.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Msm {
+    lag: usize,
+    counts: Array2<u64>,
+    transition_matrix: Array2<f64>,
+    stationary: Array1<f64>,
+    eigenvalues: Array1<f64>,
+}
+
+impl Msm {
+    /// Returns the lag, in number of observations, of the transition matrix.
+    #[inline]
+    pub fn lag(&self) -> usize {
+        self.lag
+    }
+
+    /// Returns the number of states.
+    #[inline]
+    pub fn nstates(&self) -> usize {
+        self.counts.nrows()
+    }
+
+    /// Returns the matrix of transition counts at the lag.
+    #[inline]
+    pub fn counts(&self) -> &Array2<u64> {
+        &self.counts
+    }
+
+    /// Returns the estimated transition matrix at the lag.
+    #[inline]
+    pub fn transition_matrix(&self) -> &Array2<f64> {
+        &self.transition_matrix
+    }
+
+    /// Returns the stationary distribution of the estimated chain.
+    #[inline]
+    pub fn stationary_distribution(&self) -> &Array1<f64> {
+        &self.stationary
+    }
+
+    /// Returns the eigenvalues of the transition matrix, in decreasing order.
+    ///
+    /// # Remarks
+    ///
+    /// For non-reversible estimates, these are the eigenvalues of its additive reversibilization.
+    #[inline]
+    pub fn eigenvalues(&self) -> &Array1<f64> {
+        &self.eigenvalues
+    }
+
+    /// Returns the implied timescales `-lag / ln|λ|`, in number of observations, of every eigenvalue `λ`
+    /// but the first one, which is one, from the slowest to the fastest.
+    #[inline]
+    pub fn implied_timescales(&self) -> Vec<f64> {
+        let mut timescales: Vec<f64> = self
+            .eigenvalues
+            .iter()
+            .skip(1)
+            .map(|lambda| -(self.lag as f64) / lambda.abs().ln())
+            .collect();
+        timescales.sort_by(|a, b| b.partial_cmp(a).expect("Timescales are not NaN."));
+        timescales
+    }
+
+    /// Returns the estimated chain, started at `state_index`, which moves `lag` observations per step.
+    ///
+    /// # Panics
+    ///
+    /// If `state_index` is not a state.
+    #[inline]
+    pub fn chain<R>(&self, state_index: usize, rng: R) -> FiniteMarkovChain<usize, f64, R>
+    where
+        R: Rng,
+    {
+        assert!(state_index < self.nstates(), "The initial state must be a state of the model.");
+        FiniteMarkovChain::from((state_index, self.transition_matrix.clone(), rng))
+    }
+}
+
+/// Results of a Chapman-Kolmogorov test, created by the [chapman_kolmogorov_test] method of [MsmBuilder].
+///
+/// Matrices have one row per set and one column per number of lags `k`, from zero.
+///
+/// [chapman_kolmogorov_test]: struct.MsmBuilder.html#method.chapman_kolmogorov_test
+/// [MsmBuilder]: struct.MsmBuilder.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChapmanKolmogorov {
+    lag: usize,
+    predicted: Array2<f64>,
+    estimated: Array2<f64>,
+    standard_errors: Array2<f64>,
+}
+
+impl ChapmanKolmogorov {
+    /// Returns the lag of the model tested.
+    #[inline]
+    pub fn lag(&self) -> usize {
+        self.lag
+    }
+
+    /// Returns the probabilities of staying in each set predicted by the model, `P(lag)^k`.
+    #[inline]
+    pub fn predicted(&self) -> &Array2<f64> {
+        &self.predicted
+    }
+
+    /// Returns the probabilities of staying in each set estimated at lag `k * lag`, `P(k lag)`.
+    #[inline]
+    pub fn estimated(&self) -> &Array2<f64> {
+        &self.estimated
+    }
+
+    /// Returns the standard errors of the estimated probabilities, 
+    /// taking transitions as independent observations.
+    #[inline]
+    pub fn standard_errors(&self) -> &Array2<f64> {
+        &self.standard_errors
+    }
+
+    /// Returns the largest absolute difference between predicted and estimated probabilities.
+    #[inline]
+    pub fn max_discrepancy(&self) -> f64 {
+        (&self.predicted - &self.estimated).iter().fold(0., |max: f64, x| max.max(x.abs()))
+    }
+
+    /// Returns `true` if every predicted probability is within `n_sigmas` standard errors of the estimated one.
+    #[inline]
+    pub fn passes(&self, n_sigmas: f64) -> bool {
+        self.predicted
+            .iter()
+            .zip(self.estimated.iter())
+            .zip(self.standard_errors.iter())
+            .all(|((p, e), sigma)| (p - e).abs() <= n_sigmas * sigma || p == e)
+    }
+}
+
+/// Returns the maximum likelihood transition matrix in detailed balance with its stationary distribution,
+/// by the fixed-point iteration `x_ij = (c_ij + c_ji) / (c_i / x_i + c_j / x_j)` on the symmetric flows `x`.
+#[inline]
+fn reversible_estimate(counts: &Array2<f64>) -> Array2<f64> {
+    let n = counts.nrows();
+    let symmetric = counts + &counts.t();
+    let outgoing = counts.sum_axis(ndarray::Axis(1));
+    let mut flows = symmetric.clone();
+    for _ in 0..REVERSIBLE_ITERATIONS {
+        let totals = flows.sum_axis(ndarray::Axis(1));
+        let next = Array2::from_shape_fn((n, n), |(i, j)| {
+            if symmetric[[i, j]] == 0. {
+                return 0.;
+            }
+            symmetric[[i, j]] / (outgoing[i] / totals[i] + outgoing[j] / totals[j])
+        });
+        let change = (&next - &flows).iter().map(|x| x.abs()).sum::<f64>() / next.sum();
+        flows = next;
+        if change < REVERSIBLE_TOLERANCE {
+            break;
+        }
+    }
+    crate::linalg::normalize_rows(flows)
+}
+
+/// Clustering of points in `D`-dimensional space by Lloyd's algorithm,
+/// initialized by k-means++.
+///
+/// # Examples
+///
+/// ```
+/// # use markovian::msm::{KMeans, MsmBuilder};
+/// let points = vec![vec![0., 0.], vec![0.1, 0.], vec![5., 5.], vec![5., 5.1]];
+/// let kmeans = KMeans::fit(&points, 2, 100, &mut rand::thread_rng());
+/// assert_eq!(kmeans.assign(&[0., 0.05]), kmeans.assign(&points[0]));
+/// let msm = MsmBuilder::new(|x: &Vec<f64>| kmeans.assign(x)).build(&[points.clone()]).unwrap();
+/// ```
+#[cfg(feature = "kmeans")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct KMeans {
+    centers: Vec<Vec<f64>>,
+}
+
+#[cfg(feature = "kmeans")]
+impl KMeans {
+    /// Fits `k` centers to `points`, with at most `max_iterations` iterations of Lloyd's algorithm.
+    ///
+    /// # Panics
+    ///
+    /// If there are less than `k` points, `k` is zero, or points do not have the same dimension.
+    #[inline]
+    pub fn fit<X, R>(points: &[X], k: usize, max_iterations: usize, rng: &mut R) -> Self
+    where
+        X: AsRef<[f64]>,
+        R: Rng + ?Sized,
+    {
+        assert!(k > 0 && points.len() >= k, "There must be at least k > 0 points.");
+        let dimension = points[0].as_ref().len();
+        assert!(points.iter().all(|point| point.as_ref().len() == dimension), "Points must have the same dimension.");
+
+        // k-means++: each new center is a point sampled with probability proportional to its squared distance
+        let mut centers = vec![points[rng.gen_range(0..points.len())].as_ref().to_vec()];
+        while centers.len() < k {
+            let distances: Vec<f64> = points.iter().map(|point| nearest(&centers, point.as_ref()).1).collect();
+            let mut threshold = rng.gen::<f64>() * distances.iter().sum::<f64>();
+            let mut chosen = points.len() - 1;
+            for (i, distance) in distances.iter().enumerate() {
+                if threshold < *distance {
+                    chosen = i;
+                    break;
+                }
+                threshold -= distance;
+            }
+            centers.push(points[chosen].as_ref().to_vec());
+        }
+
+        let mut assignments = vec![usize::MAX; points.len()];
+        for _ in 0..max_iterations {
+            let next: Vec<usize> = points.iter().map(|point| nearest(&centers, point.as_ref()).0).collect();
+            if next == assignments {
+                break;
+            }
+            assignments = next;
+            let mut sums = vec![vec![0.; dimension]; k];
+            let mut sizes = vec![0; k];
+            for (point, &cluster) in points.iter().zip(&assignments) {
+                sizes[cluster] += 1;
+                for (sum, x) in sums[cluster].iter_mut().zip(point.as_ref()) {
+                    *sum += x;
+                }
+            }
+            for ((center, sum), size) in centers.iter_mut().zip(sums).zip(sizes) {
+                // Empty clusters keep their center
+                if size > 0 {
+                    *center = sum.into_iter().map(|x| x / size as f64).collect();
+                }
+            }
+        }
+        KMeans { centers }
+    }
+
+    /// Returns the centers of the clusters.
+    #[inline]
+    pub fn centers(&self) -> &[Vec<f64>] {
+        &self.centers
+    }
+
+    /// Returns the index of the center nearest to `point`.
+    #[inline]
+    pub fn assign(&self, point: &[f64]) -> usize {
+        nearest(&self.centers, point).0
+    }
+}
+
+/// Returns the index of the center nearest to `point` and the squared distance to it.
+#[cfg(feature = "kmeans")]
+#[inline]
+fn nearest(centers: &[Vec<f64>], point: &[f64]) -> (usize, f64) {
+    centers
+        .iter()
+        .map(|center| center.iter().zip(point).map(|(c, x)| (c - x) * (c - x)).sum::<f64>())
+        .enumerate()
+        .fold((0, f64::INFINITY), |best, (i, distance)| if distance < best.1 { (i, distance) } else { best })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn two_wells() {
+        // Observations of a chain with known transition matrix, with noise around the wells
+        let p = array![[0.95, 0.05], [0.1, 0.9]];
+        let mut mc = FiniteMarkovChain::from((0, p.clone(), crate::tests::rng(1)));
+        let mut rng = crate::tests::rng(2);
+        let trajectories: Vec<Vec<f64>> = vec![(0..50_000)
+            .map(|_| {
+                let well = *mc.state().unwrap() as f64 * 2. - 1.;
+                mc.next();
+                well + rng.gen_range(-0.5..0.5)
+            })
+            .collect()];
+        let builder = MsmBuilder::new(|x: &f64| (*x > 0.) as usize);
+        let msm = builder.build(&trajectories).unwrap();
+        for (estimate, exact) in msm.transition_matrix().iter().zip(p.iter()) {
+            assert_abs_diff_eq!(estimate, exact, epsilon = 0.01);
+        }
+        // Detailed balance
+        let pi = msm.stationary_distribution();
+        let t = msm.transition_matrix();
+        assert_abs_diff_eq!(pi[0] * t[[0, 1]], pi[1] * t[[1, 0]], epsilon = 1e-10);
+
+        // The implied timescale does not depend on the lag
+        let exact = -1. / 0.85_f64.ln();
+        for &lag in &[1, 5] {
+            let msm = MsmBuilder::new(|x: &f64| (*x > 0.) as usize).with_lag(lag).with_reversible(false).build(&trajectories).unwrap();
+            assert_eq!(msm.lag(), lag);
+            assert_abs_diff_eq!(msm.implied_timescales()[0], exact, epsilon = 0.5);
+        }
+
+        assert_eq!(builder.build::<f64>(&[vec![1.]]).unwrap_err(), MsmError::NoTransitions);
+        assert_eq!(
+            MsmBuilder::new(|x: &usize| *x).with_reversible(false).build(&[vec![0, 1]]).unwrap_err(),
+            MsmError::UnvisitedState(1)
+        );
+    }
+
+    #[test]
+    fn chapman_kolmogorov() {
+        let p = array![[0.9, 0.1, 0.], [0.05, 0.9, 0.05], [0., 0.1, 0.9]];
+        let mut mc = FiniteMarkovChain::from((0, p, crate::tests::rng(4)));
+        let markovian = vec![mc.by_ref().take(100_000).collect::<Vec<usize>>()];
+        let builder = MsmBuilder::new(|x: &usize| *x).with_lag(2);
+        let test = builder.chapman_kolmogorov_test(&markovian, &[vec![0], vec![1, 2]], 4).unwrap();
+        assert_eq!(test.predicted().dim(), (2, 5));
+        assert!(test.passes(4.));
+        assert!(test.max_discrepancy() < 0.02);
+        let timescales = builder.implied_timescales(&markovian, &[1, 4]).unwrap();
+        assert_abs_diff_eq!(timescales[0][0], timescales[1][0], epsilon = 0.1 * timescales[0][0]);
+
+        // Lumping states 1 and 2 is not Markovian: the model at lag one mixes too fast
+        let lumped = vec![markovian[0].iter().map(|&x| (x > 0) as usize).collect::<Vec<usize>>()];
+        let test = MsmBuilder::new(|x: &usize| *x).chapman_kolmogorov_test(&lumped, &[vec![0]], 10).unwrap();
+        assert!(!test.passes(4.));
+    }
+
+    #[cfg(feature = "kmeans")]
+    #[test]
+    fn kmeans() {
+        let mut rng = crate::tests::rng(3);
+        let points: Vec<[f64; 2]> = (0..200).map(|i| {
+            let center = if i % 2 == 0 { 0. } else { 10. };
+            [center + rng.gen::<f64>(), center + rng.gen::<f64>()]
+        }).collect();
+        let kmeans = KMeans::fit(&points, 2, 100, &mut rng);
+        for (i, point) in points.iter().enumerate() {
+            assert_eq!(kmeans.assign(point), kmeans.assign(&points[i % 2]));
+        }
+        assert_ne!(kmeans.assign(&points[0]), kmeans.assign(&points[1]));
+    }
+}
@@ -0,0 +1,812 @@
+//! Piecewise-deterministic Markov processes, and the Zig-Zag and bouncy particle samplers.
+//!
+//! A piecewise-deterministic Markov process, [Pdmp], follows a deterministic flow between random
+//! events, where it jumps according to a jump kernel. Events happen with a rate that depends on
+//! the current state, so that the time until the next event is sampled along the flow by an
+//! [EventTime], either exactly by inverting the integrated rate, with [Exact], or by [Thinning]
+//! a Poisson process with a larger rate.
+//!
+//! As with epidemics, the process yields the period until the next event and the state right after it.
+//! If no event ever happens, it yields an infinite period and the same state.
+//! Values of the continuous trajectory are recovered by [discretize], and stationary laws are
+//! estimated from them by [time_average] and [stationary_histogram].
+//!
+//! Ready-made processes from queueing and population dynamics are in [models].
+//!
+//! For a target density proportional to `exp(-U(x))` in `R^d`, the [ZigZag] and [BouncyParticle]
+//! samplers are processes on positions and velocities, moving in straight lines, whose marginal
+//! stationary distribution on positions is the target. They only need the gradient of `U`
+//! and a bound on its Lipschitz constant, and do not reject moves, as Metropolis chains do.
+//!
+//! # Examples
+//!
+//! Zig-Zag sampler of a standard Gaussian in dimension two.
+//! ```
+//! # use markovian::pdmp::zig_zag;
+//! let mut sampler = zig_zag(vec![0., 0.], |x: &[f64]| x.to_vec(), 1., rand::thread_rng());
+//! let samples = sampler.discretize(0.5, 1_000);
+//! let mean = samples.iter().map(|(x, _)| x[0]).sum::<f64>() / 1_000.;
+//! println!("Estimated mean of the first coordinate: {}", mean);
+//! ```
+//!
+//! [Pdmp]: struct.Pdmp.html
+//! [EventTime]: trait.EventTime.html
+//! [Exact]: struct.Exact.html
+//! [Thinning]: struct.Thinning.html
+//! [discretize]: struct.Pdmp.html#method.discretize
+//! [time_average]: struct.Pdmp.html#method.time_average
+//! [stationary_histogram]: struct.Pdmp.html#method.stationary_histogram
+//! [models]: models/index.html
+//! [ZigZag]: struct.ZigZag.html
+//! [BouncyParticle]: struct.BouncyParticle.html
+
+// Traits
+use core::fmt::Debug;
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess, Transition};
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Exp1, StandardNormal};
+
+// Structs
+use crate::errors::InvalidState;
+use crate::montecarlo::Estimate;
+use crate::stats::{Histogram, Welford};
+
+// Functions
+use core::mem;
+
+pub mod models;
+
+/// Positions and velocities of a sampler moving in straight lines.
+pub type Kinetic = (Vec<f64>, Vec<f64>);
+
+/// Zig-Zag sampler, as constructed by [zig_zag].
+///
+/// [zig_zag]: fn.zig_zag.html
+pub type ZigZagSampler<G, R> = Pdmp<Kinetic, fn(&Kinetic, f64) -> Kinetic, ZigZag<G>, ZigZag<G>, R>;
+
+/// Bouncy particle sampler, as constructed by [bouncy_particle].
+///
+/// [bouncy_particle]: fn.bouncy_particle.html
+pub type BouncyParticleSampler<G, R> = Pdmp<Kinetic, fn(&Kinetic, f64) -> Kinetic, BouncyParticle<G>, BouncyParticle<G>, R>;
+
+/// Abstraction over the deterministic motion of a piecewise-deterministic process between events.
+///
+/// It is implemented by functions receiving a state and a time, and returning the state reached after that time.
+pub trait Flow<S> {
+    /// Returns the state reached from `state` after `time`.
+    fn flow_from(&self, state: &S, time: f64) -> S;
+}
+
+impl<S, F> Flow<S> for F
+where
+    F: Fn(&S, f64) -> S,
+{
+    #[inline]
+    fn flow_from(&self, state: &S, time: f64) -> S {
+        self(state, time)
+    }
+}
+
+/// Abstraction over the simulation of the time until the next event of a piecewise-deterministic process.
+pub trait EventTime<S> {
+    /// Samples the time until the next event, from `state`, of a process following `flow`,
+    /// or infinity if no event ever happens.
+    fn sample_event_time<F, R>(&self, state: &S, flow: &F, rng: &mut R) -> f64
+    where
+        F: Flow<S>,
+        R: Rng + ?Sized;
+}
+
+/// Exact simulation of event times, by inversion of the integrated rate.
+///
+/// The function `inverse` receives a state `s` and a positive value `e`, and returns the time `t`
+/// at which the rate integrated along the flow from `s` reaches `e`, or infinity if it never does.
+///
+/// # Examples
+///
+/// Rate equal to the time elapsed, `t`, so that the integrated rate is `t^2 / 2`.
+/// ```
+/// # use markovian::pdmp::{EventTime, Exact};
+/// let exact = Exact::new(|_: &f64, e: f64| (2. * e).sqrt());
+/// let time = exact.sample_event_time(&0., &|s: &f64, t: f64| s + t, &mut rand::thread_rng());
+/// assert!(time > 0.);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Exact<G> {
+    inverse: G,
+}
+
+impl<G> Exact<G> {
+    /// Constructs a new `Exact<G>` from the inverse of the integrated rate.
+    #[inline]
+    pub fn new(inverse: G) -> Self {
+        Exact { inverse }
+    }
+}
+
+impl<S, G> EventTime<S> for Exact<G>
+where
+    G: Fn(&S, f64) -> f64,
+{
+    #[inline]
+    fn sample_event_time<F, R>(&self, state: &S, _flow: &F, rng: &mut R) -> f64
+    where
+        F: Flow<S>,
+        R: Rng + ?Sized,
+    {
+        (self.inverse)(state, rng.sample(Exp1))
+    }
+}
+
+/// Simulation of event times by thinning.
+///
+/// The function `bound` receives a state `s` and returns a pair `(b, h)` such that
+/// the rate along the flow from `s` is at most `b` for times up to the horizon `h`, which can be infinite.
+/// Candidate events are proposed at rate `b`, and accepted with probability the rate over `b`.
+///
+/// If the bound is not valid, the event times are not correctly distributed.
+#[derive(Debug, Clone)]
+pub struct Thinning<L, B> {
+    rate: L,
+    bound: B,
+}
+
+impl<L, B> Thinning<L, B> {
+    /// Constructs a new `Thinning<L, B>` from the event rate and a local bound of it.
+    #[inline]
+    pub fn new(rate: L, bound: B) -> Self {
+        Thinning { rate, bound }
+    }
+}
+
+impl<S, L, B> EventTime<S> for Thinning<L, B>
+where
+    S: Clone,
+    L: Fn(&S) -> f64,
+    B: Fn(&S) -> (f64, f64),
+{
+    #[inline]
+    fn sample_event_time<F, R>(&self, state: &S, flow: &F, rng: &mut R) -> f64
+    where
+        F: Flow<S>,
+        R: Rng + ?Sized,
+    {
+        thinning(state, flow, &self.rate, &self.bound, rng)
+    }
+}
+
+/// Piecewise-deterministic Markov process, with states of type `S`, following the flow `F`,
+/// with events simulated by `C` and jumps given by `J`.
+///
+/// The flow can be any function receiving a state and a time, and returning the state reached after that time.
+/// Ready-made processes are in [models].
+///
+/// # Examples
+///
+/// A particle moving at unit speed that restarts at zero at rate one.
+/// ```
+/// # use markovian::pdmp::{Exact, Pdmp};
+/// let flow = |x: &f64, t: f64| x + t;
+/// let clock = Exact::new(|_: &f64, e: f64| e);
+/// let jump = |_: &f64| rand_distr::Uniform::new(0., 1e-9);
+/// let mut process = Pdmp::new(0., flow, clock, jump, rand::thread_rng());
+/// let (period, state) = process.next().unwrap();
+/// assert!(period > 0. && state < 1e-9);
+/// ```
+///
+/// [models]: models/index.html
+#[derive(Debug, Clone)]
+pub struct Pdmp<S, F, C, J, R> {
+    state: S,
+    initial_state: S,
+    flow: F,
+    clock: C,
+    jump: J,
+    rng: R,
+}
+
+impl<S, F, C, J, R> Pdmp<S, F, C, J, R>
+where
+    S: Clone,
+    F: Flow<S>,
+    C: EventTime<S>,
+    J: Transition<S, S>,
+    R: Rng,
+{
+    /// Constructs a new `Pdmp<S, F, C, J, R>` started at `state`.
+    #[inline]
+    pub fn new(state: S, flow: F, clock: C, jump: J, rng: R) -> Self {
+        Pdmp {
+            initial_state: state.clone(),
+            state,
+            flow,
+            clock,
+            jump,
+            rng,
+        }
+    }
+
+    /// Constructs a new `Pdmp<S, F, C, J, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(state: S, flow: F, clock: C, jump: J, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        Pdmp::new(state, flow, clock, jump, R::seed_from_u64(seed))
+    }
+
+    /// Returns the states of the continuous trajectory at times `step, 2 step, ..., n step`,
+    /// and moves the process to the last of them.
+    ///
+    /// # Panics
+    ///
+    /// If `step` is not positive and finite.
+    #[inline]
+    pub fn discretize(&mut self, step: f64, n: usize) -> Vec<S> {
+        assert!(step > 0. && step.is_finite(), "The step must be positive and finite.");
+        let mut samples = Vec::with_capacity(n);
+        // Time from the current state to the next grid point
+        let mut until_next = step;
+        while samples.len() < n {
+            let period = self.clock.sample_event_time(&self.state, &self.flow, &mut self.rng);
+            while samples.len() < n && until_next <= period {
+                samples.push(self.flow.flow_from(&self.state, until_next));
+                until_next += step;
+            }
+            if samples.len() < n {
+                let before = self.flow.flow_from(&self.state, period);
+                self.state = self.jump.sample_from(&before, &mut self.rng);
+                until_next -= period;
+            }
+        }
+        if let Some(last) = samples.last() {
+            // By the Markov property, the process can be restarted from any time
+            self.state = last.clone();
+        }
+        samples
+    }
+
+    /// Estimates the stationary expectation of `f` by its time average, after discarding
+    /// a period `burn_in`, with the trajectory discretized by `step`.
+    ///
+    /// The standard error is computed by batch means: the time average over each of `nbatches`
+    /// consecutive batches of `batch_size` values is taken as an independent sample.
+    ///
+    /// # Panics
+    ///
+    /// If `burn_in` is negative or `step` is not positive and finite.
+    ///
+    /// # Examples
+    ///
+    /// Second moment of the stationary law of a TCP window.
+    /// ```
+    /// # use markovian::pdmp::models::tcp;
+    /// let mut process = tcp(1., 1., 0.5, 0., 1., rand::thread_rng());
+    /// let estimate = process.time_average(|x| x * x, 10., 0.5, 20, 500);
+    /// assert!((estimate.mean - 2.).abs() < 10. * estimate.standard_error + 0.1);
+    /// ```
+    #[inline]
+    pub fn time_average<G>(&mut self, f: G, burn_in: f64, step: f64, nbatches: usize, batch_size: usize) -> Estimate
+    where
+        G: Fn(&S) -> f64,
+    {
+        self.burn_in(burn_in);
+        (0..nbatches)
+            .map(|_| {
+                let batch = self.discretize(step, batch_size);
+                batch.iter().map(&f).sum::<f64>() / batch_size.max(1) as f64
+            })
+            .collect::<Welford>()
+            .into()
+    }
+
+    /// Returns the histogram, with `bins` bins, of `n` values of `f` over the trajectory
+    /// discretized by `step`, after discarding a period `burn_in`,
+    /// which approximates the stationary law of `f`.
+    ///
+    /// # Panics
+    ///
+    /// If `burn_in` is negative, `step` is not positive and finite, or `n` or `bins` is zero.
+    #[inline]
+    pub fn stationary_histogram<G>(&mut self, f: G, burn_in: f64, step: f64, n: usize, bins: usize) -> Histogram
+    where
+        G: Fn(&S) -> f64,
+    {
+        self.burn_in(burn_in);
+        Histogram::from_iter(self.discretize(step, n).iter().map(f), bins)
+    }
+
+    /// Moves the process along its trajectory during `period`.
+    #[inline]
+    fn burn_in(&mut self, period: f64) {
+        assert!(period >= 0., "The burn-in period must be non-negative.");
+        if period > 0. {
+            self.discretize(period, 1);
+        }
+    }
+}
+
+impl<S, F, C, J, R> Reset for Pdmp<S, F, C, J, R>
+where
+    S: Clone,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+    }
+}
+
+impl<S, F, C, J, R> RandomSource for Pdmp<S, F, C, J, R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<S, F, C, J, R> State for Pdmp<S, F, C, J, R>
+where
+    S: Debug,
+{
+    type Item = S;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    #[inline]
+    fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        Some(&mut self.state)
+    }
+
+    #[inline]
+    fn set_state(&mut self, new_state: Self::Item) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        Ok(Some(mem::replace(&mut self.state, new_state)))
+    }
+}
+
+impl<S, F, C, J, R> Checkpoint for Pdmp<S, F, C, J, R>
+where
+    S: Debug,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<S, F, C, J, R> Iterator for Pdmp<S, F, C, J, R>
+where
+    S: Clone,
+    F: Flow<S>,
+    C: EventTime<S>,
+    J: Transition<S, S>,
+    R: Rng,
+{
+    type Item = (f64, S);
+
+    /// Changes the state to the one right after the next event,
+    /// and returns it with the period until that event.
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (period, state) = sample_event(&self.state, &self.flow, &self.clock, &self.jump, &mut self.rng);
+        self.state = state.clone();
+        Some((period, state))
+    }
+}
+
+impl<S, F, C, J, R> StochasticProcess for Pdmp<S, F, C, J, R>
+where
+    S: Clone,
+    F: Flow<S>,
+    C: EventTime<S>,
+    J: Transition<S, S>,
+    R: Rng,
+{
+    type Time = f64;
+    type State = S;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next()
+    }
+}
+
+impl<S, F, C, J, R> StateIterator for Pdmp<S, F, C, J, R>
+where
+    S: Debug + Clone,
+    F: Flow<S>,
+    C: EventTime<S>,
+    J: Transition<S, S>,
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        Some((0., self.state.clone()))
+    }
+}
+
+impl<S, F, C, J, R> Distribution<(f64, S)> for Pdmp<S, F, C, J, R>
+where
+    S: Clone,
+    F: Flow<S>,
+    C: EventTime<S>,
+    J: Transition<S, S>,
+    R: Rng,
+{
+    /// Sample a possible next event.
+    #[inline]
+    fn sample<R2>(&self, rng: &mut R2) -> (f64, S)
+    where
+        R2: Rng + ?Sized,
+    {
+        sample_event(&self.state, &self.flow, &self.clock, &self.jump, rng)
+    }
+}
+
+/// Event rates and velocity flips of the Zig-Zag sampler, for a target density proportional to `exp(-U(x))`.
+///
+/// Velocities have entries `1` or `-1`. Each one is flipped at rate `max(0, v_i ∂_i U(x))`.
+///
+/// The function `gradient` returns the gradient of `U`, and `lipschitz` bounds its Lipschitz constant,
+/// which is used to simulate event times by thinning.
+#[derive(Debug, Clone)]
+pub struct ZigZag<G> {
+    gradient: G,
+    lipschitz: f64,
+}
+
+impl<G> ZigZag<G>
+where
+    G: Fn(&[f64]) -> Vec<f64>,
+{
+    /// Constructs a new `ZigZag<G>`.
+    ///
+    /// # Panics
+    ///
+    /// If `lipschitz` is negative or not finite.
+    #[inline]
+    pub fn new(gradient: G, lipschitz: f64) -> Self {
+        assert!(lipschitz >= 0. && lipschitz.is_finite(), "The Lipschitz constant must be non-negative and finite.");
+        ZigZag { gradient, lipschitz }
+    }
+
+    /// Returns the rate at which each velocity is flipped.
+    #[inline]
+    fn rates(&self, (position, velocity): &Kinetic) -> Vec<f64> {
+        (self.gradient)(position)
+            .iter()
+            .zip(velocity)
+            .map(|(g, v)| (g * v).max(0.))
+            .collect()
+    }
+}
+
+impl<G> EventTime<Kinetic> for ZigZag<G>
+where
+    G: Fn(&[f64]) -> Vec<f64>,
+{
+    #[inline]
+    fn sample_event_time<F, R>(&self, state: &Kinetic, flow: &F, rng: &mut R) -> f64
+    where
+        F: Flow<Kinetic>,
+        R: Rng + ?Sized,
+    {
+        let rate = |state: &Kinetic| self.rates(state).iter().sum();
+        // Each partial derivative changes at most by L t sqrt(d) along the flow
+        let slope = self.lipschitz * (state.1.len() as f64).sqrt();
+        let bound = |state: &Kinetic| {
+            let horizon = 1. / slope;
+            let bound = if slope > 0. {
+                self.rates(state).iter().map(|rate| rate + slope * horizon).sum()
+            } else {
+                self.rates(state).iter().sum()
+            };
+            (bound, horizon)
+        };
+        thinning(state, flow, &rate, &bound, rng)
+    }
+}
+
+impl<G> Transition<Kinetic, Kinetic> for ZigZag<G>
+where
+    G: Fn(&[f64]) -> Vec<f64>,
+{
+    /// Flips one velocity, chosen with probability proportional to its rate.
+    #[inline]
+    fn sample_from<R>(&self, state: &Kinetic, rng: &mut R) -> Kinetic
+    where
+        R: Rng + ?Sized,
+    {
+        let rates = self.rates(state);
+        let mut threshold = rng.gen::<f64>() * rates.iter().sum::<f64>();
+        let flipped = rates
+            .iter()
+            .position(|rate| {
+                threshold -= rate;
+                threshold < 0.
+            })
+            .unwrap_or_else(|| rates.iter().rposition(|&rate| rate > 0.).unwrap_or(0));
+        let mut velocity = state.1.clone();
+        velocity[flipped] = -velocity[flipped];
+        (state.0.clone(), velocity)
+    }
+}
+
+/// Event rates, bounces and refreshments of the bouncy particle sampler,
+/// for a target density proportional to `exp(-U(x))`.
+///
+/// The velocity bounces on the level sets of `U` at rate `max(0, <v, ∇U(x)>)`,
+/// and is refreshed as a standard Gaussian vector at rate `refresh_rate`.
+///
+/// The function `gradient` returns the gradient of `U`, and `lipschitz` bounds its Lipschitz constant,
+/// which is used to simulate event times by thinning.
+#[derive(Debug, Clone)]
+pub struct BouncyParticle<G> {
+    gradient: G,
+    lipschitz: f64,
+    refresh_rate: f64,
+}
+
+impl<G> BouncyParticle<G>
+where
+    G: Fn(&[f64]) -> Vec<f64>,
+{
+    /// Constructs a new `BouncyParticle<G>`.
+    ///
+    /// # Panics
+    ///
+    /// If `lipschitz` or `refresh_rate` is negative or not finite.
+    #[inline]
+    pub fn new(gradient: G, lipschitz: f64, refresh_rate: f64) -> Self {
+        assert!(lipschitz >= 0. && lipschitz.is_finite(), "The Lipschitz constant must be non-negative and finite.");
+        assert!(refresh_rate >= 0. && refresh_rate.is_finite(), "The refresh rate must be non-negative and finite.");
+        BouncyParticle {
+            gradient,
+            lipschitz,
+            refresh_rate,
+        }
+    }
+
+    /// Returns the rate of bounces.
+    #[inline]
+    fn bounce_rate(&self, (position, velocity): &Kinetic) -> f64 {
+        dot(&(self.gradient)(position), velocity).max(0.)
+    }
+}
+
+impl<G> EventTime<Kinetic> for BouncyParticle<G>
+where
+    G: Fn(&[f64]) -> Vec<f64>,
+{
+    #[inline]
+    fn sample_event_time<F, R>(&self, state: &Kinetic, flow: &F, rng: &mut R) -> f64
+    where
+        F: Flow<Kinetic>,
+        R: Rng + ?Sized,
+    {
+        let rate = |state: &Kinetic| self.bounce_rate(state) + self.refresh_rate;
+        // The bounce rate grows at most by L |v|^2 t along the flow
+        let slope = self.lipschitz * dot(&state.1, &state.1);
+        let bound = |state: &Kinetic| {
+            let horizon = 1. / slope;
+            let growth = if slope > 0. { slope * horizon } else { 0. };
+            (self.bounce_rate(state) + growth + self.refresh_rate, horizon)
+        };
+        thinning(state, flow, &rate, &bound, rng)
+    }
+}
+
+impl<G> Transition<Kinetic, Kinetic> for BouncyParticle<G>
+where
+    G: Fn(&[f64]) -> Vec<f64>,
+{
+    /// Refreshes the velocity with probability proportional to the refresh rate,
+    /// and otherwise reflects it on the level set of `U`.
+    #[inline]
+    fn sample_from<R>(&self, state: &Kinetic, rng: &mut R) -> Kinetic
+    where
+        R: Rng + ?Sized,
+    {
+        let (position, velocity) = state;
+        let bounce_rate = self.bounce_rate(state);
+        if rng.gen::<f64>() * (bounce_rate + self.refresh_rate) < self.refresh_rate {
+            let velocity = velocity.iter().map(|_| rng.sample(StandardNormal)).collect();
+            return (position.clone(), velocity);
+        }
+        let gradient = (self.gradient)(position);
+        let norm = dot(&gradient, &gradient);
+        if norm == 0. {
+            return state.clone();
+        }
+        let scale = 2. * dot(&gradient, velocity) / norm;
+        let velocity = velocity.iter().zip(&gradient).map(|(v, g)| v - scale * g).collect();
+        (position.clone(), velocity)
+    }
+}
+
+/// Returns the Zig-Zag sampler started at `position`, with all velocities equal to one,
+/// for a target density proportional to `exp(-U(x))`.
+///
+/// See [ZigZag] for the meaning of `gradient` and `lipschitz`.
+///
+/// # Panics
+///
+/// If `lipschitz` is negative or not finite.
+///
+/// # Examples
+///
+/// Sampling a Gaussian with mean one.
+/// ```
+/// # use markovian::pdmp::zig_zag;
+/// let mut sampler = zig_zag(vec![0.], |x: &[f64]| vec![x[0] - 1.], 1., rand::thread_rng());
+/// let samples = sampler.discretize(1., 10_000);
+/// let mean = samples.iter().map(|(x, _)| x[0]).sum::<f64>() / 10_000.;
+/// assert!((mean - 1.).abs() < 0.2);
+/// ```
+///
+/// [ZigZag]: struct.ZigZag.html
+#[inline]
+pub fn zig_zag<G, R>(position: Vec<f64>, gradient: G, lipschitz: f64, rng: R) -> ZigZagSampler<G, R>
+where
+    G: Fn(&[f64]) -> Vec<f64> + Clone,
+    R: Rng,
+{
+    let velocity = vec![1.; position.len()];
+    let sampler = ZigZag::new(gradient, lipschitz);
+    Pdmp::new((position, velocity), linear_flow, sampler.clone(), sampler, rng)
+}
+
+/// Returns the bouncy particle sampler started at `position`, with a standard Gaussian velocity,
+/// for a target density proportional to `exp(-U(x))`.
+///
+/// See [BouncyParticle] for the meaning of `gradient`, `lipschitz` and `refresh_rate`.
+/// A positive refresh rate is needed for the sampler to be ergodic.
+///
+/// # Panics
+///
+/// If `lipschitz` or `refresh_rate` is negative or not finite.
+///
+/// [BouncyParticle]: struct.BouncyParticle.html
+#[inline]
+pub fn bouncy_particle<G, R>(position: Vec<f64>, gradient: G, lipschitz: f64, refresh_rate: f64, mut rng: R) -> BouncyParticleSampler<G, R>
+where
+    G: Fn(&[f64]) -> Vec<f64> + Clone,
+    R: Rng,
+{
+    let velocity = position.iter().map(|_| rng.sample(StandardNormal)).collect();
+    let sampler = BouncyParticle::new(gradient, lipschitz, refresh_rate);
+    Pdmp::new((position, velocity), linear_flow, sampler.clone(), sampler, rng)
+}
+
+/// Moves each position at its velocity during time `t`.
+#[inline]
+pub fn linear_flow((position, velocity): &Kinetic, t: f64) -> Kinetic {
+    let position = position.iter().zip(velocity).map(|(x, v)| x + v * t).collect();
+    (position, velocity.clone())
+}
+
+/// Samples the period until the next event and the state right after it.
+#[inline]
+fn sample_event<S, F, C, J, R>(state: &S, flow: &F, clock: &C, jump: &J, rng: &mut R) -> (f64, S)
+where
+    F: Flow<S>,
+    C: EventTime<S>,
+    J: Transition<S, S>,
+    R: Rng + ?Sized,
+    S: Clone,
+{
+    let period = clock.sample_event_time(state, flow, rng);
+    if period.is_finite() {
+        (period, jump.sample_from(&flow.flow_from(state, period), rng))
+    } else {
+        (period, state.clone())
+    }
+}
+
+/// Samples the time until the next event by thinning, where `bound` returns a bound of `rate`
+/// along the flow and the horizon up to which it is valid.
+#[inline]
+fn thinning<S, F, L, B, R>(state: &S, flow: &F, rate: &L, bound: &B, rng: &mut R) -> f64
+where
+    S: Clone,
+    F: Flow<S>,
+    L: Fn(&S) -> f64,
+    B: Fn(&S) -> (f64, f64),
+    R: Rng + ?Sized,
+{
+    let mut elapsed = 0.;
+    let mut current = state.clone();
+    loop {
+        let (bound, horizon) = bound(&current);
+        let candidate = if bound > 0. { rng.sample::<f64, _>(Exp1) / bound } else { f64::INFINITY };
+        if candidate > horizon {
+            if horizon.is_infinite() {
+                return f64::INFINITY;
+            }
+            elapsed += horizon;
+            current = flow.flow_from(&current, horizon);
+            continue;
+        }
+        elapsed += candidate;
+        current = flow.flow_from(&current, candidate);
+        debug_assert!(rate(&current) <= bound * (1. + 1e-9), "The bound of the event rate is not valid.");
+        if rng.gen::<f64>() * bound < rate(&current) {
+            return elapsed;
+        }
+    }
+}
+
+#[inline]
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::Welford;
+    use approx::assert_abs_diff_eq;
+
+    fn moments(samples: &[Kinetic], coordinate: usize) -> (f64, f64) {
+        let welford: Welford = samples.iter().map(|(x, _)| x[coordinate]).collect();
+        (welford.mean(), welford.variance())
+    }
+
+    #[test]
+    fn zig_zag_gaussian() {
+        // Exact event times for a standard Gaussian in dimension one: the rate is max(0, v x + t)
+        let inverse = |(x, v): &Kinetic, e: f64| {
+            let a = x[0] * v[0];
+            -a + (a.max(0.).powi(2) + 2. * e).sqrt()
+        };
+        let sampler = ZigZag::new(|x: &[f64]| x.to_vec(), 1.);
+        let mut exact = Pdmp::new((vec![0.], vec![1.]), linear_flow, Exact::new(inverse), sampler, crate::tests::rng(1));
+        let (mean, variance) = moments(&exact.discretize(0.5, 40_000), 0);
+        assert_abs_diff_eq!(mean, 0., epsilon = 0.1);
+        assert_abs_diff_eq!(variance, 1., epsilon = 0.1);
+
+        let mut thinned = zig_zag(vec![0.], |x: &[f64]| x.to_vec(), 1., crate::tests::rng(2));
+        let (mean, variance) = moments(&thinned.discretize(0.5, 40_000), 0);
+        assert_abs_diff_eq!(mean, 0., epsilon = 0.1);
+        assert_abs_diff_eq!(variance, 1., epsilon = 0.1);
+    }
+
+    #[test]
+    fn bouncy_particle_gaussian() {
+        // Independent coordinates with variances one and four
+        let gradient = |x: &[f64]| vec![x[0], x[1] / 4.];
+        let mut sampler = bouncy_particle(vec![0., 0.], gradient, 1., 1., crate::tests::rng(3));
+        let samples = sampler.discretize(0.5, 40_000);
+        let (mean, variance) = moments(&samples, 0);
+        assert_abs_diff_eq!(mean, 0., epsilon = 0.1);
+        assert_abs_diff_eq!(variance, 1., epsilon = 0.15);
+        let (mean, variance) = moments(&samples, 1);
+        assert_abs_diff_eq!(mean, 0., epsilon = 0.2);
+        assert_abs_diff_eq!(variance, 4., epsilon = 0.6);
+
+        sampler.reset();
+        let (period, (position, _)) = sampler.next().unwrap();
+        assert!(period > 0.);
+        assert_eq!(position.len(), 2);
+    }
+}
@@ -0,0 +1,348 @@
+//! Ready-made piecewise-deterministic processes: the TCP window and growth-fragmentation of cells.
+//!
+//! The [Tcp] process models the congestion window of a connection under the additive-increase
+//! multiplicative-decrease protocol: the window grows linearly, and it is multiplied by a factor
+//! smaller than one at each loss, which happens at a rate affine in the window.
+//!
+//! The [GrowthFragmentation] process follows the size of a cell along a lineage: the size grows
+//! exponentially, and at each division, which happens at a rate proportional to the size,
+//! only a fraction of it is kept by the followed daughter cell.
+//!
+//! Event times of both processes are simulated exactly, and their stationary moments are known
+//! in closed form, so that they can be compared with estimates from trajectories.
+//!
+//! # Examples
+//!
+//! Stationary mean size of a cell that divides in two halves.
+//! ```
+//! # use markovian::pdmp::models::{growth_fragmentation, Fragmentation, GrowthFragmentation};
+//! let mut process = growth_fragmentation(1., 1., 1., Fragmentation::Binary, rand::thread_rng());
+//! let estimate = process.time_average(|x| *x, 10., 0.5, 20, 500);
+//! println!("Mean size: {} ± {}", estimate.mean, estimate.standard_error);
+//! let exact = GrowthFragmentation::new(1., 1., Fragmentation::Binary).stationary_moment(1);
+//! println!("Exact: {}", exact);
+//! ```
+//!
+//! [Tcp]: struct.Tcp.html
+//! [GrowthFragmentation]: struct.GrowthFragmentation.html
+
+// Traits
+use super::{EventTime, Flow};
+use crate::Transition;
+use rand::Rng;
+use rand_distr::Exp1;
+
+// Structs
+use super::Pdmp;
+
+/// TCP window process, as constructed by [tcp].
+///
+/// [tcp]: fn.tcp.html
+pub type TcpProcess<R> = Pdmp<f64, Tcp, Tcp, Tcp, R>;
+
+/// Growth-fragmentation process, as constructed by [growth_fragmentation].
+///
+/// [growth_fragmentation]: fn.growth_fragmentation.html
+pub type GrowthFragmentationProcess<R> = Pdmp<f64, GrowthFragmentation, GrowthFragmentation, GrowthFragmentation, R>;
+
+/// Dynamics of the additive-increase multiplicative-decrease window of TCP.
+///
+/// The window grows at speed `increase`, and losses happen at rate `base_loss + proportional_loss * x`,
+/// where `x` is the window, after which the window is multiplied by `decrease`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tcp {
+    increase: f64,
+    decrease: f64,
+    base_loss: f64,
+    proportional_loss: f64,
+}
+
+impl Tcp {
+    /// Constructs a new `Tcp`.
+    ///
+    /// # Panics
+    ///
+    /// If `increase` is not positive and finite, `decrease` is not in `[0, 1)`,
+    /// or the loss rates are negative, not finite or both zero.
+    #[inline]
+    pub fn new(increase: f64, decrease: f64, base_loss: f64, proportional_loss: f64) -> Self {
+        assert!(increase > 0. && increase.is_finite(), "The increase must be positive and finite.");
+        assert!((0. ..1.).contains(&decrease), "The decrease factor must be in [0, 1).");
+        assert!(
+            base_loss >= 0. && proportional_loss >= 0. && base_loss.is_finite() && proportional_loss.is_finite(),
+            "Loss rates must be non-negative and finite."
+        );
+        assert!(base_loss + proportional_loss > 0., "Some loss rate must be positive.");
+        Tcp {
+            increase,
+            decrease,
+            base_loss,
+            proportional_loss,
+        }
+    }
+
+    /// Returns the `n`-th moment of the stationary law of the window, when it is known in closed form,
+    /// that is, if losses happen at constant rate, or at rate proportional to the window and `n` is even.
+    ///
+    /// # Examples
+    ///
+    /// With losses at rate proportional to the window, the second moment is `increase / (loss (1 - decrease))`.
+    /// ```
+    /// # use markovian::pdmp::models::Tcp;
+    /// let tcp = Tcp::new(1., 0.5, 0., 1.);
+    /// assert_eq!(tcp.stationary_moment(2), Some(2.));
+    /// assert_eq!(tcp.stationary_moment(1), None);
+    /// ```
+    #[inline]
+    pub fn stationary_moment(&self, n: u32) -> Option<f64> {
+        // The generator applied to x^k gives k a E[X^(k - 1)] = (1 - d^k) E[(c + s X) X^k]
+        let (a, d) = (self.increase, self.decrease);
+        if self.proportional_loss == 0. {
+            Some((1..=n).fold(1., |moment, k| k as f64 * a * moment / (self.base_loss * (1. - d.powi(k as i32)))))
+        } else if self.base_loss == 0. && n % 2 == 0 {
+            Some((1..=n / 2).fold(1., |moment, j| {
+                let k = 2 * j - 1;
+                k as f64 * a * moment / (self.proportional_loss * (1. - d.powi(k as i32)))
+            }))
+        } else {
+            None
+        }
+    }
+}
+
+impl Flow<f64> for Tcp {
+    #[inline]
+    fn flow_from(&self, window: &f64, time: f64) -> f64 {
+        window + self.increase * time
+    }
+}
+
+impl EventTime<f64> for Tcp {
+    /// Inverts the integrated loss rate `(c + s x) t + s a t^2 / 2`.
+    #[inline]
+    fn sample_event_time<F, R>(&self, window: &f64, _flow: &F, rng: &mut R) -> f64
+    where
+        F: Flow<f64>,
+        R: Rng + ?Sized,
+    {
+        let e: f64 = rng.sample(Exp1);
+        let linear = self.base_loss + self.proportional_loss * window;
+        let quadratic = self.proportional_loss * self.increase;
+        2. * e / (linear + (linear * linear + 2. * quadratic * e).sqrt())
+    }
+}
+
+impl Transition<f64, f64> for Tcp {
+    /// Multiplies the window by the decrease factor.
+    #[inline]
+    fn sample_from<R>(&self, window: &f64, _rng: &mut R) -> f64
+    where
+        R: Rng + ?Sized,
+    {
+        window * self.decrease
+    }
+}
+
+/// Fraction of the size kept at a division by the followed cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fragmentation {
+    /// Division into two equal halves.
+    Binary,
+    /// Division keeping a fixed fraction, in `(0, 1)`.
+    Fixed(f64),
+    /// Division keeping a uniformly distributed fraction.
+    Uniform,
+}
+
+impl Fragmentation {
+    /// Returns the expected `n`-th power of the fraction kept.
+    #[inline]
+    fn moment(&self, n: i32) -> f64 {
+        match self {
+            Fragmentation::Binary => 0.5f64.powi(n),
+            Fragmentation::Fixed(fraction) => fraction.powi(n),
+            Fragmentation::Uniform => 1. / (n + 1) as f64,
+        }
+    }
+
+    /// Returns the expected logarithm of the fraction kept.
+    #[inline]
+    fn log_moment(&self) -> f64 {
+        match self {
+            Fragmentation::Binary => 0.5f64.ln(),
+            Fragmentation::Fixed(fraction) => fraction.ln(),
+            Fragmentation::Uniform => -1.,
+        }
+    }
+}
+
+/// Dynamics of the size of a cell along a lineage, which grows exponentially and fragments.
+///
+/// The size grows at exponential rate `growth`, and divisions happen at rate `division_rate * x`,
+/// where `x` is the size, after which the size is multiplied by a fraction given by `fragmentation`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrowthFragmentation {
+    growth: f64,
+    division_rate: f64,
+    fragmentation: Fragmentation,
+}
+
+impl GrowthFragmentation {
+    /// Constructs a new `GrowthFragmentation`.
+    ///
+    /// # Panics
+    ///
+    /// If `growth` or `division_rate` is not positive and finite, or a fixed fraction is not in `(0, 1)`.
+    #[inline]
+    pub fn new(growth: f64, division_rate: f64, fragmentation: Fragmentation) -> Self {
+        assert!(growth > 0. && growth.is_finite(), "The growth rate must be positive and finite.");
+        assert!(division_rate > 0. && division_rate.is_finite(), "The division rate must be positive and finite.");
+        if let Fragmentation::Fixed(fraction) = fragmentation {
+            assert!(fraction > 0. && fraction < 1., "The fraction kept must be in (0, 1).");
+        }
+        GrowthFragmentation {
+            growth,
+            division_rate,
+            fragmentation,
+        }
+    }
+
+    /// Returns the `n`-th moment of the stationary law of the size, for `n >= 1`.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is zero.
+    ///
+    /// # Examples
+    ///
+    /// For binary division, the mean size is `growth / (division_rate ln(2))`.
+    /// ```
+    /// # use markovian::pdmp::models::{GrowthFragmentation, Fragmentation};
+    /// let dynamics = GrowthFragmentation::new(1., 1., Fragmentation::Binary);
+    /// assert!((dynamics.stationary_moment(1) - 1. / 2f64.ln()).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn stationary_moment(&self, n: u32) -> f64 {
+        assert!(n > 0, "Only moments of positive order are available.");
+        // The generator applied to ln(x) gives g = -b E[ln K] E[X],
+        // and applied to x^k gives k g E[X^k] = b (1 - E[K^k]) E[X^(k + 1)]
+        let (g, b) = (self.growth, self.division_rate);
+        let mean = -g / (b * self.fragmentation.log_moment());
+        (1..n).fold(mean, |moment, k| k as f64 * g * moment / (b * (1. - self.fragmentation.moment(k as i32))))
+    }
+}
+
+impl Flow<f64> for GrowthFragmentation {
+    #[inline]
+    fn flow_from(&self, size: &f64, time: f64) -> f64 {
+        size * (self.growth * time).exp()
+    }
+}
+
+impl EventTime<f64> for GrowthFragmentation {
+    /// Inverts the integrated division rate `b x (exp(g t) - 1) / g`.
+    #[inline]
+    fn sample_event_time<F, R>(&self, size: &f64, _flow: &F, rng: &mut R) -> f64
+    where
+        F: Flow<f64>,
+        R: Rng + ?Sized,
+    {
+        let e: f64 = rng.sample(Exp1);
+        (self.growth * e / (self.division_rate * size)).ln_1p() / self.growth
+    }
+}
+
+impl Transition<f64, f64> for GrowthFragmentation {
+    /// Multiplies the size by the fraction kept.
+    #[inline]
+    fn sample_from<R>(&self, size: &f64, rng: &mut R) -> f64
+    where
+        R: Rng + ?Sized,
+    {
+        let fraction = match self.fragmentation {
+            Fragmentation::Binary => 0.5,
+            Fragmentation::Fixed(fraction) => fraction,
+            Fragmentation::Uniform => rng.gen(),
+        };
+        size * fraction
+    }
+}
+
+/// Returns the TCP window process started at `window`.
+///
+/// See [Tcp] for the meaning of the parameters.
+///
+/// # Panics
+///
+/// If the parameters are not valid, as in [Tcp::new].
+///
+/// [Tcp]: struct.Tcp.html
+/// [Tcp::new]: struct.Tcp.html#method.new
+#[inline]
+pub fn tcp<R>(window: f64, increase: f64, decrease: f64, base_loss: f64, proportional_loss: f64, rng: R) -> TcpProcess<R>
+where
+    R: Rng,
+{
+    let dynamics = Tcp::new(increase, decrease, base_loss, proportional_loss);
+    Pdmp::new(window, dynamics, dynamics, dynamics, rng)
+}
+
+/// Returns the growth-fragmentation process started at `size`.
+///
+/// See [GrowthFragmentation] for the meaning of the parameters.
+///
+/// # Panics
+///
+/// If the parameters are not valid, as in [GrowthFragmentation::new].
+///
+/// [GrowthFragmentation]: struct.GrowthFragmentation.html
+/// [GrowthFragmentation::new]: struct.GrowthFragmentation.html#method.new
+#[inline]
+pub fn growth_fragmentation<R>(size: f64, growth: f64, division_rate: f64, fragmentation: Fragmentation, rng: R) -> GrowthFragmentationProcess<R>
+where
+    R: Rng,
+{
+    let dynamics = GrowthFragmentation::new(growth, division_rate, fragmentation);
+    Pdmp::new(size, dynamics, dynamics, dynamics, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn tcp_moments() {
+        // Constant loss rate: all moments are known
+        let dynamics = Tcp::new(2., 0.5, 1., 0.);
+        let mut process = tcp(0., 2., 0.5, 1., 0., crate::tests::rng(1));
+        let mean = process.time_average(|x| *x, 20., 0.5, 40, 1_000);
+        assert_abs_diff_eq!(mean.mean, dynamics.stationary_moment(1).unwrap(), epsilon = 4. * mean.standard_error);
+        let second = process.time_average(|x| x * x, 0., 0.5, 40, 1_000);
+        assert_abs_diff_eq!(second.mean, dynamics.stationary_moment(2).unwrap(), epsilon = 4. * second.standard_error);
+
+        // Loss rate proportional to the window
+        let dynamics = Tcp::new(1., 0.7, 0., 0.5);
+        let mut process = tcp(1., 1., 0.7, 0., 0.5, crate::tests::rng(2));
+        let second = process.time_average(|x| x * x, 20., 0.5, 40, 1_000);
+        assert_abs_diff_eq!(second.mean, dynamics.stationary_moment(2).unwrap(), epsilon = 4. * second.standard_error);
+        let fourth = process.time_average(|x| x.powi(4), 0., 0.5, 40, 1_000);
+        assert_abs_diff_eq!(fourth.mean, dynamics.stationary_moment(4).unwrap(), epsilon = 4. * fourth.standard_error);
+    }
+
+    #[test]
+    fn growth_fragmentation_moments() {
+        for &fragmentation in &[Fragmentation::Binary, Fragmentation::Fixed(0.3), Fragmentation::Uniform] {
+            let dynamics = GrowthFragmentation::new(1., 2., fragmentation);
+            let mut process = growth_fragmentation(1., 1., 2., fragmentation, crate::tests::rng(3));
+            let mean = process.time_average(|x| *x, 20., 0.5, 40, 1_000);
+            assert_abs_diff_eq!(mean.mean, dynamics.stationary_moment(1), epsilon = 4. * mean.standard_error);
+            let second = process.time_average(|x| x * x, 0., 0.5, 40, 1_000);
+            assert_abs_diff_eq!(second.mean, dynamics.stationary_moment(2), epsilon = 4. * second.standard_error);
+        }
+
+        let mut process = growth_fragmentation(1., 1., 1., Fragmentation::Binary, crate::tests::rng(4));
+        let histogram = process.stationary_histogram(|x| *x, 10., 0.5, 1_000, 10);
+        assert_eq!(histogram.total(), 1_000);
+    }
+}
@@ -0,0 +1,296 @@
+//! Stochastic Petri nets.
+//!
+//! A Petri net has places, which hold tokens, and transitions, which consume tokens
+//! from their input places and produce tokens in their output places. The number of
+//! tokens in each place is called a marking. In a stochastic Petri net, each enabled
+//! transition fires after an exponential time with its own rate, so that markings
+//! evolve as a continuous time Markov chain, simulated by the Gillespie algorithm.
+//!
+//! # Examples
+//!
+//! A closed queue with two servers: jobs in place 0 wait for service,
+//! which moves them to place 1, from where they come back to place 0.
+//! ```
+//! # use markovian::{petri::PetriNet, State};
+//! let mut net = PetriNet::new(2);
+//! net.add_transition(2.0, vec![(0, 1)], vec![(1, 1)]);
+//! net.add_transition(1.0, vec![(1, 1)], vec![(0, 1)]);
+//! let mut mc = net.clone().into_markov_chain(vec![3, 0], rand::thread_rng());
+//! let (_period, marking) = mc.next().unwrap();
+//! assert_eq!(marking, vec![2, 1]);
+//!
+//! let ctmc = net.to_ctmc(vec![3, 0], 100, rand::thread_rng()).unwrap();
+//! assert_eq!(ctmc.nstates(), 4);
+//! ```
+
+// Traits
+use crate::Transition;
+use rand::Rng;
+use rand_distr::Distribution;
+
+// Structs
+use crate::errors::StateSpaceTooLarge;
+use crate::{ContFiniteMarkovChain, TimedMarkovChain};
+use rand_distr::Exp;
+use std::collections::HashMap;
+
+/// Number of tokens in each place.
+pub type Marking = Vec<u64>;
+
+/// Transition of a Petri net.
+#[derive(Debug, Clone, PartialEq)]
+struct PetriTransition {
+    rate: f64,
+    inputs: Vec<(usize, u64)>,
+    outputs: Vec<(usize, u64)>,
+}
+
+/// Stochastic Petri net with single-server semantics:
+/// the rate of a transition does not depend on how many times it is enabled.
+///
+/// As a `Transition<Marking, (f64, Marking)>`, it samples the time until the
+/// next firing and the marking after it. If no transition is enabled, the net is
+/// dead and the time until the next firing is infinite.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PetriNet {
+    nplaces: usize,
+    transitions: Vec<PetriTransition>,
+}
+
+impl PetriNet {
+    /// Constructs a new `PetriNet` with `nplaces` places and no transitions.
+    #[inline]
+    pub fn new(nplaces: usize) -> Self {
+        PetriNet {
+            nplaces,
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Adds a transition with rate `rate` and returns its index.
+    ///
+    /// Arcs are given as pairs `(place, multiplicity)`: firing the transition consumes
+    /// `multiplicity` tokens from each input place and produces `multiplicity` tokens
+    /// in each output place.
+    ///
+    /// # Panics
+    ///
+    /// If `rate` is negative or not finite, or if an arc refers to a non-existent place.
+    #[inline]
+    pub fn add_transition(&mut self, rate: f64, inputs: Vec<(usize, u64)>, outputs: Vec<(usize, u64)>) -> usize {
+        assert!(rate >= 0.0 && rate.is_finite(), "Rates must be non-negative and finite.");
+        assert!(
+            inputs.iter().chain(&outputs).all(|&(place, _)| place < self.nplaces),
+            "Arcs must refer to existing places."
+        );
+        self.transitions.push(PetriTransition { rate, inputs, outputs });
+        self.transitions.len() - 1
+    }
+
+    /// Returns the number of places.
+    #[inline]
+    pub fn nplaces(&self) -> usize {
+        self.nplaces
+    }
+
+    /// Returns the number of transitions.
+    #[inline]
+    pub fn ntransitions(&self) -> usize {
+        self.transitions.len()
+    }
+
+    /// Returns `true` if `transition` has enough tokens in its input places to fire.
+    ///
+    /// # Panics
+    ///
+    /// If `transition` is not a valid index.
+    #[inline]
+    pub fn is_enabled(&self, transition: usize, marking: &[u64]) -> bool {
+        self.transitions[transition].inputs.iter()
+            .all(|&(place, multiplicity)| marking[place] >= multiplicity)
+    }
+
+    /// Returns the indexes of all transitions enabled in `marking` with a positive rate.
+    #[inline]
+    pub fn enabled(&self, marking: &[u64]) -> Vec<usize> {
+        (0..self.transitions.len())
+            .filter(|&i| self.transitions[i].rate > 0.0 && self.is_enabled(i, marking))
+            .collect()
+    }
+
+    /// Returns the marking after firing `transition` in `marking`.
+    ///
+    /// # Panics
+    ///
+    /// If `transition` is not enabled in `marking`.
+    #[inline]
+    pub fn fire(&self, transition: usize, marking: &[u64]) -> Marking {
+        assert!(self.is_enabled(transition, marking), "The transition is not enabled.");
+        let mut marking = marking.to_vec();
+        for &(place, multiplicity) in self.transitions[transition].inputs.iter() {
+            marking[place] -= multiplicity;
+        }
+        for &(place, multiplicity) in self.transitions[transition].outputs.iter() {
+            marking[place] += multiplicity;
+        }
+        marking
+    }
+
+    /// Returns a continuous time Markov chain simulating the net from `marking`.
+    ///
+    /// # Panics
+    ///
+    /// If `marking` has not one entry per place.
+    #[inline]
+    pub fn into_markov_chain<R>(self, marking: Marking, rng: R) -> TimedMarkovChain<f64, Marking, Self, R>
+    where
+        R: Rng,
+    {
+        assert_eq!(marking.len(), self.nplaces, "The marking must have one entry per place.");
+        TimedMarkovChain::new(marking, self, rng)
+    }
+
+    /// Returns the markings reachable from `marking`, in breadth-first order,
+    /// together with the rates between them.
+    ///
+    /// # Errors
+    ///
+    /// If there are more than `limit` reachable markings.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn reachability_graph(&self, marking: Marking, limit: usize) -> Result<(Vec<Marking>, Vec<Vec<f64>>), StateSpaceTooLarge> {
+        let mut indexes: HashMap<Marking, usize> = HashMap::new();
+        let mut markings = vec![marking.clone()];
+        let mut rates: Vec<Vec<(usize, f64)>> = Vec::new();
+        indexes.insert(marking, 0);
+        let mut current = 0;
+        while current < markings.len() {
+            let mut row = Vec::new();
+            for transition in self.enabled(&markings[current]) {
+                let next = self.fire(transition, &markings[current]);
+                let index = match indexes.get(&next) {
+                    Some(&index) => index,
+                    None => {
+                        if markings.len() == limit {
+                            return Err(StateSpaceTooLarge::new(limit));
+                        }
+                        indexes.insert(next.clone(), markings.len());
+                        markings.push(next);
+                        markings.len() - 1
+                    }
+                };
+                row.push((index, self.transitions[transition].rate));
+            }
+            rates.push(row);
+            current += 1;
+        }
+        let n = markings.len();
+        let matrix = rates.into_iter()
+            .map(|row| {
+                let mut dense = vec![0.0; n];
+                for (index, rate) in row {
+                    dense[index] += rate;
+                }
+                dense
+            })
+            .collect();
+        Ok((markings, matrix))
+    }
+
+    /// Returns the continuous time Markov chain of markings reachable from `marking`,
+    /// starting at `marking`.
+    ///
+    /// Dead markings, where no transition is enabled, are given a self-loop with rate one,
+    /// which does not change the law of the process.
+    ///
+    /// # Errors
+    ///
+    /// If there are more than `limit` reachable markings.
+    #[inline]
+    pub fn to_ctmc<R>(&self, marking: Marking, limit: usize, rng: R) -> Result<ContFiniteMarkovChain<Marking, f64, R>, StateSpaceTooLarge>
+    where
+        R: Rng,
+    {
+        let (markings, mut rates) = self.reachability_graph(marking, limit)?;
+        for (i, row) in rates.iter_mut().enumerate() {
+            if row.iter().all(|&rate| rate == 0.0) {
+                row[i] = 1.0;
+            }
+        }
+        Ok(ContFiniteMarkovChain::new(0, rates, markings, rng))
+    }
+}
+
+impl Transition<Marking, (f64, Marking)> for PetriNet {
+    #[inline]
+    fn sample_from<R>(&self, marking: &Marking, rng: &mut R) -> (f64, Marking)
+    where
+        R: Rng + ?Sized,
+    {
+        let enabled = self.enabled(marking);
+        let total: f64 = enabled.iter().map(|&i| self.transitions[i].rate).sum();
+        if total == 0.0 {
+            return (f64::INFINITY, marking.clone());
+        }
+        let period = Exp::new(total).unwrap().sample(rng);
+        let mut u = rng.gen::<f64>() * total;
+        let mut chosen = enabled[enabled.len() - 1];
+        for &i in enabled.iter() {
+            if u < self.transitions[i].rate {
+                chosen = i;
+                break;
+            }
+            u -= self.transitions[i].rate;
+        }
+        (period, self.fire(chosen, marking))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn closed_queue() -> PetriNet {
+        let mut net = PetriNet::new(2);
+        net.add_transition(2.0, vec![(0, 1)], vec![(1, 1)]);
+        net.add_transition(1.0, vec![(1, 1)], vec![(0, 1)]);
+        net
+    }
+
+    #[test]
+    fn firing() {
+        let net = closed_queue();
+
+        assert_eq!(net.enabled(&[1, 0]), vec![0]);
+        assert_eq!(net.fire(0, &[1, 0]), vec![0, 1]);
+        assert!(!net.is_enabled(1, &[1, 0]));
+    }
+
+    #[test]
+    fn simulation() {
+        let mut mc = closed_queue().into_markov_chain(vec![2, 0], crate::tests::rng(1));
+        for (period, marking) in mc.by_ref().take(100) {
+            assert!(period > 0.);
+            assert_eq!(marking.iter().sum::<u64>(), 2);
+        }
+
+        let mut dead = PetriNet::new(1);
+        dead.add_transition(1.0, vec![(0, 1)], vec![]);
+        let mut mc = dead.into_markov_chain(vec![1], crate::tests::rng(2));
+        assert_eq!(mc.nth(1).unwrap(), (f64::INFINITY, vec![0]));
+    }
+
+    #[test]
+    fn reachability() {
+        let net = closed_queue();
+        let (markings, rates) = net.reachability_graph(vec![2, 0], 10).unwrap();
+
+        assert_eq!(markings, vec![vec![2, 0], vec![1, 1], vec![0, 2]]);
+        assert_eq!(rates, vec![vec![0., 2., 0.], vec![1., 0., 2.], vec![0., 1., 0.]]);
+
+        let mut unbounded = PetriNet::new(1);
+        unbounded.add_transition(1.0, vec![], vec![(0, 1)]);
+        assert_eq!(unbounded.reachability_graph(vec![0], 10), Err(StateSpaceTooLarge::new(10)));
+    }
+}
@@ -1,6 +1,8 @@
 pub use branching::Branching;
+pub use cont_branching::ContBranching;
 pub use poisson::Poisson;
 
 
 mod branching;
+mod cont_branching;
 mod poisson;
\ No newline at end of file
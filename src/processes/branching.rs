@@ -1,8 +1,8 @@
 // Traits
-use crate::{State, StateIterator};
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
 use core::fmt::Debug;
 use num_traits::{sign::Unsigned, One, Zero};
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 use rand_distr::Distribution;
 
 // Structs
@@ -30,6 +30,7 @@ where
     R: Rng,
 {
     state: T,
+    initial_state: T,
     base_distribution: D,
     rng: R,
 }
@@ -58,11 +59,57 @@ where
     #[inline]
     pub fn new(state: T, base_distribution: D, rng: R) -> Self {
         Branching {
+            initial_state: state.clone(),
             state,
             base_distribution,
             rng,
         }
     }
+
+    /// Constructs a new `Branching<T, D, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(state: T, base_distribution: D, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        Branching::new(state, base_distribution, R::seed_from_u64(seed))
+    }
+}
+
+impl<T, D, R> Reset for Branching<T, D, R>
+where
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned,
+    D: Distribution<T>,
+    R: Rng,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+    }
+}
+
+impl<T, D, R> RandomSource for Branching<T, D, R>
+where
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned,
+    D: Distribution<T>,
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
 }
 
 impl<T, D, R> State for Branching<T, D, R>
@@ -93,6 +140,21 @@ where
     }
 }
 
+impl<T, D, R> Checkpoint for Branching<T, D, R>
+where
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned,
+    D: Distribution<T>,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
 impl<T, D, R> Iterator for Branching<T, D, R>
 where
     T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned,
@@ -131,6 +193,21 @@ where
     }
 }
 
+impl<T, D, R> StochasticProcess for Branching<T, D, R>
+where
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned,
+    D: Distribution<T>,
+    R: Rng,
+{
+    type Time = ();
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
 impl<T, D, R> StateIterator for Branching<T, D, R>
 where
     T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned,
@@ -0,0 +1,305 @@
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
+use core::fmt::Debug;
+use num_traits::{sign::Unsigned, Float, One, ToPrimitive, Zero};
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Exp1};
+
+// Structs
+use crate::errors::InvalidState;
+
+// Functions
+use core::mem;
+
+/// Markov branching process in continuous time, in the natural numbers NN = {0, 1, 2, ...}.
+/// 
+/// Each individual of the population lives for an exponential time with rate `death_rate`,
+/// independently of the rest. At its death, it is replaced by a random number of offsprings,
+/// sampled from `offspring_distribution`. 
+/// The process yields the period until the next death and the new size of the population. 
+///
+/// # Remarks
+///
+/// Once the population is extinct, the process stays at zero: it yields an infinite period.
+///
+/// # Examples
+///
+/// Binary splitting or death, with equal probability: the critical birth-death process.
+/// ```
+/// # use markovian::prelude::*;
+/// let offspring = raw_dist![(0.5, 0), (0.5, 2)];
+/// let process = ContBranching::<f64, u32, _, _>::new(10, 1., offspring, rand::thread_rng());
+/// for (time, population) in process.trajectory_until(5.) {
+///     println!("At time {}, the population is {}", time, population);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContBranching<N, T, D, R>
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned + ToPrimitive,
+    D: Distribution<T>,
+    R: Rng,
+{
+    state: T,
+    initial_state: T,
+    death_rate: N,
+    offspring_distribution: D,
+    rng: R,
+}
+
+impl<N, T, D, R> ContBranching<N, T, D, R>
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned + ToPrimitive,
+    D: Distribution<T>,
+    R: Rng,
+{
+    /// Constructs a new `ContBranching<N, T, D, R>` with `state` individuals.
+    ///
+    /// # Panics
+    ///
+    /// If `death_rate` is not positive.
+    #[inline]
+    pub fn new(state: T, death_rate: N, offspring_distribution: D, rng: R) -> Self {
+        assert!(death_rate > N::zero(), "The death rate must be positive.");
+        ContBranching {
+            initial_state: state.clone(),
+            state,
+            death_rate,
+            offspring_distribution,
+            rng,
+        }
+    }
+
+    /// Constructs a new `ContBranching<N, T, D, R>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Panics
+    ///
+    /// If `death_rate` is not positive.
+    #[inline]
+    pub fn with_seed(state: T, death_rate: N, offspring_distribution: D, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        ContBranching::new(state, death_rate, offspring_distribution, R::seed_from_u64(seed))
+    }
+
+    /// Returns the rate at which each individual dies.
+    #[inline]
+    pub fn death_rate(&self) -> N {
+        self.death_rate
+    }
+}
+
+impl<N, T, D, R> Reset for ContBranching<N, T, D, R>
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned + ToPrimitive,
+    D: Distribution<T>,
+    R: Rng,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+    }
+}
+
+impl<N, T, D, R> RandomSource for ContBranching<N, T, D, R>
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned + ToPrimitive,
+    D: Distribution<T>,
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<N, T, D, R> State for ContBranching<N, T, D, R>
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned + ToPrimitive,
+    D: Distribution<T>,
+    R: Rng,
+{
+    type Item = T;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    #[inline]
+    fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        Some(&mut self.state)
+    }
+
+    #[inline]
+    fn set_state(
+        &mut self,
+        mut new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        mem::swap(&mut self.state, &mut new_state);
+        Ok(Some(new_state))
+    }
+}
+
+impl<N, T, D, R> Checkpoint for ContBranching<N, T, D, R>
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned + ToPrimitive,
+    D: Distribution<T>,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<N, T, D, R> Iterator for ContBranching<N, T, D, R>
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned + ToPrimitive,
+    D: Distribution<T>,
+    R: Rng,
+{
+    type Item = (N, T);
+
+    /// Changes the state to the size of the population after the next death, 
+    /// and returns it with the period until that death.
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (period, state) = sample_jump(&self.state, self.death_rate, &self.offspring_distribution, &mut self.rng);
+        self.state = state.clone();
+        Some((period, state))
+    }
+}
+
+impl<N, T, D, R> StochasticProcess for ContBranching<N, T, D, R>
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned + ToPrimitive,
+    D: Distribution<T>,
+    R: Rng,
+{
+    type Time = N;
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next()
+    }
+}
+
+impl<N, T, D, R> StateIterator for ContBranching<N, T, D, R>
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned + ToPrimitive,
+    D: Distribution<T>,
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.state().cloned().map(|state| (N::zero(), state))
+    }
+}
+
+impl<N, T, D, R> Distribution<(N, T)> for ContBranching<N, T, D, R>
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned + ToPrimitive,
+    D: Distribution<T>,
+    R: Rng,
+{
+    /// Sample a possible next state. 
+    #[inline]
+    fn sample<R2>(&self, rng: &mut R2) -> (N, T)
+    where
+        R2: Rng + ?Sized,
+    { 
+        sample_jump(&self.state, self.death_rate, &self.offspring_distribution, rng)
+    }
+}
+
+/// Samples the period until the next death and the size of the population after it.
+#[inline]
+fn sample_jump<N, T, D, R>(state: &T, death_rate: N, offspring_distribution: &D, rng: &mut R) -> (N, T)
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Clone + One + Zero + PartialOrd + Unsigned + ToPrimitive,
+    D: Distribution<T>,
+    R: Rng + ?Sized,
+{
+    if state.is_zero() {
+        return (N::infinity(), T::zero());
+    }
+    let population = N::from(state.clone()).unwrap();
+    let period: N = Exp1.sample(rng) / (death_rate * population);
+    let offsprings = offspring_distribution.sample(rng);
+    (period, state.clone() + offsprings - T::one())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn pure_death() {
+        let offspring = raw_dist![(1.0, 0)];
+        let process = ContBranching::<f64, u32, _, _>::new(3, 2., offspring, crate::tests::rng(1));
+        let path: Vec<(f64, u32)> = process.take(4).collect();
+
+        assert_eq!(path.iter().map(|(_, state)| *state).collect::<Vec<u32>>(), vec![2, 1, 0, 0]);
+        assert!(path[..3].iter().all(|(period, _)| period.is_finite()));
+        assert_eq!(path[3].0, f64::INFINITY);
+    }
+
+    #[test]
+    fn yule() {
+        // Each individual splits in two at rate one: the expected population at time t is e^t
+        let mut rng = crate::tests::rng(2);
+        let samples = 2_000;
+        let mean = (0..samples)
+            .map(|_| {
+                let offspring = raw_dist![(1.0, 2)];
+                let process = ContBranching::<f64, u64, _, _>::new(1, 1., offspring, &mut rng);
+                process.trajectory_until(1.).last().unwrap().1 as f64
+            })
+            .sum::<f64>() / samples as f64;
+
+        assert!((mean - 1_f64.exp()).abs() < 0.15);
+    }
+}
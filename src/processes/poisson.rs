@@ -1,10 +1,10 @@
 // Traits
 use num_traits::Float;
 use rand_distr::{Exp1, Exp};
-use crate::{State, StateIterator};
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
 use core::fmt::Debug;
 use num_traits::{sign::Unsigned, One, Zero};
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 use rand_distr::Distribution;
 
 // Structs
@@ -65,6 +65,53 @@ where
             rng,
         })
     }
+
+    /// Constructs a new `Poisson<N, T, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(lambda: N, seed: u64) -> Result<Self, rand_distr::ExpError>
+    where
+        R: SeedableRng,
+    {
+        Poisson::new(lambda, R::seed_from_u64(seed))
+    }
+}
+
+impl<N, T, R> Reset for Poisson<N, T, R>
+where
+    N: Float,
+    Exp1: Distribution<N>, 
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned,
+    R: Rng,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state = T::zero();
+    }
+}
+
+impl<N, T, R> RandomSource for Poisson<N, T, R>
+where
+    N: Float,
+    Exp1: Distribution<N>, 
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned,
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
 }
 
 impl<N, T, R> State for Poisson<N, T, R>
@@ -96,6 +143,22 @@ where
     }
 }
 
+impl<N, T, R> Checkpoint for Poisson<N, T, R>
+where
+    N: Float,
+    Exp1: Distribution<N>,
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
 impl<N, T, R> Iterator for Poisson<N, T, R>
 where
     N: Float,
@@ -134,6 +197,22 @@ where
     }
 }
 
+impl<N, T, R> StochasticProcess for Poisson<N, T, R>
+where
+    N: Float,
+    Exp1: Distribution<N>, 
+    T: Debug + PartialEq + Clone + One + Zero + PartialOrd + Unsigned,
+    R: Rng,
+{
+    type Time = N;
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next()
+    }
+}
+
 impl<N, T, R> StateIterator for Poisson<N, T, R>
 where
     N: Float,
@@ -179,4 +258,13 @@ mod tests {
 
         assert_eq!(sample, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn restarted() {
+        let mut mc = Poisson::<f64, u64, _>::new(1., crate::tests::rng(4)).unwrap();
+        mc.nth(10);
+        let sample: Vec<u64> = mc.restarted().take(3).map(|(_, state)| state).collect();
+
+        assert_eq!(sample, vec![1, 2, 3]);
+    }
+}
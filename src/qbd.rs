@@ -0,0 +1,535 @@
+//! Quasi-birth-death (QBD) processes, analyzed by matrix-analytic methods.
+//!
+//! A QBD process is a chain in continuous time with states `(level, phase)`, where levels are
+//! natural numbers and phases are `0..n`, that moves at most one level at each jump.
+//! Its generator is given by blocks of size `n`: `A0` for jumps one level up, `A1` for jumps
+//! within a level and `A2` for jumps one level down. At level zero, `A1` is replaced by a
+//! boundary block `B1`. These processes model, for example, queues with phase-type
+//! service or arrival times, whose number of customers is the level.
+//!
+//! The stationary distribution is matrix-geometric: `π_k = π_0 R^k`, where the matrix `R`
+//! is computed from the matrix `G` of first passage probabilities one level down,
+//! the minimal non-negative solution of `A2 + A1 G + A0 G^2 = 0`.
+//!
+//! # Examples
+//!
+//! A queue where customers arrive at rate 1 and are served in two phases of rate 3 each.
+//! ```
+//! # use ndarray::array;
+//! # use markovian::qbd::Qbd;
+//! let a0 = array![[1., 0.], [0., 1.]];
+//! let a1 = array![[-4., 3.], [0., -4.]];
+//! let a2 = array![[0., 0.], [3., 0.]];
+//! let qbd = Qbd::new(a0, a1, a2).unwrap();
+//! assert!(qbd.is_stable());
+//! let pi = qbd.stationary_distribution(10).unwrap();
+//! println!("The queue is empty with probability {}", pi[0].sum());
+//! ```
+
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Exp1};
+
+// Structs
+use crate::errors::{InvalidState, QbdError};
+use ndarray::{Array1, Array2};
+
+// Functions
+use core::mem;
+
+/// Maximum number of iterations of the logarithmic reduction algorithm.
+pub const MAX_ITERATIONS: usize = 100;
+
+/// Tolerance for row sums of generators and the convergence of iterations.
+const TOLERANCE: f64 = 1e-12;
+
+/// Level-independent quasi-birth-death process, given by its blocks.
+///
+/// See the [module documentation] for the meaning of the blocks.
+///
+/// [module documentation]: index.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Qbd {
+    a0: Array2<f64>,
+    a1: Array2<f64>,
+    a2: Array2<f64>,
+    b1: Array2<f64>,
+}
+
+impl Qbd {
+    /// Constructs a new `Qbd` from the blocks for jumps one level up, within a level and one level down.
+    ///
+    /// At level zero, jumps down are suppressed: the boundary block is `A1` plus the diagonal
+    /// matrix of rates of jumps down. Use [with_boundary] to change it.
+    ///
+    /// # Errors
+    ///
+    /// If blocks are not square matrices of the same size, some rate is negative,
+    /// or the rows of `A0 + A1 + A2` do not sum up to zero.
+    ///
+    /// [with_boundary]: struct.Qbd.html#method.with_boundary
+    #[inline]
+    pub fn new(a0: Array2<f64>, a1: Array2<f64>, a2: Array2<f64>) -> Result<Self, QbdError> {
+        let n = a1.nrows();
+        if [&a0, &a1, &a2].iter().any(|block| block.dim() != (n, n)) {
+            return Err(QbdError::InvalidBlocks);
+        }
+        if [&a0, &a2].iter().any(|block| block.iter().any(|&rate| !(rate >= 0.0 && rate.is_finite()))) {
+            return Err(QbdError::InvalidBlocks);
+        }
+        let mut b1 = a1.clone();
+        for (i, rate) in a2.sum_axis(ndarray::Axis(1)).iter().enumerate() {
+            b1[[i, i]] += rate;
+        }
+        let qbd = Qbd { a0, a1, a2, b1 };
+        if !is_generator(&(&qbd.a0 + &qbd.a1 + &qbd.a2), &qbd.a1) {
+            return Err(QbdError::InvalidBlocks);
+        }
+        Ok(qbd)
+    }
+
+    /// Sets the boundary block `B1`, for jumps within level zero.
+    ///
+    /// # Errors
+    ///
+    /// If `b1` does not have the size of the other blocks, has negative rates outside its
+    /// diagonal, or the rows of `B1 + A0` do not sum up to zero.
+    #[inline]
+    pub fn with_boundary(mut self, b1: Array2<f64>) -> Result<Self, QbdError> {
+        if b1.dim() != self.a1.dim() || !is_generator(&(&b1 + &self.a0), &b1) {
+            return Err(QbdError::InvalidBlocks);
+        }
+        self.b1 = b1;
+        Ok(self)
+    }
+
+    /// Returns the number of phases.
+    #[inline]
+    pub fn nphases(&self) -> usize {
+        self.a1.nrows()
+    }
+
+    /// Returns the blocks `(A0, A1, A2, B1)`.
+    #[inline]
+    pub fn blocks(&self) -> (&Array2<f64>, &Array2<f64>, &Array2<f64>, &Array2<f64>) {
+        (&self.a0, &self.a1, &self.a2, &self.b1)
+    }
+
+    /// Returns the mean drift of the level: the rate of jumps up minus the rate of jumps down,
+    /// when phases follow their stationary distribution far from level zero.
+    ///
+    /// # Panics
+    ///
+    /// If the phases, far from level zero, do not have a unique stationary distribution.
+    #[inline]
+    pub fn drift(&self) -> f64 {
+        let generator = &self.a0 + &self.a1 + &self.a2;
+        let n = self.nphases();
+        let mut a = generator.t().to_owned();
+        a.row_mut(n - 1).fill(1.0);
+        let mut b = Array1::zeros(n);
+        b[n - 1] = 1.0;
+        let phases = crate::linalg::solve(a, b).expect("The phases must have a unique stationary distribution.");
+        phases.dot(&self.a0).sum() - phases.dot(&self.a2).sum()
+    }
+
+    /// Returns `true` if the process is positive recurrent, i.e. if its drift is negative.
+    ///
+    /// # Panics
+    ///
+    /// If the phases, far from level zero, do not have a unique stationary distribution.
+    #[inline]
+    pub fn is_stable(&self) -> bool {
+        self.drift() < 0.0
+    }
+
+    /// Returns the matrix `G`, whose entry `(i, j)` is the probability that, starting from phase `i`
+    /// in some level, the first visit to the level below is in phase `j`.
+    ///
+    /// It is computed by the logarithmic reduction algorithm of Latouche and Ramaswami,
+    /// which converges quadratically.
+    ///
+    /// # Errors
+    ///
+    /// If the algorithm does not converge after [MAX_ITERATIONS] iterations.
+    ///
+    /// [MAX_ITERATIONS]: constant.MAX_ITERATIONS.html
+    #[inline]
+    pub fn g_matrix(&self) -> Result<Array2<f64>, QbdError> {
+        let n = self.nphases();
+        let identity = Array2::<f64>::eye(n);
+        let local = crate::linalg::inverse(-&self.a1).ok_or(QbdError::InvalidBlocks)?;
+        let mut up = local.dot(&self.a0);
+        let mut down = local.dot(&self.a2);
+        let mut g = down.clone();
+        let mut transient = up.clone();
+        for _ in 0..MAX_ITERATIONS {
+            let u = up.dot(&down) + down.dot(&up);
+            let inverse = crate::linalg::inverse(&identity - &u).ok_or(QbdError::NotConverged(MAX_ITERATIONS))?;
+            up = inverse.dot(&up.dot(&up));
+            down = inverse.dot(&down.dot(&down));
+            let increment = transient.dot(&down);
+            g += &increment;
+            transient = transient.dot(&up);
+            if increment.iter().chain(transient.iter()).all(|x| x.abs() < TOLERANCE) {
+                return Ok(g);
+            }
+        }
+        Err(QbdError::NotConverged(MAX_ITERATIONS))
+    }
+
+    /// Returns the matrix `R = A0 (-(A1 + A0 G))^{-1}`, the minimal non-negative solution of
+    /// `A0 + R A1 + R^2 A2 = 0`, that gives the stationary distribution by `π_{k+1} = π_k R`.
+    ///
+    /// # Errors
+    ///
+    /// If the computation of [G] does not converge.
+    ///
+    /// [G]: struct.Qbd.html#method.g_matrix
+    #[inline]
+    pub fn r_matrix(&self) -> Result<Array2<f64>, QbdError> {
+        let g = self.g_matrix()?;
+        let inverse = crate::linalg::inverse(-(&self.a1 + &self.a0.dot(&g))).ok_or(QbdError::InvalidBlocks)?;
+        Ok(self.a0.dot(&inverse))
+    }
+
+    /// Returns the stationary distribution of the first `levels` levels,
+    /// where entry `k` has the probabilities of each phase in level `k`.
+    ///
+    /// # Errors
+    ///
+    /// If the process is not positive recurrent or the computation of [R] does not converge.
+    ///
+    /// [R]: struct.Qbd.html#method.r_matrix
+    #[inline]
+    pub fn stationary_distribution(&self, levels: usize) -> Result<Vec<Array1<f64>>, QbdError> {
+        let (pi0, r) = self.boundary_distribution()?;
+        let mut distribution = Vec::with_capacity(levels);
+        let mut current = pi0;
+        for _ in 0..levels {
+            let next = current.dot(&r);
+            distribution.push(mem::replace(&mut current, next));
+        }
+        Ok(distribution)
+    }
+
+    /// Returns the mean level under the stationary distribution, i.e. `π_0 R (I - R)^{-2} 1`.
+    ///
+    /// # Errors
+    ///
+    /// If the process is not positive recurrent or the computation of [R] does not converge.
+    ///
+    /// [R]: struct.Qbd.html#method.r_matrix
+    #[inline]
+    pub fn mean_level(&self) -> Result<f64, QbdError> {
+        let (pi0, r) = self.boundary_distribution()?;
+        let n = self.nphases();
+        let fundamental = crate::linalg::inverse(Array2::eye(n) - &r).ok_or(QbdError::Unstable)?;
+        Ok(pi0.dot(&r).dot(&fundamental).dot(&fundamental).sum())
+    }
+
+    /// Returns the stationary distribution of level zero and the matrix `R`.
+    ///
+    /// `π_0` solves `π_0 (B1 + R A2) = 0`, normalized by `π_0 (I - R)^{-1} 1 = 1`.
+    #[inline]
+    fn boundary_distribution(&self) -> Result<(Array1<f64>, Array2<f64>), QbdError> {
+        if !self.is_stable() {
+            return Err(QbdError::Unstable);
+        }
+        let r = self.r_matrix()?;
+        let n = self.nphases();
+        let fundamental = crate::linalg::inverse(Array2::eye(n) - &r).ok_or(QbdError::Unstable)?;
+        let mut a = (&self.b1 + &r.dot(&self.a2)).t().to_owned();
+        a.row_mut(n - 1).assign(&fundamental.sum_axis(ndarray::Axis(1)));
+        let mut b = Array1::zeros(n);
+        b[n - 1] = 1.0;
+        let pi0 = crate::linalg::solve(a, b).ok_or(QbdError::Unstable)?;
+        Ok((pi0, r))
+    }
+
+    /// Samples the period until the next jump from `(level, phase)` and the state after it.
+    #[inline]
+    fn sample_jump<R>(&self, state: (u64, usize), rng: &mut R) -> (f64, (u64, usize))
+    where
+        R: Rng + ?Sized,
+    {
+        let (level, phase) = state;
+        let local = if level == 0 { &self.b1 } else { &self.a1 };
+        let n = self.nphases();
+        let within = (0..n).filter(|&j| j != phase).map(move |j| (local[[phase, j]], (level, j)));
+        let up = (0..n).map(move |j| (self.a0[[phase, j]], (level + 1, j)));
+        let down = (0..n)
+            .filter(move |_| level > 0)
+            .map(move |j| (self.a2[[phase, j]], (level.saturating_sub(1), j)));
+        let total: f64 = within.clone().chain(up.clone()).chain(down.clone()).map(|(rate, _)| rate).sum();
+        if total <= 0.0 {
+            return (f64::INFINITY, state);
+        }
+        let period: f64 = Exp1.sample(rng);
+        let mut event = rng.gen::<f64>() * total;
+        let mut next = state;
+        for (rate, candidate) in within.chain(up).chain(down) {
+            if rate > 0.0 {
+                next = candidate;
+                if event < rate {
+                    break;
+                }
+                event -= rate;
+            }
+        }
+        (period / total, next)
+    }
+}
+
+/// Returns `true` if `full` has rows summing up to zero and `local` has non-negative entries outside its diagonal.
+#[inline]
+fn is_generator(full: &Array2<f64>, local: &Array2<f64>) -> bool {
+    let scale = full.iter().fold(1.0_f64, |acc, x| acc.max(x.abs()));
+    let rows_sum_zero = full.sum_axis(ndarray::Axis(1)).iter().all(|x| x.abs() <= TOLERANCE.sqrt() * scale);
+    let rates_valid = local
+        .indexed_iter()
+        .all(|((i, j), &rate)| rate.is_finite() && (i == j || rate >= 0.0));
+    rows_sum_zero && rates_valid
+}
+
+/// Simulation of a [Qbd] process, with states `(level, phase)`.
+///
+/// The process yields the period until the next jump and the new state.
+/// If the process can not leave its state, it yields an infinite period.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use markovian::{qbd::{Qbd, QbdProcess}, prelude::*};
+/// let qbd = Qbd::new(array![[1.]], array![[-3.]], array![[2.]]).unwrap();
+/// let process = QbdProcess::new(qbd, (0, 0), rand::thread_rng());
+/// for (time, (level, _)) in process.trajectory_until(10.) {
+///     println!("At time {}, the level is {}", time, level);
+/// }
+/// ```
+///
+/// [Qbd]: struct.Qbd.html
+#[derive(Debug, Clone)]
+pub struct QbdProcess<R> {
+    state: (u64, usize),
+    initial_state: (u64, usize),
+    qbd: Qbd,
+    rng: R,
+}
+
+impl<R> QbdProcess<R>
+where
+    R: Rng,
+{
+    /// Constructs a new `QbdProcess<R>` starting at `state`.
+    ///
+    /// # Panics
+    ///
+    /// If the phase of `state` is not a phase of `qbd`.
+    #[inline]
+    pub fn new(qbd: Qbd, state: (u64, usize), rng: R) -> Self {
+        assert!(state.1 < qbd.nphases(), "The phase must be a phase of the process.");
+        QbdProcess {
+            state,
+            initial_state: state,
+            qbd,
+            rng,
+        }
+    }
+
+    /// Constructs a new `QbdProcess<R>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [new].
+    ///
+    /// [new]: struct.QbdProcess.html#method.new
+    #[inline]
+    pub fn with_seed(qbd: Qbd, state: (u64, usize), seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        QbdProcess::new(qbd, state, R::seed_from_u64(seed))
+    }
+
+    /// Returns the blocks of the process.
+    #[inline]
+    pub fn qbd(&self) -> &Qbd {
+        &self.qbd
+    }
+}
+
+impl<R> Reset for QbdProcess<R> {
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state;
+    }
+}
+
+impl<R> RandomSource for QbdProcess<R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<R> State for QbdProcess<R> {
+    type Item = (u64, usize);
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    /// Changes the state, as long as the phase is a phase of the process.
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        if new_state.1 < self.qbd.nphases() {
+            Ok(Some(mem::replace(&mut self.state, new_state)))
+        } else {
+            Err(InvalidState::new(new_state))
+        }
+    }
+}
+
+impl<R> Checkpoint for QbdProcess<R>
+where
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<R> Iterator for QbdProcess<R>
+where
+    R: Rng,
+{
+    type Item = (f64, (u64, usize));
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (period, state) = self.qbd.sample_jump(self.state, &mut self.rng);
+        self.state = state;
+        Some((period, state))
+    }
+}
+
+impl<R> StochasticProcess for QbdProcess<R>
+where
+    R: Rng,
+{
+    type Time = f64;
+    type State = (u64, usize);
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next()
+    }
+}
+
+impl<R> StateIterator for QbdProcess<R>
+where
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        Some((0., self.state))
+    }
+}
+
+impl<R> Distribution<(f64, (u64, usize))> for QbdProcess<R>
+where
+    R: Rng,
+{
+    /// Sample a possible next jump.
+    #[inline]
+    fn sample<R2>(&self, rng: &mut R2) -> (f64, (u64, usize))
+    where
+        R2: Rng + ?Sized,
+    {
+        self.qbd.sample_jump(self.state, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn mm1() {
+        let qbd = Qbd::new(array![[1.]], array![[-3.]], array![[2.]]).unwrap();
+        assert_abs_diff_eq!(qbd.drift(), -1.);
+        assert_abs_diff_eq!(qbd.r_matrix().unwrap()[[0, 0]], 0.5, epsilon = 1e-12);
+        let pi = qbd.stationary_distribution(5).unwrap();
+        for (k, level) in pi.iter().enumerate() {
+            assert_abs_diff_eq!(level[0], 0.5_f64.powi(k as i32 + 1), epsilon = 1e-12);
+        }
+        assert_abs_diff_eq!(qbd.mean_level().unwrap(), 1., epsilon = 1e-12);
+
+        let unstable = Qbd::new(array![[2.]], array![[-3.]], array![[1.]]).unwrap();
+        assert_eq!(unstable.stationary_distribution(1), Err(QbdError::Unstable));
+        assert_eq!(Qbd::new(array![[2.]], array![[-1.]], array![[1.]]), Err(QbdError::InvalidBlocks));
+    }
+
+    #[test]
+    fn balance() {
+        let a0 = array![[0.5, 0.5], [0., 1.]];
+        let a1 = array![[-4., 1.], [2., -6.]];
+        let a2 = array![[1., 1.], [3., 0.]];
+        let qbd = Qbd::new(a0.clone(), a1.clone(), a2.clone()).unwrap();
+        let g = qbd.g_matrix().unwrap();
+        assert!(g.sum_axis(ndarray::Axis(1)).iter().all(|x| (x - 1.).abs() < 1e-10));
+
+        let pi = qbd.stationary_distribution(200).unwrap();
+        let total: f64 = pi.iter().map(|level| level.sum()).sum();
+        assert_abs_diff_eq!(total, 1., epsilon = 1e-9);
+        let balance = pi[0].dot(&a0) + pi[1].dot(&a1) + pi[2].dot(&a2);
+        assert!(balance.iter().all(|x| x.abs() < 1e-12));
+    }
+
+    #[test]
+    fn simulation() {
+        let qbd = Qbd::new(array![[1.]], array![[-3.]], array![[2.]]).unwrap();
+        let mut process = QbdProcess::new(qbd, (0, 0), crate::tests::rng(1));
+        let mut time = 0.;
+        let mut empty = 0.;
+        let mut state = (0, 0);
+        for (period, next) in process.by_ref().take(50_000) {
+            time += period;
+            if state.0 == 0 {
+                empty += period;
+            }
+            state = next;
+        }
+        assert_abs_diff_eq!(empty / time, 0.5, epsilon = 0.02);
+        assert!(process.set_state((3, 1)).is_err());
+    }
+}
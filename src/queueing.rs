@@ -0,0 +1,495 @@
+//! Networks of queues, and the product-form stationary distribution of Jackson networks.
+//!
+//! A [Network] has nodes, each one a queue with a single exponential server. Customers arrive
+//! from outside to each node as a Poisson process, and, once served at a node, are routed to
+//! another node, or leave the network, with probabilities given by a routing matrix.
+//! The queue lengths form a continuous-time Markov chain, simulated by the Gillespie algorithm:
+//! the process yields the period until the next arrival or service, and the new queue lengths.
+//! Routing decisions are sampled by a [FiniteMarkovChain] on the nodes and the outside.
+//!
+//! Such networks are Jackson networks. If every customer eventually leaves and each node
+//! is served faster than the total rate `λ_i` at which customers arrive to it, which solves
+//! the traffic equations `λ = a + λ P`, the stationary distribution has product form:
+//! the queue lengths are independent, and geometric with parameter `ρ_i = λ_i / μ_i`.
+//!
+//! # Examples
+//!
+//! Two queues in tandem.
+//! ```
+//! # use ndarray::array;
+//! # use markovian::queueing::Network;
+//! let routing = array![[0., 1.], [0., 0.]];
+//! let network = Network::new(vec![0, 0], vec![1., 0.], vec![2., 4.], routing, rand::thread_rng()).unwrap();
+//! assert!(network.is_stable());
+//! let utilizations = network.utilizations().unwrap();
+//! assert_eq!(utilizations.to_vec(), vec![0.5, 0.25]);
+//! println!("Both queues are empty with probability {:?}", network.stationary_probability(&[0, 0]));
+//! ```
+//!
+//! [Network]: struct.Network.html
+//! [FiniteMarkovChain]: ../struct.FiniteMarkovChain.html
+
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
+use rand::{Rng, SeedableRng};
+use rand_distr::Exp1;
+
+// Structs
+use crate::errors::{InvalidState, QueueingError};
+use crate::FiniteMarkovChain;
+use ndarray::{Array1, Array2};
+use std::collections::HashMap;
+
+// Functions
+use core::mem;
+
+/// Network of single-server queues with exponential services and Markovian routing,
+/// with states the queue length at each node.
+///
+/// See the [module documentation] for details.
+///
+/// [module documentation]: index.html
+#[derive(Debug, Clone)]
+pub struct Network<R>
+where
+    R: Rng,
+{
+    state: Vec<u64>,
+    initial_state: Vec<u64>,
+    arrival_rates: Vec<f64>,
+    service_rates: Vec<f64>,
+    routing: FiniteMarkovChain<usize, f64, R>,
+}
+
+impl<R> Network<R>
+where
+    R: Rng,
+{
+    /// Constructs a new `Network<R>` with initial queue lengths `queues`, external arrival rates
+    /// `arrival_rates`, service rates `service_rates` and routing matrix `routing`, where customers
+    /// served at node `i` go to node `j` with probability `routing[[i, j]]`, and leave otherwise.
+    ///
+    /// # Errors
+    ///
+    /// If the dimensions do not match, some rate is negative or not finite,
+    /// or some row of `routing` has negative entries or sums up to more than one.
+    #[inline]
+    pub fn new(
+        queues: Vec<u64>,
+        arrival_rates: Vec<f64>,
+        service_rates: Vec<f64>,
+        routing: Array2<f64>,
+        rng: R,
+    ) -> Result<Self, QueueingError> {
+        let n = queues.len();
+        if arrival_rates.len() != n || service_rates.len() != n || routing.dim() != (n, n) {
+            return Err(QueueingError::DimensionMismatch);
+        }
+        if let Some(&rate) = arrival_rates.iter().chain(&service_rates).find(|&&rate| rate < 0. || !rate.is_finite()) {
+            return Err(QueueingError::InvalidRate(rate));
+        }
+        // The outside of the network is the last state of the routing chain
+        let mut matrix = Array2::zeros((n + 1, n + 1));
+        for (i, row) in routing.genrows().into_iter().enumerate() {
+            let staying: f64 = row.sum();
+            if row.iter().any(|&p| p < 0. || !p.is_finite()) || staying > 1. + 1e-12 {
+                return Err(QueueingError::InvalidRouting(i));
+            }
+            matrix.slice_mut(ndarray::s![i, ..n]).assign(&row);
+            matrix[[i, n]] = (1. - staying).max(0.);
+        }
+        matrix[[n, n]] = 1.;
+        Ok(Network {
+            initial_state: queues.clone(),
+            state: queues,
+            arrival_rates,
+            service_rates,
+            routing: FiniteMarkovChain::from((n, matrix, rng)),
+        })
+    }
+
+    /// Constructs a new `Network<R>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Errors
+    ///
+    /// In the same cases as [new].
+    ///
+    /// [new]: struct.Network.html#method.new
+    #[inline]
+    pub fn with_seed(
+        queues: Vec<u64>,
+        arrival_rates: Vec<f64>,
+        service_rates: Vec<f64>,
+        routing: Array2<f64>,
+        seed: u64,
+    ) -> Result<Self, QueueingError>
+    where
+        R: SeedableRng,
+    {
+        Network::new(queues, arrival_rates, service_rates, routing, R::seed_from_u64(seed))
+    }
+
+    /// Returns the number of nodes.
+    #[inline]
+    pub fn nnodes(&self) -> usize {
+        self.state.len()
+    }
+
+    /// Returns the rates of external arrivals to each node.
+    #[inline]
+    pub fn arrival_rates(&self) -> &[f64] {
+        &self.arrival_rates
+    }
+
+    /// Returns the service rate of each node.
+    #[inline]
+    pub fn service_rates(&self) -> &[f64] {
+        &self.service_rates
+    }
+
+    /// Returns the routing matrix between nodes.
+    #[inline]
+    pub fn routing_matrix(&self) -> Array2<f64> {
+        let n = self.nnodes();
+        self.routing.transition_probabilities().slice(ndarray::s![..n, ..n]).to_owned()
+    }
+
+    /// Returns the total arrival rate to each node, solving the traffic equations `λ = a + λ P`,
+    /// or `None` if they have no unique solution, that is, if some customers may never leave.
+    ///
+    /// # Costs
+    ///
+    /// O(n^3), where n is the number of nodes.
+    #[inline]
+    pub fn throughputs(&self) -> Option<Array1<f64>> {
+        let n = self.nnodes();
+        let system = Array2::eye(n) - self.routing_matrix().t();
+        crate::linalg::solve(system, Array1::from(self.arrival_rates.clone()))
+    }
+
+    /// Returns the utilization `ρ_i = λ_i / μ_i` of each node, or `None` if the traffic equations
+    /// have no unique solution.
+    ///
+    /// Nodes with zero throughput have zero utilization, even if they are never served.
+    #[inline]
+    pub fn utilizations(&self) -> Option<Array1<f64>> {
+        let throughputs = self.throughputs()?;
+        Some(Array1::from_shape_fn(self.nnodes(), |i| {
+            if throughputs[i] <= 0. {
+                0.
+            } else {
+                throughputs[i] / self.service_rates[i]
+            }
+        }))
+    }
+
+    /// Returns `true` if the network is positive recurrent, that is, every utilization is smaller than one.
+    #[inline]
+    pub fn is_stable(&self) -> bool {
+        self.utilizations().is_some_and(|rho| rho.iter().all(|&rho| rho < 1.))
+    }
+
+    /// Returns the stationary probability of the queue lengths `queues`, in product form,
+    /// or `None` if the network is not stable.
+    ///
+    /// # Panics
+    ///
+    /// If `queues` does not have one length per node.
+    #[inline]
+    pub fn stationary_probability(&self, queues: &[u64]) -> Option<f64> {
+        assert_eq!(queues.len(), self.nnodes(), "There must be one queue length per node.");
+        if !self.is_stable() {
+            return None;
+        }
+        let utilizations = self.utilizations()?;
+        Some(
+            utilizations
+                .iter()
+                .zip(queues)
+                .map(|(&rho, &length)| (1. - rho) * rho.powi(length as i32))
+                .product(),
+        )
+    }
+
+    /// Returns the stationary mean queue length of each node, `ρ_i / (1 - ρ_i)`,
+    /// or `None` if the network is not stable.
+    #[inline]
+    pub fn stationary_mean_lengths(&self) -> Option<Array1<f64>> {
+        if !self.is_stable() {
+            return None;
+        }
+        Some(self.utilizations()?.mapv(|rho| rho / (1. - rho)))
+    }
+
+    /// Simulates the network during `total_time` and returns the fraction of time spent in each
+    /// visited state. The process is left in the state at `total_time`.
+    ///
+    /// # Panics
+    ///
+    /// If `total_time` is not positive and finite.
+    #[inline]
+    pub fn empirical_distribution(&mut self, total_time: f64) -> HashMap<Vec<u64>, f64> {
+        assert!(total_time > 0. && total_time.is_finite(), "The total time must be positive and finite.");
+        let mut occupation = HashMap::new();
+        let mut remaining = total_time;
+        loop {
+            let period = self.sample_period();
+            *occupation.entry(self.state.clone()).or_insert(0.) += period.min(remaining) / total_time;
+            if period >= remaining {
+                return occupation;
+            }
+            remaining -= period;
+            self.apply_event();
+        }
+    }
+
+    /// Simulates the network during `total_time` and returns the total variation distance
+    /// between the fraction of time spent in each state and the product-form stationary distribution,
+    /// or `None` if the network is not stable.
+    ///
+    /// # Panics
+    ///
+    /// If `total_time` is not positive and finite.
+    ///
+    /// # Examples
+    ///
+    /// A single queue with feedback: served customers come back with probability one half.
+    /// ```
+    /// # use ndarray::array;
+    /// # use markovian::queueing::Network;
+    /// let mut network = Network::new(vec![0], vec![1.], vec![4.], array![[0.5]], rand::thread_rng()).unwrap();
+    /// let distance = network.product_form_distance(10_000.).unwrap();
+    /// assert!(distance < 0.1);
+    /// ```
+    #[inline]
+    pub fn product_form_distance(&mut self, total_time: f64) -> Option<f64> {
+        if !self.is_stable() {
+            return None;
+        }
+        let empirical = self.empirical_distribution(total_time);
+        let mut visited_mass = 0.;
+        let mut distance = 0.;
+        for (queues, fraction) in empirical {
+            let probability = self.stationary_probability(&queues)?;
+            visited_mass += probability;
+            distance += (fraction - probability).abs();
+        }
+        Some((distance + (1. - visited_mass).max(0.)) / 2.)
+    }
+
+    /// Returns the total rate of events from the current state.
+    #[inline]
+    fn total_rate(&self) -> f64 {
+        let services: f64 = self
+            .state
+            .iter()
+            .zip(&self.service_rates)
+            .filter(|(&length, _)| length > 0)
+            .map(|(_, rate)| rate)
+            .sum();
+        self.arrival_rates.iter().sum::<f64>() + services
+    }
+
+    /// Samples the period until the next event, which is infinite if no event can happen.
+    #[inline]
+    fn sample_period(&mut self) -> f64 {
+        let total_rate = self.total_rate();
+        if total_rate > 0. {
+            self.routing.rng_mut().sample::<f64, _>(Exp1) / total_rate
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// Changes the state by the next arrival or service, chosen with probability proportional to its rate.
+    #[inline]
+    fn apply_event(&mut self) {
+        let n = self.nnodes();
+        let mut threshold = self.routing.rng_mut().gen::<f64>() * self.total_rate();
+        for i in 0..n {
+            threshold -= self.arrival_rates[i];
+            if threshold < 0. {
+                self.state[i] += 1;
+                return;
+            }
+        }
+        let busy: Vec<usize> = (0..n).filter(|&i| self.state[i] > 0 && self.service_rates[i] > 0.).collect();
+        let served = busy
+            .iter()
+            .copied()
+            .find(|&i| {
+                threshold -= self.service_rates[i];
+                threshold < 0.
+            })
+            .or_else(|| busy.last().copied());
+        if let Some(i) = served {
+            self.state[i] -= 1;
+            self.routing.set_state(i).expect("Nodes are states of the routing chain.");
+            let destination = self.routing.sample_index();
+            if destination < n {
+                self.state[destination] += 1;
+            }
+        }
+    }
+}
+
+impl<R> Reset for Network<R>
+where
+    R: Rng,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+    }
+}
+
+impl<R> RandomSource for Network<R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        self.routing.rng()
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        self.routing.rng_mut()
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        self.routing.set_rng(rng)
+    }
+}
+
+impl<R> State for Network<R>
+where
+    R: Rng,
+{
+    type Item = Vec<u64>;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    /// Changes the queue lengths, as long as there is one per node.
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        if new_state.len() == self.nnodes() {
+            Ok(Some(mem::replace(&mut self.state, new_state)))
+        } else {
+            Err(InvalidState::new(new_state))
+        }
+    }
+}
+
+impl<R> Checkpoint for Network<R>
+where
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<R> Iterator for Network<R>
+where
+    R: Rng,
+{
+    type Item = (f64, Vec<u64>);
+
+    /// Changes the state to the queue lengths after the next event,
+    /// and returns them with the period until that event.
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let period = self.sample_period();
+        if period.is_finite() {
+            self.apply_event();
+        }
+        Some((period, self.state.clone()))
+    }
+}
+
+impl<R> StochasticProcess for Network<R>
+where
+    R: Rng,
+{
+    type Time = f64;
+    type State = Vec<u64>;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next()
+    }
+}
+
+impl<R> StateIterator for Network<R>
+where
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        Some((0., self.state.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn jackson_network() {
+        // Arrivals to the first node, which feeds the other two, and the third one feeds back to the first
+        let routing = array![[0., 0.5, 0.5], [0., 0., 0.], [0.5, 0., 0.]];
+        let mut network = Network::new(vec![0; 3], vec![1., 0., 0.], vec![3., 2., 2.], routing, crate::tests::rng(1)).unwrap();
+        // λ_1 = 1 + λ_3 / 2 and λ_2 = λ_3 = λ_1 / 2
+        let throughputs = network.throughputs().unwrap();
+        assert_abs_diff_eq!(throughputs[0], 4. / 3., epsilon = 1e-12);
+        assert_abs_diff_eq!(throughputs[1], 2. / 3., epsilon = 1e-12);
+        assert_abs_diff_eq!(throughputs[2], 2. / 3., epsilon = 1e-12);
+        assert!(network.is_stable());
+        let means = network.stationary_mean_lengths().unwrap();
+        assert_abs_diff_eq!(means[0], 0.8, epsilon = 1e-12);
+
+        let empirical = network.empirical_distribution(50_000.);
+        assert_abs_diff_eq!(empirical.values().sum::<f64>(), 1., epsilon = 1e-9);
+        let mean_first: f64 = empirical.iter().map(|(queues, fraction)| queues[0] as f64 * fraction).sum();
+        assert_abs_diff_eq!(mean_first, 0.8, epsilon = 0.05);
+        assert!(network.product_form_distance(50_000.).unwrap() < 0.05);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Network::new(vec![0], vec![1.], vec![1., 2.], array![[0.]], crate::tests::rng(2)).err(),
+            Some(QueueingError::DimensionMismatch)
+        );
+        assert_eq!(
+            Network::new(vec![0], vec![-1.], vec![1.], array![[0.]], crate::tests::rng(3)).err(),
+            Some(QueueingError::InvalidRate(-1.))
+        );
+        assert_eq!(
+            Network::new(vec![0, 0], vec![1., 0.], vec![1., 1.], array![[0., 0.7], [0.6, 0.6]], crate::tests::rng(4)).err(),
+            Some(QueueingError::InvalidRouting(1))
+        );
+
+        // Customers never leave
+        let closed = Network::new(vec![1, 0], vec![0., 0.], vec![1., 1.], array![[0., 1.], [1., 0.]], crate::tests::rng(5)).unwrap();
+        assert!(closed.throughputs().is_none());
+        assert!(!closed.is_stable());
+    }
+}
@@ -0,0 +1,332 @@
+// Traits
+use rand_distr::Distribution;
+use crate::traits::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess, Transition};
+use core::fmt::Debug;
+use rand::{Rng, SeedableRng};
+
+// Structs
+use crate::errors::InvalidState;
+
+// Functions
+use core::mem;
+
+/// How the environment of a [RandomEnvironmentChain] is resampled.
+///
+/// [RandomEnvironmentChain]: struct.RandomEnvironmentChain.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnvironmentMode {
+    /// A new kernel is sampled before each step.
+    Annealed,
+    /// The kernel is sampled once and held fixed along the trajectory,
+    /// until [resample_environment] is called.
+    ///
+    /// [resample_environment]: struct.RandomEnvironmentChain.html#method.resample_environment
+    Quenched,
+}
+
+/// Markov chain in a random environment, in discrete time and with arbitrary space.
+///
+/// The transition kernel, of type `K`, is itself random, sampled from `environment`
+/// with the random number generator of the chain.
+/// In [Annealed] mode, a new kernel is sampled at each step, while
+/// in [Quenched] mode, the kernel is held fixed along the trajectory.
+///
+/// # Remarks
+///
+/// [Reset] restores the initial state but keeps the current kernel,
+/// so that a quenched trajectory can be repeated in the same environment.
+/// In the same way, forks share the current kernel.
+///
+/// # Examples
+///
+/// Random walk whose drift is random: up or down for the whole trajectory.
+/// ```
+/// # use markovian::{EnvironmentMode, RandomEnvironmentChain, prelude::*};
+/// fn up(state: &i32) -> Raw<Vec<(f64, i32)>> {
+///     raw_dist![(0.9, state + 1), (0.1, state - 1)]
+/// }
+/// fn down(state: &i32) -> Raw<Vec<(f64, i32)>> {
+///     raw_dist![(0.1, state + 1), (0.9, state - 1)]
+/// }
+/// let environment = raw_dist![(0.5, up as fn(&i32) -> _), (0.5, down as fn(&i32) -> _)];
+/// let mc = RandomEnvironmentChain::new(0, environment, EnvironmentMode::Quenched, rand::thread_rng());
+/// let position = mc.take(100).last().unwrap();
+/// assert!(position.abs() > 20);
+/// ```
+///
+/// [Annealed]: enum.EnvironmentMode.html#variant.Annealed
+/// [Quenched]: enum.EnvironmentMode.html#variant.Quenched
+/// [Reset]: trait.Reset.html
+#[derive(Debug, Clone)]
+pub struct RandomEnvironmentChain<T, K, D, R> {
+    state: T,
+    initial_state: T,
+    kernel: K,
+    environment: D,
+    mode: EnvironmentMode,
+    rng: R,
+}
+
+impl<T, K, D, R> RandomEnvironmentChain<T, K, D, R>
+where
+    T: Clone,
+    K: Transition<T, T>,
+    D: Distribution<K>,
+    R: Rng,
+{
+    /// Constructs a new `RandomEnvironmentChain<T, K, D, R>`, sampling its first kernel from `environment`.
+    #[inline]
+    pub fn new(state: T, environment: D, mode: EnvironmentMode, mut rng: R) -> Self {
+        let kernel = environment.sample(&mut rng);
+        RandomEnvironmentChain {
+            initial_state: state.clone(),
+            state,
+            kernel,
+            environment,
+            mode,
+            rng,
+        }
+    }
+
+    /// Constructs a new `RandomEnvironmentChain<T, K, D, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(state: T, environment: D, mode: EnvironmentMode, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        RandomEnvironmentChain::new(state, environment, mode, R::seed_from_u64(seed))
+    }
+
+    /// Returns the mode of the environment.
+    #[inline]
+    pub fn mode(&self) -> EnvironmentMode {
+        self.mode
+    }
+
+    /// Returns the current kernel.
+    ///
+    /// In [Annealed] mode, this is the kernel used in the last step.
+    ///
+    /// [Annealed]: enum.EnvironmentMode.html#variant.Annealed
+    #[inline]
+    pub fn kernel(&self) -> &K {
+        &self.kernel
+    }
+
+    /// Replaces the current kernel by `kernel`, returning the previous one.
+    #[inline]
+    pub fn set_kernel(&mut self, kernel: K) -> K {
+        mem::replace(&mut self.kernel, kernel)
+    }
+
+    /// Samples a new kernel from the environment, returning the previous one.
+    ///
+    /// In [Quenched] mode, this starts a new environment, for example, between trajectories.
+    ///
+    /// [Quenched]: enum.EnvironmentMode.html#variant.Quenched
+    #[inline]
+    pub fn resample_environment(&mut self) -> K {
+        let kernel = self.environment.sample(&mut self.rng);
+        self.set_kernel(kernel)
+    }
+}
+
+impl<T, K, D, R> Reset for RandomEnvironmentChain<T, K, D, R>
+where
+    T: Clone,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+    }
+}
+
+impl<T, K, D, R> RandomSource for RandomEnvironmentChain<T, K, D, R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<T, K, D, R> State for RandomEnvironmentChain<T, K, D, R>
+where
+    T: Debug + Clone,
+{
+    type Item = T;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    #[inline]
+    fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        Some(&mut self.state)
+    }
+
+    #[inline]
+    fn set_state(
+        &mut self,
+        mut new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        mem::swap(&mut self.state, &mut new_state);
+        Ok(Some(new_state))
+    }
+}
+
+impl<T, K, D, R> Checkpoint for RandomEnvironmentChain<T, K, D, R>
+where
+    T: Debug + Clone,
+    K: Clone,
+    R: Rng,
+{
+    /// The hidden progress is the current kernel.
+    type Hidden = K;
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {
+        self.kernel.clone()
+    }
+
+    #[inline]
+    fn set_hidden_state(&mut self, kernel: Self::Hidden) {
+        self.kernel = kernel;
+    }
+}
+
+impl<T, K, D, R> Iterator for RandomEnvironmentChain<T, K, D, R>
+where
+    T: Debug + Clone,
+    K: Transition<T, T>,
+    D: Distribution<K>,
+    R: Rng,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.mode == EnvironmentMode::Annealed {
+            self.kernel = self.environment.sample(&mut self.rng);
+        }
+        self.state = self.kernel.sample_from(&self.state, &mut self.rng);
+        self.state().cloned()
+    }
+}
+
+impl<T, K, D, R> StochasticProcess for RandomEnvironmentChain<T, K, D, R>
+where
+    T: Debug + Clone,
+    K: Transition<T, T>,
+    D: Distribution<K>,
+    R: Rng,
+{
+    type Time = ();
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<T, K, D, R> StateIterator for RandomEnvironmentChain<T, K, D, R>
+where
+    T: Debug + Clone,
+    K: Transition<T, T>,
+    D: Distribution<K>,
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.state().cloned()
+    }
+
+    #[inline]
+    fn advance(&mut self) -> bool {
+        if self.mode == EnvironmentMode::Annealed {
+            self.kernel = self.environment.sample(&mut self.rng);
+        }
+        self.state = self.kernel.sample_from(&self.state, &mut self.rng);
+        true
+    }
+}
+
+impl<T, K, D, R> Distribution<T> for RandomEnvironmentChain<T, K, D, R>
+where
+    T: Debug + Clone,
+    K: Transition<T, T>,
+    D: Distribution<K>,
+    R: Rng,
+{
+    /// Sample a possible next state.
+    ///
+    /// In [Annealed] mode, a new kernel is sampled with `rng`, without changing the current one.
+    ///
+    /// [Annealed]: enum.EnvironmentMode.html#variant.Annealed
+    #[inline]
+    fn sample<R2>(&self, rng: &mut R2) -> T
+    where
+        R2: Rng + ?Sized,
+    {
+        match self.mode {
+            EnvironmentMode::Annealed => self.environment.sample(rng).sample_from(&self.state, rng),
+            EnvironmentMode::Quenched => self.kernel.sample_from(&self.state, rng),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::Raw;
+    use pretty_assertions::assert_eq;
+
+    /// Kernel that moves deterministically by a fixed step.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Shift(i32);
+
+    impl Transition<i32, i32> for Shift {
+        fn sample_from<R>(&self, state: &i32, _: &mut R) -> i32
+        where
+            R: Rng + ?Sized,
+        {
+            state + self.0
+        }
+    }
+
+    #[test]
+    fn quenched() {
+        let environment = Raw::new(vec![(0.5, Shift(1)), (0.5, Shift(-1))]);
+        let mut mc = RandomEnvironmentChain::new(0, environment, EnvironmentMode::Quenched, crate::tests::rng(1));
+        let shift = mc.kernel().0;
+        let path: Vec<i32> = mc.by_ref().take(3).collect();
+        assert_eq!(path, vec![shift, 2 * shift, 3 * shift]);
+
+        mc.reset();
+        assert_eq!(mc.next(), Some(shift));
+    }
+
+    #[test]
+    fn annealed() {
+        let environment = Raw::new(vec![(0.5, Shift(1)), (0.5, Shift(-1))]);
+        let mc = RandomEnvironmentChain::new(0, environment, EnvironmentMode::Annealed, crate::tests::rng(2));
+        let path: Vec<i32> = mc.take(100).collect();
+        let increments: Vec<i32> = path.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(increments.contains(&1) && increments.contains(&-1));
+    }
+}
@@ -0,0 +1,177 @@
+//! Regenerative estimation of long-run averages from a single trajectory.
+//!
+//! Every visit to an atom, a state from which the process forgets its past,
+//! starts a new cycle. Cycles are independent and identically distributed,
+//! so the long-run average of a functional `f` is estimated by the ratio
+//! of the sum of `f` over cycles and the total length of cycles.
+//! Its standard error is computed by the jackknife over cycles.
+//!
+//! # Examples
+//!
+//! Long-run fraction of time a chain spends in its second state.
+//! ```
+//! # use ndarray::array;
+//! # use markovian::{FiniteMarkovChain, prelude::*, regenerative::RegenerativeEstimator};
+//! # use rand::SeedableRng;
+//! let rng = rand_pcg::Pcg64::seed_from_u64(1);
+//! let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.25, 0.75]], rng));
+//! let mut estimator = RegenerativeEstimator::new(|state: &usize| *state == 0, |state: &usize| *state as f64);
+//! estimator.extend(mc.trajectory().take(10_000));
+//! let estimate = estimator.estimate().unwrap();
+//! let (low, high) = estimate.confidence_interval(3.);
+//! assert!(low < 2. / 3. && 2. / 3. < high);
+//! ```
+
+/// Regenerative estimate of a long-run average.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegenerativeEstimate {
+    /// Ratio estimator: sum of the functional over all cycles divided by the total length of cycles.
+    pub ratio: f64,
+    /// Jackknife (bias corrected) estimator.
+    pub mean: f64,
+    /// Jackknife estimator of the standard error.
+    pub standard_error: f64,
+    /// Number of complete cycles used.
+    pub cycles: usize,
+}
+
+impl RegenerativeEstimate {
+    /// Returns the confidence interval `mean ± z * standard_error`.
+    ///
+    /// For example, `z = 1.96` gives an asymptotic 95% confidence interval.
+    #[inline]
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        (self.mean - z * self.standard_error, self.mean + z * self.standard_error)
+    }
+}
+
+/// Streaming regenerative estimator of the long-run average of a functional.
+///
+/// States are fed one at a time and only the sum and length of each cycle are stored.
+/// States before the first visit to the atom and after the last one are discarded.
+#[derive(Debug, Clone)]
+pub struct RegenerativeEstimator<A, F> {
+    is_atom: A,
+    functional: F,
+    started: bool,
+    current: (f64, usize),
+    cycles: Vec<(f64, usize)>,
+}
+
+impl<A, F> RegenerativeEstimator<A, F> {
+    /// Constructs a new `RegenerativeEstimator<A, F>` for the atom given by `is_atom`
+    /// and the functional `functional`.
+    #[inline]
+    pub fn new(is_atom: A, functional: F) -> Self {
+        RegenerativeEstimator {
+            is_atom,
+            functional,
+            started: false,
+            current: (0.0, 0),
+            cycles: Vec::new(),
+        }
+    }
+
+    /// Feeds the next state of the trajectory.
+    #[inline]
+    pub fn push<T>(&mut self, state: &T)
+    where
+        A: Fn(&T) -> bool,
+        F: Fn(&T) -> f64,
+    {
+        if (self.is_atom)(state) {
+            if self.started {
+                self.cycles.push(self.current);
+            }
+            self.started = true;
+            self.current = (0.0, 0);
+        }
+        if self.started {
+            self.current.0 += (self.functional)(state);
+            self.current.1 += 1;
+        }
+    }
+
+    /// Returns the number of complete cycles so far.
+    #[inline]
+    pub fn ncycles(&self) -> usize {
+        self.cycles.len()
+    }
+
+    /// Returns the sum of the functional and the length of each complete cycle so far.
+    #[inline]
+    pub fn cycles(&self) -> &[(f64, usize)] {
+        &self.cycles
+    }
+
+    /// Returns the regenerative estimate, or `None` if there are less than two complete cycles.
+    #[inline]
+    pub fn estimate(&self) -> Option<RegenerativeEstimate> {
+        let n = self.cycles.len();
+        if n < 2 {
+            return None;
+        }
+        let total_sum: f64 = self.cycles.iter().map(|(sum, _)| sum).sum();
+        let total_length: f64 = self.cycles.iter().map(|&(_, length)| length as f64).sum();
+        let ratio = total_sum / total_length;
+
+        let nf = n as f64;
+        let pseudo_values: Vec<f64> = self.cycles.iter()
+            .map(|&(sum, length)| {
+                let leave_one_out = (total_sum - sum) / (total_length - length as f64);
+                nf * ratio - (nf - 1.0) * leave_one_out
+            })
+            .collect();
+        let mean = pseudo_values.iter().sum::<f64>() / nf;
+        let variance = pseudo_values.iter()
+            .map(|p| (p - mean).powi(2))
+            .sum::<f64>() / (nf - 1.0);
+
+        Some(RegenerativeEstimate {
+            ratio,
+            mean,
+            standard_error: (variance / nf).sqrt(),
+            cycles: n,
+        })
+    }
+}
+
+impl<A, F, T> Extend<T> for RegenerativeEstimator<A, F>
+where
+    A: Fn(&T) -> bool,
+    F: Fn(&T) -> f64,
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for state in iter {
+            self.push(&state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn deterministic() {
+        let mut estimator = RegenerativeEstimator::new(|x: &u32| *x == 0, |x: &u32| *x as f64);
+        estimator.extend(vec![1, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1]);
+        let estimate = estimator.estimate().unwrap();
+
+        assert_eq!(estimate.cycles, 3);
+        assert_abs_diff_eq!(estimate.ratio, 1.);
+        assert_abs_diff_eq!(estimate.mean, 1.);
+        assert_abs_diff_eq!(estimate.standard_error, 0.);
+    }
+
+    #[test]
+    fn not_enough_cycles() {
+        let mut estimator = RegenerativeEstimator::new(|x: &u32| *x == 0, |x: &u32| *x as f64);
+        estimator.extend(vec![1, 0, 1, 2, 0, 1]);
+
+        assert_eq!(estimator.ncycles(), 1);
+        assert!(estimator.estimate().is_none());
+    }
+}
@@ -0,0 +1,286 @@
+// Traits
+use rand_distr::Distribution;
+use crate::traits::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess, Transition};
+use core::fmt::Debug;
+use rand::{Rng, SeedableRng};
+
+// Structs
+use crate::errors::InvalidState;
+
+// Functions
+use core::mem;
+
+/// Regime-switching process in discrete time: a finite modulating chain over regimes `0..n`
+/// and, for each regime, the dynamics of an observed process.
+///
+/// The state is the pair `(regime, value)`. At each step, the modulating chain moves first,
+/// through `modulation`, and then `value` moves through the dynamics of the new regime.
+/// Both share the random number generator and the clock of the process.
+///
+/// # Examples
+///
+/// A random walk that alternates between a calm and a volatile regime.
+/// ```
+/// # use markovian::{RegimeSwitching, prelude::*};
+/// # use rand_distr::Normal;
+/// let modulation = |regime: &usize| match regime {
+///     0 => raw_dist![(0.95, 0), (0.05, 1)],
+///     _ => raw_dist![(0.10, 0), (0.90, 1)],
+/// };
+/// let calm = |x: &f64| Normal::new(*x, 0.1).unwrap();
+/// let volatile = |x: &f64| Normal::new(*x, 2.0).unwrap();
+/// let dynamics: Vec<Box<dyn Fn(&f64) -> Normal<f64>>> = vec![Box::new(calm), Box::new(volatile)];
+/// let process = RegimeSwitching::new((0, 0.), modulation, dynamics, rand::thread_rng());
+/// for (regime, value) in process.take(10) {
+///     println!("In regime {}, the value is {}", regime, value);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RegimeSwitching<X, M, K, R> {
+    state: (usize, X),
+    initial_state: (usize, X),
+    modulation: M,
+    dynamics: Vec<K>,
+    rng: R,
+}
+
+impl<X, M, K, R> RegimeSwitching<X, M, K, R>
+where
+    X: Clone,
+    M: Transition<usize, usize>,
+    K: Transition<X, X>,
+    R: Rng,
+{
+    /// Constructs a new `RegimeSwitching<X, M, K, R>`, where `dynamics[i]` is the dynamics in regime `i`.
+    ///
+    /// # Panics
+    ///
+    /// If the regime of `state` does not have dynamics.
+    #[inline]
+    pub fn new(state: (usize, X), modulation: M, dynamics: Vec<K>, rng: R) -> Self {
+        assert!(state.0 < dynamics.len(), "Every regime must have dynamics.");
+        RegimeSwitching {
+            initial_state: state.clone(),
+            state,
+            modulation,
+            dynamics,
+            rng,
+        }
+    }
+
+    /// Constructs a new `RegimeSwitching<X, M, K, R>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Panics
+    ///
+    /// If the regime of `state` does not have dynamics.
+    #[inline]
+    pub fn with_seed(state: (usize, X), modulation: M, dynamics: Vec<K>, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        RegimeSwitching::new(state, modulation, dynamics, R::seed_from_u64(seed))
+    }
+
+    /// Returns the current regime.
+    #[inline]
+    pub fn regime(&self) -> usize {
+        self.state.0
+    }
+
+    /// Returns the number of regimes.
+    #[inline]
+    pub fn nregimes(&self) -> usize {
+        self.dynamics.len()
+    }
+
+    /// Returns the dynamics of each regime.
+    #[inline]
+    pub fn dynamics(&self) -> &[K] {
+        &self.dynamics
+    }
+}
+
+impl<X, M, K, R> Reset for RegimeSwitching<X, M, K, R>
+where
+    X: Clone,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+    }
+}
+
+impl<X, M, K, R> RandomSource for RegimeSwitching<X, M, K, R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<X, M, K, R> State for RegimeSwitching<X, M, K, R>
+where
+    X: Debug + Clone,
+{
+    type Item = (usize, X);
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    /// Changes the regime and the value, as long as the regime has dynamics.
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        if new_state.0 < self.dynamics.len() {
+            Ok(Some(mem::replace(&mut self.state, new_state)))
+        } else {
+            Err(InvalidState::new(new_state))
+        }
+    }
+}
+
+impl<X, M, K, R> Checkpoint for RegimeSwitching<X, M, K, R>
+where
+    X: Debug + Clone,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<X, M, K, R> Iterator for RegimeSwitching<X, M, K, R>
+where
+    X: Debug + Clone,
+    M: Transition<usize, usize>,
+    K: Transition<X, X>,
+    R: Rng,
+{
+    type Item = (usize, X);
+
+    /// Advances the modulating chain and then the value, in the new regime.
+    ///
+    /// # Panics
+    ///
+    /// If the modulating chain moves to a regime without dynamics.
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.state = sample_regime_step(&self.state, &self.modulation, &self.dynamics, &mut self.rng);
+        self.state().cloned()
+    }
+}
+
+impl<X, M, K, R> StochasticProcess for RegimeSwitching<X, M, K, R>
+where
+    X: Debug + Clone,
+    M: Transition<usize, usize>,
+    K: Transition<X, X>,
+    R: Rng,
+{
+    type Time = ();
+    type State = (usize, X);
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<X, M, K, R> StateIterator for RegimeSwitching<X, M, K, R>
+where
+    X: Debug + Clone,
+    M: Transition<usize, usize>,
+    K: Transition<X, X>,
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        self.state().cloned()
+    }
+}
+
+impl<X, M, K, R> Distribution<(usize, X)> for RegimeSwitching<X, M, K, R>
+where
+    X: Debug + Clone,
+    M: Transition<usize, usize>,
+    K: Transition<X, X>,
+    R: Rng,
+{
+    /// Sample a possible next state.
+    #[inline]
+    fn sample<R2>(&self, rng: &mut R2) -> (usize, X)
+    where
+        R2: Rng + ?Sized,
+    {
+        sample_regime_step(&self.state, &self.modulation, &self.dynamics, rng)
+    }
+}
+
+/// Samples the next regime and then the next value, in the new regime.
+#[inline]
+fn sample_regime_step<X, M, K, R>(state: &(usize, X), modulation: &M, dynamics: &[K], rng: &mut R) -> (usize, X)
+where
+    M: Transition<usize, usize>,
+    K: Transition<X, X>,
+    R: Rng + ?Sized,
+{
+    let regime = modulation.sample_from(&state.0, rng);
+    let kernel = dynamics.get(regime).expect("The modulating chain moved to a regime without dynamics.");
+    let value = kernel.sample_from(&state.1, rng);
+    (regime, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::Raw;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn alternating() {
+        let modulation = |regime: &usize| Raw::new(vec![(1.0, 1 - regime)]);
+        fn up(x: &i32) -> Raw<Vec<(f64, i32)>> {
+            Raw::new(vec![(1.0, x + 1)])
+        }
+        fn down(x: &i32) -> Raw<Vec<(f64, i32)>> {
+            Raw::new(vec![(1.0, x - 10)])
+        }
+        let dynamics = vec![up as fn(&i32) -> _, down];
+        let process = RegimeSwitching::new((0, 0), modulation, dynamics, crate::tests::rng(1));
+        let path: Vec<(usize, i32)> = process.take(4).collect();
+
+        assert_eq!(path, vec![(1, -10), (0, -9), (1, -19), (0, -18)]);
+    }
+
+    #[test]
+    fn invalid_regime() {
+        let modulation = |_: &usize| Raw::new(vec![(1.0, 0)]);
+        let stay = |x: &i32| Raw::new(vec![(1.0, *x)]);
+        let mut process = RegimeSwitching::new((0, 0), modulation, vec![stay], crate::tests::rng(2));
+
+        assert!(process.set_state((1, 0)).is_err());
+        assert_eq!(process.set_state((0, 5)), Ok(Some((0, 0))));
+    }
+}
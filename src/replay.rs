@@ -0,0 +1,271 @@
+//! Recording and replaying the randomness of a simulation.
+//!
+//! A [Recorder] wraps a random number generator and logs every random word it produces.
+//! The resulting [ReplayLog] can be fed to a [Replayer], which produces the same words again,
+//! so that a simulation can be reproduced exactly, or a modified process can be driven
+//! by the same randomness as the original one, as in common random numbers variance reduction.
+//!
+//! All random words are 64 bits long: `next_u32` uses the lower half of a word and
+//! `fill_bytes` uses as many words as needed, so that each call consumes whole words
+//! no matter how the process asks for randomness.
+//!
+//! # Examples
+//!
+//! Driving a random walk with an upward drift by the randomness of a symmetric one.
+//! ```
+//! # use rand::SeedableRng;
+//! # use markovian::{MarkovChain, prelude::*, replay::{Recorder, Replayer}};
+//! let symmetric = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+//! let drifted = |state: &i32| raw_dist![(0.6, state + 1), (0.4, state - 1)];
+//! let rng = Recorder::new(rand_pcg::Pcg64::seed_from_u64(1));
+//! let mut mc = MarkovChain::new(0, symmetric, rng);
+//! let original = mc.by_ref().nth(99).unwrap();
+//!
+//! let log = mc.rng().log().clone();
+//! let rng = Replayer::new(log, rand_pcg::Pcg64::seed_from_u64(2));
+//! let modified = MarkovChain::new(0, drifted, rng).nth(99).unwrap();
+//! assert!(modified >= original);
+//! ```
+//!
+//! [Recorder]: struct.Recorder.html
+//! [ReplayLog]: struct.ReplayLog.html
+//! [Replayer]: struct.Replayer.html
+
+// Traits
+use rand::RngCore;
+
+/// Sequence of random words produced during a run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ReplayLog {
+    words: Vec<u64>,
+}
+
+impl ReplayLog {
+    /// Constructs a new `ReplayLog` from random words.
+    #[inline]
+    pub fn new(words: Vec<u64>) -> Self {
+        ReplayLog { words }
+    }
+
+    /// Returns the random words of the log.
+    #[inline]
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Returns the number of random words in the log.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns `true` if the log has no random words.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the position of the first random word where the logs differ,
+    /// or `None` if they are equal.
+    ///
+    /// If one log is a prefix of the other, the first difference is at the end of the shortest.
+    /// This helps debugging runs that diverge.
+    #[inline]
+    pub fn first_difference(&self, other: &ReplayLog) -> Option<usize> {
+        self.words.iter()
+            .zip(&other.words)
+            .position(|(a, b)| a != b)
+            .or_else(|| {
+                if self.len() == other.len() {
+                    None
+                } else {
+                    Some(self.len().min(other.len()))
+                }
+            })
+    }
+}
+
+/// Random number generator that logs every random word produced by an inner generator.
+#[derive(Debug, Clone)]
+pub struct Recorder<R> {
+    rng: R,
+    log: ReplayLog,
+}
+
+impl<R> Recorder<R> {
+    /// Constructs a new `Recorder` wrapping `rng`, with an empty log.
+    #[inline]
+    pub fn new(rng: R) -> Self {
+        Recorder {
+            rng,
+            log: ReplayLog::default(),
+        }
+    }
+
+    /// Returns the log of random words produced so far.
+    #[inline]
+    pub fn log(&self) -> &ReplayLog {
+        &self.log
+    }
+
+    /// Empties the log, returning the previous one.
+    #[inline]
+    pub fn take_log(&mut self) -> ReplayLog {
+        core::mem::take(&mut self.log)
+    }
+
+    /// Returns the inner random number generator and the log.
+    #[inline]
+    pub fn into_parts(self) -> (R, ReplayLog) {
+        (self.rng, self.log)
+    }
+}
+
+impl<R> RngCore for Recorder<R>
+where
+    R: RngCore,
+{
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let word = self.rng.next_u64();
+        self.log.words.push(word);
+        word
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via_words(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Random number generator that produces the random words of a log,
+/// and then those of a fallback generator.
+#[derive(Debug, Clone)]
+pub struct Replayer<R> {
+    log: ReplayLog,
+    position: usize,
+    fallback: R,
+}
+
+impl<R> Replayer<R> {
+    /// Constructs a new `Replayer` of `log`, using `fallback` once the log is exhausted.
+    #[inline]
+    pub fn new(log: ReplayLog, fallback: R) -> Self {
+        Replayer {
+            log,
+            position: 0,
+            fallback,
+        }
+    }
+
+    /// Returns the number of random words produced so far.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns `true` if all random words of the log were produced.
+    #[inline]
+    pub fn is_exhausted(&self) -> bool {
+        self.position >= self.log.len()
+    }
+}
+
+impl<R> RngCore for Replayer<R>
+where
+    R: RngCore,
+{
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let word = match self.log.words.get(self.position) {
+            Some(&word) => word,
+            None => self.fallback.next_u64(),
+        };
+        self.position += 1;
+        word
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via_words(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Fills `dest` with the little-endian bytes of consecutive random words.
+#[inline]
+fn fill_bytes_via_words<R: RngCore>(rng: &mut R, dest: &mut [u8]) {
+    for chunk in dest.chunks_mut(8) {
+        let bytes = rng.next_u64().to_le_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn replay() {
+        let transition = |state: &u64| raw_dist![(0.3, state + 1), (0.7, *state)];
+        let rng = Recorder::new(rand_pcg::Pcg64::seed_from_u64(1));
+        let mut mc = crate::MarkovChain::new(0, transition, rng);
+        let expected: Vec<u64> = mc.by_ref().take(50).collect();
+        let log = mc.rng_mut().take_log();
+
+        let rng = Replayer::new(log, rand_pcg::Pcg64::seed_from_u64(2));
+        let mut mc = crate::MarkovChain::new(0, transition, rng);
+        assert_eq!(mc.by_ref().take(50).collect::<Vec<u64>>(), expected);
+        assert!(mc.rng().is_exhausted());
+    }
+
+    #[test]
+    fn words() {
+        let mut recorder = Recorder::new(crate::tests::rng(3));
+        let mut bytes = [0_u8; 10];
+        recorder.fill_bytes(&mut bytes);
+        let x: f64 = recorder.gen();
+        let (_, log) = recorder.into_parts();
+        assert_eq!(log.len(), 3);
+
+        let mut replayer = Replayer::new(log.clone(), crate::tests::rng(4));
+        let mut replayed = [0_u8; 10];
+        replayer.fill_bytes(&mut replayed);
+        assert_eq!(replayed, bytes);
+        assert_eq!(replayer.gen::<f64>(), x);
+        assert_eq!(replayer.position(), 3);
+    }
+
+    #[test]
+    fn first_difference() {
+        let log = ReplayLog::new(vec![1, 2, 3]);
+
+        assert_eq!(log.first_difference(&log), None);
+        assert_eq!(log.first_difference(&ReplayLog::new(vec![1, 5, 3])), Some(1));
+        assert_eq!(log.first_difference(&ReplayLog::new(vec![1, 2])), Some(2));
+    }
+}
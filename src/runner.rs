@@ -0,0 +1,217 @@
+//! Running simulations within a step or wall-clock budget.
+//!
+//! Long simulations, for example of slow-mixing chains, can be time-boxed by a [Runner],
+//! which stops consuming the process once a budget is exhausted and returns the partial
+//! result together with the reason why it stopped.
+//!
+//! # Examples
+//!
+//! Summing a random walk for at most ten milliseconds or a million steps, whatever comes first.
+//! ```
+//! # use core::time::Duration;
+//! # use markovian::{MarkovChain, prelude::*, runner::{Runner, Stop}};
+//! let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+//! let mc = MarkovChain::new(0, transition, rand::thread_rng());
+//! let outcome = Runner::new()
+//!     .with_budget(Duration::from_millis(10))
+//!     .with_max_steps(1_000_000)
+//!     .run(mc, 0_i64, |sum, state| sum + state as i64);
+//! assert!(outcome.stop == Stop::Budget || outcome.stop == Stop::MaxSteps);
+//! println!("After {} steps, the sum is {}", outcome.steps, outcome.result);
+//! ```
+//!
+//! [Runner]: struct.Runner.html
+
+// Structs
+use core::time::Duration;
+use std::time::Instant;
+
+/// Reason why a run stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stop {
+    /// The process has no more elements.
+    Finished,
+    /// The maximum number of steps was reached.
+    MaxSteps,
+    /// The wall-clock budget was exhausted.
+    Budget,
+}
+
+/// Result of a run, possibly partial.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome<B> {
+    /// Accumulated result over all consumed elements.
+    pub result: B,
+    /// Number of consumed elements.
+    pub steps: usize,
+    /// Wall-clock time of the run.
+    pub elapsed: Duration,
+    /// Reason why the run stopped.
+    pub stop: Stop,
+}
+
+impl<B> Outcome<B> {
+    /// Returns `true` if the run stopped before the process finished.
+    #[inline]
+    pub fn is_partial(&self) -> bool {
+        self.stop != Stop::Finished
+    }
+}
+
+/// Runner of simulations with a step and wall-clock budget, optionally reporting progress.
+#[derive(Debug, Clone)]
+pub struct Runner<P> {
+    max_steps: Option<usize>,
+    budget: Option<Duration>,
+    progress: Option<(usize, P)>,
+}
+
+impl Runner<fn(usize, Duration)> {
+    /// Constructs a new `Runner` without limits nor progress reports.
+    #[inline]
+    pub fn new() -> Self {
+        Runner {
+            max_steps: None,
+            budget: None,
+            progress: None,
+        }
+    }
+}
+
+impl Default for Runner<fn(usize, Duration)> {
+    #[inline]
+    fn default() -> Self {
+        Runner::new()
+    }
+}
+
+impl<P> Runner<P>
+where
+    P: FnMut(usize, Duration),
+{
+    /// Sets the wall-clock budget of runs.
+    #[inline]
+    pub fn with_budget(mut self, budget: Duration) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Sets the maximum number of steps of runs.
+    #[inline]
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Sets a callback that receives the number of steps and the elapsed time every `every` steps.
+    ///
+    /// # Panics
+    ///
+    /// If `every` is zero.
+    #[inline]
+    pub fn with_progress<Q>(self, every: usize, progress: Q) -> Runner<Q>
+    where
+        Q: FnMut(usize, Duration),
+    {
+        assert!(every > 0, "Progress must be reported every positive number of steps.");
+        Runner {
+            max_steps: self.max_steps,
+            budget: self.budget,
+            progress: Some((every, progress)),
+        }
+    }
+
+    /// Consumes `iter` until it finishes or the budget is exhausted,
+    /// folding its elements with `fold` starting from `init`.
+    #[inline]
+    pub fn run<I, B, F>(&mut self, iter: I, init: B, mut fold: F) -> Outcome<B>
+    where
+        I: IntoIterator,
+        F: FnMut(B, I::Item) -> B,
+    {
+        let start = Instant::now();
+        let mut result = init;
+        let mut steps = 0;
+        let mut iter = iter.into_iter();
+        let stop = loop {
+            if self.max_steps.is_some_and(|max_steps| steps >= max_steps) {
+                break Stop::MaxSteps;
+            }
+            if self.budget.is_some_and(|budget| start.elapsed() >= budget) {
+                break Stop::Budget;
+            }
+            match iter.next() {
+                Some(item) => {
+                    result = fold(result, item);
+                    steps += 1;
+                    if let Some((every, progress)) = self.progress.as_mut() {
+                        if steps % *every == 0 {
+                            progress(steps, start.elapsed());
+                        }
+                    }
+                },
+                None => break Stop::Finished,
+            }
+        };
+        Outcome {
+            result,
+            steps,
+            elapsed: start.elapsed(),
+            stop,
+        }
+    }
+
+    /// Consumes `iter` until it finishes or the budget is exhausted, collecting its elements.
+    #[inline]
+    pub fn collect<I>(&mut self, iter: I) -> Outcome<Vec<I::Item>>
+    where
+        I: IntoIterator,
+    {
+        self.run(iter, Vec::new(), |mut items, item| {
+            items.push(item);
+            items
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn max_steps() {
+        let outcome = Runner::new().with_max_steps(3).collect(0..);
+
+        assert_eq!(outcome.result, vec![0, 1, 2]);
+        assert_eq!(outcome.stop, Stop::MaxSteps);
+        assert!(outcome.is_partial());
+    }
+
+    #[test]
+    fn finished() {
+        let outcome = Runner::new().with_max_steps(10).run(0..3, 0, |sum, x| sum + x);
+
+        assert_eq!(outcome.result, 3);
+        assert_eq!(outcome.steps, 3);
+        assert_eq!(outcome.stop, Stop::Finished);
+    }
+
+    #[test]
+    fn budget() {
+        let outcome = Runner::new().with_budget(Duration::from_millis(1)).run(0.., (), |_, _| ());
+
+        assert_eq!(outcome.stop, Stop::Budget);
+        assert!(outcome.elapsed >= Duration::from_millis(1));
+    }
+
+    #[test]
+    fn progress() {
+        let mut reports = Vec::new();
+        Runner::new()
+            .with_progress(2, |steps, _| reports.push(steps))
+            .run(0..5, (), |_, _| ());
+
+        assert_eq!(reports, vec![2, 4]);
+    }
+}
@@ -0,0 +1,3 @@
+pub use kgram::KGram;
+
+mod kgram;
@@ -0,0 +1,314 @@
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State};
+use core::fmt::Debug;
+use core::hash::Hash;
+use rand::{Rng, SeedableRng};
+use rand_distr::Distribution;
+
+// Structs
+use crate::errors::InvalidState;
+use rand_distr::weighted_alias::WeightedAliasIndex;
+use std::collections::HashMap;
+
+// Functions
+use core::mem;
+
+/// Followers of a context: the position of each token, the tokens in first-seen order and their counts.
+type Followers<T> = (HashMap<T, usize>, Vec<T>, Vec<usize>);
+
+/// Markov Chain of order k over tokens, estimated from a sequence of tokens.
+///
+/// The state of the chain is the window of the last `order` tokens. Each 
+/// window observed in the data is followed by a token chosen proportionally
+/// to the number of times it followed that window in the data. 
+/// The iterator yields the generated tokens and stops if the current window 
+/// was never followed by a token in the data, e.g. the final window of the data. 
+///
+/// # Costs
+///
+/// **Construction**: O(n), where n is the length of the data.
+///
+/// **Sample**: O(k), where k is the order of the chain.
+///
+/// # Examples
+///
+/// Generating text from a sequence of words.
+/// ```
+/// # use markovian::sequence::KGram;
+/// let text = "the cat sat on the mat and the cat ran";
+/// let mc = KGram::from_tokens(1, text.split(' '), rand::thread_rng());
+/// let generated: Vec<&str> = mc.take(10).collect();
+/// println!("{}", generated.join(" "));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KGram<T, R> 
+where
+    T: Debug + Clone + Hash + Eq,
+    R: Rng,
+{
+    order: usize,
+    window: Vec<T>,
+    initial_window: Vec<T>,
+    transitions: HashMap<Vec<T>, (Vec<T>, WeightedAliasIndex<usize>)>,
+    rng: R,
+}
+
+impl<T, R> KGram<T, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    R: Rng,
+{
+    /// Constructs a new `KGram<T, R>` of order `order` by counting transitions in `tokens`.
+    ///
+    /// The initial state is the first `order` tokens.
+    ///
+    /// # Panics
+    ///
+    /// If `tokens` has less than `order` elements.
+    ///
+    /// # Examples
+    ///
+    /// A chain of order two over characters.
+    /// ```
+    /// # use markovian::{sequence::KGram, State};
+    /// let mc = KGram::from_tokens(2, "abracadabra".chars(), rand::thread_rng());
+    /// assert_eq!(mc.state(), Some(&vec!['a', 'b']));
+    /// ```
+    #[inline]
+    pub fn from_tokens<I>(order: usize, tokens: I, rng: R) -> Self 
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut tokens = tokens.into_iter();
+        let window: Vec<T> = tokens.by_ref().take(order).collect();
+        assert_eq!(window.len(), order, "There must be at least as many tokens as the order of the chain.");
+
+        // Followers of each context in first-seen order, with the position of each follower,
+        // so that the alias tables do not depend on the iteration order of a HashMap
+        let mut counts: HashMap<Vec<T>, Followers<T>> = HashMap::new();
+        let mut current = window.clone();
+        for token in tokens {
+            let (positions, followers, weights) = counts.entry(current.clone()).or_default();
+            let position = *positions.entry(token.clone()).or_insert_with(|| {
+                followers.push(token.clone());
+                weights.push(0);
+                followers.len() - 1
+            });
+            weights[position] += 1;
+            if order > 0 {
+                current.remove(0);
+                current.push(token);
+            }
+        }
+
+        let transitions = counts.into_iter()
+            .map(|(context, (_, followers, weights))| {
+                (context, (followers, WeightedAliasIndex::new(weights).unwrap()))
+            })
+            .collect();
+
+        KGram {
+            order,
+            initial_window: window.clone(),
+            window,
+            transitions,
+            rng,
+        }
+    }
+
+    /// Constructs a new `KGram<T, R>` as in [from_tokens], with a random number generator seeded by `seed`.
+    ///
+    /// [from_tokens]: struct.KGram.html#method.from_tokens
+    #[inline]
+    pub fn from_tokens_with_seed<I>(order: usize, tokens: I, seed: u64) -> Self 
+    where
+        I: IntoIterator<Item = T>,
+        R: SeedableRng,
+    {
+        KGram::from_tokens(order, tokens, R::seed_from_u64(seed))
+    }
+
+    /// Returns the order of the chain, i.e. the number of tokens in its state.
+    #[inline]
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns the number of windows which were followed by a token in the data.
+    ///
+    /// # Examples
+    ///
+    /// In "abracadabra", the characters `a`, `b`, `r`, `c` and `d` are followed by another character.
+    /// ```
+    /// # use markovian::sequence::KGram;
+    /// let mc = KGram::from_tokens(1, "abracadabra".chars(), rand::thread_rng());
+    /// assert_eq!(mc.ncontexts(), 5);
+    /// ```
+    #[inline]
+    pub fn ncontexts(&self) -> usize {
+        self.transitions.len()
+    }
+
+    /// Returns `true` if the current window was followed by a token in the data. 
+    #[inline]
+    pub fn can_continue(&self) -> bool {
+        self.transitions.contains_key(&self.window)
+    }
+}
+
+impl<T, R> Reset for KGram<T, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    R: Rng,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.window = self.initial_window.clone();
+    }
+}
+
+impl<T, R> RandomSource for KGram<T, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<T, R> State for KGram<T, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    R: Rng,
+{
+    type Item = Vec<T>;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.window)
+    }
+
+    /// Changes the current window.
+    ///
+    /// Fails if `new_state` does not have exactly `order` tokens.
+    #[inline]
+    fn set_state(
+        &mut self,
+        mut new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        if new_state.len() != self.order {
+            return Err(InvalidState::new(new_state));
+        }
+        mem::swap(&mut self.window, &mut new_state);
+        Ok(Some(new_state))
+    }
+}
+
+impl<T, R> Checkpoint for KGram<T, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<T, R> Iterator for KGram<T, R>
+where
+    T: Debug + Clone + Hash + Eq,
+    R: Rng,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (tokens, weights) = self.transitions.get(&self.window)?;
+        let token = tokens[weights.sample(&mut self.rng)].clone();
+        if self.order > 0 {
+            self.window.remove(0);
+            self.window.push(token.clone());
+        }
+        Some(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn deterministic() {
+        let rng = crate::tests::rng(1);
+        let mc = KGram::from_tokens(2, "abcdef".chars(), rng);
+        let sample: String = mc.collect();
+
+        assert_eq!(sample, "cdef");
+    }
+
+    #[test]
+    fn sampling_stability() {
+        let rng = crate::tests::rng(2);
+        let mc = KGram::from_tokens(1, "abacaba".chars(), rng);
+        let sample: Vec<char> = mc.take(100).collect();
+        for window in sample.windows(2) {
+            match window[0] {
+                'a' => assert!(window[1] == 'b' || window[1] == 'c'),
+                _ => assert_eq!(window[1], 'a'),
+            }
+        }
+    }
+
+    #[test]
+    fn value_stability() {
+        let text = "the cat sat on the mat and the cat ran to the hat";
+        let first: Vec<&str> = KGram::<_, rand_pcg::Pcg64>::from_tokens_with_seed(1, text.split(' '), 5).take(20).collect();
+        let second: Vec<&str> = KGram::<_, rand_pcg::Pcg64>::from_tokens_with_seed(1, text.split(' '), 5).take(20).collect();
+        assert_eq!(first, second);
+        assert_eq!(first, vec!["mat", "and", "the", "mat", "and", "the", "hat"]);
+    }
+
+    #[test]
+    fn order_zero() {
+        let rng = crate::tests::rng(3);
+        let mc = KGram::from_tokens(0, vec![1, 1, 1], rng);
+        assert_eq!(mc.ncontexts(), 1);
+        let sample: Vec<u8> = mc.take(10).collect();
+
+        assert_eq!(sample, vec![1; 10]);
+    }
+
+    #[test]
+    fn change_state() {
+        let mut mc = KGram::from_tokens(1, "ab".chars(), crate::tests::rng(4));
+        assert!(mc.set_state(vec!['a', 'b']).is_err());
+        assert_eq!(mc.set_state(vec!['b']).unwrap(), Some(vec!['a']));
+        assert!(!mc.can_continue());
+        assert_eq!(mc.next(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_enough_tokens() {
+        KGram::from_tokens(3, "ab".chars(), crate::tests::rng(5));
+    }
+}
@@ -0,0 +1,272 @@
+//! Point processes in space.
+//!
+//! A [SpatialPoisson] process samples random configurations of points in a [Region] of `D` dimensions,
+//! returned as `Vec<[f64; D]>`. The number of points in disjoint subsets are independent and Poisson distributed.
+//! Inhomogeneous intensities are sampled by thinning a homogeneous process.
+//!
+//! # Examples
+//!
+//! Trees in a square field, more frequent near the river at `x = 0`.
+//! ```
+//! # use markovian::spatial::{Region, SpatialPoisson};
+//! # use rand::distributions::Distribution;
+//! let field = Region::Rectangle { lower: [0., 0.], upper: [10., 10.] };
+//! let intensity = |point: &[f64; 2]| 2. * (-point[0] / 5.).exp();
+//! let trees = SpatialPoisson::inhomogeneous(intensity, 2., field).unwrap();
+//! let sample = trees.sample(&mut rand::thread_rng());
+//! println!("There are {} trees", sample.len());
+//! ```
+//!
+//! [SpatialPoisson]: struct.SpatialPoisson.html
+//! [Region]: enum.Region.html
+
+// Traits
+use rand::Rng;
+use rand_distr::{Distribution, StandardNormal};
+
+// Structs
+use crate::errors::SpatialError;
+
+/// Bounded region of the space of `D` dimensions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Region<const D: usize> {
+    /// Points between `lower` and `upper`, coordinate by coordinate.
+    Rectangle { lower: [f64; D], upper: [f64; D] },
+    /// Points at distance at most `radius` from `center`.
+    Ball { center: [f64; D], radius: f64 },
+}
+
+impl<const D: usize> Region<D> {
+    /// Returns the volume of the region, in `D` dimensions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use markovian::spatial::Region;
+    /// let disk = Region::Ball { center: [0., 0.], radius: 1. };
+    /// assert!((disk.volume() - std::f64::consts::PI).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn volume(&self) -> f64 {
+        match self {
+            Region::Rectangle { lower, upper } => lower.iter().zip(upper.iter()).map(|(l, u)| u - l).product(),
+            Region::Ball { radius, .. } => unit_ball_volume(D) * radius.powi(D as i32),
+        }
+    }
+
+    /// Returns `true` if `point` is in the region.
+    #[inline]
+    pub fn contains(&self, point: &[f64; D]) -> bool {
+        match self {
+            Region::Rectangle { lower, upper } => (0..D).all(|k| lower[k] <= point[k] && point[k] <= upper[k]),
+            Region::Ball { center, radius } => {
+                (0..D).map(|k| (point[k] - center[k]).powi(2)).sum::<f64>() <= radius * radius
+            }
+        }
+    }
+
+    #[inline]
+    fn is_valid(&self) -> bool {
+        match self {
+            Region::Rectangle { lower, upper } => {
+                (0..D).all(|k| lower[k].is_finite() && upper[k].is_finite() && lower[k] <= upper[k])
+            }
+            Region::Ball { center, radius } => center.iter().all(|x| x.is_finite()) && radius.is_finite() && *radius >= 0.,
+        }
+    }
+}
+
+impl<const D: usize> Distribution<[f64; D]> for Region<D> {
+    /// Samples a point uniformly at random in the region.
+    #[inline]
+    fn sample<R>(&self, rng: &mut R) -> [f64; D]
+    where
+        R: Rng + ?Sized,
+    {
+        let mut point = [0.; D];
+        match self {
+            Region::Rectangle { lower, upper } => {
+                for k in 0..D {
+                    point[k] = lower[k] + (upper[k] - lower[k]) * rng.gen::<f64>();
+                }
+            }
+            Region::Ball { center, radius } => {
+                // Uniform direction, and a radius with density proportional to r^(D - 1)
+                let mut norm = 0.;
+                while norm == 0. {
+                    for x in point.iter_mut() {
+                        *x = rng.sample(StandardNormal);
+                    }
+                    norm = point.iter().map(|x| x * x).sum::<f64>().sqrt();
+                }
+                let distance = radius * rng.gen::<f64>().powf(1. / D as f64);
+                for k in 0..D {
+                    point[k] = center[k] + point[k] / norm * distance;
+                }
+            }
+        }
+        point
+    }
+}
+
+/// Poisson point process in a region of `D` dimensions.
+///
+/// The intensity is the expected number of points per unit of volume.
+/// If it depends on the location, points of a homogeneous process of intensity `bound`
+/// are kept with probability `intensity(point) / bound`.
+///
+/// # Examples
+///
+/// Points in a ball of three dimensions.
+/// ```
+/// # use markovian::spatial::{Region, SpatialPoisson};
+/// # use rand::distributions::Distribution;
+/// let ball = Region::Ball { center: [0., 0., 0.], radius: 2. };
+/// let process = SpatialPoisson::new(10., ball).unwrap();
+/// let points: Vec<[f64; 3]> = process.sample(&mut rand::thread_rng());
+/// assert!(points.iter().all(|point| ball.contains(point)));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SpatialPoisson<F, const D: usize> {
+    bound: f64,
+    intensity: Option<F>,
+    region: Region<D>,
+}
+
+impl<const D: usize> SpatialPoisson<fn(&[f64; D]) -> f64, D> {
+    /// Constructs a new homogeneous `SpatialPoisson` process.
+    ///
+    /// # Errors
+    ///
+    /// If `intensity` is negative or not finite, or `region` has non-finite or inverted bounds.
+    #[inline]
+    pub fn new(intensity: f64, region: Region<D>) -> Result<Self, SpatialError> {
+        SpatialPoisson::build(intensity, None, region)
+    }
+}
+
+impl<F, const D: usize> SpatialPoisson<F, D>
+where
+    F: Fn(&[f64; D]) -> f64,
+{
+    /// Constructs a new inhomogeneous `SpatialPoisson` process, whose `intensity`
+    /// is at most `bound` in the region.
+    ///
+    /// # Remarks
+    ///
+    /// Where `intensity` is above `bound`, the process has intensity `bound`.
+    ///
+    /// # Errors
+    ///
+    /// If `bound` is negative or not finite, or `region` has non-finite or inverted bounds.
+    #[inline]
+    pub fn inhomogeneous(intensity: F, bound: f64, region: Region<D>) -> Result<Self, SpatialError> {
+        SpatialPoisson::build(bound, Some(intensity), region)
+    }
+
+    #[inline]
+    fn build(bound: f64, intensity: Option<F>, region: Region<D>) -> Result<Self, SpatialError> {
+        if !bound.is_finite() || bound < 0. {
+            return Err(SpatialError::InvalidIntensity(bound));
+        }
+        if !region.is_valid() {
+            return Err(SpatialError::InvalidRegion);
+        }
+        Ok(SpatialPoisson { bound, intensity, region })
+    }
+
+    #[inline]
+    pub fn region(&self) -> &Region<D> {
+        &self.region
+    }
+
+    /// Returns the expected number of points of the homogeneous process that is thinned,
+    /// which is the expected number of points if the process is homogeneous.
+    #[inline]
+    pub fn mean_points(&self) -> f64 {
+        self.bound * self.region.volume()
+    }
+}
+
+impl<F, const D: usize> Distribution<Vec<[f64; D]>> for SpatialPoisson<F, D>
+where
+    F: Fn(&[f64; D]) -> f64,
+{
+    #[inline]
+    fn sample<R>(&self, rng: &mut R) -> Vec<[f64; D]>
+    where
+        R: Rng + ?Sized,
+    {
+        let mean = self.mean_points();
+        if mean <= 0. {
+            return Vec::new();
+        }
+        let npoints = rand_distr::Poisson::new(mean).unwrap().sample(rng) as usize;
+        let mut points = Vec::with_capacity(npoints);
+        for _ in 0..npoints {
+            let point = self.region.sample(rng);
+            let kept = match &self.intensity {
+                Some(intensity) => rng.gen::<f64>() * self.bound < intensity(&point),
+                None => true,
+            };
+            if kept {
+                points.push(point);
+            }
+        }
+        points
+    }
+}
+
+/// Returns the volume of the ball of radius one in `dimension` dimensions.
+#[inline]
+fn unit_ball_volume(dimension: usize) -> f64 {
+    // V_d = V_{d - 2} 2 pi / d
+    let mut volume = if dimension % 2 == 0 { 1. } else { 2. };
+    let mut d = if dimension % 2 == 0 { 2 } else { 3 };
+    while d <= dimension {
+        volume *= 2. * core::f64::consts::PI / d as f64;
+        d += 2;
+    }
+    volume
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn volumes() {
+        assert_abs_diff_eq!(unit_ball_volume(1), 2., epsilon = 1e-12);
+        assert_abs_diff_eq!(unit_ball_volume(3), 4. / 3. * core::f64::consts::PI, epsilon = 1e-12);
+        let rectangle = Region::Rectangle { lower: [0., 1., 2.], upper: [1., 3., 5.] };
+        assert_eq!(rectangle.volume(), 6.);
+        assert_eq!(SpatialPoisson::new(-1., rectangle).unwrap_err(), SpatialError::InvalidIntensity(-1.));
+        let inverted = Region::Rectangle { lower: [1.], upper: [0.] };
+        assert_eq!(SpatialPoisson::new(1., inverted).unwrap_err(), SpatialError::InvalidRegion);
+    }
+
+    #[test]
+    fn counts() {
+        let mut rng = crate::tests::rng(1);
+        let disk = Region::Ball { center: [1., -1.], radius: 2. };
+        let homogeneous = SpatialPoisson::new(3., disk).unwrap();
+        let inhomogeneous = SpatialPoisson::inhomogeneous(|point: &[f64; 2]| if point[0] > 1. { 3. } else { 0. }, 3., disk).unwrap();
+        let samples = 500;
+        let (mut total, mut half, mut inner) = (0, 0, 0);
+        for _ in 0..samples {
+            let points = homogeneous.sample(&mut rng);
+            assert!(points.iter().all(|point| disk.contains(point)));
+            total += points.len();
+            inner += points.iter().filter(|p| (p[0] - 1.).powi(2) + (p[1] + 1.).powi(2) <= 1.).count();
+            let points = inhomogeneous.sample(&mut rng);
+            assert!(points.iter().all(|point| point[0] > 1.));
+            half += points.len();
+        }
+        let mean = homogeneous.mean_points();
+        assert_abs_diff_eq!(total as f64 / samples as f64, mean, epsilon = 0.5);
+        assert_abs_diff_eq!(half as f64 / samples as f64, mean / 2., epsilon = 0.5);
+        assert_abs_diff_eq!(inner as f64 / total as f64, 0.25, epsilon = 0.02);
+    }
+}
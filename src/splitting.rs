@@ -0,0 +1,191 @@
+//! Multilevel splitting for the estimation of rare-event probabilities.
+//!
+//! The rare event is reaching a high value of an importance function before a failure.
+//! Intermediate levels split the rare event into a sequence of less rare ones:
+//! at each stage, a fixed number of trajectories start from the states where the
+//! previous level was reached and are simulated until they reach the next level or fail.
+//! The product of the fractions of successful trajectories is an unbiased estimator
+//! of the probability of the rare event.
+//!
+//! # Examples
+//!
+//! Probability that a random walk with negative drift reaches 15 before returning to 0.
+//! ```
+//! # use markovian::{MarkovChain, prelude::*, splitting::Splitting};
+//! # use rand::SeedableRng;
+//! let transition = |state: &i32| raw_dist![(0.4, state + 1), (0.6, state - 1)];
+//! let mc = MarkovChain::new(1, transition, rand_pcg::Pcg64::seed_from_u64(1));
+//! let levels = vec![3., 6., 9., 12., 15.];
+//! let mut splitting = Splitting::new(mc, |state: &i32| *state as f64, levels, |state: &i32| *state <= 0, 1_000);
+//! let estimate = splitting.run();
+//! // Exact value: (1 - 1.5) / (1 - 1.5^15) = 0.00115...
+//! assert!((estimate.probability - 0.00115).abs() < 4. * estimate.variance.sqrt());
+//! ```
+
+// Traits
+use crate::{RandomSource, State};
+use rand::{Rng, SeedableRng};
+
+/// Result of a multilevel splitting simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplittingEstimate {
+    /// Estimate of the probability of reaching the last level before failing.
+    pub probability: f64,
+    /// Estimate of the variance of `probability`, assuming the stages are independent.
+    pub variance: f64,
+    /// Fraction of successful trajectories at each stage.
+    pub level_probabilities: Vec<f64>,
+}
+
+/// Fixed effort multilevel splitting simulator.
+///
+/// # Remarks
+///
+/// Trajectories are duplicated by [fork], so that each copy has an independent
+/// random number generator.
+///
+/// [fork]: ../trait.RandomSource.html#method.fork
+#[derive(Debug, Clone)]
+pub struct Splitting<P, I, K> {
+    process: P,
+    importance: I,
+    levels: Vec<f64>,
+    is_failure: K,
+    effort: usize,
+    max_steps: usize,
+}
+
+impl<P, I, K> Splitting<P, I, K>
+where
+    P: Iterator + State + RandomSource + Clone,
+    P::Rng: SeedableRng,
+    I: Fn(&<P as State>::Item) -> f64,
+    K: Fn(&<P as State>::Item) -> bool,
+{
+    /// Constructs a new `Splitting<P, I, K>`.
+    ///
+    /// A level is reached once the importance of the state is greater or equal to it
+    /// and `effort` trajectories are simulated at each stage.
+    ///
+    /// # Panics
+    ///
+    /// If `levels` is empty or not increasing, or if `effort` is zero.
+    #[inline]
+    pub fn new(process: P, importance: I, levels: Vec<f64>, is_failure: K, effort: usize) -> Self {
+        assert!(!levels.is_empty(), "There must be at least one level.");
+        assert!(levels.windows(2).all(|w| w[0] < w[1]), "Levels must be increasing.");
+        assert!(effort > 0, "The effort must be positive.");
+        Splitting {
+            process,
+            importance,
+            levels,
+            is_failure,
+            effort,
+            max_steps: usize::MAX,
+        }
+    }
+
+    /// Sets the maximum number of steps of a trajectory in a stage, after which it is considered a failure.
+    #[inline]
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Runs all stages and returns the estimate of the probability of reaching the last level.
+    ///
+    /// If no trajectory succeeds at some stage, the estimate is zero
+    /// and the remaining stages are not simulated.
+    #[inline]
+    pub fn run(&mut self) -> SplittingEstimate {
+        let mut entrances = vec![self.process.fork()];
+        let mut level_probabilities = Vec::with_capacity(self.levels.len());
+        for &level in self.levels.iter() {
+            let mut successes = Vec::new();
+            for _ in 0..self.effort {
+                let index = self.process.rng_mut().gen_range(0..entrances.len());
+                let mut trajectory = entrances[index].fork();
+                if self.reaches(&mut trajectory, level) {
+                    successes.push(trajectory);
+                }
+            }
+            level_probabilities.push(successes.len() as f64 / self.effort as f64);
+            if successes.is_empty() {
+                break;
+            }
+            entrances = successes;
+        }
+
+        let probability = if level_probabilities.len() == self.levels.len() {
+            level_probabilities.iter().product()
+        } else {
+            0.0
+        };
+        let relative_variance: f64 = level_probabilities.iter()
+            .map(|&p| (1.0 - p) / (self.effort as f64 * p))
+            .sum();
+        SplittingEstimate {
+            probability,
+            variance: if probability > 0.0 { probability.powi(2) * relative_variance } else { 0.0 },
+            level_probabilities,
+        }
+    }
+
+    /// Simulates `trajectory` until it reaches `level`, fails or runs out of steps.
+    #[inline]
+    fn reaches(&self, trajectory: &mut P, level: f64) -> bool {
+        for _ in 0..self.max_steps {
+            match trajectory.state() {
+                Some(state) if (self.importance)(state) >= level => return true,
+                Some(state) if (self.is_failure)(state) => return false,
+                None => return false,
+                _ => {},
+            }
+            if trajectory.next().is_none() {
+                return false;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::MarkovChain;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn deterministic() {
+        let transition = |state: &i32| raw_dist![(1.0, state + 1)];
+        let mc = MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 1);
+        let mut splitting = Splitting::new(mc, |state: &i32| *state as f64, vec![1., 5.], |_: &i32| false, 10);
+        let estimate = splitting.run();
+
+        assert_eq!(estimate.probability, 1.);
+        assert_eq!(estimate.variance, 0.);
+        assert_eq!(estimate.level_probabilities, vec![1., 1.]);
+    }
+
+    #[test]
+    fn impossible() {
+        let transition = |state: &i32| raw_dist![(1.0, state - 1)];
+        let mc = MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 2);
+        let mut splitting = Splitting::new(mc, |state: &i32| *state as f64, vec![1., 5.], |state: &i32| *state < -3, 10);
+        let estimate = splitting.run();
+
+        assert_eq!(estimate.probability, 0.);
+        assert_eq!(estimate.level_probabilities, vec![0.]);
+    }
+
+    #[test]
+    fn max_steps() {
+        let transition = |state: &i32| raw_dist![(1.0, *state)];
+        let mc = MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 3);
+        let mut splitting = Splitting::new(mc, |state: &i32| *state as f64, vec![1.], |_: &i32| false, 10)
+            .max_steps(100);
+
+        assert_eq!(splitting.run().probability, 0.);
+    }
+}
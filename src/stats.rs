@@ -0,0 +1,8 @@
+pub use histogram::{ChiSquareTest, Histogram};
+pub use quantile::QuantileSketch;
+pub use welford::Welford;
+
+mod histogram;
+mod quantile;
+pub(crate) mod special;
+mod welford;
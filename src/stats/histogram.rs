@@ -0,0 +1,267 @@
+// Traits
+use num_traits::ToPrimitive;
+
+/// Histogram of numeric values over equally spaced bins.
+///
+/// Values outside the range of the bins are counted apart, as underflow or overflow,
+/// and so are NaN values.
+///
+/// # Examples
+///
+/// Checking the stationary distribution of a two-state chain.
+/// ```
+/// # use ndarray::array;
+/// # use rand::SeedableRng;
+/// # use markovian::{FiniteMarkovChain, stats::Histogram};
+/// let rng = rand_pcg::Pcg64::seed_from_u64(1);
+/// let mc = FiniteMarkovChain::from((0, array![[0.5, 0.5], [0.5, 0.5]], rng));
+/// let histogram = Histogram::from_iter(mc.take(1_000), 2);
+/// let test = histogram.chi_square(|_, _| 0.5);
+/// assert!(test.p_value > 0.001);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    low: f64,
+    high: f64,
+    counts: Vec<usize>,
+    underflow: usize,
+    overflow: usize,
+    nan: usize,
+}
+
+/// Result of a chi-square goodness-of-fit test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChiSquareTest {
+    /// Value of the chi-square statistic.
+    pub statistic: f64,
+    /// Degrees of freedom of the asymptotic chi-square distribution.
+    pub degrees_of_freedom: usize,
+    /// Probability of a statistic at least as large, under the null hypothesis.
+    pub p_value: f64,
+}
+
+impl Histogram {
+    /// Constructs a new empty `Histogram` with `bins` bins covering `[low, high]`.
+    ///
+    /// # Panics
+    ///
+    /// If `bins` is zero or `low` is not less than `high`.
+    #[inline]
+    pub fn new(low: f64, high: f64, bins: usize) -> Self {
+        assert!(bins > 0, "There must be at least one bin.");
+        assert!(low < high, "The range of the bins must be non-empty.");
+        Histogram {
+            low,
+            high,
+            counts: vec![0; bins],
+            underflow: 0,
+            overflow: 0,
+            nan: 0,
+        }
+    }
+
+    /// Constructs a new `Histogram` with `bins` bins covering from the minimum
+    /// to the maximum of `values`.
+    ///
+    /// If all values are equal to `x`, bins cover `[x, x + 1]`.
+    ///
+    /// # Panics
+    ///
+    /// If `bins` is zero, there are no values other than NaN, or some value can not be represented as `f64`.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter<I>(values: I, bins: usize) -> Self
+    where
+        I: IntoIterator,
+        I::Item: ToPrimitive,
+    {
+        let values: Vec<f64> = values.into_iter().map(|x| x.to_f64().unwrap()).collect();
+        assert!(!values.is_empty(), "There must be at least one value.");
+        let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let mut high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if high == low {
+            high = low + 1.0;
+        }
+        let mut histogram = Histogram::new(low, high, bins);
+        histogram.extend(values);
+        histogram
+    }
+
+    /// Adds `value` to the histogram. The last bin includes its upper end.
+    #[inline]
+    pub fn push<X>(&mut self, value: X)
+    where
+        X: ToPrimitive,
+    {
+        let value = value.to_f64().unwrap();
+        if value.is_nan() {
+            self.nan += 1;
+        } else if value < self.low {
+            self.underflow += 1;
+        } else if value > self.high {
+            self.overflow += 1;
+        } else {
+            let bins = self.counts.len();
+            let index = ((value - self.low) / self.bin_width()) as usize;
+            self.counts[index.min(bins - 1)] += 1;
+        }
+    }
+
+    /// Returns the count of each bin.
+    #[inline]
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// Returns the number of values below the range of the bins.
+    #[inline]
+    pub fn underflow(&self) -> usize {
+        self.underflow
+    }
+
+    /// Returns the number of values above the range of the bins.
+    #[inline]
+    pub fn overflow(&self) -> usize {
+        self.overflow
+    }
+
+    /// Returns the number of NaN values, which belong to no bin.
+    #[inline]
+    pub fn nan(&self) -> usize {
+        self.nan
+    }
+
+    /// Returns the number of values in the range of the bins.
+    #[inline]
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Returns the width of each bin.
+    #[inline]
+    pub fn bin_width(&self) -> f64 {
+        (self.high - self.low) / self.counts.len() as f64
+    }
+
+    /// Returns the edges of the bins, from `low` to `high`.
+    #[inline]
+    pub fn edges(&self) -> Vec<f64> {
+        (0..=self.counts.len())
+            .map(|i| self.low + i as f64 * self.bin_width())
+            .collect()
+    }
+
+    /// Returns the empirical probability density function, constant on each bin,
+    /// normalized with respect to the values in range.
+    #[inline]
+    pub fn density(&self) -> Vec<f64> {
+        let normalization = self.total() as f64 * self.bin_width();
+        self.counts.iter().map(|&c| c as f64 / normalization).collect()
+    }
+
+    /// Performs a chi-square goodness-of-fit test of the values in range against a distribution,
+    /// where `probability(low, high)` is the probability of the bin from `low` to `high`.
+    ///
+    /// Probabilities are normalized over the bins, bins with zero probability are ignored
+    /// and the p-value uses the asymptotic chi-square distribution.
+    ///
+    /// # Panics
+    ///
+    /// If no value is in range or there are less than two bins with positive probability.
+    #[inline]
+    pub fn chi_square<F>(&self, probability: F) -> ChiSquareTest
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let edges = self.edges();
+        let probabilities: Vec<f64> = edges.windows(2).map(|w| probability(w[0], w[1])).collect();
+        let total_probability: f64 = probabilities.iter().sum();
+        let total = self.total() as f64;
+        assert!(total > 0.0, "There must be at least one value in range.");
+        let mut statistic = 0.0;
+        let mut used = 0;
+        for (&count, &p) in self.counts.iter().zip(&probabilities) {
+            if p > 0.0 {
+                let expected = total * p / total_probability;
+                statistic += (count as f64 - expected).powi(2) / expected;
+                used += 1;
+            }
+        }
+        assert!(used > 1, "There must be at least two bins with positive probability.");
+        let degrees_of_freedom = used - 1;
+        ChiSquareTest {
+            statistic,
+            degrees_of_freedom,
+            p_value: super::special::chi_square_survival(statistic, degrees_of_freedom),
+        }
+    }
+}
+
+impl<X> Extend<X> for Histogram
+where
+    X: ToPrimitive,
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = X>>(&mut self, values: I) {
+        for value in values {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use pretty_assertions::assert_eq;
+    use rand::Rng;
+
+    #[test]
+    fn counts() {
+        let mut histogram = Histogram::new(0., 2., 2);
+        histogram.extend(vec![-1., 0., 0.5, 1., 2., 3.]);
+
+        assert_eq!(histogram.counts(), &[2, 2]);
+        assert_eq!((histogram.underflow(), histogram.overflow()), (1, 1));
+        assert_eq!(histogram.edges(), vec![0., 1., 2.]);
+        assert_eq!(histogram.density(), vec![0.5, 0.5]);
+
+        let histogram = Histogram::from_iter(vec![3_u32, 3], 4);
+        assert_eq!(histogram.counts(), &[2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn nan() {
+        let mut histogram = Histogram::new(0., 2., 2);
+        histogram.extend(vec![f64::NAN, 0.5, f64::NAN]);
+
+        assert_eq!(histogram.counts(), &[1, 0]);
+        assert_eq!(histogram.nan(), 2);
+        assert_eq!((histogram.underflow(), histogram.overflow()), (0, 0));
+
+        let histogram = Histogram::from_iter(vec![f64::NAN, 1., 3.], 2);
+        assert_eq!(histogram.counts(), &[1, 1]);
+        assert_eq!(histogram.nan(), 1);
+    }
+
+    #[test]
+    fn chi_square() {
+        let mut histogram = Histogram::new(0., 1., 2);
+        histogram.extend(vec![0.25; 60]);
+        histogram.extend(vec![0.75; 40]);
+        let test = histogram.chi_square(|_, _| 0.5);
+
+        assert_abs_diff_eq!(test.statistic, 4.);
+        assert_eq!(test.degrees_of_freedom, 1);
+        assert_abs_diff_eq!(test.p_value, 0.0455, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn uniform_pdf() {
+        let mut rng = crate::tests::rng(1);
+        let histogram = Histogram::from_iter((0..10_000).map(|_| rng.gen::<f64>()), 10);
+        let test = histogram.chi_square(|low, high| high - low);
+
+        assert!(test.p_value > 0.001);
+    }
+}
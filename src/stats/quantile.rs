@@ -0,0 +1,221 @@
+/// Streaming estimator of a single quantile by the P² algorithm of Jain and Chlamtac.
+///
+/// Uses five markers, whose heights are adjusted by piecewise-parabolic interpolation.
+#[derive(Debug, Clone, PartialEq)]
+struct P2 {
+    probability: f64,
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+    count: usize,
+}
+
+impl P2 {
+    #[inline]
+    fn new(probability: f64) -> Self {
+        let p = probability;
+        P2 {
+            probability,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+            count: 0,
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, value: f64) {
+        if self.count < 5 {
+            self.heights[self.count] = value;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.count += 1;
+
+        let cell = if value < self.heights[0] {
+            self.heights[0] = value;
+            0
+        } else if value >= self.heights[4] {
+            self.heights[4] = value;
+            3
+        } else {
+            (0..4).find(|&i| value < self.heights[i + 1]).unwrap()
+        };
+        for position in self.positions.iter_mut().skip(cell + 1) {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(&self.increments) {
+            *desired += increment;
+        }
+
+        for i in 1..4 {
+            let d = self.desired[i] - self.positions[i];
+            if (d >= 1.0 && self.positions[i + 1] - self.positions[i] > 1.0)
+                || (d <= -1.0 && self.positions[i - 1] - self.positions[i] < -1.0)
+            {
+                let sign = d.signum();
+                let parabolic = self.parabolic(i, sign);
+                self.heights[i] = if self.heights[i - 1] < parabolic && parabolic < self.heights[i + 1] {
+                    parabolic
+                } else {
+                    self.linear(i, sign)
+                };
+                self.positions[i] += sign;
+            }
+        }
+    }
+
+    #[inline]
+    fn parabolic(&self, i: usize, sign: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + sign / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + sign) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - sign) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    #[inline]
+    fn linear(&self, i: usize, sign: f64) -> f64 {
+        let j = if sign > 0.0 { i + 1 } else { i - 1 };
+        self.heights[i] + sign * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+
+    #[inline]
+    fn estimate(&self) -> f64 {
+        match self.count {
+            0 => f64::NAN,
+            count if count < 5 => {
+                let mut values = self.heights[..count].to_vec();
+                values.sort_by(f64::total_cmp);
+                let index = (self.probability * (count - 1) as f64).round() as usize;
+                values[index]
+            }
+            _ => self.heights[2],
+        }
+    }
+}
+
+/// Streaming approximate quantiles of a sequence of values, in constant memory.
+///
+/// Each requested quantile is tracked by the P² algorithm, which needs
+/// no storage of the values and is accurate for smooth distributions.
+///
+/// # Examples
+///
+/// Median and 90% quantile of the position of a random walk, without storing the path.
+/// ```
+/// # use markovian::{MarkovChain, prelude::*, stats::QuantileSketch};
+/// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+/// let mc = MarkovChain::new(0, transition, rand::thread_rng());
+/// let mut sketch = QuantileSketch::new(&[0.5, 0.9]);
+/// sketch.extend(mc.take(10_000).map(|state| state as f64));
+/// assert!(sketch.quantile(0.5).unwrap() <= sketch.quantile(0.9).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantileSketch {
+    estimators: Vec<P2>,
+}
+
+impl QuantileSketch {
+    /// Constructs a new `QuantileSketch` tracking the quantiles of the given probabilities.
+    ///
+    /// # Panics
+    ///
+    /// If some probability is not in the interval (0, 1).
+    #[inline]
+    pub fn new(probabilities: &[f64]) -> Self {
+        assert!(
+            probabilities.iter().all(|&p| 0.0 < p && p < 1.0),
+            "Probabilities must be in the interval (0, 1)."
+        );
+        QuantileSketch {
+            estimators: probabilities.iter().map(|&p| P2::new(p)).collect(),
+        }
+    }
+
+    /// Adds `value` to the stream.
+    ///
+    /// # Panics
+    ///
+    /// If `value` is NaN, since it has no rank among the other values.
+    #[inline]
+    pub fn push(&mut self, value: f64) {
+        assert!(!value.is_nan(), "Values must not be NaN.");
+        for estimator in self.estimators.iter_mut() {
+            estimator.push(value);
+        }
+    }
+
+    /// Returns the number of values so far.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.estimators.first().map_or(0, |estimator| estimator.count)
+    }
+
+    /// Returns the estimate of the quantile of `probability`, or `None` if it is not tracked.
+    ///
+    /// The estimate is `NaN` if there are no values yet.
+    #[inline]
+    pub fn quantile(&self, probability: f64) -> Option<f64> {
+        self.estimators.iter()
+            .find(|estimator| estimator.probability == probability)
+            .map(|estimator| estimator.estimate())
+    }
+
+    /// Returns the estimates of all tracked quantiles, in the order they were requested.
+    #[inline]
+    pub fn quantiles(&self) -> Vec<f64> {
+        self.estimators.iter().map(|estimator| estimator.estimate()).collect()
+    }
+}
+
+impl Extend<f64> for QuantileSketch {
+    #[inline]
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, values: I) {
+        for value in values {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use pretty_assertions::assert_eq;
+    use rand::Rng;
+
+    #[test]
+    fn few_values() {
+        let mut sketch = QuantileSketch::new(&[0.5]);
+        assert!(sketch.quantile(0.5).unwrap().is_nan());
+        sketch.extend(vec![3., 1., 2.]);
+
+        assert_eq!(sketch.quantile(0.5), Some(2.));
+        assert_eq!(sketch.quantile(0.7), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan() {
+        let mut sketch = QuantileSketch::new(&[0.5]);
+        sketch.extend(vec![1., f64::NAN]);
+    }
+
+    #[test]
+    fn uniform() {
+        let mut rng = crate::tests::rng(1);
+        let mut sketch = QuantileSketch::new(&[0.1, 0.5, 0.99]);
+        sketch.extend((0..100_000).map(|_| rng.gen::<f64>()));
+        let quantiles = sketch.quantiles();
+
+        assert_eq!(sketch.count(), 100_000);
+        assert_abs_diff_eq!(quantiles[0], 0.1, epsilon = 0.01);
+        assert_abs_diff_eq!(quantiles[1], 0.5, epsilon = 0.01);
+        assert_abs_diff_eq!(quantiles[2], 0.99, epsilon = 0.01);
+    }
+}
@@ -0,0 +1,113 @@
+//! Special functions needed by statistical tests.
+
+/// Returns the logarithm of the gamma function, by the Lanczos approximation.
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let series = COEFFICIENTS.iter()
+        .enumerate()
+        .fold(1.000000000190015, |acc, (j, c)| acc + c / (x + 1.0 + j as f64));
+    -tmp + (2.5066282746310005 * series / x).ln()
+}
+
+/// Returns the regularized upper incomplete gamma function Q(a, x).
+pub(crate) fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    if x < a + 1.0 {
+        // Series representation of P(a, x)
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut denominator = a;
+        for _ in 0..1_000 {
+            denominator += 1.0;
+            term *= x / denominator;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        1.0 - sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+    } else {
+        // Continued fraction representation of Q(a, x), by Lentz's method
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1_000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        (-x + a * x.ln() - ln_gamma(a)).exp() * h
+    }
+}
+
+/// Returns the probability that a chi-square random variable with
+/// `degrees_of_freedom` degrees of freedom is greater than `statistic`.
+pub(crate) fn chi_square_survival(statistic: f64, degrees_of_freedom: usize) -> f64 {
+    gamma_q(degrees_of_freedom as f64 / 2.0, statistic / 2.0)
+}
+
+/// Returns the quantile of order `probability` of a chi-square distribution
+/// with `degrees_of_freedom` degrees of freedom, by bisection.
+pub(crate) fn chi_square_quantile(probability: f64, degrees_of_freedom: usize) -> f64 {
+    if degrees_of_freedom == 0 || probability <= 0.0 {
+        return 0.0;
+    }
+    let mut low = 0.0;
+    let mut high = degrees_of_freedom as f64 + 1.0;
+    while 1.0 - chi_square_survival(high, degrees_of_freedom) < probability {
+        low = high;
+        high *= 2.0;
+    }
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+        if 1.0 - chi_square_survival(middle, degrees_of_freedom) < probability {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn values() {
+        assert_abs_diff_eq!(ln_gamma(5.0), 24_f64.ln(), epsilon = 1e-10);
+        // Chi-square with 2 degrees of freedom is exponential with mean 2
+        assert_abs_diff_eq!(chi_square_survival(3.0, 2), (-1.5_f64).exp(), epsilon = 1e-10);
+        assert_abs_diff_eq!(chi_square_survival(0.5, 2), (-0.25_f64).exp(), epsilon = 1e-10);
+        assert_abs_diff_eq!(chi_square_survival(3.841458820694124, 1), 0.05, epsilon = 1e-9);
+        assert_abs_diff_eq!(chi_square_quantile(0.95, 1), 3.841458820694124, epsilon = 1e-8);
+        assert_abs_diff_eq!(chi_square_quantile(0.5, 2), 2. * 2_f64.ln(), epsilon = 1e-8);
+    }
+}
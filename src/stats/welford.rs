@@ -0,0 +1,142 @@
+/// Running mean, variance, minimum and maximum of a stream of values,
+/// computed in one pass by Welford's algorithm.
+///
+/// # Examples
+///
+/// Values are pushed one at a time.
+/// ```
+/// # use markovian::stats::Welford;
+/// let mut stats = Welford::new();
+/// stats.push(1.);
+/// stats.push(3.);
+/// assert_eq!(stats.mean(), 2.);
+/// assert_eq!(stats.variance(), 2.);
+/// assert_eq!((stats.min(), stats.max()), (1., 3.));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Welford {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Welford {
+    /// Constructs a new `Welford` with no values.
+    #[inline]
+    pub fn new() -> Self {
+        Welford {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Adds `value` to the stream.
+    #[inline]
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// Returns the number of values so far.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the mean of the values so far, or `NaN` if there are none.
+    #[inline]
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 { f64::NAN } else { self.mean }
+    }
+
+    /// Returns the (unbiased) sample variance of the values so far,
+    /// or `NaN` if there are less than two.
+    #[inline]
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 { f64::NAN } else { self.m2 / (self.count - 1) as f64 }
+    }
+
+    /// Returns the sample standard deviation of the values so far.
+    #[inline]
+    pub fn standard_deviation(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Returns the standard error of the mean, assuming independent values.
+    #[inline]
+    pub fn standard_error(&self) -> f64 {
+        (self.variance() / self.count as f64).sqrt()
+    }
+
+    /// Returns the minimum of the values so far, or infinity if there are none.
+    #[inline]
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// Returns the maximum of the values so far, or minus infinity if there are none.
+    #[inline]
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+}
+
+impl Default for Welford {
+    #[inline]
+    fn default() -> Self {
+        Welford::new()
+    }
+}
+
+impl Extend<f64> for Welford {
+    #[inline]
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, values: I) {
+        for value in values {
+            self.push(value);
+        }
+    }
+}
+
+impl core::iter::FromIterator<f64> for Welford {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = f64>>(values: I) -> Self {
+        let mut welford = Welford::new();
+        welford.extend(values);
+        welford
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn values() {
+        let stats: Welford = vec![2., 4., 4., 4., 5., 5., 7., 9.].into_iter().collect();
+
+        assert_eq!(stats.count(), 8);
+        assert_abs_diff_eq!(stats.mean(), 5.);
+        assert_abs_diff_eq!(stats.variance(), 32. / 7.);
+        assert_eq!(stats.min(), 2.);
+        assert_eq!(stats.max(), 9.);
+    }
+
+    #[test]
+    fn empty() {
+        let stats = Welford::new();
+
+        assert!(stats.mean().is_nan());
+        assert!(stats.variance().is_nan());
+    }
+}
@@ -1,8 +1,8 @@
 // Traits
 use rand_distr::Distribution;
-use crate::traits::{State, StateIterator, Transition};
+use crate::traits::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess, Transition};
 use core::fmt::Debug;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 
 // Structs
 use crate::errors::InvalidState;
@@ -25,6 +25,8 @@ use core::mem;
 #[derive(Debug, Clone)]
 pub struct TimedMarkovChain<N, T, F, R> {
     state: T,
+    // Kept once the chain leaves its initial state
+    initial_state: Option<T>,
     transition: F,
     rng: R,
     phantom: PhantomData<N>,
@@ -40,11 +42,77 @@ where
     pub fn new(state: T, transition: F, rng: R) -> Self {
         TimedMarkovChain {
             state,
+            initial_state: None,
             transition,
             rng,
             phantom: PhantomData,
         }
     }
+
+    /// Constructs a new `TimedMarkovChain<N, T, F, R>` whose random number generator is seeded by `seed`.
+    #[inline]
+    pub fn with_seed(state: T, transition: F, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        TimedMarkovChain::new(state, transition, R::seed_from_u64(seed))
+    }
+}
+
+impl<'a, N, T, F, R> TimedMarkovChain<N, T, F, &'a mut R>
+where
+    R: Rng + ?Sized,
+    F: Transition<T, (N, T)>,
+    N: From<f64>,
+{
+    /// Constructs a new `TimedMarkovChain<N, T, F, &mut R>` that borrows its random number generator.
+    #[inline]
+    pub fn new_with_rng_mut(state: T, transition: F, rng: &'a mut R) -> Self {
+        TimedMarkovChain::new(state, transition, rng)
+    }
+}
+
+impl<N, T, F, R> TimedMarkovChain<N, T, F, R>
+where
+    T: Clone,
+{
+    #[inline]
+    fn keep_initial_state(&mut self) {
+        if self.initial_state.is_none() {
+            self.initial_state = Some(self.state.clone());
+        }
+    }
+}
+
+impl<N, T, F, R> Reset for TimedMarkovChain<N, T, F, R> {
+    #[inline]
+    fn reset(&mut self) {
+        if let Some(initial_state) = self.initial_state.take() {
+            self.state = initial_state;
+        }
+    }
+}
+
+impl<N, T, F, R> RandomSource for TimedMarkovChain<N, T, F, R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
 }
 
 impl<N, T, F, R> State for TimedMarkovChain<N, T, F, R>
@@ -60,6 +128,7 @@ where
 
     #[inline]
     fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        self.keep_initial_state();
         Some(&mut self.state)
     }
 
@@ -68,11 +137,26 @@ where
         &mut self,
         mut new_state: Self::Item,
     ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        self.keep_initial_state();
         mem::swap(&mut self.state, &mut new_state);
         Ok(Some(new_state))
     }
 }
 
+impl<N, T, F, R> Checkpoint for TimedMarkovChain<N, T, F, R>
+where
+    T: Debug + Clone,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
 impl<N, T, F, R> Iterator for TimedMarkovChain<N, T, F, R>
 where
     T: Debug + Clone,
@@ -83,12 +167,28 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        self.keep_initial_state();
         let (period, state) = self.transition.sample_from(&self.state, &mut self.rng);
         self.state = state;
         self.state().cloned().map(|state| (period, state))
     }
 }
 
+impl<N, T, F, R> StochasticProcess for TimedMarkovChain<N, T, F, R>
+where
+    T: Debug + Clone,
+    F: Transition<T, (N, T)>,
+    R: Rng,
+{
+    type Time = N;
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next()
+    }
+}
+
 impl<N, T, F, R> StateIterator for TimedMarkovChain<N, T, F, R>
 where
     T: Debug + Clone,
@@ -100,6 +200,13 @@ where
     fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
         self.state().cloned().map(|state| (N::from(0.0), state))
     }
+
+    #[inline]
+    fn advance(&mut self) -> bool {
+        self.keep_initial_state();
+        self.state = self.transition.sample_from(&self.state, &mut self.rng).1;
+        true
+    }
 }
 
 impl<N, T, F, R> Distribution<(N, T)> for TimedMarkovChain<N, T, F, R>
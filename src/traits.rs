@@ -1,7 +1,25 @@
+pub use self::checkpoint::{Checkpoint, Period, Snapshot};
+pub use self::distribution_once::DistributionOnce;
+pub use self::random_iterator::RandomIterator;
+pub use self::random_source::RandomSource;
+pub use self::reset::Reset;
 pub use self::state::State;
 pub use self::state_iterator::StateIterator;
+pub use self::stochastic_process::StochasticProcess;
+pub use self::timed_iterator::TimedIterator;
 pub use self::transition::Transition;
+pub use self::transition_in_place::TransitionInPlace;
+pub use self::transition_once::TransitionOnce;
 
+mod checkpoint;
+mod distribution_once;
+mod random_iterator;
+mod random_source;
+mod reset;
 mod state;
 mod state_iterator;
+mod stochastic_process;
+mod timed_iterator;
 mod transition;
+mod transition_in_place;
+mod transition_once;
@@ -0,0 +1,241 @@
+use crate::adapters::Tracked;
+use crate::errors::InvalidState;
+use crate::traits::{RandomSource, State};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Saved progress of a simulation: the current state, the progress that is not part
+/// of the state, the random number generator and how far the simulation went.
+///
+/// With the `serde` feature, snapshots can be serialized, for example to survive
+/// the preemption of long-running jobs. The random number generator must then be
+/// serializable too, as is the case of `rand_pcg` generators with its `serde1` feature.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Snapshot<T, H, R> {
+    /// Current state of the process.
+    pub state: T,
+    /// Progress of the process that is not part of its state.
+    pub hidden: H,
+    /// Number of steps taken so far.
+    pub step: usize,
+    /// Time elapsed so far, for timed processes.
+    pub elapsed: f64,
+    /// Random number generator of the process.
+    pub rng: R,
+}
+
+/// Saving and restoring the progress of a process.
+///
+/// This trait is implemented by every process with a public state and its own random
+/// number generator. Besides the state, processes save their hidden progress,
+/// like the time of a time-inhomogeneous chain, so that a resumed process produces exactly
+/// the values the original process would have produced after the snapshot.
+///
+/// Snapshots record the number of steps and the elapsed time of processes that keep them.
+/// Other processes record zero for both: wrap them with [tracked] to count them.
+///
+/// # Examples
+///
+/// Resuming a random walk after ten steps.
+/// ```
+/// # use markovian::{prelude::*, Checkpoint, MarkovChain};
+/// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+/// let mut mc = MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 42).tracked();
+/// let first: Vec<((), i32)> = mc.by_ref().take(10).collect();
+/// let snapshot = mc.snapshot().unwrap();
+/// assert_eq!(snapshot.step, 10);
+/// let expected: Vec<((), i32)> = mc.by_ref().take(5).collect();
+///
+/// mc.resume(&snapshot).unwrap();
+/// assert_eq!(mc.take(5).collect::<Vec<((), i32)>>(), expected);
+/// ```
+///
+/// [tracked]: trait.Checkpoint.html#method.tracked
+pub trait Checkpoint: State + RandomSource {
+    /// Type of the progress of the process that is not part of its state, `()` if there is none.
+    type Hidden;
+
+    /// Returns the progress of the process that is not part of its state.
+    fn hidden_state(&self) -> Self::Hidden;
+
+    /// Restores the progress of the process that is not part of its state.
+    fn set_hidden_state(&mut self, hidden: Self::Hidden);
+
+    /// Returns the number of steps taken and the time elapsed so far,
+    /// or zero for both if the process does not keep them.
+    #[inline]
+    fn progress(&self) -> (usize, f64) {
+        (0, 0.)
+    }
+
+    /// Returns a snapshot of the current state, hidden progress and random number generator,
+    /// or `None` if the process has no public state.
+    #[inline]
+    fn snapshot(&self) -> Option<Snapshot<Self::Item, Self::Hidden, Self::Rng>>
+    where
+        Self::Item: Clone,
+        Self::Rng: Clone,
+    {
+        let (step, elapsed) = self.progress();
+        Some(Snapshot {
+            state: self.state()?.clone(),
+            hidden: self.hidden_state(),
+            step,
+            elapsed,
+            rng: self.rng().clone(),
+        })
+    }
+
+    /// Restores the state, hidden progress and random number generator saved in `snapshot`.
+    ///
+    /// # Errors
+    ///
+    /// If the process does not accept the saved state.
+    #[inline]
+    fn resume(&mut self, snapshot: &Snapshot<Self::Item, Self::Hidden, Self::Rng>) -> Result<(), InvalidState<Self::Item>>
+    where
+        Self::Item: Clone,
+        Self::Hidden: Clone,
+        Self::Rng: Clone,
+    {
+        self.set_state(snapshot.state.clone())?;
+        self.set_hidden_state(snapshot.hidden.clone());
+        self.set_rng(snapshot.rng.clone());
+        Ok(())
+    }
+
+    /// Returns a process that counts the steps taken and the time elapsed,
+    /// which its snapshots record.
+    #[inline]
+    fn tracked(self) -> Tracked<Self>
+    where
+        Self: Sized,
+    {
+        Tracked::new(self)
+    }
+}
+
+/// Time taken by a step of a process, as accumulated by [Tracked].
+///
+/// Steps in discrete time, with time `()`, take no time.
+///
+/// [Tracked]: adapters/struct.Tracked.html
+pub trait Period {
+    /// Returns the length of the period.
+    fn length(&self) -> f64;
+}
+
+impl Period for () {
+    #[inline]
+    fn length(&self) -> f64 {
+        0.
+    }
+}
+
+impl Period for f32 {
+    #[inline]
+    fn length(&self) -> f64 {
+        f64::from(*self)
+    }
+}
+
+impl Period for f64 {
+    #[inline]
+    fn length(&self) -> f64 {
+        *self
+    }
+}
+
+impl Period for u64 {
+    #[inline]
+    fn length(&self) -> f64 {
+        *self as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn resume() {
+        let transition = |state: &u64| raw_dist![(0.5, state + 1), (0.5, *state)];
+        let mut mc = crate::MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 1);
+        mc.by_ref().take(7).for_each(drop);
+        let snapshot = mc.snapshot().unwrap();
+        let expected: Vec<u64> = mc.by_ref().take(20).collect();
+
+        assert_eq!((snapshot.step, snapshot.elapsed), (0, 0.));
+        mc.resume(&snapshot).unwrap();
+        assert_eq!(mc.take(20).collect::<Vec<u64>>(), expected);
+    }
+
+    #[test]
+    fn inhomogeneous() {
+        let transition = |time: usize, state: &u64| raw_dist![(1.0, state + time as u64)];
+        let mut mc = crate::InhomogeneousMarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition, 2);
+        mc.by_ref().take(5).for_each(drop);
+        let snapshot = mc.snapshot().unwrap();
+        assert_eq!(snapshot.step, 5);
+        mc.by_ref().take(10).for_each(drop);
+
+        mc.resume(&snapshot).unwrap();
+        assert_eq!(mc.take(3).collect::<Vec<u64>>(), vec![15, 21, 28]);
+    }
+
+    #[test]
+    fn periodic() {
+        let transition_matrices = vec![vec![vec![0., 1.], vec![1., 0.]], vec![vec![1., 0.], vec![0., 1.]]];
+        let mut mc = crate::PeriodicFiniteMarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(0, transition_matrices, vec!['a', 'b'], 3);
+        mc.next();
+        let snapshot = mc.snapshot().unwrap();
+        let expected: Vec<char> = mc.by_ref().take(5).collect();
+        mc.next();
+
+        mc.resume(&snapshot).unwrap();
+        assert_eq!(mc.take(5).collect::<Vec<char>>(), expected);
+    }
+
+    #[test]
+    fn with_memory() {
+        let transition = |memory: &[u64; 2]| raw_dist![(1.0, memory[0] + memory[1])];
+        let mut process = crate::WithMemory::<_, _, rand_pcg::Pcg64, 2>::with_seed([0, 1], transition, 4);
+        process.by_ref().take(3).for_each(drop);
+        let snapshot = process.snapshot().unwrap();
+        process.by_ref().take(3).for_each(drop);
+
+        process.resume(&snapshot).unwrap();
+        assert_eq!(process.take(3).collect::<Vec<u64>>(), vec![5, 8, 13]);
+    }
+
+    #[test]
+    fn tracked() {
+        let mut poisson = Poisson::<f64, u64, rand_pcg::Pcg64>::with_seed(1., 5).unwrap().tracked();
+        let elapsed: f64 = poisson.by_ref().take(4).map(|(period, _)| period).sum();
+        let snapshot = poisson.snapshot().unwrap();
+        assert_eq!((snapshot.step, snapshot.elapsed), (4, elapsed));
+        let expected: Vec<(f64, u64)> = poisson.by_ref().take(3).collect();
+
+        poisson.resume(&snapshot).unwrap();
+        assert_eq!(poisson.progress(), (4, elapsed));
+        assert_eq!(poisson.take(3).collect::<Vec<(f64, u64)>>(), expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let transition = |state: &u64| raw_dist![(0.5, state + 1), (0.5, *state)];
+        let mut mc = crate::MarkovChain::<_, _, rand_pcg::Pcg64>::with_seed(3, transition, 2);
+        let snapshot = mc.snapshot().unwrap();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: Snapshot<u64, (), rand_pcg::Pcg64> = serde_json::from_str(&json).unwrap();
+        let expected: Vec<u64> = mc.by_ref().take(20).collect();
+
+        assert_eq!(restored, snapshot);
+        mc.resume(&restored).unwrap();
+        assert_eq!(mc.take(20).collect::<Vec<u64>>(), expected);
+    }
+}
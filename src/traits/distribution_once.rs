@@ -0,0 +1,42 @@
+// Traits
+use rand_distr::Distribution;
+use rand::Rng;
+
+/// Distribution that can be sampled only once, consuming it.
+///
+/// This trait is implemented by every `Distribution`, and can also be implemented
+/// by types that move their values out when sampled, so that no cloning is needed.
+///
+/// # Examples
+///
+/// A precomputed value, given away when sampled.
+/// ```
+/// # use markovian::DistributionOnce;
+/// # use rand::Rng;
+/// struct Given(Vec<u8>);
+/// impl DistributionOnce<Vec<u8>> for Given {
+///     fn sample_once<R: Rng + ?Sized>(self, _: &mut R) -> Vec<u8> {
+///         self.0
+///     }
+/// }
+/// assert_eq!(Given(vec![1, 2]).sample_once(&mut rand::thread_rng()), vec![1, 2]);
+/// ```
+pub trait DistributionOnce<T> {
+    /// Samples a value, consuming the distribution.
+    fn sample_once<R>(self, rng: &mut R) -> T
+    where
+        R: Rng + ?Sized;
+}
+
+impl<T, D> DistributionOnce<T> for D
+where
+    D: Distribution<T>,
+{
+    #[inline]
+    fn sample_once<R>(self, rng: &mut R) -> T
+    where
+        R: Rng + ?Sized,
+    {
+        self.sample(rng)
+    }
+}
@@ -0,0 +1,59 @@
+// Traits
+use rand_distr::Distribution;
+use rand::Rng;
+
+/// Iterator whose next element is random and can be sampled without advancing it.
+///
+/// This trait is implemented by every iterator that is also a distribution over its elements,
+/// for example, all processes of this crate.
+///
+/// # Examples
+///
+/// Peeking at possible next states with an external random number generator.
+/// ```
+/// # use markovian::{MarkovChain, RandomIterator, State, prelude::*};
+/// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+/// let mc = MarkovChain::new(0, transition, rand::thread_rng());
+/// let mut rng = rand::thread_rng();
+/// let next = mc.sample_next(&mut rng);
+/// assert!(next == 1 || next == -1);
+/// assert_eq!(mc.state(), Some(&0));
+/// ```
+pub trait RandomIterator: Iterator {
+    /// Samples a possible next element using `rng`, without changing the iterator.
+    fn sample_next<R>(&self, rng: &mut R) -> Self::Item
+    where
+        R: Rng + ?Sized;
+}
+
+impl<I> RandomIterator for I
+where
+    I: Iterator + Distribution<<I as Iterator>::Item>,
+{
+    #[inline]
+    fn sample_next<R>(&self, rng: &mut R) -> Self::Item
+    where
+        R: Rng + ?Sized,
+    {
+        self.sample(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn peek() {
+        let mut rng = crate::tests::rng(1);
+        let transition = |state: &u32| raw_dist![(1.0, (1.5, state + 1))];
+        let mc = crate::TimedMarkovChain::new(3, transition, crate::tests::rng(2));
+
+        assert_eq!(mc.sample_next(&mut rng), (1.5, 4));
+        assert_eq!(mc.state(), Some(&3));
+        let poisson = Poisson::<f64, u32, _>::new(1., crate::tests::rng(3)).unwrap();
+        assert_eq!(poisson.sample_next(&mut rng).1, 1);
+    }
+}
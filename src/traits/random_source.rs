@@ -0,0 +1,72 @@
+use rand::{Rng, SeedableRng};
+
+/// Access to the random number generator of a struct.
+/// 
+/// `RandomSource` should be implemented by structs that own the random number generator 
+/// used to produce their values, so that experiments can be made reproducible.
+/// 
+/// # Examples
+/// 
+/// Reseeding a Markov Chain to reproduce a trajectory.
+/// ```
+/// # use markovian::{MarkovChain, RandomSource};
+/// # use rand_distr::StandardNormal;
+/// let mut mc = MarkovChain::<f64, _, rand_pcg::Pcg64>::with_seed(0., |_: &f64| StandardNormal, 42);
+/// let first: Vec<f64> = mc.by_ref().take(3).collect();
+/// mc.reseed(42);
+/// let second: Vec<f64> = mc.take(3).collect();
+/// assert_eq!(first, second);
+/// ```
+pub trait RandomSource {
+    type Rng: Rng;
+
+    fn rng(&self) -> &Self::Rng;
+
+    fn rng_mut(&mut self) -> &mut Self::Rng;
+
+    /// Changes the random number generator, returning the previous one. 
+    /// 
+    /// # Remarks
+    ///
+    /// You might want to use [core::mem::replace](https://doc.rust-lang.org/core/mem/fn.replace.html).
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng;
+
+    /// Replaces the random number generator by a new one, constructed from `seed`.
+    #[inline]
+    fn reseed(&mut self, seed: u64) 
+    where
+        Self::Rng: SeedableRng,
+    {
+        self.set_rng(Self::Rng::seed_from_u64(seed));
+    }
+
+    /// Returns a clone of `self` whose random number generator is an independent stream,
+    /// seeded from the random number generator of `self`. 
+    /// 
+    /// Cloning duplicates the random number generator, so a clone produces the same values 
+    /// as the original. Forking should be used instead, for example, to simulate independent
+    /// copies of a process in parallel.
+    /// 
+    /// # Examples
+    /// 
+    /// Forked chains follow different trajectories.
+    /// ```
+    /// # use markovian::{MarkovChain, RandomSource};
+    /// # use rand_distr::StandardNormal;
+    /// let mut mc = MarkovChain::<f64, _, rand_pcg::Pcg64>::with_seed(0., |_: &f64| StandardNormal, 42);
+    /// let forked = mc.fork();
+    /// assert_ne!(mc.take(3).collect::<Vec<f64>>(), forked.take(3).collect::<Vec<f64>>());
+    /// ```
+    #[inline]
+    fn fork(&mut self) -> Self 
+    where
+        Self: Clone + Sized,
+        Self::Rng: SeedableRng,
+    {
+        let rng = Self::Rng::from_rng(self.rng_mut())
+            .expect("The random number generator failed to seed a new one.");
+        let mut forked = self.clone();
+        forked.set_rng(rng);
+        forked
+    }
+}
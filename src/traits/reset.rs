@@ -0,0 +1,34 @@
+/// Restarting a process from its initial condition. 
+/// 
+/// The initial condition is captured at construction, so that the same 
+/// configured process can be simulated many times without constructing it again. 
+/// 
+/// # Examples
+/// 
+/// Monte Carlo estimation of the expected position of a random walk after ten steps. 
+/// ```
+/// # use markovian::{prelude::*, MarkovChain};
+/// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+/// let mut mc = MarkovChain::new(0, transition, rand::thread_rng());
+/// let samples = 1_000;
+/// let mean = (0..samples)
+///     .map(|_| mc.restarted().nth(9).unwrap())
+///     .sum::<i32>() as f64 / samples as f64;
+/// println!("The expected position is approximately {}", mean);
+/// ```
+pub trait Reset {
+    /// Changes the state of the process to its initial condition. 
+    /// 
+    /// If the initial condition is random, a new sample is taken.
+    fn reset(&mut self);
+
+    /// Resets the process and returns a mutable reference to it. 
+    /// 
+    /// This allows to use iterator adapters on the restarted process, 
+    /// without consuming it.
+    #[inline]
+    fn restarted(&mut self) -> &mut Self {
+        self.reset();
+        self
+    }
+}
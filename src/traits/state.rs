@@ -42,7 +42,6 @@ use crate::errors::InvalidState;
 /// ```
 ///
 /// [Arithmetic sequence]: https://en.wikipedia.org/wiki/Arithmetic_progression
-
 pub trait State {
     type Item: core::fmt::Debug;
 
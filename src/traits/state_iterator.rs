@@ -43,4 +43,190 @@ pub trait StateIterator: Iterator + State + Sized {
     fn trajectory(self) -> Chain<std::option::IntoIter<<Self as std::iter::Iterator>::Item>, Self> {
         self.state_as_item().into_iter().chain(self)
     }
+
+    /// Advances the iterator one step, without returning the new element.
+    ///
+    /// Returns `false` if the iterator has finished. Read the new state through [state].
+    /// Implementors whose items are clones of the state should override this method
+    /// so that heavy states, like whole histories, are not cloned at every step.
+    ///
+    /// # Examples
+    ///
+    /// Growing a history without cloning it at each step.
+    /// ```
+    /// # use markovian::{MarkovChain, Transition, prelude::*};
+    /// # use rand::Rng;
+    /// struct Push;
+    /// impl Transition<Vec<bool>, Vec<bool>> for Push {
+    ///     fn sample_from<R: Rng + ?Sized>(&self, state: &Vec<bool>, rng: &mut R) -> Vec<bool> {
+    ///         let mut next = state.clone();
+    ///         next.push(rng.gen());
+    ///         next
+    ///     }
+    /// }
+    /// let mut mc = MarkovChain::new(Vec::new(), Push, rand::thread_rng());
+    /// while mc.state().unwrap().len() < 100 {
+    ///     mc.advance();
+    /// }
+    /// assert_eq!(mc.state().unwrap().len(), 100);
+    /// ```
+    ///
+    /// [state]: trait.State.html#method.state
+    #[inline]
+    fn advance(&mut self) -> bool {
+        self.next().is_some()
+    }
+
+    /// Advances the iterator `n` times and returns running statistics of
+    /// `projection` applied to each element, without storing them.
+    ///
+    /// The iterator can keep being used afterwards.
+    ///
+    /// # Examples
+    ///
+    /// Mean number of steps to the right of a random walk.
+    /// ```
+    /// # use markovian::{MarkovChain, prelude::*};
+    /// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+    /// let mut mc = MarkovChain::new(0, transition, rand::thread_rng());
+    /// let stats = mc.statistics(|state| *state as f64, 100);
+    /// println!("Mean position: {} ± {}", stats.mean(), stats.standard_error());
+    /// ```
+    #[inline]
+    fn statistics<F>(&mut self, projection: F, n: usize) -> crate::stats::Welford
+    where
+        F: Fn(&<Self as std::iter::Iterator>::Item) -> f64,
+    {
+        self.by_ref().take(n).map(|item| projection(&item)).collect()
+    }
+
+    /// Returns a new iterator that calls `observer` with the step count and
+    /// a reference to each element, before yielding it.
+    ///
+    /// Steps count transitions, so the first element yielded is step one.
+    /// Useful for logging, live plotting or keeping track of quantities in long simulations.
+    ///
+    /// # Examples
+    ///
+    /// Logging a random walk, while still using the iterator.
+    /// ```
+    /// # use markovian::{MarkovChain, prelude::*};
+    /// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+    /// let mc = MarkovChain::new(0, transition, rand::thread_rng());
+    /// let mut max = 0;
+    /// let steps = mc
+    ///     .observe(|step, state| {
+    ///         println!("At step {}, the state is {}", step, state);
+    ///         max = max.max(*state);
+    ///     })
+    ///     .take_while(|state| state.abs() < 5)
+    ///     .count();
+    /// assert!(max <= 5);
+    /// ```
+    #[inline]
+    fn observe<F>(self, observer: F) -> crate::adapters::Observe<Self, F>
+    where
+        F: FnMut(usize, &<Self as std::iter::Iterator>::Item),
+    {
+        crate::adapters::Observe::new(self, observer)
+    }
+
+    /// Returns a new iterator that yields each element together with the maximum so far,
+    /// including the current state.
+    ///
+    /// # Remarks
+    ///
+    /// Elements are compared as a whole, so this method is meant for discrete-time processes.
+    ///
+    /// # Examples
+    ///
+    /// Maximum of a random walk after 100 steps.
+    /// ```
+    /// # use markovian::{MarkovChain, prelude::*};
+    /// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+    /// let mc = MarkovChain::new(0, transition, rand::thread_rng());
+    /// let (state, max) = mc.running_max().nth(99).unwrap();
+    /// assert!(max >= state && max >= 0);
+    /// ```
+    #[inline]
+    fn running_max(self) -> crate::adapters::RunningMax<Self>
+    where
+        <Self as std::iter::Iterator>::Item: PartialOrd + Clone,
+    {
+        let initial = self.state_as_item();
+        crate::adapters::RunningMax::new(self, initial)
+    }
+
+    /// Returns a new iterator that yields each element together with the minimum so far,
+    /// including the current state.
+    ///
+    /// # Remarks
+    ///
+    /// Elements are compared as a whole, so this method is meant for discrete-time processes.
+    ///
+    /// # Examples
+    ///
+    /// Minimum of a random walk after 100 steps.
+    /// ```
+    /// # use markovian::{MarkovChain, prelude::*};
+    /// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+    /// let mc = MarkovChain::new(0, transition, rand::thread_rng());
+    /// let (state, min) = mc.running_min().nth(99).unwrap();
+    /// assert!(min <= state && min <= 0);
+    /// ```
+    #[inline]
+    fn running_min(self) -> crate::adapters::RunningMin<Self>
+    where
+        <Self as std::iter::Iterator>::Item: PartialOrd + Clone,
+    {
+        let initial = self.state_as_item();
+        crate::adapters::RunningMin::new(self, initial)
+    }
+
+    /// Returns a new iterator that yields each element together with its drawdown:
+    /// the maximum so far, including the current state, minus the element.
+    ///
+    /// # Examples
+    ///
+    /// Maximum drawdown of a random walk in 100 steps.
+    /// ```
+    /// # use markovian::{MarkovChain, prelude::*};
+    /// let transition = |state: &f64| raw_dist![(0.5, state + 1.), (0.5, state - 1.)];
+    /// let mc = MarkovChain::new(0., transition, rand::thread_rng());
+    /// let max_drawdown = mc.drawdown().take(100).map(|(_, drawdown)| drawdown).fold(0., f64::max);
+    /// assert!(max_drawdown >= 0.);
+    /// ```
+    #[inline]
+    fn drawdown(self) -> crate::adapters::Drawdown<Self>
+    where
+        <Self as std::iter::Iterator>::Item: PartialOrd + Clone + core::ops::Sub<Output = <Self as std::iter::Iterator>::Item>,
+    {
+        let initial = self.state_as_item();
+        crate::adapters::Drawdown::new(self, initial)
+    }
+
+    /// Returns a new process that makes one step of `self` at each arrival of `clock`,
+    /// a timed iterator whose periods are the times between arrivals.
+    ///
+    /// The new process yields pairs `(period, state)`.
+    ///
+    /// # Examples
+    ///
+    /// A random walk subordinated by a Poisson process: a continuous time random walk.
+    /// ```
+    /// # use markovian::{MarkovChain, prelude::*};
+    /// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+    /// let walk = MarkovChain::new(0, transition, rand::thread_rng());
+    /// let clock = Poisson::<f64, u32, _>::new(2., rand::thread_rng()).unwrap();
+    /// for (time, position) in walk.time_changed(clock).trajectory_until(10.) {
+    ///     println!("At time {}, the walk is at {}", time, position);
+    /// }
+    /// ```
+    #[inline]
+    fn time_changed<C, N, K>(self, clock: C) -> crate::adapters::TimeChanged<Self, C>
+    where
+        C: Iterator<Item = (N, K)>,
+    {
+        crate::adapters::TimeChanged::new(self, clock)
+    }
 }
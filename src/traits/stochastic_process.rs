@@ -0,0 +1,62 @@
+/// Stochastic process in discrete or continuous time.
+///
+/// Each step of the process yields the time it took and the new state.
+/// Discrete-time processes use `()` as time, while continuous-time processes
+/// use the period of time spent in the previous state.
+///
+/// This trait is implemented by all processes of this crate, so that code handling
+/// trajectories, like recording or statistics, can be written once for all of them.
+///
+/// # Examples
+///
+/// Counting the steps until a process reaches a target, in discrete or continuous time.
+/// ```
+/// # use markovian::{MarkovChain, StochasticProcess, prelude::*};
+/// fn steps_until<P: StochasticProcess>(process: &mut P, target: P::State) -> usize
+/// where
+///     P::State: PartialEq,
+/// {
+///     let mut steps = 0;
+///     while let Some((_, state)) = process.step() {
+///         steps += 1;
+///         if state == target {
+///             break;
+///         }
+///     }
+///     steps
+/// }
+/// let transition = |state: &u32| raw_dist![(1.0, state + 1)];
+/// let mut mc = MarkovChain::new(0, transition, rand::thread_rng());
+/// assert_eq!(steps_until(&mut mc, 5), 5);
+/// let mut poisson = Poisson::<f64, u32, _>::new(1., rand::thread_rng()).unwrap();
+/// assert_eq!(steps_until(&mut poisson, 5), 5);
+/// ```
+pub trait StochasticProcess {
+    /// Type of the time of each step.
+    type Time;
+    /// Type of the states.
+    type State;
+
+    /// Advances the process one step, returning the time it took and the new state,
+    /// or `None` if the process has finished.
+    fn step(&mut self) -> Option<(Self::Time, Self::State)>;
+
+    /// Advances the process at most `n` steps, returning the time and state of each of them.
+    #[inline]
+    fn steps(&mut self, n: usize) -> Vec<(Self::Time, Self::State)> {
+        (0..n).map_while(|_| self.step()).collect()
+    }
+}
+
+impl<P> StochasticProcess for Box<P>
+where
+    P: StochasticProcess + ?Sized,
+{
+    type Time = P::Time;
+    type State = P::State;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        (**self).step()
+    }
+}
@@ -0,0 +1,126 @@
+use crate::adapters::{AbsoluteTime, ObserveWithTime, OnGrid, Trajectory, Until};
+use crate::StateIterator;
+use core::ops::Add;
+use num_traits::{Float, Zero};
+
+/// Iterator whose elements are pairs `(period, state)`.
+/// 
+/// This trait is implemented for every such iterator, 
+/// for example, the continuous time processes of this crate. 
+/// It provides adapters to handle time.
+pub trait TimedIterator<N, T>: Iterator<Item = (N, T)> + Sized {
+    /// Returns a new iterator that yields the absolute time, 
+    /// i.e. the sum of all periods so far, instead of the period.
+    /// 
+    /// # Examples
+    /// 
+    /// A Poisson process, with its jump times. 
+    /// ```
+    /// # use markovian::prelude::*;
+    /// let poisson = Poisson::<f64, u32, _>::new(1., rand::thread_rng()).unwrap();
+    /// for (time, state) in poisson.trajectory().with_absolute_time().take(10) {
+    ///     println!("At time {}, the process jumps to {}", time, state);
+    /// }
+    /// ```
+    #[inline]
+    fn with_absolute_time(self) -> AbsoluteTime<Self, N> 
+    where
+        N: Zero,
+    {
+        AbsoluteTime::new(self, N::zero())
+    }
+
+    /// Returns a new iterator that stops at the first element whose time is strictly greater than `horizon`.
+    /// 
+    /// # Remarks
+    /// 
+    /// The time of the elements is compared, so this method is meant to be used after [with_absolute_time].
+    /// 
+    /// [with_absolute_time]: trait.TimedIterator.html#method.with_absolute_time
+    #[inline]
+    fn until(self, horizon: N) -> Until<Self, N> 
+    where
+        N: PartialOrd,
+    {
+        Until::new(self, horizon)
+    }
+
+    /// Returns the trajectory of the process, with absolute time, up to time `horizon`.
+    /// 
+    /// The first element is the current state, at time zero. 
+    /// 
+    /// # Examples
+    /// 
+    /// A Poisson process up to time 10.
+    /// ```
+    /// # use markovian::prelude::*;
+    /// let poisson = Poisson::<f64, u32, _>::new(1., rand::thread_rng()).unwrap();
+    /// let path: Vec<(f64, u32)> = poisson.trajectory_until(10.).collect();
+    /// assert_eq!(path[0], (0., 0));
+    /// assert!(path.iter().all(|(time, _)| time <= &10.));
+    /// ```
+    #[inline]
+    fn trajectory_until(self, horizon: N) -> Until<AbsoluteTime<Trajectory<Self>, N>, N>
+    where
+        Self: StateIterator,
+        N: Zero + Add<Output = N> + PartialOrd + Copy,
+    {
+        self.trajectory().with_absolute_time().until(horizon)
+    }
+
+    /// Returns the states of the process observed at times `0, step, 2 * step, ...`, 
+    /// for `n` observations in total. 
+    /// 
+    /// The state observed at a given time is the last one the process jumped to,
+    /// including jumps happening exactly at that time. 
+    /// If the process stops, its last state is observed for the remaining times.
+    /// 
+    /// # Examples
+    /// 
+    /// A Poisson process observed every half unit of time.
+    /// ```
+    /// # use markovian::prelude::*;
+    /// let poisson = Poisson::<f64, u32, _>::new(1., rand::thread_rng()).unwrap();
+    /// let observations: Vec<u32> = poisson.sample_on_grid(0.5, 10).collect();
+    /// assert_eq!(observations.len(), 10);
+    /// assert_eq!(observations[0], 0);
+    /// ```
+    #[inline]
+    fn sample_on_grid(self, step: N, n: usize) -> OnGrid<AbsoluteTime<Trajectory<Self>, N>, N, T>
+    where
+        Self: StateIterator,
+        N: Float,
+        T: Clone,
+    {
+        OnGrid::new(self.trajectory().with_absolute_time(), step, n)
+    }
+
+    /// Returns a new iterator that calls `observer` with the step count, the elapsed time
+    /// and a reference to the state of each element, before yielding it unchanged.
+    ///
+    /// # Examples
+    ///
+    /// Logging a Poisson process.
+    /// ```
+    /// # use markovian::prelude::*;
+    /// let poisson = Poisson::<f64, u32, _>::new(1., rand::thread_rng()).unwrap();
+    /// let periods: Vec<f64> = poisson
+    ///     .observe_with_time(|step, time, state| println!("Jump {} at time {} to {}", step, time, state))
+    ///     .take(10)
+    ///     .map(|(period, _)| period)
+    ///     .collect();
+    /// ```
+    #[inline]
+    fn observe_with_time<F>(self, observer: F) -> ObserveWithTime<Self, N, F>
+    where
+        N: Zero + Add<Output = N> + Copy,
+        F: FnMut(usize, N, &T),
+    {
+        ObserveWithTime::new(self, N::zero(), observer)
+    }
+}
+
+impl<I, N, T> TimedIterator<N, T> for I 
+where 
+    I: Iterator<Item = (N, T)>,
+{}
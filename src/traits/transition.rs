@@ -14,6 +14,36 @@ pub trait Transition<T, O> {
     fn sample_from<R>(&self, state: &T, rng: &mut R) -> O
     where
         R: Rng + ?Sized;
+
+    /// Samples a possible path of `k` steps from `state`, 
+    /// where each output is converted into the state of the next step.
+    ///
+    /// # Examples
+    ///
+    /// Hypothetical rollouts of a random walk.
+    /// ```
+    /// # use markovian::{prelude::*, Transition};
+    /// let transition = |state: &i32| raw_dist![(0.5, state + 1), (0.5, state - 1)];
+    /// let path = transition.sample_path_from(&0, 3, &mut rand::thread_rng());
+    /// assert_eq!(path.len(), 3);
+    /// assert_eq!(path[0].abs(), 1);
+    /// ```
+    #[inline]
+    fn sample_path_from<R>(&self, state: &T, k: usize, rng: &mut R) -> Vec<O>
+    where
+        R: Rng + ?Sized,
+        O: Clone + Into<T>,
+    {
+        let mut path: Vec<O> = Vec::with_capacity(k);
+        for _ in 0..k {
+            let output = match path.last() {
+                Some(last) => self.sample_from(&last.clone().into(), rng),
+                None => self.sample_from(state, rng),
+            };
+            path.push(output);
+        }
+        path
+    }
 }
 
 impl<T, O, F, D> Transition<T, O> for F
@@ -41,7 +71,7 @@ mod tests {
         let expected = 1;
         fn transition(_: &u64) -> Raw<Vec<(f64, u64)>> {
             Raw::new(vec![(1.0, 1)])
-        };
+        }
 
         assert_eq!(transition.sample_from(&0, &mut rng), expected);
 
@@ -57,10 +87,18 @@ mod tests {
 
         fn transition(_: &u64) -> rand_distr::StandardNormal {
             rand_distr::StandardNormal
-        };
+        }
 
         let sample: f64 = transition.sample_from(&0, &mut rng);
         assert_eq!(sample, expected);
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn path() {
+        let mut rng = crate::tests::rng(3);
+        let transition = |state: &u64| Raw::new(vec![(1.0, state + 2)]);
+
+        assert_eq!(transition.sample_path_from(&1, 3, &mut rng), vec![3, 5, 7]);
+    }
+}
@@ -0,0 +1,34 @@
+// Traits
+use rand::Rng;
+
+/// Transition that updates the state in place.
+///
+/// Useful when states are large, for example when they carry their whole history,
+/// so that each step costs only the change and not a copy of the state.
+///
+/// # Examples
+///
+/// A random walk that remembers its path.
+/// ```
+/// # use markovian::TransitionInPlace;
+/// # use rand::Rng;
+/// struct Walk;
+/// impl TransitionInPlace<Vec<i32>> for Walk {
+///     fn step_in_place<R>(&self, state: &mut Vec<i32>, rng: &mut R)
+///     where
+///         R: Rng + ?Sized,
+///     {
+///         let last = state[state.len() - 1];
+///         state.push(if rng.gen() { last + 1 } else { last - 1 });
+///     }
+/// }
+/// let mut path = vec![0];
+/// Walk.step_in_place(&mut path, &mut rand::thread_rng());
+/// assert_eq!(path.len(), 2);
+/// ```
+pub trait TransitionInPlace<T> {
+    /// Replaces `state` by a sample of the next state.
+    fn step_in_place<R>(&self, state: &mut T, rng: &mut R)
+    where
+        R: Rng + ?Sized;
+}
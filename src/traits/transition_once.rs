@@ -0,0 +1,59 @@
+// Traits
+use crate::traits::DistributionOnce;
+use rand::Rng;
+
+/// Transition that can be used only once, consuming it.
+///
+/// This trait is implemented by every closure `FnOnce(&T) -> D`, where `D` is a [DistributionOnce],
+/// so that transitions can move values they own into the next state.
+///
+/// [DistributionOnce]: trait.DistributionOnce.html
+pub trait TransitionOnce<T, O> {
+    /// Samples the output of the transition from `state`, consuming the transition.
+    fn sample_once_from<R>(self, state: &T, rng: &mut R) -> O
+    where
+        R: Rng + ?Sized;
+}
+
+impl<T, O, F, D> TransitionOnce<T, O> for F
+where
+    F: FnOnce(&T) -> D,
+    D: DistributionOnce<O>,
+{
+    #[inline]
+    fn sample_once_from<R>(self, state: &T, rng: &mut R) -> O
+    where
+        R: Rng + ?Sized,
+    {
+        self(state).sample_once(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    struct Given(Vec<String>);
+
+    impl DistributionOnce<Vec<String>> for Given {
+        fn sample_once<R: Rng + ?Sized>(self, _: &mut R) -> Vec<String> {
+            self.0
+        }
+    }
+
+    #[test]
+    fn move_only() {
+        let mut rng = crate::tests::rng(1);
+        let tail = vec![String::from("b")];
+        let transition = move |state: &Vec<String>| {
+            let mut next = state.clone();
+            next.extend(tail);
+            Given(next)
+        };
+
+        assert_eq!(transition.sample_once_from(&vec![String::from("a")], &mut rng), vec!["a", "b"]);
+        let sample: u32 = (|_: &u32| rand_distr::Uniform::new(0, 1)).sample_once_from(&5, &mut rng);
+        assert_eq!(sample, 0);
+    }
+}
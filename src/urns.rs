@@ -0,0 +1,489 @@
+//! Urn processes, where drawing a ball adds balls of several colors.
+//!
+//! An [Urn] contains balls of several colors. At each step, a ball is drawn uniformly at random,
+//! and it is returned together with `replacement[i][j]` balls of color `j`, if the ball drawn has color `i`.
+//! The state of the process is the number of balls of each color.
+//!
+//! Classic urns are special cases:
+//! - Pólya urn: `replacement` is a multiple of the identity.
+//! - Friedman urn: two colors, and `replacement` is `[[same, other], [other, same]]`.
+//! - Hoppe urn: a Pólya urn with a mutator of weight `theta`. When the mutator is drawn,
+//!   balls of a new color are added. The mutator itself is never removed.
+//!
+//! # Examples
+//!
+//! In a Pólya urn, the number of red balls drawn follows a Beta-Binomial distribution.
+//! ```
+//! # use markovian::urns::Urn;
+//! let mut urn = Urn::polya(vec![1, 1], 1, rand::thread_rng());
+//! let red = (0..10).filter(|_| urn.draw() == 0).count();
+//! // Uniform in 0..=10
+//! println!("{} red balls were drawn", red);
+//! ```
+//!
+//! [Urn]: struct.Urn.html
+
+// Traits
+use crate::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess};
+use rand::{Rng, SeedableRng};
+use rand_distr::{Beta, Distribution, Gamma};
+
+// Structs
+use crate::errors::InvalidState;
+use ndarray::Array2;
+
+// Functions
+use core::mem;
+
+/// Generalized urn process, with a replacement matrix and an optional mutator.
+///
+/// New colors, created by the mutator, are reinforced as in a Pólya urn.
+///
+/// # Examples
+///
+/// The proportions of a Friedman urn approach one half.
+/// ```
+/// # use markovian::urns::Urn;
+/// let mut urn = Urn::friedman([1, 0], 2, 1, rand::thread_rng());
+/// let balls = urn.nth(9_999).unwrap();
+/// let proportion = balls[0] as f64 / (balls[0] + balls[1]) as f64;
+/// assert!((proportion - 0.5).abs() < 0.05);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Urn<R> {
+    state: Vec<u64>,
+    initial_state: Vec<u64>,
+    replacement: Vec<Vec<u64>>,
+    reinforcement: Option<u64>,
+    mutation: f64,
+    rng: R,
+}
+
+impl<R> Urn<R>
+where
+    R: Rng,
+{
+    /// Constructs a new `Urn<R>` with `balls[i]` balls of color `i`, where drawing color `i`
+    /// adds `replacement[i][j]` balls of color `j`.
+    ///
+    /// # Panics
+    ///
+    /// If `replacement` is not a square matrix of the size of `balls`, or there are no balls.
+    #[inline]
+    pub fn new(balls: Vec<u64>, replacement: Vec<Vec<u64>>, rng: R) -> Self {
+        assert!(
+            replacement.len() == balls.len() && replacement.iter().all(|row| row.len() == balls.len()),
+            "The replacement matrix must be square, with one row per color."
+        );
+        assert!(balls.iter().any(|&b| b > 0), "The urn must contain some ball.");
+        let reinforcement = polya_reinforcement(&replacement);
+        Urn {
+            initial_state: balls.clone(),
+            state: balls,
+            replacement,
+            reinforcement,
+            mutation: 0.,
+            rng,
+        }
+    }
+
+    /// Constructs a new `Urn<R>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as [new].
+    ///
+    /// [new]: struct.Urn.html#method.new
+    #[inline]
+    pub fn with_seed(balls: Vec<u64>, replacement: Vec<Vec<u64>>, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        Urn::new(balls, replacement, R::seed_from_u64(seed))
+    }
+
+    /// Constructs a Pólya urn, where drawing a ball adds `reinforcement` balls of its color.
+    ///
+    /// # Panics
+    ///
+    /// If there are no balls.
+    #[inline]
+    pub fn polya(balls: Vec<u64>, reinforcement: u64, rng: R) -> Self {
+        let n = balls.len();
+        let replacement = (0..n)
+            .map(|i| (0..n).map(|j| if i == j { reinforcement } else { 0 }).collect())
+            .collect();
+        Urn::new(balls, replacement, rng)
+    }
+
+    /// Constructs a Friedman urn with two colors, where drawing a ball adds `same` balls of its color
+    /// and `other` balls of the other color.
+    ///
+    /// # Panics
+    ///
+    /// If there are no balls.
+    #[inline]
+    pub fn friedman(balls: [u64; 2], same: u64, other: u64, rng: R) -> Self {
+        Urn::new(balls.to_vec(), vec![vec![same, other], vec![other, same]], rng)
+    }
+
+    /// Constructs a Hoppe urn, with no balls and a mutator of weight `theta`.
+    ///
+    /// Each new color starts with one ball, and drawing a ball adds one ball of its color.
+    /// The partition of the balls by color follows the Ewens sampling formula.
+    ///
+    /// # Panics
+    ///
+    /// If `theta` is not positive.
+    #[inline]
+    pub fn hoppe(theta: f64, rng: R) -> Self {
+        assert!(theta > 0., "The weight of the mutator must be positive.");
+        Urn {
+            state: Vec::new(),
+            initial_state: Vec::new(),
+            replacement: Vec::new(),
+            reinforcement: Some(1),
+            mutation: theta,
+            rng,
+        }
+    }
+
+    /// Adds a mutator of weight `theta`: when drawn, a new color is created with
+    /// as many balls as the reinforcement of the Pólya urn.
+    ///
+    /// # Panics
+    ///
+    /// If `theta` is not positive, or the urn is not a Pólya urn.
+    #[inline]
+    pub fn with_mutation(mut self, theta: f64) -> Self {
+        assert!(theta > 0., "The weight of the mutator must be positive.");
+        assert!(self.reinforcement.is_some(), "Only Pólya urns can have a mutator.");
+        self.mutation = theta;
+        self
+    }
+
+    /// Returns the number of balls of each color.
+    #[inline]
+    pub fn balls(&self) -> &[u64] {
+        &self.state
+    }
+
+    /// Returns the number of colors.
+    #[inline]
+    pub fn ncolors(&self) -> usize {
+        self.state.len()
+    }
+
+    /// Returns the proportion of balls of each color.
+    #[inline]
+    pub fn proportions(&self) -> Vec<f64> {
+        let total: u64 = self.state.iter().sum();
+        self.state.iter().map(|&b| b as f64 / total as f64).collect()
+    }
+
+    /// Draws a ball, adds the corresponding balls, and returns the color drawn.
+    ///
+    /// If the mutator is drawn, the color returned is the new color.
+    #[inline]
+    pub fn draw(&mut self) -> usize {
+        let total = self.state.iter().sum::<u64>() as f64 + self.mutation;
+        let mut u = self.rng.gen::<f64>() * total;
+        let mut color = self.state.len();
+        for (i, &b) in self.state.iter().enumerate() {
+            if u < b as f64 {
+                color = i;
+                break;
+            }
+            u -= b as f64;
+        }
+        if self.mutation <= 0. {
+            // Guard against rounding past the last ball
+            color = color.min(self.state.iter().rposition(|&b| b > 0).unwrap_or(0));
+        }
+        self.add_balls(color);
+        color
+    }
+
+    /// Samples the limit of the proportions of each color, if it is known in closed form,
+    /// given the current state.
+    ///
+    /// - Pólya urns are exchangeable: the limit follows a Dirichlet distribution with parameters
+    ///   `balls[i] / reinforcement`. With a mutator, the mass of the colors yet to appear is split
+    ///   by stick-breaking, until it is negligible, and appended as new colors.
+    /// - Urns whose replacement matrix is irreducible have a deterministic limit: the left
+    ///   Perron eigenvector of the replacement matrix.
+    ///
+    /// Otherwise, returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// The limit of a Pólya urn with one ball of each color is uniform.
+    /// ```
+    /// # use markovian::urns::Urn;
+    /// let urn = Urn::polya(vec![1, 1], 1, rand::thread_rng());
+    /// let limit = urn.sample_limit_proportions(&mut rand::thread_rng()).unwrap();
+    /// assert!((limit[0] + limit[1] - 1.).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn sample_limit_proportions<R2>(&self, rng: &mut R2) -> Option<Vec<f64>>
+    where
+        R2: Rng + ?Sized,
+    {
+        match self.reinforcement {
+            Some(a) if a > 0 => Some(self.sample_dirichlet_limit(a as f64, rng)),
+            _ if self.mutation <= 0. && is_irreducible(&self.replacement) => Some(perron_left_eigenvector(&self.replacement)),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn sample_dirichlet_limit<R2>(&self, a: f64, rng: &mut R2) -> Vec<f64>
+    where
+        R2: Rng + ?Sized,
+    {
+        let gamma = |shape: f64, rng: &mut R2| -> f64 {
+            if shape > 0. {
+                Gamma::new(shape, 1.).expect("The shape is positive.").sample(rng)
+            } else {
+                0.
+            }
+        };
+        let mut limit: Vec<f64> = self.state.iter().map(|&b| gamma(b as f64 / a, rng)).collect();
+        let mut rest = gamma(self.mutation / a, rng);
+        let total: f64 = limit.iter().sum::<f64>() + rest;
+        for proportion in limit.iter_mut() {
+            *proportion /= total;
+        }
+        rest /= total;
+        if rest > 0. {
+            // New colors start with `a` balls
+            let stick = Beta::new(1., self.mutation / a).expect("The parameters are positive.");
+            while rest > f64::EPSILON {
+                let piece = rest * stick.sample(rng);
+                limit.push(piece);
+                rest -= piece;
+            }
+        }
+        limit
+    }
+
+    #[inline]
+    fn add_balls(&mut self, color: usize) {
+        if color < self.replacement.len() {
+            for (balls, added) in self.state.iter_mut().zip(&self.replacement[color]) {
+                *balls += added;
+            }
+        } else if color < self.state.len() {
+            self.state[color] += self.reinforcement.unwrap_or(1);
+        } else {
+            self.state.push(self.reinforcement.unwrap_or(1));
+        }
+    }
+}
+
+impl<R> Reset for Urn<R> {
+    #[inline]
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+    }
+}
+
+impl<R> RandomSource for Urn<R>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<R> State for Urn<R> {
+    type Item = Vec<u64>;
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    /// Changes the number of balls of each color, as long as the number of colors does not change.
+    #[inline]
+    fn set_state(
+        &mut self,
+        new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        if new_state.len() == self.state.len() {
+            Ok(Some(mem::replace(&mut self.state, new_state)))
+        } else {
+            Err(InvalidState::new(new_state))
+        }
+    }
+}
+
+impl<R> Checkpoint for Urn<R>
+where
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<R> Iterator for Urn<R>
+where
+    R: Rng,
+{
+    type Item = Vec<u64>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.draw();
+        Some(self.state.clone())
+    }
+}
+
+impl<R> StochasticProcess for Urn<R>
+where
+    R: Rng,
+{
+    type Time = ();
+    type State = Vec<u64>;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<R> StateIterator for Urn<R>
+where
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        Some(self.state.clone())
+    }
+
+    #[inline]
+    fn advance(&mut self) -> bool {
+        self.draw();
+        true
+    }
+}
+
+/// Returns `Some(a)` if `replacement` is `a` times the identity.
+#[inline]
+fn polya_reinforcement(replacement: &[Vec<u64>]) -> Option<u64> {
+    let a = replacement.first().map(|row| row[0])?;
+    let is_polya = replacement.iter().enumerate().all(|(i, row)| {
+        row.iter().enumerate().all(|(j, &r)| r == if i == j { a } else { 0 })
+    });
+    if is_polya {
+        Some(a)
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if every color leads to every other color through the replacement matrix.
+#[inline]
+fn is_irreducible(replacement: &[Vec<u64>]) -> bool {
+    let n = replacement.len();
+    let p = Array2::from_shape_fn((n, n), |(i, j)| replacement[i][j] as f64);
+    (0..n).all(|j| {
+        let target: Vec<bool> = (0..n).map(|i| i == j).collect();
+        crate::linalg::backward_reachable(&p, &target).into_iter().all(|b| b)
+    })
+}
+
+/// Returns the left Perron eigenvector of an irreducible non-negative matrix, normalized to sum one.
+#[inline]
+fn perron_left_eigenvector(replacement: &[Vec<u64>]) -> Vec<f64> {
+    let n = replacement.len();
+    // Shifting by the identity makes the power iteration converge for periodic matrices
+    let shifted = Array2::from_shape_fn((n, n), |(i, j)| replacement[i][j] as f64 + if i == j { 1. } else { 0. });
+    let mut vector = ndarray::Array1::from_elem(n, 1. / n as f64);
+    for _ in 0..100_000 {
+        let mut next = vector.dot(&shifted);
+        next /= next.sum();
+        let change = (&next - &vector).mapv(f64::abs).sum();
+        vector = next;
+        if change < 1e-15 {
+            break;
+        }
+    }
+    vector.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn beta_binomial() {
+        // With one ball of each color, the number of red draws is uniform in 0..=n
+        let mut urn = Urn::polya(vec![1, 1], 1, crate::tests::rng(1));
+        let samples = 6_000;
+        let mut counts = [0; 3];
+        for _ in 0..samples {
+            urn.reset();
+            let red = (0..2).filter(|_| urn.draw() == 0).count();
+            counts[red] += 1;
+        }
+        for count in counts.iter() {
+            assert!((*count as f64 / samples as f64 - 1. / 3.).abs() < 0.03);
+        }
+    }
+
+    #[test]
+    fn hoppe() {
+        let mut urn = Urn::hoppe(1., crate::tests::rng(2));
+        assert_eq!(urn.draw(), 0);
+        assert_eq!(urn.balls(), &[1]);
+        for _ in 0..99 {
+            urn.draw();
+        }
+        assert_eq!(urn.balls().iter().sum::<u64>(), 100);
+
+        let limit = urn.sample_limit_proportions(&mut crate::tests::rng(3)).unwrap();
+        assert!(limit.len() >= urn.ncolors());
+        assert!((limit.iter().sum::<f64>() - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn limits() {
+        let mut rng = crate::tests::rng(4);
+        let friedman = Urn::friedman([5, 1], 0, 1, crate::tests::rng(5));
+        let limit = friedman.sample_limit_proportions(&mut rng).unwrap();
+        assert!((limit[0] - 0.5).abs() < 1e-12);
+
+        // Triangular urns are not irreducible
+        let triangular = Urn::new(vec![1, 1], vec![vec![1, 1], vec![0, 1]], crate::tests::rng(6));
+        assert_eq!(triangular.sample_limit_proportions(&mut rng), None);
+
+        // The mean of the Dirichlet limit is the current proportion
+        let polya = Urn::polya(vec![3, 1], 2, crate::tests::rng(7));
+        let samples = 4_000;
+        let mean = (0..samples)
+            .map(|_| polya.sample_limit_proportions(&mut rng).unwrap()[0])
+            .sum::<f64>() / samples as f64;
+        assert!((mean - 0.75).abs() < 0.02);
+    }
+}
@@ -0,0 +1,133 @@
+//! Terminal rendering of small finite chains.
+//!
+//! Transition matrices are drawn as heatmaps with block characters and
+//! the current state of a chain is animated in place, using ANSI escape codes.
+//!
+//! # Examples
+//!
+//! Heatmap of a two-state chain.
+//! ```
+//! # use ndarray::array;
+//! # use markovian::viz::ascii::heatmap;
+//! println!("{}", heatmap(&array![[0.9, 0.1], [0.5, 0.5]]));
+//! ```
+
+// Traits
+use core::fmt::Debug;
+use rand::Rng;
+use rand_distr::{weighted_alias::AliasableWeight, Uniform};
+use std::io::Write;
+
+// Structs
+use crate::FiniteMarkovChain;
+use core::time::Duration;
+use ndarray::Array2;
+
+/// Characters used to shade probabilities, from zero to one.
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Returns the character shading the probability `p`.
+///
+/// Only zero is drawn blank, so that possible transitions are always visible.
+#[inline]
+fn shade(p: f64) -> char {
+    if p <= 0. {
+        SHADES[0]
+    } else {
+        let level = (p.min(1.) * (SHADES.len() - 1) as f64).ceil() as usize;
+        SHADES[level.max(1)]
+    }
+}
+
+/// Returns a heatmap of `matrix`, whose entries should be probabilities.
+///
+/// Each entry is drawn by two characters, rows and columns are labeled by their index.
+#[inline]
+pub fn heatmap(matrix: &Array2<f64>) -> String {
+    let width = matrix.ncols().saturating_sub(1).to_string().len().max(2);
+    let label_width = matrix.nrows().saturating_sub(1).to_string().len();
+    let mut output = format!("{:label_width$} ", "", label_width = label_width);
+    for j in 0..matrix.ncols() {
+        output.push_str(&format!("{:>width$}", j, width = width));
+    }
+    output.push('\n');
+    for (i, row) in matrix.genrows().into_iter().enumerate() {
+        output.push_str(&format!("{:>label_width$} ", i, label_width = label_width));
+        for &p in row.iter() {
+            output.extend(std::iter::repeat(shade(p)).take(width));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Returns a line with one cell per state, where the cell of `current` is marked.
+#[inline]
+pub fn state_line(nstates: usize, current: usize) -> String {
+    (0..nstates)
+        .map(|i| if i == current { "[●]" } else { "[ ]" })
+        .collect()
+}
+
+/// Prints the heatmap of the transition probabilities of `mc` and then animates
+/// its state for `steps` steps, waiting `delay` between them.
+///
+/// # Errors
+///
+/// If writing fails.
+///
+/// # Examples
+///
+/// Animating a random walk on a cycle, without delay.
+/// ```
+/// # use ndarray::Array2;
+/// # use core::time::Duration;
+/// # use markovian::{FiniteMarkovChain, viz::ascii::animate};
+/// let transition = Array2::from_shape_fn((5, 5), |(i, j)| {
+///     if (i + 1) % 5 == j || (j + 1) % 5 == i { 0.5 } else { 0. }
+/// });
+/// let mut mc = FiniteMarkovChain::from((0, transition, rand::thread_rng()));
+/// animate(&mut mc, 10, Duration::from_millis(0), std::io::stdout()).unwrap();
+/// ```
+#[inline]
+pub fn animate<T, W, R, O>(mc: &mut FiniteMarkovChain<T, W, R>, steps: usize, delay: Duration, mut writer: O) -> std::io::Result<()>
+where
+    W: AliasableWeight + Debug + Clone + num_traits::ToPrimitive,
+    Uniform<W>: Debug + Clone,
+    T: Debug + PartialEq + Clone,
+    R: Rng,
+    O: Write,
+{
+    write!(writer, "{}", heatmap(&mc.transition_probabilities()))?;
+    let index = |mc: &FiniteMarkovChain<T, W, R>, state: &T| {
+        mc.state_space().iter().position(|s| s == state).unwrap()
+    };
+    let nstates = mc.nstates();
+    let current = crate::State::state(mc).cloned().unwrap();
+    write!(writer, "{} step 0: {:?}", state_line(nstates, index(mc, &current)), current)?;
+    writer.flush()?;
+    for step in 1..=steps {
+        std::thread::sleep(delay);
+        let state = match mc.next() {
+            Some(state) => state,
+            None => break,
+        };
+        // Return to the start of the line and clear it
+        write!(writer, "\r\x1b[2K{} step {}: {:?}", state_line(nstates, index(mc, &state)), step, state)?;
+        writer.flush()?;
+    }
+    writeln!(writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn render() {
+        assert_eq!(heatmap(&array![[1., 0.], [0.3, 0.7]]), "   0 1\n0 ██  \n1 ▒▒▓▓\n");
+        assert_eq!(state_line(3, 1), "[ ][●][ ]");
+    }
+}
@@ -0,0 +1,234 @@
+// Traits
+use crate::traits::{Checkpoint, RandomSource, Reset, State, StateIterator, StochasticProcess, Transition};
+use core::fmt::Debug;
+use rand::{Rng, SeedableRng};
+
+// Structs
+use crate::errors::InvalidState;
+
+// Functions
+use core::mem;
+
+/// Process in discrete time whose next element depends on the last `K` elements.
+///
+/// The state is the window of the last `K` elements, in chronological order, 
+/// which is shifted in place at every step, so memory is never allocated.
+/// Iterating yields only the new elements.
+///
+/// # Examples
+///
+/// A random walk with momentum: it tends to repeat the last two moves.
+/// ```
+/// # use markovian::{WithMemory, prelude::*};
+/// let transition = |memory: &[i32; 3]| {
+///     let momentum = memory[2] - memory[0];
+///     let last = memory[2];
+///     match momentum {
+///         2 => raw_dist![(0.9, last + 1), (0.1, last - 1)],
+///         -2 => raw_dist![(0.1, last + 1), (0.9, last - 1)],
+///         _ => raw_dist![(0.5, last + 1), (0.5, last - 1)],
+///     }
+/// };
+/// let mut process = WithMemory::new([-2, -1, 0], transition, rand::thread_rng());
+/// let next = process.next().unwrap();
+/// assert_eq!(process.state(), Some(&[-1, 0, next]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WithMemory<T, F, R, const K: usize> {
+    state: [T; K],
+    initial_state: [T; K],
+    transition: F,
+    rng: R,
+}
+
+impl<T, F, R, const K: usize> WithMemory<T, F, R, K>
+where
+    T: Clone,
+    R: Rng,
+    F: Transition<[T; K], T>,
+{
+    /// Constructs a new process whose window of the last `K` elements is `memory`, 
+    /// the oldest element being first.
+    ///
+    /// # Panics
+    ///
+    /// If `K` is zero.
+    #[inline]
+    pub fn new(memory: [T; K], transition: F, rng: R) -> Self {
+        assert!(K > 0, "The memory must hold at least one element.");
+        WithMemory {
+            initial_state: memory.clone(),
+            state: memory,
+            transition,
+            rng,
+        }
+    }
+
+    /// Constructs a new `WithMemory<T, F, R, K>` whose random number generator is seeded by `seed`.
+    ///
+    /// # Panics
+    ///
+    /// If `K` is zero.
+    #[inline]
+    pub fn with_seed(memory: [T; K], transition: F, seed: u64) -> Self
+    where
+        R: SeedableRng,
+    {
+        WithMemory::new(memory, transition, R::seed_from_u64(seed))
+    }
+
+    /// Returns the most recent element.
+    #[inline]
+    pub fn latest(&self) -> &T {
+        &self.state[K - 1]
+    }
+
+    /// Samples the next element and shifts it into the window.
+    #[inline]
+    fn shift(&mut self) {
+        let next = self.transition.sample_from(&self.state, &mut self.rng);
+        self.state.rotate_left(1);
+        self.state[K - 1] = next;
+    }
+}
+
+impl<T, F, R, const K: usize> Reset for WithMemory<T, F, R, K>
+where
+    T: Clone,
+{
+    #[inline]
+    fn reset(&mut self) {
+        self.state.clone_from(&self.initial_state);
+    }
+}
+
+impl<T, F, R, const K: usize> RandomSource for WithMemory<T, F, R, K>
+where
+    R: Rng,
+{
+    type Rng = R;
+
+    #[inline]
+    fn rng(&self) -> &Self::Rng {
+        &self.rng
+    }
+
+    #[inline]
+    fn rng_mut(&mut self) -> &mut Self::Rng {
+        &mut self.rng
+    }
+
+    #[inline]
+    fn set_rng(&mut self, rng: Self::Rng) -> Self::Rng {
+        mem::replace(&mut self.rng, rng)
+    }
+}
+
+impl<T, F, R, const K: usize> State for WithMemory<T, F, R, K>
+where
+    T: Debug,
+{
+    type Item = [T; K];
+
+    #[inline]
+    fn state(&self) -> Option<&Self::Item> {
+        Some(&self.state)
+    }
+
+    #[inline]
+    fn state_mut(&mut self) -> Option<&mut Self::Item> {
+        Some(&mut self.state)
+    }
+
+    #[inline]
+    fn set_state(
+        &mut self,
+        mut new_state: Self::Item,
+    ) -> Result<Option<Self::Item>, InvalidState<Self::Item>> {
+        mem::swap(&mut self.state, &mut new_state);
+        Ok(Some(new_state))
+    }
+}
+
+impl<T, F, R, const K: usize> Checkpoint for WithMemory<T, F, R, K>
+where
+    T: Debug,
+    R: Rng,
+{
+    type Hidden = ();
+
+    #[inline]
+    fn hidden_state(&self) -> Self::Hidden {}
+
+    #[inline]
+    fn set_hidden_state(&mut self, _: Self::Hidden) {}
+}
+
+impl<T, F, R, const K: usize> Iterator for WithMemory<T, F, R, K>
+where
+    T: Debug + Clone,
+    F: Transition<[T; K], T>,
+    R: Rng,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.shift();
+        Some(self.latest().clone())
+    }
+}
+
+impl<T, F, R, const K: usize> StochasticProcess for WithMemory<T, F, R, K>
+where
+    T: Debug + Clone,
+    F: Transition<[T; K], T>,
+    R: Rng,
+{
+    type Time = ();
+    type State = T;
+
+    #[inline]
+    fn step(&mut self) -> Option<(Self::Time, Self::State)> {
+        self.next().map(|state| ((), state))
+    }
+}
+
+impl<T, F, R, const K: usize> StateIterator for WithMemory<T, F, R, K>
+where
+    T: Debug + Clone,
+    F: Transition<[T; K], T>,
+    R: Rng,
+{
+    #[inline]
+    fn state_as_item(&self) -> Option<<Self as std::iter::Iterator>::Item> {
+        Some(self.latest().clone())
+    }
+
+    #[inline]
+    fn advance(&mut self) -> bool {
+        self.shift();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::Raw;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn fibonacci() {
+        let transition = |memory: &[u64; 2]| Raw::new(vec![(1.0, memory[0] + memory[1])]);
+        let mut process = WithMemory::new([0, 1], transition, crate::tests::rng(1));
+        let trajectory: Vec<u64> = process.state_as_item().into_iter().chain(process.by_ref().take(5)).collect();
+        assert_eq!(trajectory, vec![1, 1, 2, 3, 5, 8]);
+        assert_eq!(process.state(), Some(&[5, 8]));
+
+        assert!(process.advance());
+        assert_eq!(process.latest(), &13);
+        process.reset();
+        assert_eq!(process.state(), Some(&[0, 1]));
+    }
+}